@@ -7,6 +7,10 @@
 )]
 
 use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use genetic_sudoku::genetics::{
+    generate_initial_population, run_simulation, DefaultRng, GAParams, RngStrategy, MAX_POPULATION,
+};
+use genetic_sudoku::solver::{Budget, GeneticSolver, Solver};
 use genetic_sudoku::sudoku::{Board, Row};
 use rand::rngs::OsRng;
 use rand::{thread_rng, Rng, SeedableRng};
@@ -19,6 +23,8 @@ const BAD_BOARD: Board<4> = Board([
     Row([1, 2, 3, 4]),
 ]);
 
+const BAD_BOARD_9: Board<9> = Board([Row([1, 2, 3, 4, 5, 6, 7, 8, 9]); 9]);
+
 fn bench_count_row_duplicates(c: &mut Criterion) {
     c.bench_function("count_row_duplicates", |b| {
         b.iter(|| black_box(BAD_BOARD).count_row_duplicates());
@@ -31,6 +37,12 @@ fn bench_count_box_duplicates(c: &mut Criterion) {
     });
 }
 
+fn bench_fitness(c: &mut Criterion) {
+    c.bench_function("fitness_9x9", |b| {
+        b.iter(|| black_box(BAD_BOARD_9).fitness());
+    });
+}
+
 fn bench_thread_rng(c: &mut Criterion) {
     let mut rng = thread_rng();
 
@@ -47,11 +59,65 @@ fn bench_pcg64mcg(c: &mut Criterion) {
     });
 }
 
+fn bench_rng_strategy(c: &mut Criterion) {
+    let board = Board::<9>::read("boards/medium.txt").unwrap();
+
+    for (name, rng_strategy) in [
+        ("run_simulation_per_child_rng", RngStrategy::PerChild),
+        ("run_simulation_per_worker_rng", RngStrategy::PerWorker),
+    ] {
+        let params = GAParams::new(1000, 0.5, 0.05, None)
+            .unwrap()
+            .with_rng_strategy(rng_strategy);
+        let mut population =
+            generate_initial_population::<9, MAX_POPULATION, DefaultRng>(&params, &board);
+        let mut scores = Vec::with_capacity(MAX_POPULATION);
+
+        c.bench_function(name, |b| {
+            b.iter(|| {
+                run_simulation::<9, MAX_POPULATION, DefaultRng>(
+                    &params,
+                    1,
+                    black_box(&board),
+                    &mut population,
+                    &mut scores,
+                )
+            });
+        });
+    }
+}
+
+fn bench_compact_population(c: &mut Criterion) {
+    let board = Board::<9>::read("boards/medium.txt").unwrap();
+    let budget = Budget {
+        generations: Some(10),
+        duration: None,
+        target: None,
+    };
+
+    for (name, compact) in [
+        ("solve_10_generations_boards", false),
+        ("solve_10_generations_compact", true),
+    ] {
+        let params = GAParams::new(1000, 0.5, 0.05, None)
+            .unwrap()
+            .with_compact_population(compact);
+        let solver = GeneticSolver::new(params);
+
+        c.bench_function(name, |b| {
+            b.iter(|| solver.solve(black_box(&board), budget, &mut ()));
+        });
+    }
+}
+
 criterion_group!(
     benches,
     bench_count_row_duplicates,
     bench_count_box_duplicates,
+    bench_fitness,
     bench_thread_rng,
     bench_pcg64mcg,
+    bench_rng_strategy,
+    bench_compact_population,
 );
 criterion_main!(benches);
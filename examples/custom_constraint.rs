@@ -0,0 +1,66 @@
+#![warn(
+    clippy::all,
+    // clippy::restriction,
+    clippy::pedantic,
+    clippy::nursery,
+    clippy::cargo
+)]
+
+//! Solves a diagonal Sudoku, whose main diagonal must also hold every digit
+//! once, by adding a custom constraint to the fitness score with a Rhai
+//! script.
+//!
+//! ```text
+//! cargo run --release --features scripting --example custom_constraint
+//! ```
+
+use genetic_sudoku::genetics::GAParams;
+use genetic_sudoku::scripting::FitnessScript;
+use genetic_sudoku::solver::{Budget, GeneticSolver, Solver};
+use genetic_sudoku::sudoku::Board;
+use std::collections::HashSet;
+use std::error::Error;
+use std::sync::Arc;
+
+/// Counts the duplicates on the main diagonal.
+const DIAGONAL: &str = r"
+let seen = [];
+let duplicates = 0;
+for i in 0..grid.len() {
+    let digit = grid[i][i];
+    if seen.contains(digit) { duplicates += 1; } else { seen.push(digit); }
+}
+duplicates
+";
+
+const PUZZLE: &str = "\
+1000
+0000
+0020
+0000
+";
+
+fn main() -> Result<(), Box<dyn Error>> {
+    let puzzle = Board::<4>::parse(PUZZLE)?;
+    let script = FitnessScript::compile(DIAGONAL)?;
+    let params = GAParams::new(100, 0.5, 0.05, Some(100))?.with_script(Some(Arc::new(script)));
+
+    let budget = Budget {
+        generations: Some(10_000),
+        duration: None,
+        target: None,
+    };
+    let outcome = GeneticSolver::new(params).solve(&puzzle, budget, &mut ());
+    let Some(solution) = outcome.solution() else {
+        return Err(format!(
+            "{} after {} generations",
+            outcome.termination, outcome.generation
+        )
+        .into());
+    };
+
+    let diagonal: HashSet<u8> = (0..4).map(|i| solution.0[i].0[i]).collect();
+    assert_eq!(4, diagonal.len());
+    println!("Solved in {} generations:\n{solution}", outcome.generation);
+    Ok(())
+}
@@ -0,0 +1,68 @@
+#![warn(
+    clippy::all,
+    // clippy::restriction,
+    clippy::pedantic,
+    clippy::nursery,
+    clippy::cargo
+)]
+
+//! Solves a 9x9 puzzle within a budget of generations, drawing the run's
+//! progress as it goes with an observer that drives an `indicatif` progress
+//! bar.
+//!
+//! ```text
+//! cargo run --release --example progress_bar -- boards/medium.txt
+//! ```
+
+use genetic_sudoku::formats;
+use genetic_sudoku::genetics::GAParams;
+use genetic_sudoku::solver::{Budget, GeneticSolver, Observer, Progress, Solver};
+use indicatif::{ProgressBar, ProgressStyle};
+use std::error::Error;
+
+const GENERATIONS: u64 = 20_000;
+
+// The braces are `indicatif` template keys, not format arguments.
+#[allow(clippy::literal_string_with_formatting_args)]
+const TEMPLATE: &str = "{elapsed_precise} [{bar:40}] generation {pos}/{len}, {msg}";
+
+/// Moves the bar to the last generation evaluated, showing the best score.
+struct Bar(ProgressBar);
+
+impl<const N: usize> Observer<N> for Bar {
+    // Only progress is drawn, so solvers need not copy their population
+    // out for the observer.
+    fn needs_population(&self) -> bool {
+        false
+    }
+
+    fn on_progress(&mut self, progress: &Progress) {
+        self.0.set_position(progress.generation);
+        if let Some(best) = progress.best {
+            self.0.set_message(format!("best fitness {best}"));
+        }
+    }
+}
+
+fn main() -> Result<(), Box<dyn Error>> {
+    let path = std::env::args()
+        .nth(1)
+        .unwrap_or_else(|| "boards/medium.txt".to_owned());
+    let puzzle = formats::load::<9, _>(path)?[0];
+
+    let bar = ProgressBar::new(GENERATIONS).with_style(ProgressStyle::with_template(TEMPLATE)?);
+    let params = GAParams::new(500, 0.5, 0.05, Some(1000))?;
+    let budget = Budget {
+        generations: Some(GENERATIONS),
+        duration: None,
+        target: None,
+    };
+    let outcome = GeneticSolver::new(params).solve(&puzzle, budget, &mut Bar(bar.clone()));
+    bar.finish();
+
+    match outcome.solution() {
+        Some(solution) => println!("{solution}"),
+        None => println!("{}", outcome.termination),
+    }
+    Ok(())
+}
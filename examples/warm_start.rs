@@ -0,0 +1,58 @@
+#![warn(
+    clippy::all,
+    // clippy::restriction,
+    clippy::pedantic,
+    clippy::nursery,
+    clippy::cargo
+)]
+
+//! Solves every puzzle in a file one after another, each warm-started from
+//! the solution of the one before, and saves the last solution so that the
+//! next invocation can start warm too.
+//!
+//! ```text
+//! cargo run --release --example warm_start -- PUZZLES [SOLUTION]
+//! ```
+//!
+//! `PUZZLES` is any board file, such as a collection with one puzzle per
+//! line. If `SOLUTION` exists, it seeds the first run; either way, the last
+//! solution found is written to it.
+
+use genetic_sudoku::formats;
+use genetic_sudoku::genetics::GAParams;
+use genetic_sudoku::solver::Budget;
+use genetic_sudoku::stream::StreamSolver;
+use std::error::Error;
+use std::path::Path;
+
+fn main() -> Result<(), Box<dyn Error>> {
+    let mut args = std::env::args().skip(1);
+    let puzzles =
+        formats::load::<9, _>(args.next().ok_or("usage: warm_start PUZZLES [SOLUTION]")?)?;
+    let saved = args.next();
+
+    let params = GAParams::new(500, 0.5, 0.05, Some(1000))?;
+    let mut solver = StreamSolver::new(params, 0)?.with_warm_start(0.25);
+    if let Some(path) = saved.as_deref().filter(|path| Path::new(path).exists()) {
+        solver = solver.with_previous(formats::load::<9, _>(path)?[0]);
+    }
+
+    let budget = Budget {
+        generations: Some(50_000),
+        duration: None,
+        target: None,
+    };
+    for (i, outcome) in solver.solve_all(&puzzles, budget).iter().enumerate() {
+        println!(
+            "Puzzle {}: {} after {} generations",
+            i + 1,
+            outcome.termination,
+            outcome.generation
+        );
+    }
+
+    if let (Some(path), Some(solution)) = (saved, solver.previous()) {
+        std::fs::write(path, solution.to_string() + "\n")?;
+    }
+    Ok(())
+}
@@ -0,0 +1,37 @@
+#![warn(
+    clippy::all,
+    // clippy::restriction,
+    clippy::pedantic,
+    clippy::nursery,
+    clippy::cargo
+)]
+
+//! Solves a puzzle read from standard input with `solve_str`, within a
+//! budget of generations rather than of time, and writes the solution to
+//! standard output. It builds natively, and for WebAssembly runtimes with
+//! WASI, where the solver's thread pool runs on the calling thread:
+//!
+//! ```text
+//! cargo build --release --target wasm32-wasip1 --example wasm_solve
+//! wasmtime target/wasm32-wasip1/release/examples/wasm_solve.wasm < boards/easy4.txt
+//! ```
+
+use genetic_sudoku::solver::Budget;
+use genetic_sudoku::{solve_str, SolveOptions};
+use std::error::Error;
+use std::io::Read;
+
+fn main() -> Result<(), Box<dyn Error>> {
+    let mut puzzle = String::new();
+    std::io::stdin().read_to_string(&mut puzzle)?;
+
+    // Seeded, so that the same puzzle solves the same way in every runtime.
+    let options = SolveOptions::default().with_seed(1).with_budget(Budget {
+        generations: Some(100_000),
+        duration: None,
+        target: None,
+    });
+    let solution = solve_str(&puzzle, options)?;
+    println!("{solution}");
+    Ok(())
+}
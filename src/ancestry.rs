@@ -0,0 +1,322 @@
+#![warn(
+    clippy::all,
+    // clippy::restriction,
+    clippy::pedantic,
+    clippy::nursery,
+    clippy::cargo
+)]
+
+use super::genetics::{Inheritance, Lineage};
+use super::solver::Observer;
+use super::sudoku::{Board, Fitness};
+use std::collections::VecDeque;
+
+/// How a candidate on a lineage came to be in its generation.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum Origin<const N: usize> {
+    /// Generated at random, in the first generation, after a restart or as
+    /// an immigrant, or bred before the oldest generation recorded.
+    Random,
+    /// Carried over unchanged from the generation before, as an elite or a
+    /// survivor under plus selection.
+    CarriedOver,
+    /// Bred from two candidates of the generation before.
+    Bred {
+        parents: (Board<N>, Board<N>),
+        parent_scores: [Fitness; 2],
+        /// Where each cell of the child came from, reconstructed by
+        /// comparing it with its parents (see [`reconstruct`]).
+        mask: [[Inheritance; N]; N],
+    },
+}
+
+/// One candidate on a lineage.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct Step<const N: usize> {
+    pub generation: u64,
+    pub board: Board<N>,
+    pub score: Fitness,
+    pub origin: Origin<N>,
+}
+
+/// One generation, as recorded.
+#[derive(Debug, Clone)]
+struct Record<const N: usize> {
+    generation: u64,
+    population: Vec<Board<N>>,
+    scores: Vec<Fitness>,
+    lineage: Vec<Lineage>,
+}
+
+/// A ring buffer of the populations of the last `capacity` generations,
+/// with how each was bred, for tracing the lineage of the fittest
+/// candidate back through them after a run ends.
+///
+/// Lineage is only reported by solvers with a single population, and every
+/// candidate of every generation is kept, so the capacity should be chosen
+/// with the population size in mind.
+#[derive(Debug, Clone)]
+pub struct LineageRecorder<const N: usize> {
+    capacity: usize,
+    records: VecDeque<Record<N>>,
+}
+
+impl<const N: usize> LineageRecorder<N> {
+    /// Returns an empty recorder.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `capacity` is 0.
+    #[inline]
+    #[must_use]
+    pub fn new(capacity: usize) -> Self {
+        assert!(capacity > 0);
+
+        Self {
+            capacity,
+            records: VecDeque::with_capacity(capacity),
+        }
+    }
+
+    /// Returns the number of generations held.
+    #[inline]
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.records.len()
+    }
+
+    #[inline]
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.records.is_empty()
+    }
+
+    #[inline]
+    pub fn clear(&mut self) {
+        self.records.clear();
+    }
+
+    /// Traces the fittest candidate of the latest generation back to where
+    /// its line began, and returns its ancestors, oldest first, followed by
+    /// the candidate itself.
+    ///
+    /// A child is traced through whichever parent it took more cells from,
+    /// its first parent if it took as many from each. The lineage begins at
+    /// a candidate generated at random, or at the oldest generation held.
+    #[inline]
+    #[must_use]
+    pub fn winning_lineage(&self) -> Vec<Step<N>> {
+        let mut steps = Vec::new();
+        let Some(latest) = self.records.back() else {
+            return steps;
+        };
+        let Some(mut i) = (0..latest.scores.len()).min_by_key(|&i| latest.scores[i]) else {
+            return steps;
+        };
+
+        for (age, record) in self.records.iter().enumerate().rev() {
+            let previous = age
+                .checked_sub(1)
+                .map(|age| &self.records[age])
+                .filter(|previous| previous.generation + 1 == record.generation);
+            let board = record.population[i];
+            let (origin, parent) = match (previous, record.lineage.get(i)) {
+                (Some(previous), Some(lineage)) => {
+                    let parents = lineage.parents.map(|parent| previous.population[parent]);
+                    let mask = reconstruct(&board, (&parents[0], &parents[1]));
+                    let cells = |parent| mask.iter().flatten().filter(|&&c| c == parent).count();
+                    let followed =
+                        usize::from(cells(Inheritance::ParentY) > cells(Inheritance::ParentX));
+                    let origin = Origin::Bred {
+                        parents: parents.into(),
+                        parent_scores: lineage.parent_scores,
+                        mask,
+                    };
+                    (origin, Some(lineage.parents[followed]))
+                }
+                (Some(previous), None) if !record.lineage.is_empty() => {
+                    let carried = previous.population.iter().position(|&b| b == board);
+                    carried.map_or((Origin::Random, None), |j| (Origin::CarriedOver, Some(j)))
+                }
+                _ => (Origin::Random, None),
+            };
+
+            steps.push(Step {
+                generation: record.generation,
+                board,
+                score: record.scores[i],
+                origin,
+            });
+            match parent {
+                Some(parent) => i = parent,
+                None => break,
+            }
+        }
+
+        steps.reverse();
+        steps
+    }
+}
+
+impl<const N: usize> Observer<N> for LineageRecorder<N> {
+    #[inline]
+    fn on_generation(&mut self, generation: u64, population: &[Board<N>], scores: &[Fitness]) {
+        if population.len() != scores.len() {
+            return;
+        }
+        if self.records.len() == self.capacity {
+            self.records.pop_front();
+        }
+        self.records.push_back(Record {
+            generation,
+            population: population.to_vec(),
+            scores: scores.to_vec(),
+            lineage: Vec::new(),
+        });
+    }
+
+    #[inline]
+    fn on_lineage(&mut self, generation: u64, lineage: &[Lineage]) {
+        if let Some(record) = self.records.back_mut() {
+            if record.generation == generation {
+                record.lineage = lineage.to_vec();
+            }
+        }
+    }
+}
+
+/// Reconstructs where each cell of `child` came from by comparing it with
+/// its `parents`: from the first parent if it matches, otherwise from the
+/// second if that matches, otherwise by mutation.
+///
+/// A mutation that happens to pick a parent's digit cannot be told apart
+/// from inheritance, and changes made after breeding, by local search,
+/// column repair or the backtracking finisher, show as mutations.
+#[inline]
+#[must_use]
+pub fn reconstruct<const N: usize>(
+    child: &Board<N>,
+    parents: (&Board<N>, &Board<N>),
+) -> [[Inheritance; N]; N] {
+    let mut mask = [[Inheritance::Mutation; N]; N];
+
+    for (i, row) in mask.iter_mut().enumerate() {
+        for (j, cell) in row.iter_mut().enumerate() {
+            let digit = child.0[i].0[j];
+            if digit == parents.0 .0[i].0[j] {
+                *cell = Inheritance::ParentX;
+            } else if digit == parents.1 .0[i].0[j] {
+                *cell = Inheritance::ParentY;
+            }
+        }
+    }
+
+    mask
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::genetics::GAParams;
+    use crate::solver::{Budget, GeneticSolver, Solver};
+    use crate::testing::SOLVED_4;
+
+    #[test]
+    fn test_reconstruct() {
+        let mut x = SOLVED_4;
+        x.0[0].0 = [4, 3, 2, 1];
+        let mut child = SOLVED_4;
+        child.0[3].0[3] = 1;
+
+        let mask = reconstruct(&child, (&x, &SOLVED_4));
+        assert_eq!([Inheritance::ParentY; 4], mask[0]);
+        assert_eq!([Inheritance::ParentX; 4], mask[1]);
+        assert_eq!(Inheritance::Mutation, mask[3][3]);
+    }
+
+    #[test]
+    fn test_winning_lineage() {
+        let population = |first: Board<4>| vec![first, SOLVED_4];
+        let scores = [Fitness::new(2), Fitness::new(1)];
+        let lineage = |parents, mutated| Lineage {
+            parents,
+            parent_scores: [Fitness::new(2), Fitness::new(1)],
+            mutated,
+        };
+        let mut mutant = SOLVED_4;
+        mutant.0[0].0[0] = 2;
+
+        let mut recorder = LineageRecorder::new(3);
+        assert!(recorder.winning_lineage().is_empty());
+        for generation in 0..4 {
+            recorder.on_generation(generation, &population(mutant), &scores);
+        }
+        recorder.on_lineage(3, &[lineage([1, 0], true)]);
+        assert_eq!(3, recorder.len());
+
+        // The fittest candidate of generation 3 was carried over from
+        // generation 2, which was not bred.
+        let steps = recorder.winning_lineage();
+        assert_eq!(2, steps.len());
+        assert_eq!((2, Origin::Random), (steps[0].generation, steps[0].origin));
+        assert_eq!(
+            (3, Origin::CarriedOver),
+            (steps[1].generation, steps[1].origin)
+        );
+
+        recorder.on_generation(4, &[mutant, SOLVED_4], &[Fitness::ZERO, Fitness::new(1)]);
+        recorder.on_lineage(4, &[lineage([0, 1], true), lineage([1, 1], false)]);
+        let steps = recorder.winning_lineage();
+        assert_eq!(3, steps.len());
+        assert_eq!(mutant, steps[2].board);
+        assert_eq!(Fitness::ZERO, steps[2].score);
+        let Origin::Bred { parents, mask, .. } = steps[2].origin else {
+            panic!("expected a bred child");
+        };
+        assert_eq!((mutant, SOLVED_4), parents);
+        assert!(mask
+            .iter()
+            .flatten()
+            .all(|&cell| cell == Inheritance::ParentX));
+
+        // The lineage continues through the first parent, which took only
+        // its first cell from its second parent.
+        let Origin::Bred { parents, mask, .. } = steps[1].origin else {
+            panic!("expected a bred child");
+        };
+        assert_eq!((SOLVED_4, mutant), parents);
+        assert_eq!(Inheritance::ParentY, mask[0][0]);
+        assert_eq!(Inheritance::ParentX, mask[0][1]);
+        assert_eq!(Origin::Random, steps[0].origin);
+    }
+
+    #[test]
+    fn test_recorded_run() {
+        let mut base = SOLVED_4;
+        base.0[0].0 = [0; 4];
+        base.0[2].0[..2].fill(0);
+        let mut recorder = LineageRecorder::new(100);
+        let params = GAParams::new(20, 0.5, 0.1, None)
+            .unwrap()
+            .with_seed(5)
+            .with_elitism(1);
+        let outcome = GeneticSolver::new(params).solve(
+            &base,
+            Budget {
+                generations: Some(100),
+                duration: None,
+                target: None,
+            },
+            &mut recorder,
+        );
+
+        let steps = recorder.winning_lineage();
+        let last = steps.last().unwrap();
+        assert_eq!(outcome.generation, last.generation);
+        assert_eq!(Some(&base.overlay(&last.board)), outcome.solution());
+        assert_eq!(Origin::Random, steps[0].origin);
+        for (step, next) in steps.iter().zip(&steps[1..]) {
+            assert_eq!(step.generation + 1, next.generation);
+        }
+    }
+}
@@ -0,0 +1,169 @@
+#![warn(
+    clippy::all,
+    // clippy::restriction,
+    clippy::pedantic,
+    clippy::nursery,
+    clippy::cargo
+)]
+
+use super::cancel::CancellationToken;
+use super::solver::{Budget, Observer, Outcome, Progress, Solver};
+use super::sudoku::Board;
+use std::sync::{Arc, Mutex};
+use std::thread::{self, JoinHandle};
+use std::time::Duration;
+
+/// What the solver thread shares with its `Anytime` handle.
+#[derive(Debug, Default)]
+struct Shared {
+    progress: Mutex<Option<Progress>>,
+    cancellation: CancellationToken,
+}
+
+/// The observer the solver thread reports to.
+struct Reporter(Arc<Shared>);
+
+impl<const N: usize> Observer<N> for Reporter {
+    #[inline]
+    fn needs_population(&self) -> bool {
+        false
+    }
+
+    #[inline]
+    fn on_progress(&mut self, progress: &Progress) {
+        *self.0.progress.lock().unwrap() = Some(*progress);
+    }
+
+    #[inline]
+    fn is_cancelled(&self) -> bool {
+        self.0.cancellation.is_cancelled()
+    }
+}
+
+/// A solver run in the background within a total budget, whose progress can
+/// be queried at any time, such as to drive a progress bar.
+///
+/// Any backend can be run this way, since every backend accounts for its
+/// budget the same way.
+#[derive(Debug)]
+pub struct Anytime<const N: usize> {
+    shared: Arc<Shared>,
+    budget: Budget,
+    thread: JoinHandle<Outcome<N>>,
+}
+
+impl<const N: usize> Anytime<N> {
+    /// Starts solving `base` with `solver` within `budget` on a new thread.
+    #[inline]
+    #[must_use]
+    pub fn spawn<S: Solver<N> + Send + 'static>(solver: S, base: Board<N>, budget: Budget) -> Self {
+        let shared = Arc::new(Shared::default());
+        let mut reporter = Reporter(Arc::clone(&shared));
+        let thread = thread::spawn(move || solver.solve(&base, budget, &mut reporter));
+
+        Self {
+            shared,
+            budget,
+            thread,
+        }
+    }
+
+    /// Returns the run's progress so far.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the solver thread panicked while reporting its progress.
+    #[inline]
+    #[must_use]
+    pub fn progress(&self) -> Progress {
+        self.shared.progress.lock().unwrap().unwrap_or(Progress {
+            generation: 0,
+            elapsed: Duration::ZERO,
+            budget_used: self.budget.used(0, Duration::ZERO),
+            best: None,
+            fitness: 0.0,
+        })
+    }
+
+    /// Returns whether the run has ended.
+    #[inline]
+    #[must_use]
+    pub fn is_finished(&self) -> bool {
+        self.thread.is_finished()
+    }
+
+    /// Asks the run to stop before its next generation. It then ends as
+    /// `Termination::Cancelled`.
+    #[inline]
+    pub fn cancel(&self) {
+        self.shared.cancellation.cancel();
+    }
+
+    /// Waits for the run to end, and returns its outcome.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the solver thread panicked.
+    #[inline]
+    #[must_use]
+    pub fn join(self) -> Outcome<N> {
+        self.thread.join().unwrap()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::genetics::GAParams;
+    use crate::solver::GeneticSolver;
+    use crate::termination::Termination;
+    use crate::testing::SOLVED_9;
+
+    #[test]
+    fn test_anytime() {
+        let mut base = SOLVED_9;
+        for row in &mut base.0 {
+            row.0[..6].fill(0);
+        }
+        let params = GAParams::new(30, 0.5, 0.05, None).unwrap().with_seed(2);
+        let budget = Budget {
+            generations: Some(4),
+            duration: None,
+            target: None,
+        };
+
+        let anytime = Anytime::spawn(GeneticSolver::new(params.clone()), base, budget);
+        let outcome = anytime.join();
+        assert_eq!(Termination::BudgetExhausted, outcome.termination);
+
+        let anytime = Anytime::spawn(GeneticSolver::new(params.clone()), base, budget);
+        while !anytime.is_finished() {
+            let progress = anytime.progress();
+            assert!((0.0..=1.0).contains(&progress.budget_used.unwrap()));
+            assert!((0.0..=1.0).contains(&progress.fitness));
+        }
+        let progress = anytime.progress();
+        assert_eq!(3, progress.generation);
+        assert_eq!(Some(0.75), progress.budget_used);
+        assert_eq!(outcome.best.map(|(_, score)| score), progress.best);
+        drop(anytime.join());
+
+        // A solved run has made all the progress there is to make.
+        let mut easy = SOLVED_9;
+        easy.0[0].0[0] = 0;
+        let anytime = Anytime::spawn(GeneticSolver::new(params), easy, Budget::unlimited());
+        while !anytime.is_finished() {}
+        let progress = anytime.progress();
+        assert_eq!(None, progress.budget_used);
+        assert!((progress.fitness - 1.0).abs() < f64::EPSILON);
+        assert_eq!(Termination::Solved, anytime.join().termination);
+
+        let unstarted = Anytime::spawn(
+            GeneticSolver::new(GAParams::new(30, 0.5, 0.05, None).unwrap()),
+            base,
+            Budget::unlimited(),
+        );
+        unstarted.cancel();
+        assert_eq!(Termination::Cancelled, unstarted.join().termination);
+    }
+}
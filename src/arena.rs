@@ -0,0 +1,323 @@
+#![warn(
+    clippy::all,
+    // clippy::restriction,
+    clippy::pedantic,
+    clippy::nursery,
+    clippy::cargo
+)]
+
+use super::sudoku::{Board, Row};
+use rayon::prelude::*;
+use rayon::slice::{ChunksExact, ChunksExactMut};
+
+/// A population of boards of size N stored back to back in one `Vec<u8>`.
+///
+/// Each board is a row-major slice of N * N digits. Boards are handed out
+/// as slices into the arena rather than moved around as `Board<N>` values,
+/// which keeps large populations of large boards cheap to breed.
+#[derive(Debug, Clone, Default, Eq, PartialEq)]
+pub struct PopulationArena<const N: usize> {
+    cells: Vec<u8>,
+}
+
+impl<const N: usize> PopulationArena<N> {
+    /// The number of digits in one board.
+    pub const BOARD_LEN: usize = N * N;
+
+    #[inline]
+    #[must_use]
+    pub const fn new() -> Self {
+        Self { cells: Vec::new() }
+    }
+
+    /// Returns an empty arena with room for `boards` boards.
+    #[inline]
+    #[must_use]
+    pub fn with_capacity(boards: usize) -> Self {
+        Self {
+            cells: Vec::with_capacity(boards * Self::BOARD_LEN),
+        }
+    }
+
+    /// Returns an arena holding copies of `boards`, in order.
+    #[inline]
+    #[must_use]
+    pub fn from_boards(boards: &[Board<N>]) -> Self {
+        let mut arena = Self::with_capacity(boards.len());
+        for board in boards {
+            arena.push(board);
+        }
+        arena
+    }
+
+    /// Returns the number of boards in the arena.
+    #[inline]
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.cells.len() / Self::BOARD_LEN
+    }
+
+    #[inline]
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.cells.is_empty()
+    }
+
+    #[inline]
+    pub fn clear(&mut self) {
+        self.cells.clear();
+    }
+
+    /// Shortens the arena to `len` boards.
+    #[inline]
+    pub fn truncate(&mut self, len: usize) {
+        self.cells.truncate(len * Self::BOARD_LEN);
+    }
+
+    /// Resizes the arena to `len` boards, filling new boards with zeros.
+    #[inline]
+    pub fn resize(&mut self, len: usize) {
+        self.cells.resize(len * Self::BOARD_LEN, 0);
+    }
+
+    /// Appends a copy of `board`.
+    #[inline]
+    pub fn push(&mut self, board: &Board<N>) {
+        for row in &board.0 {
+            self.cells.extend_from_slice(&row.0);
+        }
+    }
+
+    /// Replaces the `i`th board with a copy of `board`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `i` is out of range.
+    #[inline]
+    pub fn set(&mut self, i: usize, board: &Board<N>) {
+        let cells = &mut self.cells[i * Self::BOARD_LEN..(i + 1) * Self::BOARD_LEN];
+        for (chunk, row) in cells.chunks_exact_mut(N).zip(&board.0) {
+            chunk.copy_from_slice(&row.0);
+        }
+    }
+
+    /// Returns the digits of the `i`th board, or `None` if out of range.
+    #[inline]
+    #[must_use]
+    pub fn get(&self, i: usize) -> Option<&[u8]> {
+        self.cells
+            .get(i * Self::BOARD_LEN..(i + 1) * Self::BOARD_LEN)
+    }
+
+    /// Returns a copy of the `i`th board.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `i` is out of range.
+    #[inline]
+    #[must_use]
+    pub fn board(&self, i: usize) -> Board<N> {
+        to_board(self.get(i).expect("board index out of range"))
+    }
+
+    /// Returns copies of every board, in order.
+    #[inline]
+    #[must_use]
+    pub fn to_boards(&self) -> Vec<Board<N>> {
+        self.iter().map(to_board).collect()
+    }
+
+    /// Iterates over the digits of every board.
+    #[inline]
+    pub fn iter(&self) -> impl Iterator<Item = &[u8]> {
+        self.cells.chunks_exact(Self::BOARD_LEN)
+    }
+
+    /// Iterates in parallel over the digits of every board.
+    #[inline]
+    #[must_use]
+    pub fn par_iter(&self) -> ChunksExact<'_, u8> {
+        self.cells.par_chunks_exact(Self::BOARD_LEN)
+    }
+
+    /// Iterates in parallel over the digits of every board, mutably.
+    #[inline]
+    pub fn par_iter_mut(&mut self) -> ChunksExactMut<'_, u8> {
+        self.cells.par_chunks_exact_mut(Self::BOARD_LEN)
+    }
+}
+
+/// The storage a population is bred in: a `Vec<Board<N>>`, or a
+/// `PopulationArena<N>` for large populations of large boards.
+pub(crate) trait Population<const N: usize>: Sized + Sync {
+    fn len(&self) -> usize;
+
+    /// Returns a copy of the `i`th board.
+    fn board(&self, i: usize) -> Board<N>;
+
+    /// Replaces the `i`th board with a copy of `board`.
+    fn set(&mut self, i: usize, board: &Board<N>);
+
+    /// Shortens the population to `len` boards.
+    fn truncate(&mut self, len: usize);
+
+    /// Appends a copy of `board`.
+    fn push(&mut self, board: &Board<N>);
+
+    /// Replaces every board with `boards`.
+    fn assign(&mut self, boards: &[Board<N>]);
+
+    /// Makes a new population of `len` boards in parallel, calling `make`
+    /// with per-worker state from `init` and the index of each board, and
+    /// returns it with what `make` returned beside each board.
+    fn par_make<S, T: Send>(
+        &self,
+        len: usize,
+        init: impl Fn() -> S + Sync + Send,
+        make: impl Fn(&mut S, usize) -> (Board<N>, T) + Sync + Send,
+    ) -> (Self, Vec<T>);
+}
+
+impl<const N: usize> Population<N> for Vec<Board<N>> {
+    #[inline]
+    fn len(&self) -> usize {
+        self.len()
+    }
+
+    #[inline]
+    fn board(&self, i: usize) -> Board<N> {
+        self[i]
+    }
+
+    #[inline]
+    fn set(&mut self, i: usize, board: &Board<N>) {
+        self[i] = *board;
+    }
+
+    #[inline]
+    fn truncate(&mut self, len: usize) {
+        self.truncate(len);
+    }
+
+    #[inline]
+    fn push(&mut self, board: &Board<N>) {
+        self.push(*board);
+    }
+
+    #[inline]
+    fn assign(&mut self, boards: &[Board<N>]) {
+        self.clear();
+        self.extend_from_slice(boards);
+    }
+
+    fn par_make<S, T: Send>(
+        &self,
+        len: usize,
+        init: impl Fn() -> S + Sync + Send,
+        make: impl Fn(&mut S, usize) -> (Board<N>, T) + Sync + Send,
+    ) -> (Self, Vec<T>) {
+        (0..len).into_par_iter().map_init(init, make).unzip()
+    }
+}
+
+impl<const N: usize> Population<N> for PopulationArena<N> {
+    #[inline]
+    fn len(&self) -> usize {
+        self.len()
+    }
+
+    #[inline]
+    fn board(&self, i: usize) -> Board<N> {
+        self.board(i)
+    }
+
+    #[inline]
+    fn set(&mut self, i: usize, board: &Board<N>) {
+        self.set(i, board);
+    }
+
+    #[inline]
+    fn truncate(&mut self, len: usize) {
+        self.truncate(len);
+    }
+
+    #[inline]
+    fn push(&mut self, board: &Board<N>) {
+        self.push(board);
+    }
+
+    #[inline]
+    fn assign(&mut self, boards: &[Board<N>]) {
+        *self = Self::from_boards(boards);
+    }
+
+    fn par_make<S, T: Send>(
+        &self,
+        len: usize,
+        init: impl Fn() -> S + Sync + Send,
+        make: impl Fn(&mut S, usize) -> (Board<N>, T) + Sync + Send,
+    ) -> (Self, Vec<T>) {
+        let mut arena = Self::with_capacity(len);
+        arena.resize(len);
+        let extras = arena
+            .par_iter_mut()
+            .enumerate()
+            .map_init(init, |state, (i, cells)| {
+                let (board, extra) = make(state, i);
+                for (chunk, row) in cells.chunks_exact_mut(N).zip(&board.0) {
+                    chunk.copy_from_slice(&row.0);
+                }
+                extra
+            })
+            .collect();
+        (arena, extras)
+    }
+}
+
+/// Copies a row-major slice of N * N digits into a Board.
+///
+/// # Panics
+///
+/// Panics if `cells` does not hold exactly N * N digits.
+#[inline]
+#[must_use]
+pub fn to_board<const N: usize>(cells: &[u8]) -> Board<N> {
+    assert_eq!(N * N, cells.len());
+
+    Board(std::array::from_fn(|i| {
+        Row(std::array::from_fn(|j| cells[i * N + j]))
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{SOLVED_4, SOLVED_9};
+
+    #[test]
+    fn test_arena() {
+        let mut transposed = SOLVED_4;
+        for (i, row) in SOLVED_4.0.iter().enumerate() {
+            for (j, &digit) in row.0.iter().enumerate() {
+                transposed.0[j].0[i] = digit;
+            }
+        }
+
+        let mut arena = PopulationArena::from_boards(&[SOLVED_4, transposed]);
+        assert_eq!(2, arena.len());
+        assert_eq!(
+            Some(&[1, 2, 3, 4, 3, 4, 1, 2][..]),
+            arena.get(0).map(|b| &b[..8])
+        );
+        assert_eq!(transposed, arena.board(1));
+        assert_eq!(None, arena.get(2));
+        assert_eq!(vec![SOLVED_4, transposed], arena.to_boards());
+
+        arena.resize(3);
+        assert_eq!(Some(&[0; 16][..]), arena.get(2));
+        arena.truncate(1);
+        assert_eq!(vec![SOLVED_4], arena.to_boards());
+
+        assert_eq!(SOLVED_9, PopulationArena::from_boards(&[SOLVED_9]).board(0));
+    }
+}
@@ -0,0 +1,241 @@
+#![warn(
+    clippy::all,
+    // clippy::restriction,
+    clippy::pedantic,
+    clippy::nursery,
+    clippy::cargo
+)]
+
+use super::genetics::{Inheritance, CROSSOVER_RATE};
+use super::solver::Observer;
+use super::sudoku::Board;
+
+/// One child bred for an audit, with exactly how its operators made it.
+///
+/// Audit children are bred from the same population, with the same
+/// operators and mutation rate, as the children of their generation, but
+/// are not added to the population.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct AuditSample<const N: usize> {
+    /// The generation the child was bred from.
+    pub generation: u64,
+    /// The mutation rate the child was bred with.
+    pub mutation_rate: f32,
+    pub parents: (Board<N>, Board<N>),
+    pub child: Board<N>,
+    /// The parent crossover chose for each cell: `Inheritance::ParentX` or
+    /// `Inheritance::ParentY`.
+    pub crossover: [[Inheritance; N]; N],
+    /// Whether each cell was reported mutated, whether or not its digit
+    /// changed.
+    pub mutated: [[bool; N]; N],
+}
+
+/// Totals over audited children, for chi-squared tests that the operators
+/// behave as configured.
+///
+/// The tests check that crossover takes cells from either parent at
+/// `CROSSOVER_RATE`, and that cells mutate at the mutation rate.
+/// Both tests count every cell of every child as one trial, which holds
+/// exactly for uniform crossover and random resetting, the defaults. Other
+/// operators decide cells together, so their statistics run high even when
+/// they are working as intended.
+#[derive(Debug, Copy, Clone, Default, PartialEq)]
+pub struct AuditStats {
+    children: u64,
+    from_x: u64,
+    from_y: u64,
+    cells: u64,
+    mutated: u64,
+    expected_mutated: f64,
+}
+
+impl AuditStats {
+    /// Adds an audited child to the totals.
+    #[inline]
+    pub fn record<const N: usize>(&mut self, sample: &AuditSample<N>) {
+        let crossover = sample.crossover.iter().flatten();
+        let from_x = crossover
+            .filter(|&&parent| parent == Inheritance::ParentX)
+            .count() as u64;
+        let cells = (N * N) as u64;
+
+        self.children += 1;
+        self.from_x += from_x;
+        self.from_y += cells - from_x;
+        self.cells += cells;
+        self.mutated += sample.mutated.iter().flatten().filter(|&&m| m).count() as u64;
+        #[allow(clippy::cast_precision_loss)]
+        let expected = f64::from(sample.mutation_rate) * cells as f64;
+        self.expected_mutated += expected;
+    }
+
+    /// Returns the number of children audited.
+    #[inline]
+    #[must_use]
+    pub const fn children(&self) -> u64 {
+        self.children
+    }
+
+    /// Returns the chi-squared statistic, with one degree of freedom, of
+    /// the cells taken from each parent against `CROSSOVER_RATE`, or `None`
+    /// if no children were audited.
+    #[inline]
+    #[must_use]
+    pub fn crossover_chi_squared(&self) -> Option<f64> {
+        #[allow(clippy::cast_precision_loss)]
+        let (from_x, from_y) = (self.from_x as f64, self.from_y as f64);
+        let total = from_x + from_y;
+
+        chi_squared(&[
+            (from_x, total * CROSSOVER_RATE),
+            (from_y, total * (1.0 - CROSSOVER_RATE)),
+        ])
+    }
+
+    /// Returns the chi-squared statistic, with one degree of freedom, of
+    /// the cells mutated against the mutation rates the children were bred
+    /// with, or `None` if no children were audited or every rate was 0.0 or
+    /// 1.0, leaving nothing random to test.
+    #[inline]
+    #[must_use]
+    pub fn mutation_chi_squared(&self) -> Option<f64> {
+        #[allow(clippy::cast_precision_loss)]
+        let (mutated, cells) = (self.mutated as f64, self.cells as f64);
+
+        chi_squared(&[
+            (mutated, self.expected_mutated),
+            (cells - mutated, cells - self.expected_mutated),
+        ])
+    }
+}
+
+impl<const N: usize> Observer<N> for AuditStats {
+    #[inline]
+    fn on_audit(&mut self, _: u64, samples: &[AuditSample<N>]) {
+        for sample in samples {
+            self.record(sample);
+        }
+    }
+
+    #[inline]
+    fn needs_population(&self) -> bool {
+        false
+    }
+}
+
+/// Returns Pearson's chi-squared statistic of `(observed, expected)`
+/// counts, or `None` if any count is expected never to happen.
+fn chi_squared(counts: &[(f64, f64)]) -> Option<f64> {
+    counts
+        .iter()
+        .map(|&(observed, expected)| {
+            (expected > 0.0).then(|| (observed - expected).powi(2) / expected)
+        })
+        .sum()
+}
+
+/// Returns the probability of a chi-squared statistic with one degree of
+/// freedom at least as large as `chi_squared`, if the counts it was
+/// computed from followed their expectations.
+///
+/// Small p-values, such as those below 0.001, suggest an operator does not
+/// behave as configured.
+#[inline]
+#[must_use]
+pub fn p_value(chi_squared: f64) -> f64 {
+    erfc((chi_squared.max(0.0) / 2.0).sqrt())
+}
+
+/// The complementary error function of `x` ≥ 0, to within 1.5e-7
+/// (Abramowitz and Stegun 7.1.26).
+fn erfc(x: f64) -> f64 {
+    const P: f64 = 0.327_591_1;
+    const A: [f64; 5] = [
+        0.254_829_592,
+        -0.284_496_736,
+        1.421_413_741,
+        -1.453_152_027,
+        1.061_405_429,
+    ];
+
+    let t = 1.0 / P.mul_add(x, 1.0);
+    let polynomial = A.iter().rev().fold(0.0_f64, |sum, &a| sum.mul_add(t, a)) * t;
+    polynomial * (-x * x).exp()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::genetics::GAParams;
+    use crate::solver::{Budget, GeneticSolver, Solver};
+    use crate::testing::SOLVED_9;
+
+    #[test]
+    fn test_p_value() {
+        assert!((p_value(0.0) - 1.0).abs() < 1e-6);
+        assert!((p_value(3.841) - 0.05).abs() < 1e-4);
+        assert!((p_value(10.828) - 0.001).abs() < 1e-5);
+    }
+
+    #[test]
+    fn test_audit_stats() {
+        let mut stats = AuditStats::default();
+        assert_eq!(None, stats.crossover_chi_squared());
+        assert_eq!(None, stats.mutation_chi_squared());
+
+        // 45 cells from each parent, and 9 of 81 cells mutated at a rate of
+        // 1/9, fit exactly.
+        let mut sample = AuditSample {
+            generation: 0,
+            mutation_rate: 1.0 / 9.0,
+            parents: (SOLVED_9, SOLVED_9),
+            child: SOLVED_9,
+            crossover: [[Inheritance::ParentX; 9]; 9],
+            mutated: [[false; 9]; 9],
+        };
+        for cell in sample.crossover.iter_mut().flatten().take(45) {
+            *cell = Inheritance::ParentY;
+        }
+        sample.mutated[0] = [true; 9];
+        stats.record(&sample);
+        stats.record(&AuditSample {
+            crossover: [[Inheritance::ParentX; 9]; 9],
+            ..sample
+        });
+        assert_eq!(2, stats.children());
+        assert!(stats.crossover_chi_squared().unwrap() > 10.0);
+        assert!(stats.mutation_chi_squared().unwrap() < 1e-3);
+
+        let mut unmutated = AuditStats::default();
+        unmutated.record(&AuditSample {
+            mutation_rate: 0.0,
+            ..sample
+        });
+        assert_eq!(None, unmutated.mutation_chi_squared());
+    }
+
+    #[test]
+    fn test_audit_run() {
+        let mut base = SOLVED_9;
+        for row in &mut base.0 {
+            row.0.fill(0);
+        }
+        let budget = Budget {
+            generations: Some(20),
+            duration: None,
+            target: None,
+        };
+        let mut stats = AuditStats::default();
+        let params = GAParams::new(20, 0.5, 0.1, None)
+            .unwrap()
+            .with_seed(3)
+            .with_audit(5);
+        GeneticSolver::new(params).solve(&base, budget, &mut stats);
+
+        // The operators behave as configured, so neither test rejects them.
+        assert_eq!(100, stats.children());
+        assert!(p_value(stats.crossover_chi_squared().unwrap()) > 0.001);
+        assert!(p_value(stats.mutation_chi_squared().unwrap()) > 0.001);
+    }
+}
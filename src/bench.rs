@@ -0,0 +1,229 @@
+#![warn(
+    clippy::all,
+    // clippy::restriction,
+    clippy::pedantic,
+    clippy::nursery,
+    clippy::cargo
+)]
+
+use super::solver::Observer;
+use super::sudoku::{Board, Fitness};
+use serde_json::{json, Map, Value};
+use std::time::Duration;
+
+/// One solved run of benchmark mode.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct BenchRun {
+    /// The generation the run was solved in.
+    pub generations: u64,
+    /// The number of candidates scored, across every generation.
+    pub evaluations: u64,
+    pub duration: Duration,
+}
+
+/// The results of benchmark mode so far, for tracking solver performance
+/// across versions.
+///
+/// [`BenchReport::to_bmf`] writes them in the Bencher Metric Format, which
+/// Bencher and similar tools read directly. The metric names are stable:
+///
+/// * `evaluations_per_second` - candidates scored per second, over all runs
+/// * `median_generations` - the median generations to a solution, bounded
+///   by the fewest and most
+/// * `latency` - the median time to a solution in nanoseconds, bounded by
+///   the fastest and slowest
+/// * `runs` - the number of runs
+#[derive(Debug, Clone, Default, Eq, PartialEq)]
+pub struct BenchReport {
+    runs: Vec<BenchRun>,
+}
+
+impl BenchReport {
+    #[inline]
+    #[must_use]
+    pub const fn new() -> Self {
+        Self { runs: Vec::new() }
+    }
+
+    /// Adds a run to the report.
+    #[inline]
+    pub fn record(&mut self, run: BenchRun) {
+        self.runs.push(run);
+    }
+
+    /// Returns the runs recorded, in the order they were recorded.
+    #[inline]
+    #[must_use]
+    pub fn runs(&self) -> &[BenchRun] {
+        &self.runs
+    }
+
+    /// Returns the median generations to a solution, or `None` if no runs
+    /// were recorded. The lower median is used for an even number of runs.
+    #[inline]
+    #[must_use]
+    pub fn median_generations(&self) -> Option<u64> {
+        lower_median(self.runs.iter().map(|run| run.generations))
+    }
+
+    /// Returns the candidates scored per second over all runs, or `None` if
+    /// no time was recorded.
+    #[inline]
+    #[must_use]
+    pub fn evaluations_per_second(&self) -> Option<f64> {
+        let seconds: f64 = self.runs.iter().map(|run| run.duration.as_secs_f64()).sum();
+        let evaluations: u64 = self.runs.iter().map(|run| run.evaluations).sum();
+
+        #[allow(clippy::cast_precision_loss)]
+        (seconds > 0.0).then(|| evaluations as f64 / seconds)
+    }
+
+    /// Returns the report as Bencher Metric Format JSON, with its metrics
+    /// under the benchmark `name`. Metrics without a value, such as those
+    /// of an empty report, are left out.
+    #[inline]
+    #[must_use]
+    pub fn to_bmf(&self, name: &str) -> Value {
+        let mut metrics = Map::new();
+        let generations = || self.runs.iter().map(|run| run.generations);
+        let nanos = || {
+            self.runs
+                .iter()
+                .map(|run| u64::try_from(run.duration.as_nanos()).unwrap_or(u64::MAX))
+        };
+
+        if let Some(value) = self.evaluations_per_second() {
+            metrics.insert(
+                "evaluations_per_second".to_owned(),
+                json!({ "value": value }),
+            );
+        }
+        if let Some(value) = self.median_generations() {
+            metrics.insert(
+                "median_generations".to_owned(),
+                json!({
+                    "value": value,
+                    "lower_value": generations().min(),
+                    "upper_value": generations().max(),
+                }),
+            );
+        }
+        if let Some(value) = lower_median(nanos()) {
+            metrics.insert(
+                "latency".to_owned(),
+                json!({
+                    "value": value,
+                    "lower_value": nanos().min(),
+                    "upper_value": nanos().max(),
+                }),
+            );
+        }
+        metrics.insert("runs".to_owned(), json!({ "value": self.runs.len() }));
+
+        json!({ name: metrics })
+    }
+}
+
+/// Returns the lower median of `values`, or `None` if there are none.
+fn lower_median(values: impl Iterator<Item = u64>) -> Option<u64> {
+    let mut values: Vec<u64> = values.collect();
+    let middle = values.len().checked_sub(1)? / 2;
+    let (_, &mut median, _) = values.select_nth_unstable(middle);
+    Some(median)
+}
+
+/// Counts the candidates a run scores.
+#[derive(Debug, Copy, Clone, Default, Eq, PartialEq)]
+pub struct EvaluationCounter {
+    evaluations: u64,
+}
+
+impl EvaluationCounter {
+    /// Returns the number of candidates scored since the last call, and
+    /// starts counting again from 0.
+    #[inline]
+    pub fn take(&mut self) -> u64 {
+        std::mem::take(&mut self.evaluations)
+    }
+}
+
+impl<const N: usize> Observer<N> for EvaluationCounter {
+    #[inline]
+    fn on_generation(&mut self, _: u64, _: &[Board<N>], scores: &[Fitness]) {
+        self.evaluations += scores.len() as u64;
+    }
+
+    #[inline]
+    fn needs_population(&self) -> bool {
+        false
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::genetics::GAParams;
+    use crate::solver::{Budget, GeneticSolver, Solver};
+    use crate::testing::SOLVED_9;
+
+    #[test]
+    fn test_bench_report() {
+        let mut report = BenchReport::new();
+        assert_eq!(None, report.median_generations());
+        assert_eq!(None, report.evaluations_per_second());
+        assert_eq!(
+            json!({ "empty": { "runs": { "value": 0 } } }),
+            report.to_bmf("empty")
+        );
+
+        for (generations, millis) in [(30, 300), (10, 100), (20, 600)] {
+            report.record(BenchRun {
+                generations,
+                evaluations: generations * 100,
+                duration: Duration::from_millis(millis),
+            });
+        }
+        assert_eq!(3, report.runs().len());
+        assert_eq!(Some(20), report.median_generations());
+        assert_eq!(Some(6000.0), report.evaluations_per_second());
+        assert_eq!(
+            json!({
+                "solve/default": {
+                    "evaluations_per_second": { "value": 6000.0 },
+                    "median_generations": { "value": 20, "lower_value": 10, "upper_value": 30 },
+                    "latency": {
+                        "value": 300_000_000,
+                        "lower_value": 100_000_000,
+                        "upper_value": 600_000_000,
+                    },
+                    "runs": { "value": 3 },
+                }
+            }),
+            report.to_bmf("solve/default")
+        );
+    }
+
+    #[test]
+    fn test_evaluation_counter() {
+        let mut base = SOLVED_9;
+        base.0[0].0[..2].fill(0);
+        let budget = Budget {
+            generations: Some(3),
+            duration: None,
+            target: None,
+        };
+        let mut counter = EvaluationCounter::default();
+        let outcome = GeneticSolver::new(GAParams::new(50, 0.5, 0.0, None).unwrap()).solve(
+            &base,
+            budget,
+            &mut counter,
+        );
+
+        // Duplicate candidates may be dropped, so a generation scores at
+        // most the population size.
+        let evaluations = counter.take();
+        assert!(evaluations > 0);
+        assert!(evaluations <= (outcome.generation + 1) * 50);
+        assert_eq!(0, counter.take());
+    }
+}
@@ -0,0 +1,93 @@
+#![warn(
+    clippy::all,
+    // clippy::restriction,
+    clippy::pedantic,
+    clippy::nursery,
+    clippy::cargo
+)]
+
+use super::solver::Observer;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+/// A flag that stops the runs observing it, settable from any thread, such
+/// as a signal handler or the stop button of a GUI.
+///
+/// Clones share the flag, so one can be handed to a run as its observer and
+/// another kept to cancel it with. A cancelled run stops before its next
+/// generation, or at the next checkpoint of a generation scored in chunks,
+/// and ends as `Termination::Cancelled` with the best board found so far.
+#[derive(Debug, Clone, Default)]
+pub struct CancellationToken(Arc<AtomicBool>);
+
+impl CancellationToken {
+    /// Returns a token that has not been cancelled.
+    #[inline]
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Cancels the runs observing this token, or any clone of it.
+    #[inline]
+    pub fn cancel(&self) {
+        self.0.store(true, Ordering::SeqCst);
+    }
+
+    /// Returns true if this token, or any clone of it, has been cancelled.
+    #[inline]
+    #[must_use]
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::SeqCst)
+    }
+}
+
+/// Shares a flag the embedder already has, cancelling once it is set.
+impl From<Arc<AtomicBool>> for CancellationToken {
+    #[inline]
+    fn from(flag: Arc<AtomicBool>) -> Self {
+        Self(flag)
+    }
+}
+
+impl<const N: usize> Observer<N> for CancellationToken {
+    #[inline]
+    fn needs_population(&self) -> bool {
+        false
+    }
+
+    #[inline]
+    fn is_cancelled(&self) -> bool {
+        Self::is_cancelled(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::genetics::GAParams;
+    use crate::solver::{Budget, GeneticSolver, Solver};
+    use crate::termination::Termination;
+    use crate::testing::SOLVED_9;
+
+    #[test]
+    fn test_cancellation_token() {
+        let flag = Arc::new(AtomicBool::new(false));
+        let token = CancellationToken::from(Arc::clone(&flag));
+        let clone = token.clone();
+        assert!(!clone.is_cancelled());
+        flag.store(true, Ordering::SeqCst);
+        assert!(token.is_cancelled() && clone.is_cancelled());
+
+        let mut base = SOLVED_9;
+        for row in &mut base.0 {
+            row.0.fill(0);
+        }
+        let params = GAParams::new(10, 0.5, 0.05, None).unwrap();
+        let mut token = CancellationToken::new();
+        token.cancel();
+        let outcome = GeneticSolver::new(params).solve(&base, Budget::unlimited(), &mut token);
+        assert_eq!(Termination::Cancelled, outcome.termination);
+        assert_eq!(0, outcome.generation);
+    }
+}
@@ -0,0 +1,314 @@
+#![warn(
+    clippy::all,
+    // clippy::restriction,
+    clippy::pedantic,
+    clippy::nursery,
+    clippy::cargo
+)]
+
+use super::arena::to_board;
+use super::errors::CertificateError;
+use super::fingerprint::RunConfig;
+use super::sudoku::{box_dims, Board};
+use hmac::{Hmac, Mac};
+use serde::Serialize;
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+use std::fmt::Write;
+
+/// Checks that `solution` solves `puzzle` under the classic rules.
+///
+/// This is deliberately independent of `Board::fitness` and the exact
+/// solver: it keeps every given of `puzzle`, and sorts each row, column and
+/// box to check it holds each digit from 1 to N exactly once.
+///
+/// # Panics
+///
+/// Panics if N is not a supported board size.
+#[inline]
+#[must_use]
+pub fn verify<const N: usize>(puzzle: &Board<N>, solution: &Board<N>) -> bool {
+    let keeps_givens = puzzle.0.iter().zip(&solution.0).all(|(given, solved)| {
+        given
+            .0
+            .iter()
+            .zip(&solved.0)
+            .all(|(&given, &digit)| given == 0 || given == digit)
+    });
+
+    let (rows, cols) = box_dims::<N>();
+    let digit = |(row, col): (usize, usize)| solution.0[row].0[col];
+    let is_complete = |unit: &mut dyn Iterator<Item = u8>| {
+        let mut digits: Vec<u8> = unit.collect();
+        digits.sort_unstable();
+        digits.into_iter().eq(1..=u8::try_from(N).unwrap())
+    };
+
+    keeps_givens
+        && (0..N).all(|i| {
+            let (top, left) = ((i / rows) * rows, (i % rows) * cols);
+
+            is_complete(&mut (0..N).map(|j| digit((i, j))))
+                && is_complete(&mut (0..N).map(|j| digit((j, i))))
+                && is_complete(&mut (0..N).map(|j| digit((top + j / cols, left + j % cols))))
+        })
+}
+
+/// A record of a solved run, for auditing.
+///
+/// The certificate holds the run's configuration, including the puzzle and
+/// parameters, with its fingerprint and seed, and the solution with the
+/// verdict of `verify` on it. It is written as JSON together with a SHA-256
+/// hash of its contents and, given a key, an HMAC-SHA256 signature of them.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Certificate {
+    run: RunConfig,
+    fingerprint: String,
+    seed: Option<u64>,
+    generation: u64,
+    solution: Vec<Vec<u8>>,
+    verified: bool,
+}
+
+impl Certificate {
+    /// Returns the certificate of a solved run.
+    ///
+    /// # Arguments
+    ///
+    /// * `run` - the configuration of the run
+    /// * `seed` - the seed of the run, if it was seeded
+    /// * `puzzle` - the puzzle solved, to verify the solution against
+    /// * `solution` - the solution found
+    /// * `generation` - the generation the solution was found in
+    #[inline]
+    #[must_use]
+    pub fn new<const N: usize>(
+        run: RunConfig,
+        seed: Option<u64>,
+        puzzle: &Board<N>,
+        solution: &Board<N>,
+        generation: u64,
+    ) -> Self {
+        Self {
+            fingerprint: format!("{:016x}", run.fingerprint()),
+            run,
+            seed,
+            generation,
+            solution: solution.0.iter().map(|row| row.0.to_vec()).collect(),
+            verified: verify(puzzle, solution),
+        }
+    }
+
+    /// Returns true if the solution passed `verify`.
+    #[inline]
+    #[must_use]
+    pub const fn verified(&self) -> bool {
+        self.verified
+    }
+
+    /// Returns the certificate as compact JSON, with a `"hash"` of
+    /// its contents and, if `key` is given, a `"signature"` of them.
+    ///
+    /// # Panics
+    ///
+    /// Does not panic in practice: every field serializes to JSON.
+    #[inline]
+    #[must_use]
+    pub fn to_json(&self, key: Option<&[u8]>) -> String {
+        let mut value = serde_json::to_value(self).expect("certificate is serializable");
+        let contents = value.to_string();
+
+        value["hash"] = hex(&Sha256::digest(&contents)).into();
+        if let Some(key) = key {
+            value["signature"] = hex(&mac(key, &contents).finalize().into_bytes()).into();
+        }
+
+        value.to_string()
+    }
+}
+
+/// Checks a certificate written by `Certificate::to_json` for boards of size
+/// N: that its contents match its hash and, if `key` is given, its
+/// signature, and that its solution solves its puzzle.
+///
+/// # Errors
+///
+/// Fails with the first check that does not pass.
+#[inline]
+pub fn check<const N: usize>(text: &str, key: Option<&[u8]>) -> Result<(), CertificateError> {
+    let mut value: Value =
+        serde_json::from_str(text).map_err(|_| CertificateError::Malformed("JSON"))?;
+    let fields = value
+        .as_object_mut()
+        .ok_or(CertificateError::Malformed("JSON"))?;
+    let hash = fields
+        .remove("hash")
+        .ok_or(CertificateError::Malformed("hash"))?;
+    let signature = fields.remove("signature");
+    let contents = value.to_string();
+
+    if hash.as_str() != Some(&hex(&Sha256::digest(&contents))) {
+        return Err(CertificateError::HashMismatch);
+    }
+    if let Some(key) = key {
+        let signature = signature.ok_or(CertificateError::Unsigned)?;
+        let signature = signature
+            .as_str()
+            .and_then(unhex)
+            .ok_or(CertificateError::Malformed("signature"))?;
+        mac(key, &contents)
+            .verify_slice(&signature)
+            .map_err(|_| CertificateError::BadSignature)?;
+    }
+
+    let puzzle =
+        grid::<N>(&value["run"]["puzzle"]["grid"]).ok_or(CertificateError::Malformed("puzzle"))?;
+    let solution = grid::<N>(&value["solution"]).ok_or(CertificateError::Malformed("solution"))?;
+    if !verify(&puzzle, &solution) {
+        return Err(CertificateError::InvalidSolution);
+    }
+
+    Ok(())
+}
+
+/// Returns the size of the boards a certificate is for, going by its
+/// solution, or `None` if it has none. The size is not checked; `check`
+/// does that.
+#[inline]
+#[must_use]
+pub fn size(text: &str) -> Option<usize> {
+    let value: Value = serde_json::from_str(text).ok()?;
+    value["solution"].as_array().map(Vec::len)
+}
+
+fn mac(key: &[u8], contents: &str) -> Hmac<Sha256> {
+    let mut mac = Hmac::<Sha256>::new_from_slice(key).expect("HMAC takes keys of any length");
+    mac.update(contents.as_bytes());
+    mac
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().fold(String::new(), |mut hex, byte| {
+        let _ = write!(hex, "{byte:02x}");
+        hex
+    })
+}
+
+fn unhex(text: &str) -> Option<Vec<u8>> {
+    if text.len() % 2 != 0 {
+        return None;
+    }
+
+    (0..text.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(text.get(i..i + 2)?, 16).ok())
+        .collect()
+}
+
+fn grid<const N: usize>(value: &Value) -> Option<Board<N>> {
+    let rows: Vec<Vec<u8>> = serde_json::from_value(value.clone()).ok()?;
+
+    (rows.len() == N && rows.iter().all(|row| row.len() == N)).then(|| to_board(&rows.concat()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::genetics::GAParams;
+    use crate::sudoku::Row;
+    use crate::testing::{SOLVED_4, SOLVED_6};
+
+    fn certify(solution: &Board<4>) -> Certificate {
+        let mut puzzle = SOLVED_4;
+        puzzle.0[0].0[..2].fill(0);
+        let params = GAParams::new(10, 0.5, 0.05, None).unwrap().with_seed(7);
+
+        Certificate::new(
+            RunConfig::classic(&puzzle, &params),
+            params.seed(),
+            &puzzle,
+            solution,
+            3,
+        )
+    }
+
+    #[test]
+    fn test_verify() {
+        let mut puzzle = SOLVED_4;
+        puzzle.0[1].0[1] = 0;
+        assert!(verify(&puzzle, &SOLVED_4));
+
+        let mut wrong = SOLVED_4;
+        wrong.0[0].0.swap(0, 1);
+        assert!(!verify(&puzzle, &wrong));
+
+        // Rows and columns complete, but not boxes.
+        let latin = Board([
+            Row([1, 2, 3, 4]),
+            Row([2, 3, 4, 1]),
+            Row([3, 4, 1, 2]),
+            Row([4, 1, 2, 3]),
+        ]);
+        assert!(!verify(&Board([Row::default(); 4]), &latin));
+
+        let mut moved = puzzle;
+        moved.0[0].0[0] = 2;
+        assert!(!verify(&moved, &SOLVED_4));
+
+        // Boxes of 2 rows by 3 columns, not 3 rows by 2 columns.
+        let empty = Board([Row::default(); 6]);
+        let transposed = Board([
+            Row([1, 4, 2, 5, 3, 6]),
+            Row([2, 5, 3, 6, 4, 1]),
+            Row([3, 6, 4, 1, 5, 2]),
+            Row([4, 1, 5, 2, 6, 3]),
+            Row([5, 2, 6, 3, 1, 4]),
+            Row([6, 3, 1, 4, 2, 5]),
+        ]);
+        assert!(verify(&empty, &SOLVED_6));
+        assert!(!verify(&empty, &transposed));
+    }
+
+    #[test]
+    fn test_check() {
+        let certificate = certify(&SOLVED_4);
+        assert!(certificate.verified());
+
+        let unsigned = certificate.to_json(None);
+        assert_eq!(Some(4), size(&unsigned));
+        assert_eq!(None, size("[]"));
+        assert_eq!(Ok(()), check::<4>(&unsigned, None));
+        assert_eq!(
+            Err(CertificateError::Unsigned),
+            check::<4>(&unsigned, Some(b"key"))
+        );
+
+        let signed = certificate.to_json(Some(b"key"));
+        assert_eq!(Ok(()), check::<4>(&signed, None));
+        assert_eq!(Ok(()), check::<4>(&signed, Some(b"key")));
+        assert_eq!(
+            Err(CertificateError::BadSignature),
+            check::<4>(&signed, Some(b"other key"))
+        );
+        assert_eq!(
+            Err(CertificateError::HashMismatch),
+            check::<4>(
+                &signed.replace("\"generation\":3", "\"generation\":4"),
+                None
+            )
+        );
+        assert_eq!(
+            Err(CertificateError::Malformed("puzzle")),
+            check::<9>(&signed, None)
+        );
+
+        let mut wrong = SOLVED_4;
+        wrong.0[0].0.swap(0, 1);
+        let wrong = certify(&wrong);
+        assert!(!wrong.verified());
+        assert_eq!(
+            Err(CertificateError::InvalidSolution),
+            check::<4>(&wrong.to_json(None), None)
+        );
+    }
+}
@@ -0,0 +1,318 @@
+#![warn(
+    clippy::all,
+    // clippy::restriction,
+    clippy::pedantic,
+    clippy::nursery,
+    clippy::cargo
+)]
+
+use super::stats::GenerationStats;
+use super::sudoku::Fitness;
+use serde_json::Value;
+use std::time::Duration;
+
+/// One run as printed by `--stats`: its configuration, sampled fitness curve
+/// and solution.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct StatsLog {
+    /// The run's `RunConfig`, as JSON.
+    pub config: Option<Value>,
+    /// The sampled generations, in order, with the time since the first
+    /// generation at which each was recorded, if the log has it.
+    pub samples: Vec<(GenerationStats, Option<Duration>)>,
+    /// The generation the solution was found in, and how long the run took.
+    pub solution: Option<(u64, Duration)>,
+}
+
+impl StatsLog {
+    /// Parses the first run of a `--stats` log. Lines that are not stats or
+    /// solution lines, such as the solved board, are skipped.
+    #[inline]
+    #[must_use]
+    pub fn parse(text: &str) -> Self {
+        let mut log = Self::default();
+
+        for line in text.lines() {
+            if let Some(config) = line.strip_prefix("Stats: Config: ") {
+                log.config = serde_json::from_str(config).ok();
+            } else if let Some(sample) = line.strip_prefix("Stats: Generation: ") {
+                log.samples.extend(parse_sample(sample));
+            } else if let Some(solution) = line.strip_prefix("Solution: Generation: ") {
+                let fields = fields(solution);
+                log.solution = fields
+                    .first()
+                    .and_then(|generation| generation.parse().ok())
+                    .zip(field(&fields, "Duration").and_then(parse_duration));
+                break;
+            }
+        }
+
+        log
+    }
+
+    /// Returns the best score of the latest sample at or before
+    /// `generation`, or `None` if the log has no sample that early or ended
+    /// before it.
+    #[inline]
+    #[must_use]
+    pub fn best_at(&self, generation: u64) -> Option<Fitness> {
+        latest(&self.samples, |(stats, _)| stats.generation <= generation)
+            .filter(|_| self.last_generation() >= Some(generation))
+    }
+
+    /// Returns the best score of the latest sample recorded at or before
+    /// `elapsed`, or `None` if the log has no timed sample that early or
+    /// ended before it.
+    #[inline]
+    #[must_use]
+    pub fn best_after(&self, elapsed: Duration) -> Option<Fitness> {
+        let last = self.samples.iter().filter_map(|(_, time)| *time).max();
+
+        latest(&self.samples, |(_, time)| {
+            time.is_some_and(|time| time <= elapsed)
+        })
+        .filter(|_| last >= Some(elapsed))
+    }
+
+    fn last_generation(&self) -> Option<u64> {
+        self.samples.last().map(|(stats, _)| stats.generation)
+    }
+}
+
+fn latest(
+    samples: &[(GenerationStats, Option<Duration>)],
+    before: impl Fn(&(GenerationStats, Option<Duration>)) -> bool,
+) -> Option<Fitness> {
+    samples
+        .iter()
+        .take_while(|sample| before(sample))
+        .last()
+        .map(|(stats, _)| stats.best)
+}
+
+/// Splits the rest of a `Key: value | Key: value` line into its values,
+/// keeping the keys.
+fn fields(line: &str) -> Vec<&str> {
+    line.split(" | ").collect()
+}
+
+fn field<'a>(fields: &[&'a str], key: &str) -> Option<&'a str> {
+    fields
+        .iter()
+        .find_map(|field| field.strip_prefix(key)?.strip_prefix(": "))
+}
+
+fn parse_sample(sample: &str) -> Option<(GenerationStats, Option<Duration>)> {
+    let fields = fields(sample);
+    let number = |key| field(&fields, key)?.parse().ok();
+
+    Some((
+        GenerationStats {
+            generation: fields.first()?.parse().ok()?,
+            best: number("Best")?,
+            median: number("Median")?,
+            worst: number("Worst")?,
+        },
+        field(&fields, "Elapsed").and_then(parse_duration),
+    ))
+}
+
+/// Parses a duration in the `Debug` format of `Duration`, such as `1.5s`
+/// or `812.3µs`.
+#[inline]
+#[must_use]
+pub fn parse_duration(text: &str) -> Option<Duration> {
+    let units = [("ns", 1e-9), ("µs", 1e-6), ("ms", 1e-3), ("s", 1.0)];
+    let (value, scale) = units
+        .iter()
+        .find_map(|&(unit, scale)| Some((text.strip_suffix(unit)?, scale)))?;
+
+    Duration::try_from_secs_f64(value.parse::<f64>().ok()? * scale).ok()
+}
+
+/// A configuration field whose value differs between two runs.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FieldDiff {
+    /// The path to the field, such as `solver.Genetic.mutation_rate`.
+    pub path: String,
+    /// The field's value in each run, or `None` if the run lacks it.
+    pub values: [Option<String>; 2],
+}
+
+/// Lists the fields that differ between two JSON configurations, in path
+/// order. Objects are compared field by field; anything else, including
+/// arrays such as the puzzle grid, is compared whole.
+#[inline]
+#[must_use]
+pub fn diff_configs(a: &Value, b: &Value) -> Vec<FieldDiff> {
+    let mut diffs = Vec::new();
+    diff_values("", Some(a), Some(b), &mut diffs);
+    diffs
+}
+
+fn diff_values(path: &str, a: Option<&Value>, b: Option<&Value>, diffs: &mut Vec<FieldDiff>) {
+    if let (Some(Value::Object(a)), Some(Value::Object(b))) = (a, b) {
+        let mut keys: Vec<&String> = a.keys().chain(b.keys()).collect();
+        keys.sort();
+        keys.dedup();
+
+        for key in keys {
+            let path = if path.is_empty() {
+                key.clone()
+            } else {
+                format!("{path}.{key}")
+            };
+            diff_values(&path, a.get(key), b.get(key), diffs);
+        }
+    } else if a != b {
+        diffs.push(FieldDiff {
+            path: path.to_owned(),
+            values: [a.map(Value::to_string), b.map(Value::to_string)],
+        });
+    }
+}
+
+/// Aligns the fitness curves of two logs by generation: for every sampled
+/// generation of either, the best score each log had reached by then.
+#[inline]
+#[must_use]
+pub fn align_by_generation(logs: [&StatsLog; 2]) -> Vec<(u64, [Option<Fitness>; 2])> {
+    let mut generations: Vec<u64> = logs
+        .iter()
+        .flat_map(|log| log.samples.iter().map(|(stats, _)| stats.generation))
+        .collect();
+    generations.sort_unstable();
+    generations.dedup();
+
+    generations
+        .into_iter()
+        .map(|generation| (generation, logs.map(|log| log.best_at(generation))))
+        .collect()
+}
+
+/// Aligns the fitness curves of two logs by wall-clock time: at `steps`
+/// evenly spaced times up to the end of the longer run, the best score each
+/// log had reached by then.
+#[inline]
+#[must_use]
+pub fn align_by_time(logs: [&StatsLog; 2], steps: u32) -> Vec<(Duration, [Option<Fitness>; 2])> {
+    let end = logs
+        .iter()
+        .flat_map(|log| log.samples.iter().filter_map(|(_, time)| *time))
+        .max()
+        .unwrap_or_default();
+
+    (1..=steps)
+        .map(|step| {
+            let elapsed = end * step / steps;
+            (elapsed, logs.map(|log| log.best_after(elapsed)))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const LOG: &str = "\
+Stats: Config: {\"version\":\"0.1.0\",\"solver\":{\"Genetic\":{\"population\":100,\"mutation_rate\":0.05}}}
+Stats: Generation: 0 | Best: 30 | Median: 40 | Worst: 50 | Elapsed: 0ns
+Stats: Generation: 10 | Best: 12 | Median: 20 | Worst: 31 | Elapsed: 2ms
+Stats: Generation: 20 | Best: 4 | Median: 9 | Worst: 17 | Elapsed: 4.5ms
+Stats: Generations: 21 | Last 100 Best p10: 4 | p50: 12 | p90: 30
+Solution: Generation: 20 | Duration: 4.6ms | Fingerprint: 0123456789abcdef
+1 2 3 4 5 6 7 8 9
+";
+
+    #[test]
+    fn test_parse() {
+        let log = StatsLog::parse(LOG);
+
+        assert_eq!(3, log.samples.len());
+        assert_eq!(
+            (
+                GenerationStats {
+                    generation: 10,
+                    best: Fitness::new(12),
+                    median: Fitness::new(20),
+                    worst: Fitness::new(31),
+                },
+                Some(Duration::from_millis(2))
+            ),
+            log.samples[1]
+        );
+        assert_eq!(Some((20, Duration::from_micros(4600))), log.solution);
+        assert_eq!(Some(Fitness::new(12)), log.best_at(15));
+        assert_eq!(None, log.best_at(21));
+        assert_eq!(
+            Some(Fitness::new(30)),
+            log.best_after(Duration::from_millis(1))
+        );
+        assert_eq!(None, log.best_after(Duration::from_millis(5)));
+        assert_eq!(
+            Some(100),
+            log.config.unwrap()["solver"]["Genetic"]["population"].as_u64()
+        );
+
+        assert_eq!(StatsLog::default(), StatsLog::parse("Board:\n1 2 3\n"));
+        assert_eq!(
+            Some(Duration::from_nanos(812_300)),
+            parse_duration("812.3µs")
+        );
+        assert_eq!(None, parse_duration("5 parsecs"));
+    }
+
+    #[test]
+    fn test_diff_configs() {
+        let a = serde_json::json!({"solver": {"rate": 0.05, "n": 100}, "grid": [[1]]});
+        let b = serde_json::json!({"solver": {"rate": 0.1, "n": 100, "seed": 1}, "grid": [[1]]});
+
+        assert_eq!(
+            vec![
+                FieldDiff {
+                    path: "solver.rate".to_owned(),
+                    values: [Some("0.05".to_owned()), Some("0.1".to_owned())],
+                },
+                FieldDiff {
+                    path: "solver.seed".to_owned(),
+                    values: [None, Some("1".to_owned())],
+                },
+            ],
+            diff_configs(&a, &b)
+        );
+        assert!(diff_configs(&a, &a).is_empty());
+    }
+
+    #[test]
+    fn test_align() {
+        let a = StatsLog::parse(LOG);
+        let b = StatsLog::parse(&LOG.replace("Generation: 20 |", "Generation: 15 |"));
+
+        assert_eq!(
+            vec![
+                (0, [Some(Fitness::new(30)), Some(Fitness::new(30))]),
+                (10, [Some(Fitness::new(12)), Some(Fitness::new(12))]),
+                (15, [Some(Fitness::new(12)), Some(Fitness::new(4))]),
+                (20, [Some(Fitness::new(4)), None]),
+            ],
+            align_by_generation([&a, &b])
+        );
+        assert_eq!(
+            vec![
+                (
+                    Duration::from_micros(1500),
+                    [Some(Fitness::new(30)), Some(Fitness::new(30))]
+                ),
+                (
+                    Duration::from_millis(3),
+                    [Some(Fitness::new(12)), Some(Fitness::new(12))]
+                ),
+                (
+                    Duration::from_micros(4500),
+                    [Some(Fitness::new(4)), Some(Fitness::new(4))]
+                ),
+            ],
+            align_by_time([&a, &b], 3)
+        );
+    }
+}
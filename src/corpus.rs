@@ -0,0 +1,207 @@
+#![warn(
+    clippy::all,
+    // clippy::restriction,
+    clippy::pedantic,
+    clippy::nursery,
+    clippy::cargo
+)]
+
+use super::fingerprint::RunConfig;
+use super::formats::{self, Format};
+use super::genetics::GAParams;
+use super::grading::{grade, Probe};
+use super::results::{HardSeed, ResultsDb, RunRecord};
+use super::sudoku::Board;
+use std::cmp::Ordering;
+use std::fmt::{Display, Formatter};
+
+/// How a hard seed fared when it was replayed.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum Change {
+    /// It solved the puzzle, or got closer to solving it.
+    Improved,
+    /// It reached the same best fitness score as before.
+    Unchanged,
+    /// It ended further from solving the puzzle than before.
+    Worse,
+}
+
+impl Display for Change {
+    #[inline]
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Improved => write!(f, "improved"),
+            Self::Unchanged => write!(f, "unchanged"),
+            Self::Worse => write!(f, "worse"),
+        }
+    }
+}
+
+/// A hard seed of the corpus, replayed with the current algorithm.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Replay {
+    pub hard_seed: HardSeed,
+    /// The probe run with the seed, within its original budget.
+    pub probe: Probe,
+    /// Whether the run had the same fingerprint as the recorded one. If not,
+    /// the parameters changed, not only the algorithm.
+    pub same_config: bool,
+}
+
+impl Replay {
+    /// Compares the replayed probe with the recorded one.
+    #[inline]
+    #[must_use]
+    pub fn change(&self) -> Change {
+        if self.probe.solved.is_some() {
+            return Change::Improved;
+        }
+
+        match self.probe.best.cmp(&self.hard_seed.best) {
+            Ordering::Less => Change::Improved,
+            Ordering::Equal => Change::Unchanged,
+            Ordering::Greater => Change::Worse,
+        }
+    }
+}
+
+/// Grades `base` like `grade`, and adds every seed that fails to solve it
+/// within `generations` generations to the seed corpus of `db`. Returns
+/// those seeds, whether or not the corpus already held them.
+///
+/// # Arguments
+///
+/// * `db` - The results database holding the corpus
+/// * `base` - The puzzle to probe
+/// * `params` - GA parameters for every probe; any seed is replaced
+/// * `probes` - The number of probes to run
+/// * `generations` - The generation budget of each probe
+/// * `seed` - The seed of the first probe
+///
+/// # Errors
+///
+/// Fails if a seed cannot be written to the database.
+#[inline]
+pub fn record<const N: usize>(
+    db: &ResultsDb,
+    base: &Board<N>,
+    params: &GAParams,
+    probes: usize,
+    generations: u64,
+    seed: u64,
+) -> rusqlite::Result<Vec<HardSeed>> {
+    let hardness = grade(base, params, probes, generations, seed);
+    let hard_seeds: Vec<HardSeed> = hardness
+        .probes()
+        .iter()
+        .filter(|probe| probe.solved.is_none())
+        .map(|probe| HardSeed {
+            recorded_at: RunRecord::now(),
+            puzzle_hash: base.puzzle_hash(),
+            puzzle: base.to_string(),
+            seed: probe.seed,
+            generations,
+            best: probe.best,
+            fingerprint: fingerprint(base, params, probe.seed),
+        })
+        .collect();
+
+    for hard_seed in &hard_seeds {
+        db.add_hard_seed(hard_seed)?;
+    }
+
+    Ok(hard_seeds)
+}
+
+/// Replays the seed corpus of `db` with `params`, each seed within the
+/// budget it was recorded with. Seeds of puzzles that are not of size N are
+/// skipped.
+///
+/// # Arguments
+///
+/// * `db` - The results database holding the corpus
+/// * `params` - GA parameters for every replay; any seed is replaced
+/// * `puzzle_hash` - only replay seeds of this puzzle, if given
+///
+/// # Errors
+///
+/// Fails if the corpus cannot be read from the database.
+#[inline]
+pub fn replay<const N: usize>(
+    db: &ResultsDb,
+    params: &GAParams,
+    puzzle_hash: Option<u64>,
+) -> rusqlite::Result<Vec<Replay>> {
+    Ok(db
+        .hard_seeds(puzzle_hash)?
+        .into_iter()
+        .filter_map(|hard_seed| {
+            let base = formats::parse_as::<N>(Format::Grid, &hard_seed.puzzle).ok()?[0];
+            let probe = grade(&base, params, 1, hard_seed.generations, hard_seed.seed).probes()[0];
+
+            Some(Replay {
+                same_config: fingerprint(&base, params, hard_seed.seed) == hard_seed.fingerprint,
+                hard_seed,
+                probe,
+            })
+        })
+        .collect())
+}
+
+/// Returns the fingerprint of a probe of `base` seeded with `seed`.
+fn fingerprint<const N: usize>(base: &Board<N>, params: &GAParams, seed: u64) -> u64 {
+    RunConfig::classic(base, &params.clone().with_seed(seed)).fingerprint()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sudoku::Fitness;
+    use crate::testing::SOLVED_9;
+
+    #[test]
+    fn test_corpus() {
+        let db = ResultsDb::open(":memory:").unwrap();
+        let params = GAParams::new(20, 0.5, 0.05, None).unwrap();
+        let mut base = SOLVED_9;
+        for row in &mut base.0 {
+            row.0[..6].fill(0);
+        }
+
+        // No probe solves the puzzle in a single generation.
+        let hard_seeds = record(&db, &base, &params, 3, 1, 10).unwrap();
+        assert_eq!(
+            vec![10, 11, 12],
+            hard_seeds.iter().map(|s| s.seed).collect::<Vec<_>>()
+        );
+        assert_eq!(hard_seeds, db.hard_seeds(Some(base.puzzle_hash())).unwrap());
+        assert!(db.hard_seeds(Some(0)).unwrap().is_empty());
+
+        // Recording the same seeds again leaves the corpus as it was.
+        record(&db, &base, &params, 3, 1, 10).unwrap();
+        assert_eq!(3, db.hard_seeds(None).unwrap().len());
+
+        // Replays are deterministic, so nothing changes without a change.
+        let replays = replay::<9>(&db, &params, None).unwrap();
+        assert_eq!(3, replays.len());
+        for replay in &replays {
+            assert!(replay.same_config);
+            assert_eq!(replay.hard_seed.best, replay.probe.best);
+            assert_eq!(Change::Unchanged, replay.change());
+        }
+
+        // Replays with other parameters are flagged as such.
+        let elitist = params.with_elitism(1);
+        assert!(replay::<9>(&db, &elitist, None)
+            .unwrap()
+            .iter()
+            .all(|replay| !replay.same_config));
+        assert!(replay::<4>(&db, &elitist, None).unwrap().is_empty());
+
+        let mut replay = replays[0].clone();
+        replay.probe.best = replay.hard_seed.best + Fitness::new(1);
+        assert_eq!(Change::Worse, replay.change());
+        replay.probe.solved = Some(3);
+        assert_eq!(Change::Improved, replay.change());
+    }
+}
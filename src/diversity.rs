@@ -0,0 +1,231 @@
+#![warn(
+    clippy::all,
+    // clippy::restriction,
+    clippy::pedantic,
+    clippy::nursery,
+    clippy::cargo
+)]
+
+use super::solver::Observer;
+use super::sudoku::{Board, Fitness};
+use std::collections::{BTreeMap, VecDeque};
+
+/// How varied one generation is.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DiversitySnapshot {
+    pub generation: u64,
+    /// The mean number of cells in which a candidate differs from the
+    /// fittest candidate.
+    pub diversity: f64,
+    /// The population's `entropy`.
+    pub entropy: f64,
+    /// The number of candidates with each score, by increasing score.
+    /// Scores no candidate has are left out.
+    pub histogram: Vec<(Fitness, usize)>,
+}
+
+impl DiversitySnapshot {
+    /// Summarizes one scored generation, or returns `None` if it is empty.
+    #[inline]
+    #[must_use]
+    pub fn of<const N: usize>(
+        generation: u64,
+        population: &[Board<N>],
+        scores: &[Fitness],
+    ) -> Option<Self> {
+        let (fittest, _) = scores.iter().enumerate().min_by_key(|(_, score)| **score)?;
+        let fittest = population.get(fittest)?;
+
+        let mut counts = BTreeMap::new();
+        for &score in scores {
+            *counts.entry(score).or_insert(0) += 1;
+        }
+
+        Some(Self {
+            generation,
+            diversity: mean_distance(population, fittest),
+            entropy: entropy(population),
+            histogram: counts.into_iter().collect(),
+        })
+    }
+}
+
+/// Returns the mean number of cells in which a candidate of `population`
+/// differs from `board`, or 0 if the population is empty.
+#[inline]
+#[must_use]
+pub fn mean_distance<const N: usize>(population: &[Board<N>], board: &Board<N>) -> f64 {
+    if population.is_empty() {
+        return 0.0;
+    }
+
+    let differing: usize = population.iter().map(|other| other.distance(board)).sum();
+    #[allow(clippy::cast_precision_loss)]
+    let mean = differing as f64 / population.len() as f64;
+    mean
+}
+
+/// Returns the mean entropy of each cell's digit across `population`.
+///
+/// It is scaled to between 0, when every candidate has the same digit in
+/// every cell, and 1, when every digit is equally common in every cell.
+/// Givens count as cells where all candidates agree.
+#[inline]
+#[must_use]
+pub fn entropy<const N: usize>(population: &[Board<N>]) -> f64 {
+    if population.is_empty() {
+        return 0.0;
+    }
+
+    let mut total = 0.0;
+    for row in 0..N {
+        for col in 0..N {
+            let mut counts = [0_usize; 256];
+            for board in population {
+                counts[usize::from(board.0[row].0[col])] += 1;
+            }
+
+            for count in counts.into_iter().filter(|&count| count > 0) {
+                #[allow(clippy::cast_precision_loss)]
+                let p = count as f64 / population.len() as f64;
+                total -= p * p.ln();
+            }
+        }
+    }
+
+    #[allow(clippy::cast_precision_loss)]
+    let max = (N * N) as f64 * (N as f64).ln();
+    total / max
+}
+
+/// The diversity and score distribution of the last `capacity` generations.
+///
+/// Memory stays bounded however long the run takes, since the oldest
+/// snapshot is dropped once the history is full. Solvers with several
+/// populations record one snapshot per population.
+#[derive(Debug, Clone)]
+pub struct DiversityHistory {
+    capacity: usize,
+    snapshots: VecDeque<DiversitySnapshot>,
+}
+
+impl DiversityHistory {
+    /// Returns an empty history.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `capacity` is 0.
+    #[inline]
+    #[must_use]
+    pub fn new(capacity: usize) -> Self {
+        assert!(capacity > 0);
+
+        Self {
+            capacity,
+            snapshots: VecDeque::with_capacity(capacity),
+        }
+    }
+
+    /// Records a snapshot, dropping the oldest if the history is full.
+    #[inline]
+    pub fn record(&mut self, snapshot: DiversitySnapshot) {
+        if self.snapshots.len() == self.capacity {
+            self.snapshots.pop_front();
+        }
+        self.snapshots.push_back(snapshot);
+    }
+
+    /// Returns the snapshots held, oldest first.
+    #[inline]
+    pub fn snapshots(&self) -> impl Iterator<Item = &DiversitySnapshot> {
+        self.snapshots.iter()
+    }
+
+    /// Returns the number of snapshots held.
+    #[inline]
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.snapshots.len()
+    }
+
+    #[inline]
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.snapshots.is_empty()
+    }
+
+    #[inline]
+    pub fn clear(&mut self) {
+        self.snapshots.clear();
+    }
+}
+
+impl<const N: usize> Observer<N> for DiversityHistory {
+    #[inline]
+    fn on_generation(&mut self, generation: u64, population: &[Board<N>], scores: &[Fitness]) {
+        if let Some(snapshot) = DiversitySnapshot::of(generation, population, scores) {
+            self.record(snapshot);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::SOLVED_4;
+
+    #[test]
+    fn test_snapshot() {
+        let mut other = SOLVED_4;
+        other.0[0].0.swap(0, 1);
+
+        let (zero, two) = (Fitness::ZERO, Fitness::new(2));
+        let snapshot =
+            DiversitySnapshot::of(7, &[other, SOLVED_4, SOLVED_4], &[two, zero, zero]).unwrap();
+        assert_eq!(7, snapshot.generation);
+        assert!((snapshot.diversity - 2.0 / 3.0).abs() < 1e-9);
+        assert_eq!(vec![(zero, 2), (two, 1)], snapshot.histogram);
+        assert!(snapshot.entropy > 0.0 && snapshot.entropy < 1.0);
+
+        assert_eq!(None, DiversitySnapshot::of::<4>(0, &[], &[]));
+    }
+
+    #[test]
+    fn test_entropy() {
+        assert!(entropy(&[SOLVED_4; 5]).abs() < 1e-9);
+        assert!(entropy::<4>(&[]).abs() < 1e-9);
+
+        // Every digit equally common in every cell.
+        let shifted: Vec<Board<4>> = (0..4)
+            .map(|shift| {
+                let mut board = SOLVED_4;
+                for row in &mut board.0 {
+                    row.0.rotate_left(shift);
+                }
+                board
+            })
+            .collect();
+        assert!((entropy(&shifted) - 1.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_history() {
+        let mut history = DiversityHistory::new(3);
+
+        for generation in 0..10 {
+            history.on_generation(generation, &[SOLVED_4], &[Fitness::ZERO]);
+        }
+
+        assert_eq!(3, history.len());
+        assert_eq!(
+            vec![7, 8, 9],
+            history
+                .snapshots()
+                .map(|snapshot| snapshot.generation)
+                .collect::<Vec<_>>()
+        );
+
+        history.clear();
+        assert!(history.is_empty());
+    }
+}
@@ -0,0 +1,304 @@
+#![warn(
+    clippy::all,
+    // clippy::restriction,
+    clippy::pedantic,
+    clippy::nursery,
+    clippy::cargo
+)]
+
+use super::sudoku::{box_dims, Board, CellChange, GivensMask};
+
+/// The smallest terminal, in columns and lines, a duel is drawn in at all.
+pub const MIN_SIZE: (usize, usize) = (20, 6);
+
+/// The columns the GA's pane title may take up, which may be wider than
+/// its board.
+const GA_TITLE_WIDTH: usize = 36;
+
+/// A movement of the cursor of a `Duel`.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum Move {
+    Up,
+    Down,
+    Left,
+    Right,
+}
+
+/// How much of a duel a terminal has room to draw.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum Layout {
+    /// Both boards side by side, with gaps between their boxes.
+    Full,
+    /// The human's board alone, without gaps between its boxes and
+    /// scrolled to the cursor, with the GA's progress cut down to its
+    /// score.
+    Compact(Viewport),
+    /// Not even a compact board fits, only a warning.
+    TooSmall,
+}
+
+/// The part of a board a compact duel shows: `rows` rows from `top`, and
+/// `cols` columns from `left`.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct Viewport {
+    pub top: usize,
+    pub left: usize,
+    pub rows: usize,
+    pub cols: usize,
+}
+
+/// Returns the terminal size, in columns and lines, that a full duel of
+/// size N needs: both boards side by side with their titles, and the help
+/// line below them.
+///
+/// # Panics
+///
+/// Panics if N has no box dimensions (see [`box_dims`]).
+#[inline]
+#[must_use]
+pub const fn full_size<const N: usize>() -> (usize, usize) {
+    let (rows, cols) = box_dims::<N>();
+    let board_width = 2 * (N - 1) + 2 * ((N - 1) / cols) + 1;
+    let pane_width = if board_width > GA_TITLE_WIDTH {
+        board_width
+    } else {
+        GA_TITLE_WIDTH
+    };
+
+    (2 * N + 10 + pane_width, 3 + N + N / rows)
+}
+
+/// The first of `shown` consecutive lines out of `total` to show so that
+/// line `at` is as close to the middle as the edges allow.
+const fn scroll(at: usize, shown: usize, total: usize) -> usize {
+    if shown >= total {
+        return 0;
+    }
+
+    let top = at.saturating_sub(shown / 2);
+    if top < total - shown {
+        top
+    } else {
+        total - shown
+    }
+}
+
+/// The human's side of a race against the genetic algorithm: a puzzle
+/// filled in one cell at a time, at a cursor that wraps around the board.
+///
+/// Givens are locked, and every entry is checked against its row, column
+/// and box as soon as it is made.
+#[derive(Debug, Clone)]
+pub struct Duel<const N: usize> {
+    board: Board<N>,
+    givens: GivensMask<N>,
+    cursor: (usize, usize),
+}
+
+impl<const N: usize> Duel<N> {
+    /// Starts filling in `puzzle`, with the cursor at its top left cell.
+    #[inline]
+    #[must_use]
+    pub fn new(puzzle: &Board<N>) -> Self {
+        Self {
+            board: *puzzle,
+            givens: GivensMask::of(puzzle),
+            cursor: (0, 0),
+        }
+    }
+
+    /// Returns the puzzle with the human's entries filled in.
+    #[inline]
+    #[must_use]
+    pub const fn board(&self) -> &Board<N> {
+        &self.board
+    }
+
+    /// Returns the cells holding givens.
+    #[inline]
+    #[must_use]
+    pub const fn givens(&self) -> &GivensMask<N> {
+        &self.givens
+    }
+
+    /// Returns the `(row, column)` of the cell under the cursor.
+    #[inline]
+    #[must_use]
+    pub const fn cursor(&self) -> (usize, usize) {
+        self.cursor
+    }
+
+    /// Moves the cursor one cell, wrapping around at the edges.
+    #[inline]
+    pub const fn move_cursor(&mut self, to: Move) {
+        let (row, col) = self.cursor;
+
+        self.cursor = match to {
+            Move::Up => ((row + N - 1) % N, col),
+            Move::Down => ((row + 1) % N, col),
+            Move::Left => (row, (col + N - 1) % N),
+            Move::Right => (row, (col + 1) % N),
+        };
+    }
+
+    /// Writes `digit` into the cell under the cursor, or empties it if
+    /// `digit` is 0. Entries that clash with others are kept, and reported
+    /// by `conflicts`.
+    ///
+    /// Returns `false`, leaving the board as it was, if the cell holds a
+    /// given or `digit` is greater than N.
+    #[inline]
+    pub fn enter(&mut self, digit: u8) -> bool {
+        let (row, col) = self.cursor;
+        if self.givens.contains(row, col) || usize::from(digit) > N {
+            return false;
+        }
+
+        self.board
+            .apply_around(&self.givens, &[CellChange { row, col, digit }])
+            .is_ok()
+    }
+
+    /// Returns the cells holding a digit that is repeated within a row,
+    /// column or box.
+    #[inline]
+    #[must_use]
+    pub fn conflicts(&self) -> GivensMask<N> {
+        let mut conflicts = GivensMask::default();
+
+        for violation in self.board.violations() {
+            if violation.digit != 0 {
+                for (row, col) in violation.cells {
+                    conflicts.insert(row, col);
+                }
+            }
+        }
+
+        conflicts
+    }
+
+    /// Returns the number of cells still empty.
+    #[inline]
+    #[must_use]
+    pub fn remaining(&self) -> usize {
+        N * N - GivensMask::of(&self.board).len()
+    }
+
+    /// Returns how to draw the duel in a terminal `width` columns wide and
+    /// `height` lines high. A compact board takes two columns a cell and a
+    /// line a row, with a status line and a blank line above it and a help
+    /// line below.
+    #[inline]
+    #[must_use]
+    pub const fn layout(&self, width: usize, height: usize) -> Layout {
+        let (full_width, full_height) = full_size::<N>();
+        if width >= full_width && height >= full_height {
+            return Layout::Full;
+        }
+        if width < MIN_SIZE.0 || height < MIN_SIZE.1 {
+            return Layout::TooSmall;
+        }
+
+        let (rows, cols) = (height - 3, width / 2);
+        let (rows, cols) = (
+            if rows < N { rows } else { N },
+            if cols < N { cols } else { N },
+        );
+        Layout::Compact(Viewport {
+            top: scroll(self.cursor.0, rows, N),
+            left: scroll(self.cursor.1, cols, N),
+            rows,
+            cols,
+        })
+    }
+
+    /// Returns whether every cell is filled in without any conflicts.
+    #[inline]
+    #[must_use]
+    pub fn is_solved(&self) -> bool {
+        self.remaining() == 0 && self.board.fitness().is_solution()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::SOLVED_4;
+
+    #[test]
+    fn test_duel() {
+        let mut puzzle = SOLVED_4;
+        puzzle.0[0].0[1] = 0;
+        puzzle.0[3].0[3] = 0;
+        let mut duel = Duel::new(&puzzle);
+        assert_eq!(2, duel.remaining());
+
+        // The cursor starts on a given, which stays locked.
+        assert!(!duel.enter(4));
+        duel.move_cursor(Move::Left);
+        assert_eq!((0, 3), duel.cursor());
+        duel.move_cursor(Move::Up);
+        assert_eq!((3, 3), duel.cursor());
+
+        // A digit already in the row is accepted, but flagged with the
+        // cells it clashes with.
+        assert!(duel.enter(2));
+        let conflicts = duel.conflicts();
+        assert!(conflicts.contains(3, 3));
+        assert!(conflicts.contains(3, 0));
+        assert!(!duel.is_solved());
+        assert!(!duel.enter(5));
+
+        assert!(duel.enter(3));
+        assert!(duel.conflicts().is_empty());
+        duel.move_cursor(Move::Down);
+        duel.move_cursor(Move::Right);
+        duel.move_cursor(Move::Right);
+        assert_eq!((0, 1), duel.cursor());
+        assert!(duel.enter(2));
+        assert!(duel.is_solved());
+        assert_eq!(&SOLVED_4, duel.board());
+
+        assert!(duel.enter(0));
+        assert_eq!(1, duel.remaining());
+    }
+
+    #[test]
+    fn test_layout() {
+        let mut duel = Duel::<25>::new(&Board([crate::sudoku::Row::default(); 25]));
+        assert_eq!((117, 33), full_size::<25>());
+        assert_eq!((64, 15), full_size::<9>());
+        assert_eq!(Layout::Full, duel.layout(117, 33));
+        assert_eq!(Layout::TooSmall, duel.layout(19, 33));
+        assert_eq!(Layout::TooSmall, duel.layout(117, 5));
+
+        // The cursor starts at the top left, so the view does too.
+        let view = |top, left, rows, cols| {
+            Layout::Compact(Viewport {
+                top,
+                left,
+                rows,
+                cols,
+            })
+        };
+        assert_eq!(view(0, 0, 21, 25), duel.layout(80, 24));
+
+        // The view follows the cursor, keeping it in the middle until it
+        // reaches the far edge.
+        for _ in 0..12 {
+            duel.move_cursor(Move::Down);
+            duel.move_cursor(Move::Right);
+        }
+        assert_eq!(view(2, 2, 21, 20), duel.layout(40, 24));
+        duel.move_cursor(Move::Up);
+        duel.move_cursor(Move::Up);
+        assert_eq!(view(0, 2, 21, 20), duel.layout(40, 24));
+        for _ in 0..12 {
+            duel.move_cursor(Move::Right);
+        }
+        assert_eq!(view(0, 5, 21, 20), duel.layout(40, 24));
+
+        // A small board fits as soon as its whole pane does.
+        assert_eq!(Layout::Full, Duel::new(&SOLVED_4).layout(80, 24));
+    }
+}
@@ -6,20 +6,544 @@
     clippy::cargo
 )]
 
-use super::sudoku::Board;
+use super::sudoku::{Board, Fitness, Unit};
+use super::termination::Termination;
 use std::error::Error;
 use std::fmt::{Debug, Display, Formatter};
 
 #[derive(Debug)]
-pub struct NoSolutionFound<const N: usize> {
-    pub next_generation: Vec<Board<N>>,
-}
+pub struct NoSolutionFound;
 
-impl<const N: usize> Display for NoSolutionFound<N> {
+impl Display for NoSolutionFound {
     #[inline]
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         Debug::fmt(self, f)
     }
 }
 
-impl<const N: usize> Error for NoSolutionFound<N> {}
+impl Error for NoSolutionFound {}
+
+/// Why a given could not be added to a board.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum GivenConflict {
+    /// The cell lies outside the board or the digit is not in `1..=N`.
+    OutOfRange { row: usize, col: usize, digit: u8 },
+    /// The cell already holds a different digit.
+    Occupied {
+        row: usize,
+        col: usize,
+        existing: u8,
+    },
+    /// The digit already appears elsewhere in one of the cell's units.
+    Duplicate {
+        row: usize,
+        col: usize,
+        digit: u8,
+        unit: Unit,
+    },
+}
+
+impl Display for GivenConflict {
+    #[inline]
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match *self {
+            Self::OutOfRange { row, col, digit } => {
+                write!(f, "cannot place {digit} at ({row}, {col}): out of range")
+            }
+            Self::Occupied { row, col, existing } => {
+                write!(f, "cell ({row}, {col}) already holds {existing}")
+            }
+            Self::Duplicate {
+                row,
+                col,
+                digit,
+                unit,
+            } => write!(
+                f,
+                "cannot place {digit} at ({row}, {col}): already in its {unit:?}"
+            ),
+        }
+    }
+}
+
+impl Error for GivenConflict {}
+
+/// Why a change could not be made to a board.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum SudokuError {
+    /// The cell lies outside the board or the digit is greater than N.
+    OutOfRange { row: usize, col: usize, digit: u8 },
+    /// The cell holds a given, which no change may touch.
+    Given { row: usize, col: usize },
+}
+
+impl Display for SudokuError {
+    #[inline]
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match *self {
+            Self::OutOfRange { row, col, digit } => {
+                write!(f, "cannot set ({row}, {col}) to {digit}: out of range")
+            }
+            Self::Given { row, col } => write!(f, "cell ({row}, {col}) holds a given"),
+        }
+    }
+}
+
+impl Error for SudokuError {}
+
+/// Why a puzzle document does not describe a puzzle of the expected size.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum SchemaError {
+    /// A grid field does not have N rows of N values; `bad_row` is the
+    /// first row of the wrong length.
+    GridSize {
+        field: &'static str,
+        rows: usize,
+        bad_row: Option<usize>,
+    },
+    /// A grid digit is greater than N.
+    DigitOutOfRange { row: usize, col: usize, digit: u8 },
+    /// A region or cage lists a cell outside the grid.
+    CellOutOfRange {
+        field: &'static str,
+        row: usize,
+        col: usize,
+    },
+    /// A cell is listed twice in a region, or in more than one cage.
+    DuplicateCell {
+        field: &'static str,
+        row: usize,
+        col: usize,
+    },
+    /// A region or cage has more than N cells, or a region map region does
+    /// not have exactly N.
+    RegionSize {
+        field: &'static str,
+        index: usize,
+        size: usize,
+    },
+    /// A region map label is not in `0..N`.
+    BadLabel {
+        row: usize,
+        col: usize,
+        label: usize,
+    },
+    /// No set of distinct digits fits in the cage and adds up to its sum.
+    CageSum { index: usize, sum: u32 },
+}
+
+impl Display for SchemaError {
+    #[inline]
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match *self {
+            Self::GridSize {
+                field,
+                rows,
+                bad_row: Some(row),
+            } => write!(f, "{field}: row {row} of {rows} has the wrong length"),
+            Self::GridSize {
+                field,
+                rows,
+                bad_row: None,
+            } => write!(f, "{field}: wrong number of rows ({rows})"),
+            Self::DigitOutOfRange { row, col, digit } => {
+                write!(f, "grid: digit {digit} at ({row}, {col}) is out of range")
+            }
+            Self::CellOutOfRange { field, row, col } => {
+                write!(f, "{field}: cell ({row}, {col}) is outside the grid")
+            }
+            Self::DuplicateCell { field, row, col } => {
+                write!(f, "{field}: cell ({row}, {col}) is listed more than once")
+            }
+            Self::RegionSize { field, index, size } => {
+                write!(f, "{field}: region {index} has {size} cells")
+            }
+            Self::BadLabel { row, col, label } => {
+                write!(
+                    f,
+                    "region_map: label {label} at ({row}, {col}) is out of range"
+                )
+            }
+            Self::CageSum { index, sum } => {
+                write!(f, "cages: cage {index} cannot add up to {sum}")
+            }
+        }
+    }
+}
+
+impl Error for SchemaError {}
+
+/// Why `solve_str` returned no solution.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum SolveError {
+    /// The puzzle is not a board of a supported size in a supported format.
+    Malformed,
+    /// Some givens of the puzzle conflict with each other.
+    ConflictingGivens,
+    /// The solve options cannot be used.
+    InvalidOptions(ParamError),
+    /// The solver stopped before finding a solution.
+    Unsolved {
+        termination: Termination,
+        /// The fitness score of the best board found, if any.
+        best: Option<Fitness>,
+    },
+}
+
+impl SolveError {
+    /// Returns the termination reason the error corresponds to.
+    #[inline]
+    #[must_use]
+    pub const fn termination(self) -> Termination {
+        match self {
+            Self::Malformed | Self::ConflictingGivens | Self::InvalidOptions(_) => {
+                Termination::InvalidPuzzle
+            }
+            Self::Unsolved { termination, .. } => termination,
+        }
+    }
+}
+
+impl Display for SolveError {
+    #[inline]
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match *self {
+            Self::Malformed => write!(f, "malformed sudoku board"),
+            Self::ConflictingGivens => write!(f, "conflicting givens"),
+            Self::InvalidOptions(err) => write!(f, "invalid options: {err}"),
+            Self::Unsolved {
+                termination,
+                best: Some(best),
+            } => write!(f, "no solution found ({termination}, best fitness {best})"),
+            Self::Unsolved {
+                termination,
+                best: None,
+            } => write!(f, "no solution found ({termination})"),
+        }
+    }
+}
+
+impl Error for SolveError {}
+
+/// Why a solution certificate does not check out.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum CertificateError {
+    /// The certificate is not JSON, or lacks a field or has one of the
+    /// wrong type.
+    Malformed(&'static str),
+    /// The content hash does not match the certificate's contents.
+    HashMismatch,
+    /// A key was given but the certificate is not signed.
+    Unsigned,
+    /// The signature does not match the key and the contents.
+    BadSignature,
+    /// The solution does not solve the puzzle.
+    InvalidSolution,
+}
+
+impl Display for CertificateError {
+    #[inline]
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match *self {
+            Self::Malformed(field) => write!(f, "malformed certificate: bad {field}"),
+            Self::HashMismatch => write!(f, "content hash does not match"),
+            Self::Unsigned => write!(f, "certificate is not signed"),
+            Self::BadSignature => write!(f, "signature does not match"),
+            Self::InvalidSolution => write!(f, "solution does not solve the puzzle"),
+        }
+    }
+}
+
+impl Error for CertificateError {}
+
+/// Why a recorded run could not be read.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum RecordingError {
+    /// The recording is not JSON, or lacks a field or has one of the wrong
+    /// type or size.
+    Malformed(&'static str),
+}
+
+impl Display for RecordingError {
+    #[inline]
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match *self {
+            Self::Malformed(field) => write!(f, "malformed recording: bad {field}"),
+        }
+    }
+}
+
+impl Error for RecordingError {}
+
+/// Why a plugin could not be loaded.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum PluginError {
+    /// The file is not a shared library that can be loaded.
+    Load(String),
+    /// The library does not export the plugin entry point.
+    MissingEntryPoint,
+    /// The plugin was built against another version of the plugin
+    /// interface.
+    AbiMismatch { expected: u32, found: u32 },
+}
+
+impl Display for PluginError {
+    #[inline]
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Load(reason) => write!(f, "cannot load plugin: {reason}"),
+            Self::MissingEntryPoint => write!(f, "not a plugin: no entry point"),
+            Self::AbiMismatch { expected, found } => write!(
+                f,
+                "plugin built for interface version {found}, expected {expected}"
+            ),
+        }
+    }
+}
+
+impl Error for PluginError {}
+
+/// Why a fitness script failed.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum ScriptError {
+    /// The script does not compile.
+    Compile(String),
+    /// The script failed while scoring a board.
+    Run(String),
+    /// The script scored a board with a negative penalty.
+    Negative(i64),
+}
+
+impl Display for ScriptError {
+    #[inline]
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Compile(reason) => write!(f, "script does not compile: {reason}"),
+            Self::Run(reason) => write!(f, "script failed: {reason}"),
+            Self::Negative(penalty) => write!(f, "script returned a negative penalty {penalty}"),
+        }
+    }
+}
+
+impl Error for ScriptError {}
+
+/// Why candidate overrides could not be read, or do not fit a puzzle.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum OverrideError {
+    /// A line is not of the form `ROW,COL,DIGITS`, or has a row, column or
+    /// digit of 0, or a digit too large for any board.
+    Syntax { line: usize },
+    /// A cell is listed on more than one line.
+    DuplicateCell { row: usize, col: usize },
+    /// The cell lies outside the board or a digit is greater than N.
+    OutOfRange { row: usize, col: usize, digit: u8 },
+    /// The cell holds a given that is not among its allowed digits.
+    Given { row: usize, col: usize, given: u8 },
+}
+
+impl Display for OverrideError {
+    #[inline]
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match *self {
+            Self::Syntax { line } => {
+                write!(f, "line {line}: expected ROW,COL,DIGITS such as 3,4,1/5/7")
+            }
+            Self::DuplicateCell { row, col } => {
+                write!(f, "cell ({row}, {col}) is listed more than once")
+            }
+            Self::OutOfRange { row, col, digit } => {
+                write!(f, "cannot allow {digit} at ({row}, {col}): out of range")
+            }
+            Self::Given { row, col, given } => {
+                write!(
+                    f,
+                    "cell ({row}, {col}) holds the given {given}, which is not allowed"
+                )
+            }
+        }
+    }
+}
+
+impl Error for OverrideError {}
+
+/// A run that stopped before finding a solution, with the best board it
+/// found.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct StoppedEarly<const N: usize> {
+    /// Why the run stopped.
+    pub termination: Termination,
+    /// The best board found and its fitness score, if any generation was
+    /// evaluated.
+    pub best: Option<(Board<N>, Fitness)>,
+    /// The last generation evaluated.
+    pub generation: u64,
+}
+
+impl<const N: usize> Display for StoppedEarly<N> {
+    #[inline]
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self.best {
+            Some((_, best)) => write!(
+                f,
+                "no solution found by generation {} ({}, best fitness {best})",
+                self.generation, self.termination
+            ),
+            None => write!(f, "no solution found ({})", self.termination),
+        }
+    }
+}
+
+impl<const N: usize> Error for StoppedEarly<N> {}
+
+/// Why GA parameters could not be built.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum ParamError {
+    /// The population is larger than the largest supported population.
+    PopulationTooLarge { population: usize, max: usize },
+    /// The selection rate is not between 0.0 and 1.0.
+    SelectionRate(f32),
+    /// The mutation rate is not between 0.0 and 1.0.
+    MutationRate(f32),
+    /// The population and selection rate keep fewer than two survivors per
+    /// generation, which leaves no pair of parents to breed children from.
+    NoParentPairs {
+        population: usize,
+        selection_rate: f32,
+        survivors: usize,
+    },
+    /// The population schedule shrinks the population below its minimum,
+    /// which is larger than the population.
+    ScheduleMinimum { population: usize, min: usize },
+    /// The chunk size is 0.
+    ChunkSize,
+    /// A tournament has no candidates.
+    TournamentSize,
+    /// Pairs of parents breed no children.
+    ChildrenPerPair,
+    /// The cataclysm rate is not between 0.0 and 1.0.
+    CataclysmRate(f32),
+    /// The hypermutation factor is negative or not finite.
+    HypermutationFactor(f32),
+    /// An island model has no islands.
+    NoIslands,
+    /// A portfolio has no members.
+    EmptyPortfolio,
+    /// A portfolio has fewer threads than members, each of which needs one.
+    PortfolioThreads { members: usize, threads: usize },
+}
+
+impl Display for ParamError {
+    #[inline]
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match *self {
+            Self::PopulationTooLarge { population, max } => {
+                write!(
+                    f,
+                    "population {population} is larger than the maximum of {max}"
+                )
+            }
+            Self::SelectionRate(rate) => {
+                write!(f, "selection rate {rate} is not between 0.0 and 1.0")
+            }
+            Self::MutationRate(rate) => {
+                write!(f, "mutation rate {rate} is not between 0.0 and 1.0")
+            }
+            Self::NoParentPairs {
+                population,
+                selection_rate,
+                survivors,
+            } => write!(
+                f,
+                "a population of {population} with a selection rate of {selection_rate} keeps \
+                 {survivors} survivor(s), but breeding needs at least 2"
+            ),
+            Self::ScheduleMinimum { population, min } => write!(
+                f,
+                "population schedule minimum {min} is larger than the population of {population}"
+            ),
+            Self::ChunkSize => write!(f, "chunk size is 0"),
+            Self::TournamentSize => write!(f, "tournament size is 0"),
+            Self::ChildrenPerPair => write!(f, "pairs of parents must breed at least one child"),
+            Self::CataclysmRate(rate) => {
+                write!(f, "cataclysm rate {rate} is not between 0.0 and 1.0")
+            }
+            Self::HypermutationFactor(factor) => {
+                write!(f, "hypermutation factor {factor} is negative or not finite")
+            }
+            Self::NoIslands => write!(f, "there must be at least one island"),
+            Self::EmptyPortfolio => write!(f, "a portfolio needs at least one member"),
+            Self::PortfolioThreads { members, threads } => write!(
+                f,
+                "a portfolio of {members} members needs at least {members} threads, not {threads}"
+            ),
+        }
+    }
+}
+
+impl Error for ParamError {}
+
+impl From<ParamError> for SolveError {
+    #[inline]
+    fn from(err: ParamError) -> Self {
+        Self::InvalidOptions(err)
+    }
+}
+
+impl From<ParamError> for PresetError {
+    #[inline]
+    fn from(err: ParamError) -> Self {
+        Self::Params(err)
+    }
+}
+
+/// Why a parameter preset could not be imported.
+#[derive(Debug, Clone, PartialEq)]
+pub enum PresetError {
+    /// The preset is not JSON, or lacks a field, has one of the wrong type
+    /// or one it does not know.
+    Malformed(String),
+    /// The preset was written in a version of the format this build cannot
+    /// read.
+    UnsupportedVersion(u32),
+    /// The parameters cannot be run.
+    Invalid(&'static str),
+    /// The parameters cannot be built.
+    Params(ParamError),
+}
+
+impl Display for PresetError {
+    #[inline]
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Malformed(reason) => write!(f, "malformed preset: {reason}"),
+            Self::UnsupportedVersion(version) => {
+                write!(f, "unsupported preset format version {version}")
+            }
+            Self::Invalid(reason) => write!(f, "invalid preset: {reason}"),
+            Self::Params(err) => write!(f, "invalid preset: {err}"),
+        }
+    }
+}
+
+impl Error for PresetError {}
+
+/// A cell that propagation left with no digit it could hold, which makes
+/// the puzzle unsolvable.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct Contradiction {
+    pub row: usize,
+    pub col: usize,
+}
+
+impl Display for Contradiction {
+    #[inline]
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "cell ({}, {}) has no candidates left after propagation",
+            self.row, self.col
+        )
+    }
+}
+
+impl Error for Contradiction {}
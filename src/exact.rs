@@ -0,0 +1,337 @@
+#![warn(
+    clippy::all,
+    // clippy::restriction,
+    clippy::pedantic,
+    clippy::nursery,
+    clippy::cargo
+)]
+
+use super::sudoku::unit::{UnitScorer, Units};
+use super::sudoku::{box_index, Board, Unit};
+use std::fmt::{Display, Formatter};
+
+/// How a hinted digit was deduced.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum Technique {
+    /// The cell has only one candidate digit left.
+    NakedSingle,
+    /// The digit has only one possible cell left in the given unit.
+    HiddenSingle(Unit),
+    /// The digit was taken from the puzzle's solution.
+    Revealed,
+}
+
+impl Display for Technique {
+    #[inline]
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::NakedSingle => write!(f, "naked single"),
+            Self::HiddenSingle(Unit::Row) => write!(f, "hidden single in its row"),
+            Self::HiddenSingle(Unit::Column) => write!(f, "hidden single in its column"),
+            Self::HiddenSingle(Unit::Box) => write!(f, "hidden single in its box"),
+            Self::HiddenSingle(Unit::Region) => write!(f, "hidden single in its region"),
+            Self::Revealed => write!(f, "revealed from the solution"),
+        }
+    }
+}
+
+/// A digit that can be placed in an empty cell.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct Hint {
+    pub row: usize,
+    pub col: usize,
+    pub digit: u8,
+    pub technique: Technique,
+}
+
+/// Returns the candidate digits of every cell as bitmasks, with bit `d` set
+/// if digit `d` can be placed in the cell. Filled in cells have no
+/// candidates.
+///
+/// # Panics
+///
+/// Panics if N is not a supported board size.
+#[inline]
+#[must_use]
+pub fn candidates<const N: usize>(board: &Board<N>) -> [[u64; N]; N] {
+    let mut missing = [[0_u64; N]; 3];
+
+    for (unit, index, cells) in Units::<N>::new() {
+        let mut scorer = UnitScorer::<N>::new();
+        for (row, col) in cells {
+            scorer.check(board.0[row].0[col]);
+        }
+
+        missing[kind(unit)][index] = scorer
+            .missing_digits()
+            .fold(0, |mask, digit| mask | 1 << digit);
+    }
+
+    let mut candidates = [[0; N]; N];
+
+    for (row, cells) in candidates.iter_mut().enumerate() {
+        for (col, cell) in cells.iter_mut().enumerate() {
+            if board.0[row].0[col] == 0 {
+                *cell = missing[kind(Unit::Row)][row]
+                    & missing[kind(Unit::Column)][col]
+                    & missing[kind(Unit::Box)][box_index::<N>(row, col)];
+            }
+        }
+    }
+
+    candidates
+}
+
+/// Finds a cell a human could fill next, preferring naked singles over
+/// hidden singles.
+///
+/// Returns `None` if the board has no singles left.
+///
+/// # Panics
+///
+/// Panics if N is not a supported board size.
+#[inline]
+#[must_use]
+pub fn find_single<const N: usize>(board: &Board<N>) -> Option<Hint> {
+    let candidates = candidates(board);
+
+    for (row, cells) in candidates.iter().enumerate() {
+        for (col, cell) in cells.iter().enumerate() {
+            if cell.is_power_of_two() {
+                return Some(Hint {
+                    row,
+                    col,
+                    digit: digit_of(*cell),
+                    technique: Technique::NakedSingle,
+                });
+            }
+        }
+    }
+
+    for (unit, _, cells) in Units::<N>::new() {
+        for digit in 1..=N {
+            let mut places = cells
+                .iter()
+                .filter(|&&(row, col)| candidates[row][col] & 1 << digit != 0);
+
+            if let (Some(&(row, col)), None) = (places.next(), places.next()) {
+                return Some(Hint {
+                    row,
+                    col,
+                    digit: u8::try_from(digit).expect("digit size exceeds 255"),
+                    technique: Technique::HiddenSingle(unit),
+                });
+            }
+        }
+    }
+
+    None
+}
+
+/// Finds up to `limit` solutions of `board` by backtracking search, always
+/// branching on the empty cell with the fewest candidates.
+///
+/// # Arguments
+///
+/// * `board` - The puzzle to solve, with 0 for empty cells
+/// * `limit` - The maximum number of solutions to return; 2 is enough to
+///   tell whether the solution is unique
+///
+/// # Panics
+///
+/// Panics if N is not a supported board size.
+#[inline]
+#[must_use]
+pub fn solve<const N: usize>(board: &Board<N>, limit: usize) -> Vec<Board<N>> {
+    let mut solutions = Vec::new();
+
+    if board
+        .violations()
+        .iter()
+        .all(|violation| violation.digit == 0)
+    {
+        search(*board, limit, &mut solutions);
+    }
+
+    solutions
+}
+
+/// Reveals the first empty cell of `board` from its unique solution.
+///
+/// Returns `Err` with the number of solutions found (0 or 2) if the puzzle
+/// does not have exactly one solution, and `Ok(None)` if the board is
+/// already full.
+///
+/// # Errors
+///
+/// Fails if the puzzle has no solution or more than one.
+///
+/// # Panics
+///
+/// Panics if N is not a supported board size.
+#[inline]
+pub fn reveal<const N: usize>(board: &Board<N>) -> Result<Option<Hint>, usize> {
+    let solutions = solve(board, 2);
+    let [solution] = solutions.as_slice() else {
+        return Err(solutions.len());
+    };
+
+    for (row, cells) in board.0.iter().enumerate() {
+        for (col, &digit) in cells.0.iter().enumerate() {
+            if digit == 0 {
+                return Ok(Some(Hint {
+                    row,
+                    col,
+                    digit: solution.0[row].0[col],
+                    technique: Technique::Revealed,
+                }));
+            }
+        }
+    }
+
+    Ok(None)
+}
+
+fn search<const N: usize>(mut board: Board<N>, limit: usize, solutions: &mut Vec<Board<N>>) {
+    if solutions.len() >= limit {
+        return;
+    }
+
+    let candidates = candidates(&board);
+    let mut best: Option<(usize, usize, u64)> = None;
+
+    for (row, cells) in candidates.iter().enumerate() {
+        for (col, &cell) in cells.iter().enumerate() {
+            if board.0[row].0[col] != 0 {
+                continue;
+            }
+            if cell == 0 {
+                return;
+            }
+            if best.is_none_or(|(_, _, mask)| cell.count_ones() < mask.count_ones()) {
+                best = Some((row, col, cell));
+            }
+        }
+    }
+
+    let Some((row, col, mut mask)) = best else {
+        solutions.push(board);
+        return;
+    };
+
+    while mask != 0 {
+        board.0[row].0[col] = digit_of(mask);
+        mask &= mask - 1;
+        search(board, limit, solutions);
+    }
+}
+
+const fn kind(unit: Unit) -> usize {
+    match unit {
+        Unit::Row => 0,
+        Unit::Column => 1,
+        Unit::Box => 2,
+        Unit::Region => panic!("classic units have no regions"),
+    }
+}
+
+fn digit_of(mask: u64) -> u8 {
+    u8::try_from(mask.trailing_zeros()).expect("digit size exceeds 255")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sudoku::{GivensMask, Row};
+    use crate::testing::{SOLVED_4, SOLVED_6, SOLVED_9};
+
+    #[test]
+    fn test_candidates() {
+        let mut cells = GivensMask::default();
+        cells.insert(0, 0);
+        cells.insert(0, 1);
+        cells.insert(1, 0);
+        let candidates = candidates(&SOLVED_4.mask(&cells));
+
+        assert_eq!(1 << 1, candidates[0][0]);
+        assert_eq!(1 << 2, candidates[0][1]);
+        assert_eq!(1 << 3, candidates[1][0]);
+        assert_eq!(0, candidates[0][2]);
+    }
+
+    #[test]
+    fn test_find_single() {
+        let mut cells = GivensMask::default();
+        cells.insert(4, 4);
+        let hint = find_single(&SOLVED_9.mask(&cells));
+
+        assert_eq!(
+            Some(Hint {
+                row: 4,
+                col: 4,
+                digit: 9,
+                technique: Technique::NakedSingle
+            }),
+            hint
+        );
+
+        // Every cell has several candidates, but the second column and the
+        // second box leave only one place for 1 in the first row.
+        let board = Board([
+            Row([0, 0, 0, 0]),
+            Row([0, 0, 0, 1]),
+            Row([0, 1, 0, 0]),
+            Row([0, 0, 0, 0]),
+        ]);
+        assert_eq!(
+            Some(Hint {
+                row: 0,
+                col: 0,
+                digit: 1,
+                technique: Technique::HiddenSingle(Unit::Row)
+            }),
+            find_single(&board)
+        );
+        assert_eq!(None, find_single(&SOLVED_9));
+    }
+
+    #[test]
+    fn test_solve() {
+        let mut cells = GivensMask::default();
+        for i in 0..9 {
+            cells.insert(i, (i * 4) % 9);
+            cells.insert(i, (i * 7 + 2) % 9);
+        }
+        let puzzle = SOLVED_9.mask(&cells);
+
+        assert_eq!(vec![SOLVED_9], solve(&puzzle, 2));
+        assert_eq!(
+            Ok(Some(Hint {
+                row: 0,
+                col: 0,
+                digit: 1,
+                technique: Technique::Revealed
+            })),
+            reveal(&puzzle)
+        );
+
+        let mut cells = GivensMask::default();
+        for i in 0..6 {
+            cells.insert(i, i);
+            cells.insert(i, 5 - i);
+        }
+        assert_eq!(vec![SOLVED_6], solve(&SOLVED_6.mask(&cells), 2));
+
+        let empty = Board([Row::default(); 4]);
+        assert_eq!(2, solve(&empty, 2).len());
+        assert_eq!(Err(2), reveal(&empty));
+
+        let conflicting = Board([
+            Row([1, 1, 0, 0]),
+            Row([0, 0, 0, 0]),
+            Row([0, 0, 0, 0]),
+            Row([0, 0, 0, 0]),
+        ]);
+        assert_eq!(Err(0), reveal(&conflicting));
+    }
+}
@@ -0,0 +1,526 @@
+#![warn(
+    clippy::all,
+    // clippy::restriction,
+    clippy::pedantic,
+    clippy::nursery,
+    clippy::cargo
+)]
+
+use super::formats::document::PuzzleDocument;
+use super::genetics::{
+    Crossover, Curriculum, Dedup, Encoding, GAParams, Hypermutation, Mutation, MutationSchedule,
+    Pairing, PopulationSchedule, RngStrategy, Selection,
+};
+use super::restarts::{AutoRestart, Cataclysm, StallRestart};
+use super::solver::backtrack::Hybrid;
+use super::sudoku::{fnv1a, Board, UnitWeights};
+use serde::Serialize;
+
+/// Whether the random choices of a run are reproducible.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Serialize)]
+pub enum SeedPolicy {
+    /// Every RNG is seeded from the OS.
+    Random,
+    /// Every RNG is derived from a fixed seed.
+    Seeded,
+}
+
+/// The parts of `GAParams` that can change the course of a run.
+///
+/// Whether the population is compact is left out, since it only changes
+/// how the population is stored, and so are whether the run is profiled,
+/// audited or scored in chunks.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+struct ParamsConfig {
+    population: usize,
+    selection_rate: f32,
+    mutation_rate: f32,
+    #[serde(skip_serializing_if = "is_constant")]
+    mutation_schedule: MutationSchedule,
+    restart: Option<u64>,
+    rng_strategy: RngStrategy,
+    population_schedule: PopulationSchedule,
+    #[serde(skip_serializing_if = "is_zero")]
+    column_repair: usize,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    dedup: Option<Dedup>,
+    #[serde(skip_serializing_if = "is_truncation")]
+    selection: Selection,
+    #[serde(skip_serializing_if = "is_uniform")]
+    crossover: Crossover,
+    #[serde(skip_serializing_if = "is_random_resetting")]
+    mutation_operator: Mutation,
+    #[serde(skip_serializing_if = "is_cells")]
+    encoding: Encoding,
+    #[serde(skip_serializing_if = "is_full")]
+    weights: UnitWeights,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    curriculum: Option<Curriculum>,
+    #[serde(skip_serializing_if = "is_zero")]
+    elitism: usize,
+    #[serde(skip_serializing_if = "is_false")]
+    plus_selection: bool,
+    #[serde(skip_serializing_if = "is_adjacent")]
+    pairing: Pairing,
+    #[serde(skip_serializing_if = "is_zero")]
+    incest_prevention: usize,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    children_per_pair: Option<usize>,
+    #[serde(skip_serializing_if = "is_zero")]
+    local_search: usize,
+    #[serde(skip_serializing_if = "is_zero")]
+    tabu: usize,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    auto_restart: Option<AutoRestart>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    stall_restart: Option<StallRestart>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    cataclysm: Option<Cataclysm>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    hypermutation: Option<Hypermutation>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    hybrid: Option<Hybrid>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    candidate_overrides: Option<String>,
+    #[cfg(feature = "scripting")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    script: Option<String>,
+}
+
+#[allow(clippy::trivially_copy_pass_by_ref)]
+const fn is_zero(value: &usize) -> bool {
+    *value == 0
+}
+
+#[allow(clippy::trivially_copy_pass_by_ref)]
+const fn is_false(value: &bool) -> bool {
+    !*value
+}
+
+#[allow(clippy::trivially_copy_pass_by_ref)]
+fn is_constant(schedule: &MutationSchedule) -> bool {
+    *schedule == MutationSchedule::Constant
+}
+
+#[allow(clippy::trivially_copy_pass_by_ref)]
+fn is_truncation(selection: &Selection) -> bool {
+    *selection == Selection::Truncation
+}
+
+#[allow(clippy::trivially_copy_pass_by_ref)]
+fn is_uniform(crossover: &Crossover) -> bool {
+    *crossover == Crossover::Uniform
+}
+
+#[allow(clippy::trivially_copy_pass_by_ref)]
+fn is_random_resetting(mutation: &Mutation) -> bool {
+    *mutation == Mutation::RandomResetting
+}
+
+#[allow(clippy::trivially_copy_pass_by_ref)]
+fn is_full(weights: &UnitWeights) -> bool {
+    *weights == UnitWeights::FULL
+}
+
+#[allow(clippy::trivially_copy_pass_by_ref)]
+fn is_adjacent(pairing: &Pairing) -> bool {
+    *pairing == Pairing::Adjacent
+}
+
+#[allow(clippy::trivially_copy_pass_by_ref)]
+fn is_cells(encoding: &Encoding) -> bool {
+    *encoding == Encoding::Cells
+}
+
+impl From<&GAParams> for ParamsConfig {
+    #[inline]
+    fn from(params: &GAParams) -> Self {
+        Self {
+            population: params.population(),
+            selection_rate: params.selection_rate(),
+            mutation_rate: params.mutation_rate(),
+            mutation_schedule: params.mutation_schedule(),
+            restart: params.restart(),
+            rng_strategy: params.rng_strategy(),
+            population_schedule: params.population_schedule(),
+            column_repair: params.column_repair(),
+            dedup: params.dedup(),
+            selection: params.selection(),
+            crossover: params.crossover(),
+            mutation_operator: params.mutation_operator(),
+            encoding: params.encoding(),
+            weights: params.weights(),
+            curriculum: params.curriculum(),
+            elitism: params.elitism(),
+            plus_selection: params.plus_selection(),
+            pairing: params.pairing(),
+            incest_prevention: params.incest_prevention(),
+            children_per_pair: params.children_per_pair(),
+            local_search: params.local_search(),
+            tabu: params.tabu(),
+            auto_restart: params.auto_restart(),
+            stall_restart: params.stall_restart(),
+            cataclysm: params.cataclysm(),
+            hypermutation: params.hypermutation(),
+            hybrid: params.hybrid(),
+            candidate_overrides: params
+                .candidate_overrides()
+                .map(|overrides| format!("{:016x}", overrides.hash())),
+            #[cfg(feature = "scripting")]
+            script: params
+                .script()
+                .map(|script| format!("{:016x}", script.hash())),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+enum SolverConfig {
+    Genetic(ParamsConfig),
+    Tempering {
+        replicas: Vec<ParamsConfig>,
+        swap_interval: u64,
+    },
+    Islands {
+        params: ParamsConfig,
+        islands: usize,
+        migration_interval: u64,
+    },
+    Portfolio {
+        members: Vec<ParamsConfig>,
+        threads: usize,
+        reallocation_interval: u64,
+    },
+}
+
+/// Everything that determines what results a run can be compared with.
+///
+/// Two runs with the same configuration solved the same puzzle under the
+/// same constraints, with the same solver, parameters and seed policy, and
+/// the same version of this crate. The seed itself is left out, so seeded
+/// runs that differ only in their seed are still comparable.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct RunConfig {
+    version: &'static str,
+    puzzle: PuzzleDocument,
+    solver: SolverConfig,
+    seed_policy: SeedPolicy,
+}
+
+impl RunConfig {
+    /// Returns the configuration of a `GeneticSolver` run.
+    ///
+    /// # Arguments
+    ///
+    /// * `puzzle` - the puzzle and the constraints it is solved under
+    /// * `params` - the GA parameters of the run
+    #[inline]
+    #[must_use]
+    pub fn genetic(puzzle: PuzzleDocument, params: &GAParams) -> Self {
+        Self {
+            version: env!("CARGO_PKG_VERSION"),
+            puzzle,
+            solver: SolverConfig::Genetic(params.into()),
+            seed_policy: seed_policy(std::slice::from_ref(params)),
+        }
+    }
+
+    /// Returns the configuration of a `TemperingSolver` run.
+    ///
+    /// # Arguments
+    ///
+    /// * `puzzle` - the puzzle and the constraints it is solved under
+    /// * `replicas` - the GA parameters of each replica, coldest first
+    /// * `swap_interval` - the number of generations between swaps
+    #[inline]
+    #[must_use]
+    pub fn tempering(puzzle: PuzzleDocument, replicas: &[GAParams], swap_interval: u64) -> Self {
+        Self {
+            version: env!("CARGO_PKG_VERSION"),
+            puzzle,
+            solver: SolverConfig::Tempering {
+                replicas: replicas.iter().map(Into::into).collect(),
+                swap_interval,
+            },
+            seed_policy: seed_policy(replicas),
+        }
+    }
+
+    /// Returns the configuration of an `IslandSolver` run.
+    ///
+    /// # Arguments
+    ///
+    /// * `puzzle` - the puzzle and the constraints it is solved under
+    /// * `params` - the GA parameters of the whole population
+    /// * `islands` - the number of islands
+    /// * `migration_interval` - the number of generations between migrations
+    #[inline]
+    #[must_use]
+    pub fn islands(
+        puzzle: PuzzleDocument,
+        params: &GAParams,
+        islands: usize,
+        migration_interval: u64,
+    ) -> Self {
+        Self {
+            version: env!("CARGO_PKG_VERSION"),
+            puzzle,
+            solver: SolverConfig::Islands {
+                params: params.into(),
+                islands,
+                migration_interval,
+            },
+            seed_policy: seed_policy(std::slice::from_ref(params)),
+        }
+    }
+
+    /// Returns the configuration of a `PortfolioSolver` run.
+    ///
+    /// # Arguments
+    ///
+    /// * `puzzle` - the puzzle and the constraints it is solved under
+    /// * `members` - the GA parameters of each member
+    /// * `threads` - the number of worker threads the members share
+    /// * `reallocation_interval` - the number of generations between
+    ///   reallocations of the threads
+    #[inline]
+    #[must_use]
+    pub fn portfolio(
+        puzzle: PuzzleDocument,
+        members: &[GAParams],
+        threads: usize,
+        reallocation_interval: u64,
+    ) -> Self {
+        Self {
+            version: env!("CARGO_PKG_VERSION"),
+            puzzle,
+            solver: SolverConfig::Portfolio {
+                members: members.iter().map(Into::into).collect(),
+                threads,
+                reallocation_interval,
+            },
+            seed_policy: seed_policy(members),
+        }
+    }
+
+    /// Returns the configuration of a `GeneticSolver` run on a classic
+    /// puzzle.
+    #[inline]
+    #[must_use]
+    pub fn classic<const N: usize>(board: &Board<N>, params: &GAParams) -> Self {
+        Self::genetic(PuzzleDocument::from_board(board), params)
+    }
+
+    /// Returns the canonical serialization of the configuration: compact
+    /// JSON with fields in declaration order.
+    ///
+    /// # Panics
+    ///
+    /// Does not panic in practice: every field serializes to JSON.
+    #[inline]
+    #[must_use]
+    pub fn canonical(&self) -> String {
+        serde_json::to_string(self).expect("configuration is serializable")
+    }
+
+    /// Returns a stable 64-bit hash of the canonical serialization.
+    ///
+    /// Runs with equal fingerprints are comparable. Like
+    /// `Board::puzzle_hash`, it uses FNV-1a, so the value is the same across
+    /// runs and platforms and can be stored.
+    #[inline]
+    #[must_use]
+    pub fn fingerprint(&self) -> u64 {
+        fnv1a(self.canonical().into_bytes())
+    }
+}
+
+fn seed_policy(params: &[GAParams]) -> SeedPolicy {
+    if params.iter().all(|params| params.seed().is_some()) {
+        SeedPolicy::Seeded
+    } else {
+        SeedPolicy::Random
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::overrides::CandidateOverrides;
+    use crate::testing::{SOLVED_4, SOLVED_9};
+
+    #[test]
+    fn test_canonical() {
+        let params = GAParams::new(100, 0.5, 0.05, Some(500)).unwrap();
+
+        assert_eq!(
+            format!(
+                "{{\"version\":\"{}\",\"puzzle\":{{\"grid\":[[1,2,3,4],[3,4,1,2],[4,3,2,1],\
+                 [2,1,4,3]]}},\"solver\":{{\"Genetic\":{{\"population\":100,\
+                 \"selection_rate\":0.5,\"mutation_rate\":0.05,\"restart\":500,\
+                 \"rng_strategy\":\"PerChild\",\"population_schedule\":\"Fixed\"}}}},\
+                 \"seed_policy\":\"Random\"}}",
+                env!("CARGO_PKG_VERSION")
+            ),
+            RunConfig::classic(&SOLVED_4, &params).canonical()
+        );
+    }
+
+    #[test]
+    fn test_fingerprint() {
+        let params = GAParams::new(100, 0.5, 0.05, None).unwrap();
+        let fingerprint = RunConfig::classic(&SOLVED_9, &params).fingerprint();
+
+        assert_eq!(
+            fingerprint,
+            RunConfig::classic(&SOLVED_9, &params.clone().with_compact_population(true))
+                .fingerprint()
+        );
+        assert_eq!(
+            fingerprint,
+            RunConfig::classic(&SOLVED_9, &params.clone().with_profiling(true)).fingerprint()
+        );
+
+        #[cfg(feature = "scripting")]
+        {
+            let script = crate::scripting::FitnessScript::compile("0").unwrap();
+            let scripted = params.clone().with_script(Some(script.into()));
+            assert_ne!(
+                fingerprint,
+                RunConfig::classic(&SOLVED_9, &scripted).fingerprint()
+            );
+        }
+
+        let mut variant = PuzzleDocument::from_board(&SOLVED_9);
+        variant.diagonal = true;
+        let seeded = params.clone().with_seed(1);
+        let ranked = params.clone().with_selection(Selection::Rank).unwrap();
+        let bursting = params
+            .clone()
+            .with_hypermutation(Some(Hypermutation::default()))
+            .unwrap();
+        for other in [
+            RunConfig::classic(&SOLVED_4, &params),
+            RunConfig::classic(&SOLVED_9, &GAParams::new(100, 0.5, 0.06, None).unwrap()),
+            RunConfig::genetic(variant, &params),
+            RunConfig::classic(&SOLVED_9, &seeded),
+            RunConfig::classic(&SOLVED_9, &params.clone().with_column_repair(2)),
+            RunConfig::classic(&SOLVED_9, &params.clone().with_dedup(Some(Dedup::Random))),
+            RunConfig::classic(&SOLVED_9, &ranked),
+            RunConfig::classic(&SOLVED_9, &params.clone().with_elitism(1)),
+            RunConfig::classic(&SOLVED_9, &params.clone().with_plus_selection(true)),
+            RunConfig::classic(&SOLVED_9, &params.clone().with_local_search(10)),
+            RunConfig::classic(
+                &SOLVED_9,
+                &params.clone().with_crossover(Crossover::TwoPoint),
+            ),
+            RunConfig::classic(
+                &SOLVED_9,
+                &params
+                    .clone()
+                    .with_mutation_operator(Mutation::SwapWithinRow),
+            ),
+            RunConfig::classic(
+                &SOLVED_9,
+                &params.clone().with_encoding(Encoding::Permutation),
+            ),
+            RunConfig::classic(
+                &SOLVED_9,
+                &params
+                    .clone()
+                    .with_curriculum(Some(Curriculum { generations: 10 })),
+            ),
+            RunConfig::classic(
+                &SOLVED_9,
+                &params
+                    .clone()
+                    .with_auto_restart(Some(AutoRestart::default())),
+            ),
+            RunConfig::classic(
+                &SOLVED_9,
+                &params.clone().with_stall_restart(Some(StallRestart {
+                    generations: 100,
+                    keep: 1,
+                })),
+            ),
+            RunConfig::classic(&SOLVED_9, &bursting.with_hybrid(Some(Hybrid::default()))),
+            RunConfig::classic(
+                &SOLVED_9,
+                &params
+                    .clone()
+                    .with_mutation_schedule(MutationSchedule::Cosine { generations: 10 }),
+            ),
+            RunConfig::classic(
+                &SOLVED_9,
+                &params.clone().with_candidate_overrides(Some(
+                    CandidateOverrides::parse("1,1,1/2").unwrap().into(),
+                )),
+            ),
+            RunConfig::islands(PuzzleDocument::from_board(&SOLVED_9), &params, 4, 10),
+            RunConfig::tempering(
+                PuzzleDocument::from_board(&SOLVED_9),
+                std::slice::from_ref(&params),
+                10,
+            ),
+            RunConfig::portfolio(PuzzleDocument::from_board(&SOLVED_9), &[params], 4, 10),
+        ] {
+            assert_ne!(fingerprint, other.fingerprint(), "{}", other.canonical());
+        }
+
+        assert_eq!(
+            RunConfig::classic(&SOLVED_9, &seeded).fingerprint(),
+            RunConfig::classic(&SOLVED_9, &seeded.with_seed(2)).fingerprint()
+        );
+    }
+
+    #[test]
+    fn test_fingerprint_defaults() {
+        let params = GAParams::new(100, 0.5, 0.05, None).unwrap();
+        let fingerprint = |params: &GAParams| RunConfig::classic(&SOLVED_9, params).fingerprint();
+
+        // Settings left at their defaults leave fingerprints as they were.
+        assert_eq!(
+            fingerprint(&params),
+            fingerprint(
+                &params
+                    .clone()
+                    .with_weights(UnitWeights::FULL)
+                    .with_pairing(Pairing::Adjacent)
+                    .with_incest_prevention(0)
+            )
+        );
+        let boxes = UnitWeights {
+            boxes: 2.0,
+            ..UnitWeights::FULL
+        };
+        assert_ne!(
+            fingerprint(&params),
+            fingerprint(&params.clone().with_weights(boxes))
+        );
+        assert_ne!(
+            fingerprint(&params),
+            fingerprint(&params.clone().with_incest_prevention(5))
+        );
+        assert_ne!(
+            fingerprint(&params),
+            fingerprint(&params.clone().with_pairing(Pairing::RoundRobin))
+        );
+        assert_ne!(
+            fingerprint(&params),
+            fingerprint(&params.clone().with_children_per_pair(Some(2)).unwrap())
+        );
+        assert_ne!(
+            fingerprint(&params),
+            fingerprint(&params.clone().with_tabu(10))
+        );
+        assert_ne!(
+            fingerprint(&params),
+            fingerprint(
+                &params
+                    .clone()
+                    .with_cataclysm(Some(Cataclysm::default()))
+                    .unwrap()
+            )
+        );
+    }
+}
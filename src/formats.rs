@@ -0,0 +1,391 @@
+#![warn(
+    clippy::all,
+    // clippy::restriction,
+    clippy::pedantic,
+    clippy::nursery,
+    clippy::cargo
+)]
+
+pub mod document;
+pub mod pretty;
+
+use super::arena::to_board;
+use super::sudoku::Board;
+use document::PuzzleDocument;
+use serde::Deserialize;
+use serde_json::Value;
+use std::fmt::{Display, Formatter};
+use std::io::{Error, ErrorKind};
+use std::path::Path;
+
+/// A textual representation of one or more boards.
+///
+/// Empty cells are written as `0` or `.` in every format, and as `null` or
+/// `0` in JSON.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum Format {
+    /// One board as N lines of N cells, optionally separated by whitespace.
+    Grid,
+    /// One board on a single line of N * N cells.
+    Line,
+    /// A collection of boards, one N * N cell line per board.
+    Sdm,
+    /// Comma-separated cells, either one board row or one board per line.
+    Csv,
+    /// A JSON grid, flat cell array or line string, a [`PuzzleDocument`]
+    /// without variant constraints, or an array of any of these.
+    Json,
+    /// One board drawn by [`pretty::Pretty`], with row and column labels,
+    /// box borders, and optionally candidates in braces for empty cells.
+    Pretty,
+}
+
+impl Display for Format {
+    #[inline]
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Grid => write!(f, "grid"),
+            Self::Line => write!(f, "line"),
+            Self::Sdm => write!(f, "sdm"),
+            Self::Csv => write!(f, "csv"),
+            Self::Json => write!(f, "json"),
+            Self::Pretty => write!(f, "pretty"),
+        }
+    }
+}
+
+impl Format {
+    /// Guesses the format of `text` for boards of size N.
+    ///
+    /// JSON is recognized by its leading bracket or brace, drawings by the
+    /// `|` of their box borders, CSV by commas, and line-per-board formats
+    /// by every line holding exactly N * N cells. Anything else is taken to
+    /// be a grid.
+    #[inline]
+    #[must_use]
+    pub fn detect<const N: usize>(text: &str) -> Self {
+        let text = text.trim_start();
+        if text.starts_with('[') || text.starts_with('{') {
+            return Self::Json;
+        }
+
+        let lines: Vec<&str> = lines(text).collect();
+        if lines.iter().any(|line| line.contains('|')) {
+            Self::Pretty
+        } else if lines.iter().any(|line| line.contains(',')) {
+            Self::Csv
+        } else if lines.iter().all(|line| line.chars().count() == N * N) {
+            if lines.len() == 1 {
+                Self::Line
+            } else {
+                Self::Sdm
+            }
+        } else {
+            Self::Grid
+        }
+    }
+}
+
+/// Reads every board in a file, whatever its format.
+///
+/// # Errors
+///
+/// Fails if the file is nonexistent or unreadable, or if its contents are
+/// malformed or of the wrong size.
+#[inline]
+pub fn load<const N: usize, P: AsRef<Path>>(path: P) -> Result<Vec<Board<N>>, Error> {
+    parse(&std::fs::read_to_string(path)?)
+}
+
+/// Parses every board in `text`, detecting its format first.
+///
+/// # Errors
+///
+/// Fails if the text holds no boards, or is malformed or of the wrong size
+/// for its detected format.
+#[inline]
+pub fn parse<const N: usize>(text: &str) -> Result<Vec<Board<N>>, Error> {
+    parse_as(Format::detect::<N>(text), text)
+}
+
+/// Parses every board in `text` in the given format.
+///
+/// # Errors
+///
+/// Fails if the text holds no boards, or is malformed or of the wrong size.
+#[inline]
+pub fn parse_as<const N: usize>(format: Format, text: &str) -> Result<Vec<Board<N>>, Error> {
+    let boards = match format {
+        Format::Grid => {
+            let mut cells = Vec::with_capacity(N * N);
+            for line in lines(text) {
+                if line.contains(char::is_whitespace) {
+                    for token in line.split_whitespace() {
+                        cells.push(cell::<N>(token)?);
+                    }
+                } else {
+                    for ch in line.chars() {
+                        cells.push(cell::<N>(ch.encode_utf8(&mut [0; 4]))?);
+                    }
+                }
+            }
+            vec![board(&cells)?]
+        }
+        Format::Pretty => {
+            let cells = pretty::cells(text)
+                .into_iter()
+                .map(cell::<N>)
+                .collect::<Result<Vec<_>, _>>()?;
+            vec![board(&cells)?]
+        }
+        Format::Line | Format::Sdm => lines(text)
+            .map(|line| {
+                let cells = line
+                    .chars()
+                    .map(|ch| cell::<N>(ch.encode_utf8(&mut [0; 4])))
+                    .collect::<Result<Vec<_>, _>>()?;
+                board(&cells)
+            })
+            .collect::<Result<_, _>>()?,
+        Format::Csv => {
+            let cells = lines(text)
+                .flat_map(|line| line.split(','))
+                .map(|field| match field.trim() {
+                    "" => Ok(0),
+                    field => cell::<N>(field),
+                })
+                .collect::<Result<Vec<_>, _>>()?;
+            cells.chunks(N * N).map(board).collect::<Result<_, _>>()?
+        }
+        Format::Json => {
+            let value: Value = serde_json::from_str(text).map_err(|err| malformed(&err))?;
+            let mut boards = Vec::new();
+            json_boards(&value, &mut boards)?;
+            boards
+        }
+    };
+
+    if boards.is_empty() {
+        return Err(malformed("no boards found"));
+    }
+
+    Ok(boards)
+}
+
+/// Rewrites every board in `input`, whatever its format, in the canonical
+/// line format: one line of N * N cells per board.
+///
+/// Empty cells are written as `0`, and digits past 9 as the capital
+/// letters `A` to `Z`, so that the lines can be handed to other sudoku
+/// tools that read 81-character puzzles.
+///
+/// # Errors
+///
+/// Fails if the input holds no boards, is malformed or of the wrong size,
+/// or holds the digit 36, which has no single-character form.
+#[inline]
+pub fn normalize<const N: usize>(input: &str) -> Result<String, Error> {
+    let lines = parse::<N>(input)?
+        .iter()
+        .map(|board| {
+            board
+                .0
+                .iter()
+                .flat_map(|row| row.0)
+                .map(|digit| {
+                    char::from_digit(u32::from(digit), 36)
+                        .map(|ch| ch.to_ascii_uppercase())
+                        .ok_or_else(|| malformed(&format!("digit {digit} has no line form")))
+                })
+                .collect()
+        })
+        .collect::<Result<Vec<String>, _>>()?;
+
+    Ok(lines.join("\n"))
+}
+
+/// Returns the non-blank lines of `text`, trimmed.
+fn lines(text: &str) -> impl Iterator<Item = &str> {
+    text.lines().map(str::trim).filter(|line| !line.is_empty())
+}
+
+fn malformed<E: Display + ?Sized>(reason: &E) -> Error {
+    Error::new(
+        ErrorKind::InvalidData,
+        format!("malformed sudoku board: {reason}"),
+    )
+}
+
+fn cell<const N: usize>(token: &str) -> Result<u8, Error> {
+    if token == "." {
+        return Ok(0);
+    }
+
+    let mut chars = token.chars();
+    #[allow(clippy::cast_possible_truncation)]
+    let digit = match (chars.next(), chars.next()) {
+        // Digits past 35 have no single-character form.
+        (Some(ch), None) => ch.to_digit((N as u32 + 1).min(36)).map(|digit| digit as u8),
+        _ => token.parse().ok().filter(|&digit| usize::from(digit) <= N),
+    };
+
+    digit.ok_or_else(|| malformed(&format!("bad cell {token:?}")))
+}
+
+fn board<const N: usize>(cells: &[u8]) -> Result<Board<N>, Error> {
+    if cells.len() != N * N {
+        return Err(Error::new(ErrorKind::InvalidData, "wrong board size"));
+    }
+
+    Ok(to_board(cells))
+}
+
+fn json_cell<const N: usize>(value: &Value) -> Option<u8> {
+    match value {
+        Value::Null => Some(0),
+        Value::Number(digit) => digit
+            .as_u64()
+            .filter(|&digit| digit <= N as u64)
+            .and_then(|digit| u8::try_from(digit).ok()),
+        _ => None,
+    }
+}
+
+fn json_boards<const N: usize>(value: &Value, boards: &mut Vec<Board<N>>) -> Result<(), Error> {
+    match value {
+        Value::String(line) => boards.extend(parse_as::<N>(Format::Line, line)?),
+        Value::Object(_) => {
+            let document = PuzzleDocument::deserialize(value).map_err(|err| malformed(&err))?;
+            let board = document.board().map_err(|err| malformed(&err))?;
+            // Boards leave their variant constraints behind, so solving one
+            // would only solve the classic puzzle.
+            if !document.is_classic() {
+                return Err(malformed("variant constraints are not supported"));
+            }
+            boards.push(board);
+        }
+        Value::Array(values) => {
+            let rows: Option<Vec<u8>> = if values.len() == N && values.iter().all(Value::is_array) {
+                values
+                    .iter()
+                    .filter_map(Value::as_array)
+                    .filter(|row| row.len() == N)
+                    .flatten()
+                    .map(json_cell::<N>)
+                    .collect()
+            } else {
+                values.iter().map(json_cell::<N>).collect()
+            };
+
+            match rows {
+                Some(cells) if cells.len() == N * N => boards.push(to_board(&cells)),
+                _ => {
+                    for value in values {
+                        json_boards(value, boards)?;
+                    }
+                }
+            }
+        }
+        _ => return Err(malformed("unexpected JSON value")),
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::SOLVED_9;
+
+    fn rows(separator: &str) -> Vec<String> {
+        SOLVED_9
+            .0
+            .iter()
+            .map(|row| {
+                let digits: Vec<String> = row.0.iter().map(ToString::to_string).collect();
+                digits.join(separator)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_detect() {
+        let grid = rows("").join("\n");
+        let line = rows("").concat();
+
+        assert_eq!(Format::Grid, Format::detect::<9>(&grid));
+        assert_eq!(Format::Grid, Format::detect::<9>(&SOLVED_9.to_string()));
+        assert_eq!(Format::Line, Format::detect::<9>(&line));
+        assert_eq!(
+            Format::Sdm,
+            Format::detect::<9>(&format!("{line}\n{line}\n"))
+        );
+        assert_eq!(Format::Csv, Format::detect::<9>("1,2,,4\n"));
+        assert_eq!(Format::Json, Format::detect::<9>("  [[1]]"));
+        assert_eq!(Format::Json, Format::detect::<9>("{}"));
+    }
+
+    #[test]
+    fn test_parse() {
+        let line = rows("").concat();
+        let csv = rows(",").join("\n");
+        let json = format!("[[{}]]", rows(",").join("],["));
+
+        for text in [
+            rows("").join("\n"),
+            SOLVED_9.to_string(),
+            line.clone(),
+            csv.clone(),
+            json.clone(),
+            format!("{{\"grid\": {json}}}"),
+            format!("[\"{line}\"]"),
+        ] {
+            assert_eq!(vec![SOLVED_9], parse::<9>(&text).unwrap(), "{text}");
+        }
+
+        let sdm = parse::<9>(&format!("{line}\n\n{}\n", line.replacen('1', ".", 1))).unwrap();
+        assert_eq!(2, sdm.len());
+        assert_eq!(SOLVED_9, sdm[0]);
+        assert_eq!(0, sdm[1].0[0].0[0]);
+        assert_eq!(
+            2,
+            parse::<9>(&format!("[{json}, \"{line}\"]")).unwrap().len()
+        );
+        assert_eq!(2, parse::<9>(&format!("{csv}\n{csv}")).unwrap().len());
+    }
+
+    #[test]
+    fn test_normalize() {
+        let line = rows("").concat();
+        let csv = rows(",").join("\n");
+        let blanked = line.replacen('5', "0", 1);
+
+        assert_eq!(line, normalize::<9>(&SOLVED_9.to_string()).unwrap());
+        assert_eq!(line, normalize::<9>(&csv).unwrap());
+        assert_eq!(
+            format!("{line}\n{blanked}"),
+            normalize::<9>(&format!("[\"{line}\", \"{}\"]", blanked.replace('0', "."))).unwrap()
+        );
+        assert_eq!(
+            "0G".repeat(128),
+            normalize::<16>(&".g".repeat(128)).unwrap()
+        );
+        assert!(normalize::<9>("1234").is_err());
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        for text in ["", "1234", "[1, 2]", "{\"cells\": []}", "[\"12\"]", "1,x"] {
+            assert_eq!(
+                ErrorKind::InvalidData,
+                parse::<9>(text).unwrap_err().kind(),
+                "{text}"
+            );
+        }
+        assert!(parse::<9>(&rows("").join("\n").replace('1', "a")).is_err());
+
+        let json = format!("[[{}]]", rows(",").join("],["));
+        let variant = parse::<9>(&format!("{{\"grid\": {json}, \"diagonal\": true}}"));
+        assert_eq!(ErrorKind::InvalidData, variant.unwrap_err().kind());
+    }
+}
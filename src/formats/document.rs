@@ -0,0 +1,427 @@
+#![warn(
+    clippy::all,
+    // clippy::restriction,
+    clippy::pedantic,
+    clippy::nursery,
+    clippy::cargo
+)]
+
+use crate::arena::to_board;
+use crate::errors::SchemaError;
+use crate::sudoku::unit::UnitSet;
+use crate::sudoku::Board;
+use serde::{Deserialize, Serialize};
+use std::io::{Error, ErrorKind};
+
+/// A `[row, column]` cell coordinate.
+pub type Cell = [usize; 2];
+
+/// A killer cage: cells whose digits are distinct and add up to `sum`.
+#[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Cage {
+    pub cells: Vec<Cell>,
+    /// The sum of the cage's digits, or `None` for a cage that only
+    /// requires distinct digits.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sum: Option<u32>,
+}
+
+/// A puzzle together with the variant constraints it is played under.
+///
+/// The JSON form is an object with a `"grid"` of N rows of N digits, 0 for
+/// an empty cell, and optional variant fields:
+///
+/// ```json
+/// {
+///   "grid": [[0, 0, 3, 0], [0, 4, 0, 0], [0, 0, 0, 2], [0, 1, 0, 0]],
+///   "diagonal": true,
+///   "regions": [[[0, 0], [0, 3], [3, 0], [3, 3]]],
+///   "cages": [{ "cells": [[0, 0], [0, 1]], "sum": 3 }]
+/// }
+/// ```
+///
+/// A document says nothing about N itself; [`PuzzleDocument::validate`]
+/// checks it against the board size it is to be used with.
+#[derive(Debug, Clone, Default, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct PuzzleDocument {
+    pub grid: Vec<Vec<u8>>,
+    /// The main diagonal, top left to bottom right, holds distinct digits.
+    #[serde(default, skip_serializing_if = "is_false")]
+    pub diagonal: bool,
+    /// The anti-diagonal, top right to bottom left, holds distinct digits.
+    #[serde(default, skip_serializing_if = "is_false")]
+    pub anti_diagonal: bool,
+    /// Extra regions of distinct digits, on top of rows, columns and boxes.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub regions: Vec<Vec<Cell>>,
+    /// Assigns each cell a region label in `0..N`, replacing the boxes, as
+    /// in jigsaw sudoku.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub region_map: Option<Vec<Vec<usize>>>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub cages: Vec<Cage>,
+}
+
+#[allow(clippy::trivially_copy_pass_by_ref)]
+const fn is_false(value: &bool) -> bool {
+    !*value
+}
+
+impl PuzzleDocument {
+    /// Returns a document holding `board` with no variant constraints.
+    #[inline]
+    #[must_use]
+    pub fn from_board<const N: usize>(board: &Board<N>) -> Self {
+        Self {
+            grid: board.0.iter().map(|row| row.0.to_vec()).collect(),
+            ..Self::default()
+        }
+    }
+
+    /// Parses and validates a document for boards of size N.
+    ///
+    /// # Errors
+    ///
+    /// Fails with `InvalidData` if `text` is not a JSON document of this
+    /// schema, or if the document does not validate.
+    #[inline]
+    pub fn parse<const N: usize>(text: &str) -> Result<Self, Error> {
+        let document: Self =
+            serde_json::from_str(text).map_err(|err| Error::new(ErrorKind::InvalidData, err))?;
+        document
+            .validate::<N>()
+            .map_err(|err| Error::new(ErrorKind::InvalidData, err))?;
+
+        Ok(document)
+    }
+
+    /// Returns true if the document only has the classic constraints.
+    #[inline]
+    #[must_use]
+    pub fn is_classic(&self) -> bool {
+        !self.diagonal
+            && !self.anti_diagonal
+            && self.regions.is_empty()
+            && self.region_map.is_none()
+            && self.cages.is_empty()
+    }
+
+    /// Checks that the document describes a puzzle of size N.
+    ///
+    /// # Errors
+    ///
+    /// Fails on the first of:
+    /// * a grid that is not N by N, or holds digits greater than N
+    /// * a region or cage cell outside the grid, or listed twice
+    /// * an extra region of more than N cells
+    /// * a region map that is not N by N, or does not split the grid into
+    ///   N regions of N cells
+    /// * a cell in more than one cage, a cage of more than N cells, or one
+    ///   with a sum its cells cannot add up to
+    #[inline]
+    pub fn validate<const N: usize>(&self) -> Result<(), SchemaError> {
+        check_grid::<N, _>("grid", &self.grid)?;
+        for (row, col, &digit) in cells(&self.grid) {
+            if usize::from(digit) > N {
+                return Err(SchemaError::DigitOutOfRange { row, col, digit });
+            }
+        }
+
+        for (index, region) in self.regions.iter().enumerate() {
+            check_cells::<N>("regions", index, region)?;
+        }
+
+        if let Some(region_map) = &self.region_map {
+            check_grid::<N, _>("region_map", region_map)?;
+
+            let mut sizes = [0; N];
+            for (row, col, &label) in cells(region_map) {
+                *sizes
+                    .get_mut(label)
+                    .ok_or(SchemaError::BadLabel { row, col, label })? += 1;
+            }
+            if let Some((label, &size)) = sizes.iter().enumerate().find(|(_, &size)| size != N) {
+                return Err(SchemaError::RegionSize {
+                    field: "region_map",
+                    index: label,
+                    size,
+                });
+            }
+        }
+
+        let mut caged = [[false; N]; N];
+        for (index, cage) in self.cages.iter().enumerate() {
+            check_cells::<N>("cages", index, &cage.cells)?;
+
+            for &[row, col] in &cage.cells {
+                if std::mem::replace(&mut caged[row][col], true) {
+                    return Err(SchemaError::DuplicateCell {
+                        field: "cages",
+                        row,
+                        col,
+                    });
+                }
+            }
+
+            if let Some(sum) = cage.sum {
+                // The smallest and largest sums of k distinct digits.
+                let k = u32::try_from(cage.cells.len()).unwrap_or(u32::MAX);
+                let n = u32::try_from(N).unwrap_or(u32::MAX);
+                let min = k * (k + 1) / 2;
+                let max = k * (2 * n + 1 - k) / 2;
+                if !(min..=max).contains(&sum) {
+                    return Err(SchemaError::CageSum { index, sum });
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Returns the grid as a board, after validating the document.
+    ///
+    /// # Errors
+    ///
+    /// Fails if the document does not validate for size N.
+    #[inline]
+    pub fn board<const N: usize>(&self) -> Result<Board<N>, SchemaError> {
+        self.validate::<N>()?;
+
+        Ok(to_board(&self.grid.concat()))
+    }
+
+    /// Returns the units of the document, after validating it.
+    ///
+    /// The region map, if any, replaces the boxes, and the diagonals, the
+    /// extra regions and the cells of each cage follow as regions, in that
+    /// order. Cage sums are not a unit constraint, and are left out.
+    ///
+    /// # Errors
+    ///
+    /// Fails if the document does not validate for size N.
+    #[inline]
+    pub fn units<const N: usize>(&self) -> Result<UnitSet<N>, SchemaError> {
+        self.validate::<N>()?;
+
+        let mut units = UnitSet::classic();
+        if let Some(region_map) = &self.region_map {
+            units = units.with_region_map(std::array::from_fn(|row| {
+                std::array::from_fn(|col| region_map[row][col])
+            }));
+        }
+        if self.diagonal {
+            units = units.with_diagonal();
+        }
+        if self.anti_diagonal {
+            units = units.with_anti_diagonal();
+        }
+
+        let regions = self
+            .regions
+            .iter()
+            .chain(self.cages.iter().map(|cage| &cage.cells));
+        Ok(regions.fold(units, |units, cells| {
+            units.with_region(cells.iter().map(|&cell| cell.into()).collect())
+        }))
+    }
+}
+
+fn cells<T>(grid: &[Vec<T>]) -> impl Iterator<Item = (usize, usize, &T)> {
+    grid.iter().enumerate().flat_map(|(row, values)| {
+        values
+            .iter()
+            .enumerate()
+            .map(move |(col, value)| (row, col, value))
+    })
+}
+
+fn check_grid<const N: usize, T>(field: &'static str, grid: &[Vec<T>]) -> Result<(), SchemaError> {
+    let bad_row = grid.iter().position(|row| row.len() != N);
+
+    if grid.len() != N || bad_row.is_some() {
+        return Err(SchemaError::GridSize {
+            field,
+            rows: grid.len(),
+            bad_row,
+        });
+    }
+
+    Ok(())
+}
+
+fn check_cells<const N: usize>(
+    field: &'static str,
+    index: usize,
+    cells: &[Cell],
+) -> Result<(), SchemaError> {
+    if cells.len() > N {
+        return Err(SchemaError::RegionSize {
+            field,
+            index,
+            size: cells.len(),
+        });
+    }
+
+    let mut seen = [[false; N]; N];
+
+    for &[row, col] in cells {
+        if row >= N || col >= N {
+            return Err(SchemaError::CellOutOfRange { field, row, col });
+        }
+        if std::mem::replace(&mut seen[row][col], true) {
+            return Err(SchemaError::DuplicateCell { field, row, col });
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sudoku::{Fitness, Unit};
+    use crate::testing::SOLVED_4;
+
+    const DOCUMENT: &str = r#"{
+        "grid": [[0, 0, 3, 0], [0, 4, 0, 0], [0, 0, 0, 2], [0, 1, 0, 0]],
+        "diagonal": true,
+        "regions": [[[0, 0], [0, 3], [3, 0], [3, 3]]],
+        "region_map": [[0, 0, 1, 1], [0, 0, 1, 1], [2, 2, 3, 3], [2, 2, 3, 3]],
+        "cages": [{ "cells": [[0, 0], [0, 1]], "sum": 3 }, { "cells": [[1, 0]] }]
+    }"#;
+
+    #[test]
+    fn test_parse() {
+        let document = PuzzleDocument::parse::<4>(DOCUMENT).unwrap();
+
+        assert!(document.diagonal);
+        assert!(!document.anti_diagonal);
+        assert!(!document.is_classic());
+        assert_eq!(1, document.regions.len());
+        assert_eq!(Some(3), document.cages[0].sum);
+        assert_eq!(None, document.cages[1].sum);
+        assert_eq!(3, document.board::<4>().unwrap().0[0].0[2]);
+
+        assert!(PuzzleDocument::parse::<9>(DOCUMENT).is_err());
+        assert!(PuzzleDocument::parse::<4>(r#"{"grid": [], "extra": 1}"#).is_err());
+    }
+
+    #[test]
+    fn test_units() {
+        let document = PuzzleDocument::parse::<4>(DOCUMENT).unwrap();
+        let units = document.units::<4>().unwrap();
+        let regions: Vec<_> = units
+            .units()
+            .filter(|&(unit, _, _)| unit == Unit::Region)
+            .map(|(_, _, cells)| cells.to_vec())
+            .collect();
+
+        assert_eq!(
+            vec![
+                vec![(0, 0), (1, 1), (2, 2), (3, 3)],
+                vec![(0, 0), (0, 3), (3, 0), (3, 3)],
+                vec![(0, 0), (0, 1)],
+                vec![(1, 0)],
+            ],
+            regions
+        );
+        // Swapping two columns keeps rows, columns and boxes whole, but
+        // repeats two digits on the diagonal.
+        let mut board = SOLVED_4;
+        for row in &mut board.0 {
+            row.0.swap(0, 1);
+        }
+        assert!(board.fitness().is_solution());
+        assert_eq!(Fitness::new(2), board.fitness_in(&units));
+        assert_eq!(2, board.violations_in(&units).len());
+        assert!(PuzzleDocument::from_board(&SOLVED_4)
+            .units::<4>()
+            .unwrap()
+            .is_classic());
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let document = PuzzleDocument::from_board(&SOLVED_4);
+        let json = serde_json::to_string(&document).unwrap();
+
+        assert!(document.is_classic());
+        assert_eq!(
+            r#"{"grid":[[1,2,3,4],[3,4,1,2],[4,3,2,1],[2,1,4,3]]}"#,
+            json
+        );
+        assert_eq!(
+            SOLVED_4,
+            PuzzleDocument::parse::<4>(&json).unwrap().board().unwrap()
+        );
+
+        let document: PuzzleDocument = serde_json::from_str(DOCUMENT).unwrap();
+        let json = serde_json::to_string(&document).unwrap();
+        assert_eq!(document, serde_json::from_str(&json).unwrap());
+    }
+
+    #[test]
+    fn test_validate() {
+        let valid: PuzzleDocument = serde_json::from_str(DOCUMENT).unwrap();
+        let check = |edit: fn(&mut PuzzleDocument)| {
+            let mut document = valid.clone();
+            edit(&mut document);
+            document.validate::<4>().unwrap_err()
+        };
+
+        assert_eq!(
+            SchemaError::GridSize {
+                field: "grid",
+                rows: 4,
+                bad_row: Some(2),
+            },
+            check(|d| d.grid[2].push(0))
+        );
+        assert_eq!(
+            SchemaError::DigitOutOfRange {
+                row: 1,
+                col: 1,
+                digit: 5,
+            },
+            check(|d| d.grid[1][1] = 5)
+        );
+        assert_eq!(
+            SchemaError::CellOutOfRange {
+                field: "regions",
+                row: 4,
+                col: 0,
+            },
+            check(|d| d.regions[0][1] = [4, 0])
+        );
+        assert_eq!(
+            SchemaError::BadLabel {
+                row: 0,
+                col: 0,
+                label: 4,
+            },
+            check(|d| d.region_map.as_mut().unwrap()[0][0] = 4)
+        );
+        assert_eq!(
+            SchemaError::RegionSize {
+                field: "region_map",
+                index: 0,
+                size: 3,
+            },
+            check(|d| d.region_map.as_mut().unwrap()[0][0] = 1)
+        );
+        assert_eq!(
+            SchemaError::DuplicateCell {
+                field: "cages",
+                row: 0,
+                col: 1,
+            },
+            check(|d| d.cages[1].cells.push([0, 1]))
+        );
+        assert_eq!(
+            SchemaError::CageSum { index: 0, sum: 8 },
+            check(|d| d.cages[0].sum = Some(8))
+        );
+    }
+}
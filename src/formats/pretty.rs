@@ -0,0 +1,203 @@
+#![warn(
+    clippy::all,
+    // clippy::restriction,
+    clippy::pedantic,
+    clippy::nursery,
+    clippy::cargo
+)]
+
+use crate::exact::candidates;
+use crate::sudoku::{box_dims, Board};
+use std::fmt::{Display, Formatter};
+
+/// Returns the name of a cell: its row as a letter from `A` and its column
+/// as a number from 1, such as `C7`.
+///
+/// # Panics
+///
+/// Panics if `row` is past `Z`.
+#[inline]
+#[must_use]
+pub fn cell_name(row: usize, col: usize) -> String {
+    format!("{}{}", row_label(row), col + 1)
+}
+
+fn row_label(row: usize) -> char {
+    char::from(b'A' + u8::try_from(row).expect("row fits in a letter"))
+}
+
+/// A board drawn with its box borders and with row letters and column
+/// numbers around it:
+///
+/// ```text
+///     1 2   3 4
+///   +-----+-----+
+/// A | 1 2 | 3 4 |
+/// B | 3 . | . 2 |
+///   +-----+-----+
+/// ...
+/// ```
+///
+/// Empty cells are drawn as `.`, or with their candidates in braces, such
+/// as `{14}`. Either way the drawing parses back as `Format::Pretty`.
+#[derive(Debug, Copy, Clone)]
+pub struct Pretty<'a, const N: usize> {
+    board: &'a Board<N>,
+    candidates: bool,
+}
+
+impl<'a, const N: usize> Pretty<'a, N> {
+    #[inline]
+    #[must_use]
+    pub const fn new(board: &'a Board<N>) -> Self {
+        Self {
+            board,
+            candidates: false,
+        }
+    }
+
+    /// Draws the candidates of each empty cell instead of a `.`.
+    #[inline]
+    #[must_use]
+    pub const fn with_candidates(mut self) -> Self {
+        self.candidates = true;
+        self
+    }
+
+    fn cells(&self) -> Vec<Vec<String>> {
+        let candidates = self.candidates.then(|| candidates(self.board));
+        let separator = if N > 9 { "," } else { "" };
+
+        self.board
+            .0
+            .iter()
+            .enumerate()
+            .map(|(i, row)| {
+                row.0
+                    .iter()
+                    .enumerate()
+                    .map(|(j, &digit)| match (digit, &candidates) {
+                        (0, Some(candidates)) => {
+                            let digits: Vec<String> = (1..=N)
+                                .filter(|digit| candidates[i][j] & 1 << digit != 0)
+                                .map(|digit| digit.to_string())
+                                .collect();
+                            format!("{{{}}}", digits.join(separator))
+                        }
+                        (0, None) => ".".to_owned(),
+                        (digit, _) => digit.to_string(),
+                    })
+                    .collect()
+            })
+            .collect()
+    }
+}
+
+impl<const N: usize> Display for Pretty<'_, N> {
+    #[inline]
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let (rows, cols) = box_dims::<N>();
+        let cells = self.cells();
+        let width = cells.iter().flatten().map(String::len).max().unwrap_or(1);
+        let boxes = |row: &[String]| {
+            let boxes: Vec<String> = row
+                .chunks(cols)
+                .map(|cells| {
+                    let cells: Vec<String> =
+                        cells.iter().map(|cell| format!("{cell:width$}")).collect();
+                    cells.join(" ")
+                })
+                .collect();
+            boxes
+        };
+        let border = format!(
+            "  +{}",
+            format!("{}+", "-".repeat(cols * (width + 1) + 1)).repeat(rows)
+        );
+
+        let labels: Vec<String> = (1..=N).map(|col| col.to_string()).collect();
+        writeln!(f, "    {}", boxes(&labels).join("   ").trim_end())?;
+        for (i, row) in cells.iter().enumerate() {
+            if i % rows == 0 {
+                writeln!(f, "{border}")?;
+            }
+            writeln!(f, "{} | {} |", row_label(i), boxes(row).join(" | "))?;
+        }
+        write!(f, "{border}")
+    }
+}
+
+/// Returns the cells of a drawing made by `Pretty`, row by row, with 0 for
+/// empty cells. Lines other than labelled rows are skipped.
+pub(super) fn cells(text: &str) -> Vec<&str> {
+    text.lines()
+        .filter_map(|line| {
+            let (label, row) = line.split_once('|')?;
+            let label = label.trim();
+            (label.len() == 1 && label.chars().all(|ch| ch.is_ascii_uppercase())).then_some(row)
+        })
+        .flat_map(|row| row.split(|ch: char| ch == '|' || ch.is_whitespace()))
+        .filter(|token| !token.is_empty())
+        .map(|token| if token.starts_with('{') { "0" } else { token })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::formats::{parse, Format};
+    use crate::testing::{SOLVED_4, SOLVED_6, SOLVED_9};
+
+    #[test]
+    fn test_pretty() {
+        let mut puzzle = SOLVED_4;
+        puzzle.0[1].0[1] = 0;
+        puzzle.0[1].0[2] = 0;
+
+        assert_eq!(
+            "    1 2   3 4\n  \
+             +-----+-----+\n\
+             A | 1 2 | 3 4 |\n\
+             B | 3 . | . 2 |\n  \
+             +-----+-----+\n\
+             C | 4 3 | 2 1 |\n\
+             D | 2 1 | 4 3 |\n  \
+             +-----+-----+",
+            Pretty::new(&puzzle).to_string()
+        );
+
+        let drawing = Pretty::new(&puzzle).with_candidates().to_string();
+        assert!(drawing.contains("B | 3   {4} | {1} 2   |"), "{drawing}");
+        assert_eq!(Format::Pretty, Format::detect::<4>(&drawing));
+        assert_eq!(vec![puzzle], parse::<4>(&drawing).unwrap());
+
+        let drawing = Pretty::new(&SOLVED_6).to_string();
+        assert!(
+            drawing.starts_with(
+                "    1 2 3   4 5 6\n  \
+                 +-------+-------+\n\
+                 A | 1 2 3 | 4 5 6 |\n\
+                 B | 4 5 6 | 1 2 3 |\n  \
+                 +-------+-------+\n"
+            ),
+            "{drawing}"
+        );
+        assert_eq!(vec![SOLVED_6], parse::<6>(&drawing).unwrap());
+
+        let mut puzzle = SOLVED_9;
+        puzzle.0[8].0[8] = 0;
+        for drawing in [
+            Pretty::new(&puzzle).to_string(),
+            Pretty::new(&puzzle).with_candidates().to_string(),
+        ] {
+            assert_eq!(vec![puzzle], parse::<9>(&drawing).unwrap(), "{drawing}");
+        }
+    }
+
+    #[test]
+    fn test_cell_name() {
+        assert_eq!("A1", cell_name(0, 0));
+        assert_eq!("C7", cell_name(2, 6));
+        assert_eq!("P16", cell_name(15, 15));
+    }
+}
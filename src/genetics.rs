@@ -6,24 +6,353 @@
     clippy::cargo
 )]
 
-use super::errors::NoSolutionFound;
-use super::sudoku::{Board, Row};
+pub mod crossover;
+pub mod mutation;
+mod permutation;
+
+use super::arena::{to_board, Population, PopulationArena};
+use super::audit::AuditSample;
+use super::errors::{NoSolutionFound, ParamError};
+use super::overrides::CandidateOverrides;
+use super::profile::{BreedClock, BreedTimer, Phase, PhaseTimer};
+use super::restarts::{AutoRestart, Cataclysm, StallCounter, StallRestart};
+#[cfg(feature = "scripting")]
+use super::scripting::FitnessScript;
+use super::solver::backtrack::Hybrid;
+use super::stats::PopulationStats;
+use super::sudoku::unit::UnitScorer;
+use super::sudoku::{box_dims, Board, Fitness, Objectives, Row, UnitWeights};
+use super::tabu::TabuArchive;
 use arrayvec::ArrayVec;
+use rand::distributions::{Bernoulli, Uniform, WeightedIndex};
 use rand::rngs::OsRng;
-use rand::{distributions::Uniform, Rng, SeedableRng};
+use rand::seq::{IteratorRandom, SliceRandom};
+use rand::{Rng, SeedableRng};
 use rand_pcg::Pcg64Mcg;
-use rayon::iter::Zip;
 use rayon::prelude::*;
-use rayon::vec::IntoIter;
+use serde::{Deserialize, Serialize};
+use std::borrow::Cow;
+use std::collections::hash_map::RandomState;
+use std::collections::{BTreeMap, HashMap, VecDeque};
+use std::fmt::{Display, Formatter};
+use std::hash::BuildHasher;
+use std::ops::ControlFlow;
+use std::sync::Arc;
+
+pub use crossover::{Crossover, CrossoverOperator};
+pub use mutation::{Mutation, MutationOperator};
 
 pub const MAX_POPULATION: usize = 100_000;
 
+/// The random number generator the solvers breed with, and the backend to
+/// pick for the functions generic over one unless another is wanted.
+pub type DefaultRng = Pcg64Mcg;
+
+/// The probability that a child inherits a cell from its first parent
+/// rather than its second, when the cell does not mutate.
+pub const CROSSOVER_RATE: f64 = 0.5;
+
+/// How random number generators are provided to child generation.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Serialize, Deserialize)]
+pub enum RngStrategy {
+    /// Seed a fresh RNG from the OS for every child.
+    PerChild,
+    /// Seed one RNG per rayon worker split and reuse it across children.
+    PerWorker,
+}
+
+impl Default for RngStrategy {
+    #[inline]
+    fn default() -> Self {
+        Self::PerChild
+    }
+}
+
+/// How the survivors that breed the next generation are selected.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Serialize, Deserialize)]
+pub enum Selection {
+    /// Keep the fittest candidates outright.
+    Truncation,
+    /// Keep the fittest of `size` candidates drawn at random, with
+    /// replacement, once per survivor. Smaller tournaments lower the
+    /// selection pressure.
+    Tournament { size: usize },
+    /// Draw each survivor at random, with replacement, weighted by rank: of
+    /// n candidates, the fittest weighs n, the next n - 1, and so on down
+    /// to 1. The weights depend only on the order of the scores, so they
+    /// keep the same pressure however narrow the range of scores is.
+    Rank,
+    /// Treat the duplicates of rows, columns and boxes as three objectives,
+    /// and keep candidates front by front, as in NSGA-II: first those no
+    /// other candidate dominates, then those only the first front
+    /// dominates, and so on. The front that does not fit whole keeps its
+    /// most isolated candidates, by crowding distance, to spread the
+    /// survivors along it. Penalties and curriculum weights play no part.
+    Pareto,
+}
+
+impl Default for Selection {
+    #[inline]
+    fn default() -> Self {
+        Self::Truncation
+    }
+}
+
+/// How survivors are paired as parents.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Serialize, Deserialize)]
+pub enum Pairing {
+    /// Pair survivors in ranking order: the fittest with the next fittest,
+    /// and so on down.
+    Adjacent,
+    /// Pair survivors in a random order.
+    Shuffle,
+    /// Pair each survivor of the fitter half, in ranking order, with one of
+    /// the other half drawn at random without replacement.
+    BestWithRandom,
+    /// Pair survivors by ranking as in a round-robin tournament, going
+    /// through its rounds one per generation, so that n survivors are each
+    /// paired with every other over n - 1 generations.
+    RoundRobin,
+}
+
+impl Default for Pairing {
+    #[inline]
+    fn default() -> Self {
+        Self::Adjacent
+    }
+}
+
+/// How the population size changes over the course of a run.
+///
+/// Schedules only ever shrink the population below the size given to
+/// `GAParams::new`. A restart always regenerates a full-sized population.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Serialize, Deserialize)]
+pub enum PopulationSchedule {
+    /// Keep the population at its initial size.
+    Fixed,
+    /// Shrink linearly to `min` over `generations` generations, counted from
+    /// the start of the run or the most recent restart.
+    Linear { min: usize, generations: u64 },
+    /// Shrink linearly towards `min` as the best fitness score of a
+    /// generation falls from `fitness` to 0.
+    Fitness { min: usize, fitness: Fitness },
+}
+
+impl Default for PopulationSchedule {
+    #[inline]
+    fn default() -> Self {
+        Self::Fixed
+    }
+}
+
+/// How the mutation rate changes over the course of a run.
+///
+/// Like the temperature of simulated annealing, the rate starts at the one
+/// given to `GAParams::new`, for exploration, and decays to a hundredth of
+/// it over `generations` generations, counted from the start of the run or
+/// the most recent restart, then stays there.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Serialize, Deserialize)]
+pub enum MutationSchedule {
+    /// Keep the mutation rate as it is.
+    Constant,
+    /// Decay by the same factor every generation.
+    Exponential { generations: u64 },
+    /// Decay by the same amount every generation.
+    Linear { generations: u64 },
+    /// Decay along half a cosine wave: slowly, then quickly, then slowly.
+    Cosine { generations: u64 },
+}
+
+impl Default for MutationSchedule {
+    #[inline]
+    fn default() -> Self {
+        Self::Constant
+    }
+}
+
+/// The fraction of the initial mutation rate a `MutationSchedule` decays
+/// to.
+pub const MUTATION_FLOOR: f32 = 0.01;
+
+/// Settings for bursts of hypermutation, which shake up a stalled population.
+///
+/// Once the best score has not improved for `stall` generations, children
+/// are bred with `factor` times the mutation rate, up to 1.0, for the next
+/// `generations` generations, before it returns to normal.
+#[derive(Debug, Copy, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Hypermutation {
+    /// The number of generations without a better best score before a
+    /// burst.
+    pub stall: u64,
+    /// How many times the mutation rate a burst breeds with.
+    pub factor: f32,
+    /// The number of generations a burst lasts.
+    pub generations: u64,
+}
+
+impl Default for Hypermutation {
+    #[inline]
+    fn default() -> Self {
+        Self {
+            stall: 50,
+            factor: 10.0,
+            generations: 5,
+        }
+    }
+}
+
+/// Decides, generation by generation, which generations' children are bred
+/// in a hypermutation burst.
+#[derive(Debug, Clone)]
+pub(crate) struct Bursts {
+    settings: Hypermutation,
+    stalls: StallCounter,
+    left: u64,
+}
+
+impl Bursts {
+    pub(crate) const fn new(settings: Hypermutation) -> Self {
+        Self {
+            settings,
+            stalls: StallCounter::new(settings.stall),
+            left: 0,
+        }
+    }
+
+    /// Records a scored generation that did not solve the puzzle, and
+    /// returns the parameters to breed its children with: `params` with its
+    /// mutation rate raised during a burst, or `None` to breed with `params`
+    /// as they are. Stalls during a burst do not extend it.
+    pub(crate) fn record(&mut self, params: &GAParams, scores: &[Fitness]) -> Option<GAParams> {
+        if self.stalls.stalled(scores) && self.left == 0 {
+            self.left = self.settings.generations;
+        }
+        if self.left == 0 {
+            return None;
+        }
+
+        self.left -= 1;
+        let rate = (params.mutation_rate * self.settings.factor).clamp(0.0, 1.0);
+        Some(GAParams {
+            mutation_rate: rate,
+            mutation: Bernoulli::new(f64::from(rate)).unwrap(),
+            ..params.clone()
+        })
+    }
+}
+
+/// How candidates encode a board.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Serialize, Deserialize)]
+pub enum Encoding {
+    /// Every free cell holds any digit. Children are bred with the
+    /// crossover and mutation operators.
+    Cells,
+    /// The free cells of each row hold a permutation of the digits its
+    /// givens leave out, so that no row ever repeats a digit. Children are
+    /// bred a row at a time with order crossover and swap mutation, and the
+    /// crossover and mutation operators are not used.
+    Permutation,
+}
+
+impl Default for Encoding {
+    #[inline]
+    fn default() -> Self {
+        Self::Cells
+    }
+}
+
+impl Encoding {
+    /// Scores a candidate bred under this encoding. Permutation rows never
+    /// repeat a digit, so their scoring is skipped.
+    fn fitness<const N: usize>(self, board: &Board<N>) -> Fitness {
+        match self {
+            Self::Cells => board.fitness(),
+            Self::Permutation => board.fitness_with_valid_rows(),
+        }
+    }
+}
+
+/// What replaces a candidate identical to another of its generation, once
+/// the givens are overlaid.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Serialize, Deserialize)]
+pub enum Dedup {
+    /// A fresh random candidate, as the initial population holds.
+    Random,
+    /// A mutated copy of the duplicate, bred as a child of two copies of it.
+    Mutate,
+}
+
+impl Display for Dedup {
+    #[inline]
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Random => write!(f, "random"),
+            Self::Mutate => write!(f, "mutate"),
+        }
+    }
+}
+
+/// Phases box constraints into fitness scores over the first generations
+/// of a run, so that populations organize their rows and columns before
+/// tackling the full set of constraints.
+///
+/// The weight of box duplicates rises linearly from 0.0 to 1.0 over
+/// `generations` generations, counted from the start of the run or the
+/// most recent restart. Solutions are recognized by their full score all
+/// the same.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Serialize, Deserialize)]
+pub struct Curriculum {
+    pub generations: u64,
+}
+
+/// The parameters of a genetic algorithm run, set with the `with_*`
+/// builders.
+///
+/// Some settings drive the generation loop of `GeneticSolver` itself, and
+/// solvers that run generations their own way, such as `IslandSolver` and
+/// `TemperingSolver`, ignore them: a compact population, profiling,
+/// auditing, chunked scoring, column repair, dedup, the tabu archive,
+/// automatic, stall and cataclysmic restarts, hypermutation, and the
+/// backtracking finisher.
+#[derive(Debug, Clone)]
 pub struct GAParams {
     population: usize,
+    selection_rate: f32,
     num_survivors: usize,
-    num_children_per_parent_pairs: usize,
     mutation_rate: f32,
+    mutation: Bernoulli,
+    mutation_schedule: MutationSchedule,
     restart: Option<u64>,
+    rng_strategy: RngStrategy,
+    population_schedule: PopulationSchedule,
+    seed: Option<u64>,
+    compact: bool,
+    profiling: bool,
+    audit: usize,
+    chunk_size: Option<usize>,
+    column_repair: usize,
+    dedup: Option<Dedup>,
+    selection: Selection,
+    crossover: Crossover,
+    mutation_operator: Mutation,
+    encoding: Encoding,
+    weights: UnitWeights,
+    curriculum: Option<Curriculum>,
+    elitism: usize,
+    plus_selection: bool,
+    pairing: Pairing,
+    incest_prevention: usize,
+    children_per_pair: Option<usize>,
+    tabu: usize,
+    local_search: usize,
+    auto_restart: Option<AutoRestart>,
+    stall_restart: Option<StallRestart>,
+    cataclysm: Option<Cataclysm>,
+    hypermutation: Option<Hypermutation>,
+    hybrid: Option<Hybrid>,
+    candidate_overrides: Option<Arc<CandidateOverrides>>,
+    #[cfg(feature = "scripting")]
+    script: Option<Arc<FitnessScript>>,
 }
 
 impl GAParams {
@@ -36,220 +365,3637 @@ impl GAParams {
     /// * `mutation_rate` - the rate at which values should mutate
     /// * `restart` - the number of generations before a population restart
     ///
-    /// # Panics
-    /// Panics if the given population is greater than `MAX_POPULATION`.
+    /// The mutation rate is converted once, here, to a `Bernoulli`
+    /// distribution. The `f32` is widened to `f64` exactly and then scaled to
+    /// a 64-bit threshold, so rates are applied with a resolution of 2^-64: a
+    /// rate of exactly 0.0 never mutates and a rate of exactly 1.0 always
+    /// does.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `ParamError` if the given population is greater than
+    /// `MAX_POPULATION`, if either rate is not between 0.0 and 1.0
+    /// inclusive, or if the population and selection rate keep fewer than
+    /// the two survivors breeding needs.
     #[inline]
-    #[must_use]
     pub fn new(
         population: usize,
         selection_rate: f32,
         mutation_rate: f32,
         restart: Option<u64>,
-    ) -> Self {
-        assert!(population <= MAX_POPULATION);
-        #[allow(
-            clippy::cast_sign_loss,
-            clippy::cast_possible_truncation,
-            clippy::cast_precision_loss
-        )]
-        let num_survivors = (population as f32 * selection_rate).floor() as usize;
-        let num_parent_pairs = num_survivors / 2;
-        let num_children_per_parent_pairs = population / num_parent_pairs;
-        Self {
+    ) -> Result<Self, ParamError> {
+        if population > MAX_POPULATION {
+            return Err(ParamError::PopulationTooLarge {
+                population,
+                max: MAX_POPULATION,
+            });
+        }
+        if !is_rate(selection_rate) {
+            return Err(ParamError::SelectionRate(selection_rate));
+        }
+        let mutation = Bernoulli::new(f64::from(mutation_rate))
+            .map_err(|_| ParamError::MutationRate(mutation_rate))?;
+        let num_survivors = check_parent_pairs(population, selection_rate)?;
+        Ok(Self {
             population,
+            selection_rate,
             num_survivors,
-            num_children_per_parent_pairs,
             mutation_rate,
+            mutation,
+            mutation_schedule: MutationSchedule::default(),
             restart,
-        }
+            rng_strategy: RngStrategy::default(),
+            population_schedule: PopulationSchedule::default(),
+            seed: None,
+            compact: false,
+            profiling: false,
+            audit: 0,
+            chunk_size: None,
+            column_repair: 0,
+            dedup: None,
+            selection: Selection::default(),
+            crossover: Crossover::default(),
+            mutation_operator: Mutation::default(),
+            encoding: Encoding::default(),
+            weights: UnitWeights::FULL,
+            curriculum: None,
+            elitism: 0,
+            plus_selection: false,
+            pairing: Pairing::Adjacent,
+            incest_prevention: 0,
+            children_per_pair: None,
+            tabu: 0,
+            local_search: 0,
+            auto_restart: None,
+            stall_restart: None,
+            cataclysm: None,
+            hypermutation: None,
+            hybrid: None,
+            candidate_overrides: None,
+            #[cfg(feature = "scripting")]
+            script: None,
+        })
     }
-}
-
-/// Generates an initial population.
-///
-/// Generates a randomly initialized population.
-///
-/// # Arguments
-///
-/// * `params` - GA parameters
-///
-/// # Panics
-///
-/// Potentially panics if intermediate vectors cannot be converted to fixed
-/// sized arrays.
-#[inline]
-#[must_use]
-pub fn generate_initial_population<const N: usize, const M: usize>(
-    params: &GAParams,
-) -> Vec<Board<N>> {
-    let max_digit = u8::try_from(N).expect("digit size exceeds 255");
-    let values_range = Uniform::from(1..=max_digit);
-    let mut rng = Pcg64Mcg::from_rng(OsRng).unwrap();
-    let mut boards: Vec<Board<N>> = Vec::with_capacity(M);
 
-    for _ in 0..params.population {
-        let mut board: ArrayVec<Row<N>, N> = ArrayVec::new_const();
+    /// Returns the size of the population.
+    #[inline]
+    #[must_use]
+    pub const fn population(&self) -> usize {
+        self.population
+    }
 
-        for _ in 0..N {
-            let mut row: ArrayVec<u8, N> = ArrayVec::new_const();
+    /// Returns the fraction of the population selected per generation.
+    #[inline]
+    #[must_use]
+    pub const fn selection_rate(&self) -> f32 {
+        self.selection_rate
+    }
 
-            for _ in 0..N {
-                row.push(rng.sample(values_range));
-            }
+    /// Returns the number of survivors selected per generation.
+    #[inline]
+    #[must_use]
+    pub const fn num_survivors(&self) -> usize {
+        self.num_survivors
+    }
 
-            board.push(Row(row.into_inner().unwrap()));
-        }
+    /// Returns the rate at which values mutate.
+    #[inline]
+    #[must_use]
+    pub const fn mutation_rate(&self) -> f32 {
+        self.mutation_rate
+    }
 
-        boards.push(Board(board.into_inner().unwrap()));
+    /// Returns how the mutation rate changes over a run.
+    #[inline]
+    #[must_use]
+    pub const fn mutation_schedule(&self) -> MutationSchedule {
+        self.mutation_schedule
     }
 
-    boards
-}
+    /// Sets how the mutation rate changes over a run. `Constant`, the
+    /// default, keeps it as it is.
+    ///
+    /// # Arguments
+    ///
+    /// * `mutation_schedule` - the mutation schedule to use
+    #[inline]
+    #[must_use]
+    pub const fn with_mutation_schedule(mut self, mutation_schedule: MutationSchedule) -> Self {
+        self.mutation_schedule = mutation_schedule;
+        self
+    }
 
-/// Runs the simulation.
-///
-/// Evaluates the given `population` fitness against the `base` Board to find
-/// the closest to correct solutions. Returns a valid solution.
-///
-/// # Arguments
-///
-/// * `params` - GA parameters
-/// * `generation` - the current generation counter
-/// * `base` - The base Board to find solutions for
-/// * `population` - The population to evaluate fitness for
-///
-/// # Errors
-///
-/// Will return `Err(NoSolutionFound)` containing the next generation if a
-/// valid solution was not found.
-#[inline]
-pub fn run_simulation<const N: usize, const M: usize>(
-    params: &GAParams,
-    generation: u64,
-    base: &Board<N>,
-    population: Vec<Board<N>>,
-) -> Result<Board<N>, NoSolutionFound<N>> {
-    let population_scores: Result<Vec<(Board<N>, u8)>, Board<N>> = population
-        .into_par_iter()
-        .map(|candidate| -> Result<(Board<N>, u8), Board<N>> {
-            let solution = base.overlay(&candidate);
-            let score = solution.fitness();
-            if score == 0 {
-                Err(solution)
-            } else {
-                Ok((solution, score))
+    /// Returns the mutation rate children of `generation` are bred with.
+    #[inline]
+    #[must_use]
+    pub fn scheduled_mutation_rate(&self, generation: u64) -> f32 {
+        let (generations, decay): (u64, fn(f32) -> f32) = match self.mutation_schedule {
+            MutationSchedule::Constant => return self.mutation_rate,
+            MutationSchedule::Exponential { generations } => {
+                (generations, |progress| MUTATION_FLOOR.powf(progress))
             }
-        })
-        .collect();
+            MutationSchedule::Linear { generations } => (generations, |progress| {
+                (1.0 - MUTATION_FLOOR).mul_add(-progress, 1.0)
+            }),
+            MutationSchedule::Cosine { generations } => (generations, |progress| {
+                let wave = f32::midpoint(1.0, (std::f32::consts::PI * progress).cos());
+                (1.0 - MUTATION_FLOOR).mul_add(wave, MUTATION_FLOOR)
+            }),
+        };
+        #[allow(clippy::cast_precision_loss)]
+        let progress = (self.since_restart(generation) as f32 / generations.max(1) as f32).min(1.0);
 
-    match population_scores {
-        Err(valid_solution) => Ok(valid_solution),
-        Ok(population_scores) => {
-            let next_generation = next_generation::<N, M>(params, generation, population_scores);
-            Err(NoSolutionFound { next_generation })
+        (self.mutation_rate * decay(progress)).clamp(0.0, 1.0)
+    }
+
+    /// Returns the mutation distribution children of `generation` are bred
+    /// with.
+    fn scheduled_mutation(&self, generation: u64) -> Bernoulli {
+        match self.mutation_schedule {
+            MutationSchedule::Constant => self.mutation,
+            _ => Bernoulli::new(f64::from(self.scheduled_mutation_rate(generation))).unwrap(),
         }
     }
-}
 
-fn next_generation<const N: usize, const M: usize>(
-    params: &GAParams,
-    generation: u64,
-    population_scores: Vec<(Board<N>, u8)>,
-) -> Vec<Board<N>> {
-    if let Some(restart) = params.restart {
-        if (generation % restart == 0) && (generation != 0) {
-            return generate_initial_population::<N, M>(params);
+    /// Returns the number of generations since the start of the run or the
+    /// most recent restart.
+    const fn since_restart(&self, generation: u64) -> u64 {
+        match self.restart {
+            Some(restart) => generation % restart,
+            None => generation,
         }
     }
 
-    make_parents(natural_selection(params, population_scores))
-        .flat_map(|parents| make_children::<N, M>(params, parents))
-        .collect()
-}
+    /// Returns the number of generations before a population restart.
+    #[inline]
+    #[must_use]
+    pub const fn restart(&self) -> Option<u64> {
+        self.restart
+    }
 
-fn natural_selection<const N: usize>(
-    params: &GAParams,
-    mut population_scores: Vec<(Board<N>, u8)>,
-) -> Vec<Board<N>> {
-    population_scores.par_sort_unstable_by_key(|(_, score)| *score);
+    /// Returns how RNGs are provided to child generation.
+    #[inline]
+    #[must_use]
+    pub const fn rng_strategy(&self) -> RngStrategy {
+        self.rng_strategy
+    }
 
-    population_scores
-        .drain(..params.num_survivors)
-        .map(|(survivor, _)| survivor)
-        .collect()
-}
+    /// Returns how the population size changes over the course of a run.
+    #[inline]
+    #[must_use]
+    pub const fn population_schedule(&self) -> PopulationSchedule {
+        self.population_schedule
+    }
 
-fn make_parents<const N: usize>(
-    survivors: Vec<Board<N>>,
-) -> Zip<IntoIter<Board<N>>, IntoIter<Board<N>>> {
-    let parents: (Vec<Option<Board<N>>>, Vec<Option<Board<N>>>) = survivors
-        .into_par_iter()
-        .enumerate()
-        .map(|(i, survivor)| -> (Option<Board<N>>, Option<Board<N>>) {
-            match i % 2 {
-                0 => (Some(survivor), None),
-                1 => (None, Some(survivor)),
-                _ => (None, None),
-            }
-        })
-        .collect();
+    /// Returns the RNG seed, if the run is seeded.
+    #[inline]
+    #[must_use]
+    pub const fn seed(&self) -> Option<u64> {
+        self.seed
+    }
+
+    /// Seeds every random choice of the run, making it reproducible.
+    ///
+    /// Seeded runs derive one RNG per initial population and per child from
+    /// the seed, the generation and the child's position, so the result does
+    /// not depend on how rayon schedules the work. The RNG strategy is
+    /// ignored.
+    ///
+    /// # Arguments
+    ///
+    /// * `seed` - the seed to derive all RNGs from
+    #[inline]
+    #[must_use]
+    pub const fn with_seed(mut self, seed: u64) -> Self {
+        self.seed = Some(seed);
+        self
+    }
 
-    let parents_x = parents
-        .0
-        .into_iter()
-        .flatten()
-        .collect::<Vec<Board<N>>>()
-        .into_par_iter();
+    /// Returns true if the population is stored in a `PopulationArena`.
+    #[inline]
+    #[must_use]
+    pub const fn compact(&self) -> bool {
+        self.compact
+    }
 
-    let parents_y = parents
-        .1
-        .into_iter()
-        .flatten()
-        .collect::<Vec<Board<N>>>()
-        .into_par_iter();
+    /// Stores the population in a contiguous `PopulationArena` instead of a
+    /// `Vec<Board<N>>`, so that breeding writes children in place rather than
+    /// moving boards around.
+    ///
+    /// # Arguments
+    ///
+    /// * `compact` - whether to use a compact population
+    #[inline]
+    #[must_use]
+    pub const fn with_compact_population(mut self, compact: bool) -> Self {
+        self.compact = compact;
+        self
+    }
 
-    parents_x.zip(parents_y)
-}
+    /// Returns true if the time spent in each phase of a generation is
+    /// measured.
+    #[inline]
+    #[must_use]
+    pub const fn profiling(&self) -> bool {
+        self.profiling
+    }
 
-fn make_children<const N: usize, const M: usize>(
-    params: &GAParams,
-    parents: (Board<N>, Board<N>),
-) -> Vec<Board<N>> {
-    let Board(parent_x) = parents.0;
-    let Board(parent_y) = parents.1;
+    /// Measures the time spent in each phase of every generation, and
+    /// reports it as `Metrics::Phases`. Timing every child adds a little
+    /// overhead, so this is off by default.
+    ///
+    /// # Arguments
+    ///
+    /// * `profiling` - whether to profile generations
+    #[inline]
+    #[must_use]
+    pub const fn with_profiling(mut self, profiling: bool) -> Self {
+        self.profiling = profiling;
+        self
+    }
 
-    let max_digit = u8::try_from(N).expect("digit size exceeds 255");
-    let values_range: Uniform<u8> = Uniform::from(1..=max_digit);
-    let mutation_rate = f64::from(params.mutation_rate);
+    /// Returns the number of children sampled for an audit each generation.
+    #[inline]
+    #[must_use]
+    pub const fn audit(&self) -> usize {
+        self.audit
+    }
 
-    (0..params.num_children_per_parent_pairs)
-        .into_par_iter()
-        .map(|_| {
-            let mut rng = Pcg64Mcg::from_rng(OsRng).unwrap();
-            let mut child: ArrayVec<Row<N>, N> = ArrayVec::new_const();
-
-            for i in 0..N {
-                let Row(x_values) = parent_x[i];
-                let Row(y_values) = parent_y[i];
-                let mut child_values: ArrayVec<u8, N> = ArrayVec::new_const();
-
-                for j in 0..N {
-                    if rng.gen_bool(mutation_rate) {
-                        child_values.push(rng.sample(values_range));
-                        continue;
-                    }
-
-                    if rng.gen_bool(0.5) {
-                        child_values.push(x_values[j]);
-                    } else {
-                        child_values.push(y_values[j]);
-                    }
-                }
+    /// Breeds `samples` extra children each generation, recording exactly
+    /// how crossover and mutation made them, and reports them through
+    /// `Observer::on_audit` for statistical tests of the operators, such as
+    /// `AuditStats`. The extra children are not added to the population,
+    /// and a seeded run draws them from RNG streams of their own, so
+    /// auditing never changes the course of a run. 0, the default, turns
+    /// auditing off.
+    ///
+    /// # Arguments
+    ///
+    /// * `samples` - the number of children to audit per generation
+    #[inline]
+    #[must_use]
+    pub const fn with_audit(mut self, samples: usize) -> Self {
+        self.audit = samples;
+        self
+    }
 
-                child.push(Row(child_values.into_inner().unwrap()));
-            }
+    /// Returns the number of candidates scored between checkpoints, if
+    /// generations are scored in chunks.
+    #[inline]
+    #[must_use]
+    pub const fn chunk_size(&self) -> Option<usize> {
+        self.chunk_size
+    }
 
-            Board(child.into_inner().unwrap())
-        })
-        .collect()
+    /// Scores each generation `size` candidates at a time, with a checkpoint
+    /// between chunks at which the run can be cancelled or run out of time,
+    /// and which is reported through `Observer::on_chunk`. Scoring a huge
+    /// population at once can otherwise hold up cancellation, progress and
+    /// the time budget for seconds. The scores are the same either way.
+    /// `None`, the default, scores whole generations. A compact population
+    /// is always scored whole.
+    ///
+    /// # Arguments
+    ///
+    /// * `size` - the number of candidates to score between checkpoints
+    ///
+    /// # Errors
+    ///
+    /// Returns `ParamError::ChunkSize` if `size` is 0.
+    #[inline]
+    pub fn with_chunk_size(mut self, size: Option<usize>) -> Result<Self, ParamError> {
+        if matches!(size, Some(0)) {
+            return Err(ParamError::ChunkSize);
+        }
+        self.chunk_size = size;
+        Ok(self)
+    }
+
+    /// Returns the most column repair swaps made per candidate.
+    #[inline]
+    #[must_use]
+    pub const fn column_repair(&self) -> usize {
+        self.column_repair
+    }
+
+    /// Repairs each candidate with `Board::repair_columns` before it is
+    /// scored, making up to `swaps` swaps. 0, the default, turns the repair
+    /// off.
+    ///
+    /// # Arguments
+    ///
+    /// * `swaps` - the most swaps to make per candidate
+    #[inline]
+    #[must_use]
+    pub const fn with_column_repair(mut self, swaps: usize) -> Self {
+        self.column_repair = swaps;
+        self
+    }
+
+    /// Returns what replaces duplicate candidates, if they are replaced.
+    #[inline]
+    #[must_use]
+    pub const fn dedup(&self) -> Option<Dedup> {
+        self.dedup
+    }
+
+    /// Replaces every candidate identical to one before it in its
+    /// generation, once the givens are overlaid, before the generation is
+    /// scored. `None`, the default, keeps duplicates.
+    ///
+    /// # Arguments
+    ///
+    /// * `dedup` - what replaces each duplicate
+    #[inline]
+    #[must_use]
+    pub const fn with_dedup(mut self, dedup: Option<Dedup>) -> Self {
+        self.dedup = dedup;
+        self
+    }
+
+    /// Returns how survivors are selected.
+    #[inline]
+    #[must_use]
+    pub const fn selection(&self) -> Selection {
+        self.selection
+    }
+
+    /// Sets how survivors are selected. Truncation, the default, converges
+    /// fastest; tournaments and rank selection give less fit candidates a
+    /// chance, which keeps more diversity on hard boards.
+    ///
+    /// # Arguments
+    ///
+    /// * `selection` - the selection scheme to use
+    ///
+    /// # Errors
+    ///
+    /// Returns `ParamError::TournamentSize` if a tournament has no
+    /// candidates.
+    #[inline]
+    pub fn with_selection(mut self, selection: Selection) -> Result<Self, ParamError> {
+        if matches!(selection, Selection::Tournament { size: 0 }) {
+            return Err(ParamError::TournamentSize);
+        }
+        self.selection = selection;
+        Ok(self)
+    }
+
+    /// Returns the crossover operator children are bred with.
+    #[inline]
+    #[must_use]
+    pub const fn crossover(&self) -> Crossover {
+        self.crossover
+    }
+
+    /// Breeds children with `crossover`. The default is uniform crossover.
+    #[inline]
+    #[must_use]
+    pub const fn with_crossover(mut self, crossover: Crossover) -> Self {
+        self.crossover = crossover;
+        self
+    }
+
+    /// Returns the mutation operator children are mutated with.
+    #[inline]
+    #[must_use]
+    pub const fn mutation_operator(&self) -> Mutation {
+        self.mutation_operator
+    }
+
+    /// Mutates children with `mutation`, at the mutation rate. The default
+    /// is random resetting.
+    #[inline]
+    #[must_use]
+    pub const fn with_mutation_operator(mut self, mutation: Mutation) -> Self {
+        self.mutation_operator = mutation;
+        self
+    }
+
+    /// Returns how candidates encode a board.
+    #[inline]
+    #[must_use]
+    pub const fn encoding(&self) -> Encoding {
+        self.encoding
+    }
+
+    /// Encodes candidates with `encoding`. The default encodes cells.
+    #[inline]
+    #[must_use]
+    pub const fn with_encoding(mut self, encoding: Encoding) -> Self {
+        self.encoding = encoding;
+        self
+    }
+
+    /// Returns how much the duplicates in each kind of unit count towards
+    /// fitness scores.
+    #[inline]
+    #[must_use]
+    pub const fn weights(&self) -> UnitWeights {
+        self.weights
+    }
+
+    /// Selects candidates by their duplicates in each kind of unit weighted
+    /// by `weights`, such as to over-weigh box constraints on puzzles whose
+    /// boxes are hard to satisfy. `UnitWeights::FULL`, the default, counts
+    /// every duplicate once. Scores, and the solutions they recognize, stay
+    /// unweighted all the same.
+    #[inline]
+    #[must_use]
+    pub const fn with_weights(mut self, weights: UnitWeights) -> Self {
+        self.weights = weights;
+        self
+    }
+
+    /// Returns the curriculum box constraints are phased in with, if any.
+    #[inline]
+    #[must_use]
+    pub const fn curriculum(&self) -> Option<Curriculum> {
+        self.curriculum
+    }
+
+    /// Phases box constraints in with `curriculum`, up to their weight.
+    /// `None`, the default, weighs every constraint fully from the start.
+    #[inline]
+    #[must_use]
+    pub const fn with_curriculum(mut self, curriculum: Option<Curriculum>) -> Self {
+        self.curriculum = curriculum;
+        self
+    }
+
+    /// Returns the weights candidates are selected with in `generation`.
+    #[inline]
+    #[must_use]
+    pub fn unit_weights(&self, generation: u64) -> UnitWeights {
+        let weights = self.weights;
+        self.curriculum
+            .map_or(weights, |Curriculum { generations }| {
+                #[allow(clippy::cast_precision_loss)]
+                let phase =
+                    (self.since_restart(generation) as f32 / generations.max(1) as f32).min(1.0);
+
+                UnitWeights {
+                    boxes: weights.boxes * phase,
+                    ..weights
+                }
+            })
+    }
+
+    /// Returns the script that adds a custom term to every fitness score, if
+    /// there is one.
+    #[cfg(feature = "scripting")]
+    #[inline]
+    #[must_use]
+    pub const fn script(&self) -> Option<&Arc<FitnessScript>> {
+        self.script.as_ref()
+    }
+
+    /// Adds the penalty `script` gives each candidate to its fitness score,
+    /// so that only boards that also satisfy its constraint are solutions.
+    /// Candidates the script fails on score `Fitness::MAX`.
+    #[cfg(feature = "scripting")]
+    #[inline]
+    #[must_use]
+    pub fn with_script(mut self, script: Option<Arc<FitnessScript>>) -> Self {
+        self.script = script;
+        self
+    }
+
+    /// Returns the digits particular cells are restricted to, if any are.
+    #[inline]
+    #[must_use]
+    pub const fn candidate_overrides(&self) -> Option<&Arc<CandidateOverrides>> {
+        self.candidate_overrides.as_ref()
+    }
+
+    /// Restricts cells to the digits `overrides` allows them. Candidates are
+    /// kept within the overrides as they are initialized and mutated, and
+    /// score a point for each cell outside them, so that only boards that
+    /// respect them are solutions. `None`, the default, restricts no cells.
+    ///
+    /// # Arguments
+    ///
+    /// * `overrides` - the cells to restrict and their allowed digits
+    #[inline]
+    #[must_use]
+    pub fn with_candidate_overrides(mut self, overrides: Option<Arc<CandidateOverrides>>) -> Self {
+        self.candidate_overrides = overrides;
+        self
+    }
+
+    /// Returns the number of fittest candidates carried over unchanged into
+    /// each next generation.
+    #[inline]
+    #[must_use]
+    pub const fn elitism(&self) -> usize {
+        self.elitism
+    }
+
+    /// Carries the `elites` fittest candidates of each generation over to
+    /// the next one unchanged, in place of as many children, so that the
+    /// best score never gets worse between restarts. 0, the default, carries
+    /// none over.
+    ///
+    /// # Arguments
+    ///
+    /// * `elites` - the number of candidates to carry over
+    #[inline]
+    #[must_use]
+    pub const fn with_elitism(mut self, elites: usize) -> Self {
+        self.elitism = elites;
+        self
+    }
+
+    /// Returns whether survivors compete with their children for survival.
+    #[inline]
+    #[must_use]
+    pub const fn plus_selection(&self) -> bool {
+        self.plus_selection
+    }
+
+    /// Carries the survivors of each generation over to the next one
+    /// unchanged, alongside their children, as a (μ+λ) evolution strategy
+    /// does, so that parents compete with their children for survival
+    /// instead of being discarded once bred. Only as many children are bred
+    /// as the population has room for beside the survivors. False, the
+    /// default, replaces the survivors with their children.
+    ///
+    /// # Arguments
+    ///
+    /// * `plus_selection` - whether to carry the survivors over
+    #[inline]
+    #[must_use]
+    pub const fn with_plus_selection(mut self, plus_selection: bool) -> Self {
+        self.plus_selection = plus_selection;
+        self
+    }
+
+    /// Returns how survivors are paired as parents.
+    #[inline]
+    #[must_use]
+    pub const fn pairing(&self) -> Pairing {
+        self.pairing
+    }
+
+    /// Sets how survivors are paired as parents. Adjacent pairing, the
+    /// default, always breeds the fittest two survivors together; the other
+    /// policies mix fitter survivors with less fit ones.
+    ///
+    /// # Arguments
+    ///
+    /// * `pairing` - the pairing policy to use
+    #[inline]
+    #[must_use]
+    pub const fn with_pairing(mut self, pairing: Pairing) -> Self {
+        self.pairing = pairing;
+        self
+    }
+
+    /// Returns the least number of cells in which a pair of parents should
+    /// differ, or 0 if any survivors may be paired.
+    #[inline]
+    #[must_use]
+    pub const fn incest_prevention(&self) -> usize {
+        self.incest_prevention
+    }
+
+    /// Keeps near-identical survivors from being paired as parents, whose
+    /// children would waste evaluations on boards already in the
+    /// population. Survivors are paired in the order `with_pairing` gives,
+    /// each with the next unpaired survivor that differs from it in at least
+    /// `min_distance` cells, looking up to `MATE_WINDOW` survivors ahead,
+    /// or failing that with the most distant of them. 0, the default,
+    /// pairs survivors in ranking order.
+    ///
+    /// # Arguments
+    ///
+    /// * `min_distance` - the least Hamming distance between parents
+    #[inline]
+    #[must_use]
+    pub const fn with_incest_prevention(mut self, min_distance: usize) -> Self {
+        self.incest_prevention = min_distance;
+        self
+    }
+
+    /// Returns how many children each pair of parents breeds, or `None` if
+    /// it follows from the population size.
+    #[inline]
+    #[must_use]
+    pub const fn children_per_pair(&self) -> Option<usize> {
+        self.children_per_pair
+    }
+
+    /// Sets how many children each pair of parents breeds. Children beyond
+    /// the room in the next generation are dropped, and the room they leave
+    /// is topped up with random immigrants, so that the population size
+    /// stays as scheduled. `None`, the default, shares the room out between
+    /// the pairs, the first pairs breeding one child more when it does not
+    /// divide evenly, so that no immigrants are needed.
+    ///
+    /// # Arguments
+    ///
+    /// * `children` - the number of children per pair of parents
+    ///
+    /// # Errors
+    ///
+    /// Returns `ParamError::ChildrenPerPair` if `children` is 0.
+    #[inline]
+    pub fn with_children_per_pair(mut self, children: Option<usize>) -> Result<Self, ParamError> {
+        if matches!(children, Some(0)) {
+            return Err(ParamError::ChildrenPerPair);
+        }
+        self.children_per_pair = children;
+        Ok(self)
+    }
+
+    /// Returns how many children the pairs of `num_survivors` survivors
+    /// breed towards `num_children` children.
+    fn brood(&self, num_children: usize, num_survivors: usize) -> Brood {
+        let pairs = num_survivors / 2;
+
+        self.children_per_pair.map_or(
+            Brood {
+                pairs,
+                per_pair: num_children / pairs,
+                extra: num_children % pairs,
+            },
+            |per_pair| Brood {
+                pairs,
+                per_pair,
+                extra: 0,
+            },
+        )
+    }
+
+    /// Returns the most best boards of past generations kept in the tabu
+    /// archive, or 0 if there is none.
+    #[inline]
+    #[must_use]
+    pub const fn tabu(&self) -> usize {
+        self.tabu
+    }
+
+    /// Keeps the best boards of the last `capacity` generations in a tabu
+    /// archive, and replaces each child identical to one of them with a
+    /// mutated copy of it, so that the search does not cycle back through
+    /// the same local optima. Candidates carried over unchanged are left
+    /// alone. 0, the default, keeps no archive.
+    ///
+    /// # Arguments
+    ///
+    /// * `capacity` - the most boards to keep in the archive
+    #[inline]
+    #[must_use]
+    pub const fn with_tabu(mut self, capacity: usize) -> Self {
+        self.tabu = capacity;
+        self
+    }
+
+    /// Returns the most local search steps taken per child.
+    #[inline]
+    #[must_use]
+    pub const fn local_search(&self) -> usize {
+        self.local_search
+    }
+
+    /// Hill-climbs each child after crossover and mutation, making this a
+    /// memetic algorithm. Each of up to `steps` steps swaps two free cells
+    /// of a random row, and keeps the swap only if it lowers the child's
+    /// fitness score. Rows keep their digits, so only column and box
+    /// duplicates change. 0, the default, turns local search off.
+    ///
+    /// # Arguments
+    ///
+    /// * `steps` - the most swaps to try per child
+    #[inline]
+    #[must_use]
+    pub const fn with_local_search(mut self, steps: usize) -> Self {
+        self.local_search = steps;
+        self
+    }
+
+    /// Returns the settings for automatic restarts, if they are on.
+    #[inline]
+    #[must_use]
+    pub const fn auto_restart(&self) -> Option<AutoRestart> {
+        self.auto_restart
+    }
+
+    /// Restarts the population whenever a fresh one is expected to solve
+    /// the puzzle sooner, as described by `AutoRestart`, in addition to any
+    /// fixed restarts. `None`, the default, turns automatic restarts off.
+    ///
+    /// # Arguments
+    ///
+    /// * `settings` - when to consider a restart
+    #[inline]
+    #[must_use]
+    pub const fn with_auto_restart(mut self, settings: Option<AutoRestart>) -> Self {
+        self.auto_restart = settings;
+        self
+    }
+
+    /// Returns the settings for restarts after the best score stalls, if
+    /// they are on.
+    #[inline]
+    #[must_use]
+    pub const fn stall_restart(&self) -> Option<StallRestart> {
+        self.stall_restart
+    }
+
+    /// Restarts all but the fittest candidates of the population whenever
+    /// the best score has not improved for a while, as described by
+    /// `StallRestart`, in addition to any other restarts. `None`, the
+    /// default, turns these restarts off.
+    ///
+    /// # Arguments
+    ///
+    /// * `settings` - when to restart, and how many candidates to keep
+    #[inline]
+    #[must_use]
+    pub const fn with_stall_restart(mut self, settings: Option<StallRestart>) -> Self {
+        self.stall_restart = settings;
+        self
+    }
+
+    /// Returns the settings for cataclysmic restarts, if they are on.
+    #[inline]
+    #[must_use]
+    pub const fn cataclysm(&self) -> Option<Cataclysm> {
+        self.cataclysm
+    }
+
+    /// Restarts the population around its fittest candidate whenever the
+    /// best score has not improved for a while, as described by
+    /// `Cataclysm`, in place of any stall restarts. `None`, the default,
+    /// turns these restarts off.
+    ///
+    /// # Arguments
+    ///
+    /// * `settings` - when to restart, and how heavily to mutate the copies
+    ///
+    /// # Errors
+    ///
+    /// Returns `ParamError::CataclysmRate` if the rate is not between 0.0
+    /// and 1.0.
+    #[inline]
+    pub fn with_cataclysm(mut self, settings: Option<Cataclysm>) -> Result<Self, ParamError> {
+        if let Some(settings) = settings {
+            if !is_rate(settings.rate) {
+                return Err(ParamError::CataclysmRate(settings.rate));
+            }
+        }
+        self.cataclysm = settings;
+        Ok(self)
+    }
+
+    /// Returns the number of generations without a better best score after
+    /// which the population restarts, keeping its fittest candidates, if
+    /// cataclysmic or stall restarts are on.
+    pub(crate) fn stall_generations(&self) -> Option<u64> {
+        self.cataclysm
+            .map(|settings| settings.generations)
+            .or_else(|| self.stall_restart.map(|settings| settings.generations))
+    }
+
+    /// Returns the settings for hypermutation bursts, if they are on.
+    #[inline]
+    #[must_use]
+    pub const fn hypermutation(&self) -> Option<Hypermutation> {
+        self.hypermutation
+    }
+
+    /// Raises the mutation rate for a few generations whenever the best
+    /// score has not improved for a while, as described by `Hypermutation`.
+    /// `None`, the default, turns these bursts off.
+    ///
+    /// # Arguments
+    ///
+    /// * `settings` - when to burst, by how much and for how long
+    ///
+    /// # Errors
+    ///
+    /// Returns `ParamError::HypermutationFactor` if the burst's factor is
+    /// negative or not finite.
+    #[inline]
+    pub fn with_hypermutation(
+        mut self,
+        settings: Option<Hypermutation>,
+    ) -> Result<Self, ParamError> {
+        if let Some(settings) = settings {
+            if !settings.factor.is_finite() || settings.factor < 0.0 {
+                return Err(ParamError::HypermutationFactor(settings.factor));
+            }
+        }
+        self.hypermutation = settings;
+        Ok(self)
+    }
+
+    /// Returns the settings for handing stalled runs to the backtracking
+    /// finisher, if it is on.
+    #[inline]
+    #[must_use]
+    pub const fn hybrid(&self) -> Option<Hybrid> {
+        self.hybrid
+    }
+
+    /// Hands the fittest candidate to an exact backtracking finisher once
+    /// the best score is low and has stopped improving, as described by
+    /// `Hybrid`. `None`, the default, leaves every run to the GA.
+    ///
+    /// # Arguments
+    ///
+    /// * `settings` - how close to a solution, and how stalled, a run must
+    ///   be before a handoff
+    #[inline]
+    #[must_use]
+    pub const fn with_hybrid(mut self, settings: Option<Hybrid>) -> Self {
+        self.hybrid = settings;
+        self
+    }
+
+    /// Sets how RNGs are provided to child generation.
+    ///
+    /// # Arguments
+    ///
+    /// * `rng_strategy` - the RNG strategy to use
+    #[inline]
+    #[must_use]
+    pub const fn with_rng_strategy(mut self, rng_strategy: RngStrategy) -> Self {
+        self.rng_strategy = rng_strategy;
+        self
+    }
+
+    /// Sets the size of the population, such as to split a population
+    /// between islands.
+    ///
+    /// # Arguments
+    ///
+    /// * `population` - the size of the population to use
+    ///
+    /// # Errors
+    ///
+    /// Returns a `ParamError` if the population is greater than
+    /// `MAX_POPULATION`, smaller than the population schedule's minimum
+    /// population, or keeps fewer than the two survivors breeding needs.
+    #[inline]
+    pub fn with_population(mut self, population: usize) -> Result<Self, ParamError> {
+        if population > MAX_POPULATION {
+            return Err(ParamError::PopulationTooLarge {
+                population,
+                max: MAX_POPULATION,
+            });
+        }
+        check_schedule(population, self.selection_rate, self.population_schedule)?;
+        self.population = population;
+        self.num_survivors = survivors(population, self.selection_rate);
+        Ok(self)
+    }
+
+    /// Sets how the population size changes over the course of a run.
+    ///
+    /// # Arguments
+    ///
+    /// * `population_schedule` - the population schedule to use
+    ///
+    /// # Errors
+    ///
+    /// Returns a `ParamError` if the schedule's minimum population is larger
+    /// than the population, or too small to select at least one pair of
+    /// parents.
+    #[inline]
+    pub fn with_population_schedule(
+        mut self,
+        population_schedule: PopulationSchedule,
+    ) -> Result<Self, ParamError> {
+        check_schedule(self.population, self.selection_rate, population_schedule)?;
+        self.population_schedule = population_schedule;
+        Ok(self)
+    }
+
+    /// Returns the population size scheduled for the next generation.
+    ///
+    /// # Arguments
+    ///
+    /// * `generation` - the generation that was just evaluated
+    /// * `best` - the best fitness score of that generation
+    #[inline]
+    #[must_use]
+    pub fn scheduled_population(&self, generation: u64, best: Fitness) -> usize {
+        let shrink = |min: usize, numerator: u64, denominator: u64| {
+            let range = (self.population - min) as u64;
+            let shrunk = range.saturating_mul(numerator.min(denominator)) / denominator;
+            usize::try_from(shrunk).map_or(min, |shrunk| self.population - shrunk)
+        };
+
+        match self.population_schedule {
+            PopulationSchedule::Fixed => self.population,
+            PopulationSchedule::Linear { min, generations } => {
+                shrink(min, self.since_restart(generation) + 1, generations.max(1))
+            }
+            PopulationSchedule::Fitness { min, fitness } => {
+                let improvement = fitness.saturating_sub(best);
+                shrink(
+                    min,
+                    u64::from(improvement.get()),
+                    u64::from(fitness.get().max(1)),
+                )
+            }
+        }
+    }
+}
+
+/// Returns true if `rate` is a valid probability, between 0.0 and 1.0
+/// inclusive. NaN is not.
+#[inline]
+#[must_use]
+pub fn is_rate(rate: f32) -> bool {
+    (0.0..=1.0).contains(&rate)
+}
+
+/// Returns the number of survivors a population of `population` keeps at
+/// `selection_rate`, or an error if they make no pair of parents.
+fn check_parent_pairs(population: usize, selection_rate: f32) -> Result<usize, ParamError> {
+    let num_survivors = survivors(population, selection_rate);
+    if num_survivors < 2 {
+        return Err(ParamError::NoParentPairs {
+            population,
+            selection_rate,
+            survivors: num_survivors,
+        });
+    }
+    Ok(num_survivors)
+}
+
+/// Checks that `schedule` keeps a population of `population` at
+/// `selection_rate` at least as large as its minimum, and the minimum
+/// large enough to breed from, along with the population itself.
+fn check_schedule(
+    population: usize,
+    selection_rate: f32,
+    schedule: PopulationSchedule,
+) -> Result<(), ParamError> {
+    check_parent_pairs(population, selection_rate)?;
+    match schedule {
+        PopulationSchedule::Fixed => Ok(()),
+        PopulationSchedule::Linear { min, .. } | PopulationSchedule::Fitness { min, .. } => {
+            if min > population {
+                return Err(ParamError::ScheduleMinimum { population, min });
+            }
+            check_parent_pairs(min, selection_rate).map(|_| ())
+        }
+    }
+}
+
+fn survivors(population: usize, selection_rate: f32) -> usize {
+    #[allow(
+        clippy::cast_sign_loss,
+        clippy::cast_possible_truncation,
+        clippy::cast_precision_loss
+    )]
+    let num_survivors = (population as f32 * selection_rate).floor() as usize;
+    num_survivors
+}
+
+/// Generates an initial population.
+///
+/// Generates a randomly initialized population. With
+/// `Encoding::Permutation`, the rows of each candidate permute the digits
+/// the rows of `base` are missing.
+///
+/// Candidates are drawn from an `R`, seeded from the OS or, in a seeded
+/// run, derived from the seed. `DefaultRng` is what the solvers use.
+///
+/// # Arguments
+///
+/// * `params` - GA parameters
+/// * `base` - The base Board to find solutions for
+///
+/// # Panics
+///
+/// Potentially panics if intermediate vectors cannot be converted to fixed
+/// sized arrays.
+#[inline]
+#[must_use]
+pub fn generate_initial_population<const N: usize, const M: usize, R: Rng + SeedableRng>(
+    params: &GAParams,
+    base: &Board<N>,
+) -> Vec<Board<N>> {
+    initial_population::<N, M, R>(params, base, 0)
+}
+
+pub(crate) fn initial_population<const N: usize, const M: usize, R: Rng + SeedableRng>(
+    params: &GAParams,
+    base: &Board<N>,
+    generation: u64,
+) -> Vec<Board<N>> {
+    let mut boards: Vec<Board<N>> = Vec::with_capacity(M);
+    fill_initial_population::<N, R>(params, base, generation, &mut boards);
+    boards
+}
+
+/// Replaces all but the fittest candidates of a scored population with a
+/// random initial population, keeping as many as `params`'s stall restarts
+/// do, or with a cataclysm if `params` has cataclysmic restarts on. The
+/// kept candidates come last, as elites do.
+pub(crate) fn restart_keeping<const N: usize>(
+    params: &GAParams,
+    base: &Board<N>,
+    generation: u64,
+    population: &mut Vec<Board<N>>,
+    scores: &[Fitness],
+) {
+    if let Some(settings) = params.cataclysm {
+        cataclysm(params, settings, base, generation, population, scores);
+        return;
+    }
+
+    let keep = params.stall_restart.map_or(0, |settings| settings.keep);
+    let kept: Vec<Board<N>> = rank(keep.min(params.population), scores)
+        .iter()
+        .map(|&i| population[i])
+        .collect();
+
+    fill_initial_population::<N, DefaultRng>(params, base, generation, population);
+    population.truncate(params.population - kept.len());
+    population.extend(kept);
+}
+
+/// Replaces all but the fittest candidate of a scored population with
+/// copies of it bred at the mutation rate of `settings`, as described by
+/// `Cataclysm`. The champion comes last, as elites do.
+fn cataclysm<const N: usize>(
+    params: &GAParams,
+    settings: Cataclysm,
+    base: &Board<N>,
+    generation: u64,
+    population: &mut Vec<Board<N>>,
+    scores: &[Fitness],
+) {
+    let Some(&fittest) = rank(1, scores).first() else {
+        return;
+    };
+    let champion = population[fittest];
+    let operators = Operators {
+        mutation_rate: Bernoulli::new(f64::from(settings.rate)).unwrap(),
+        ..Operators::new(params, generation, None)
+    };
+
+    population.clear();
+    population.par_extend((0..params.population - 1).into_par_iter().map(|i| {
+        let mut rng: DefaultRng = params.seed.map_or_else(
+            || DefaultRng::from_rng(OsRng).unwrap(),
+            |seed| seeded_rng(seed, [generation, CATACLYSM_STREAM, i as u64]),
+        );
+        make_child(
+            &mut rng,
+            base,
+            &(champion, champion),
+            operators,
+            |_, _, _| {},
+        )
+    }));
+    population.push(champion);
+}
+
+/// Replaces the contents of `boards` with a random initial population,
+/// keeping its allocation.
+pub(crate) fn fill_initial_population<const N: usize, R: Rng + SeedableRng>(
+    params: &GAParams,
+    base: &Board<N>,
+    generation: u64,
+    boards: &mut Vec<Board<N>>,
+) {
+    let mut rng: R = params.seed.map_or_else(
+        || R::from_rng(OsRng).unwrap(),
+        |seed| seeded_rng(seed, [generation, INITIAL_STREAM, u64::MAX]),
+    );
+    boards.clear();
+    boards.extend((0..params.population).map(|_| random_genome(params, base, &mut rng)));
+
+    if let Some(overrides) = &params.candidate_overrides {
+        let permutation = params.encoding == Encoding::Permutation;
+        for board in boards.iter_mut() {
+            for (i, row) in board.0.iter_mut().enumerate() {
+                overrides.enforce_row(&mut rng, i, &base.0[i].0, &mut row.0, permutation, |_| {});
+            }
+        }
+    }
+}
+
+/// Returns `count` random candidates, with any candidate overrides
+/// enforced, to top up a generation for which too few children were bred.
+fn immigrants<const N: usize, R: Rng + SeedableRng>(
+    params: &GAParams,
+    base: &Board<N>,
+    generation: u64,
+    count: usize,
+) -> Vec<Board<N>> {
+    if count == 0 {
+        return Vec::new();
+    }
+
+    let mut rng: R = params.seed.map_or_else(
+        || R::from_rng(OsRng).unwrap(),
+        |seed| seeded_rng(seed, [generation, IMMIGRANT_STREAM, u64::MAX]),
+    );
+    let permutation = params.encoding == Encoding::Permutation;
+    (0..count)
+        .map(|_| {
+            let mut board = random_genome(params, base, &mut rng);
+            if let Some(overrides) = &params.candidate_overrides {
+                for (i, row) in board.0.iter_mut().enumerate() {
+                    overrides.enforce_row(
+                        &mut rng,
+                        i,
+                        &base.0[i].0,
+                        &mut row.0,
+                        permutation,
+                        |_| {},
+                    );
+                }
+            }
+            board
+        })
+        .collect()
+}
+
+/// Returns a random candidate for `base` in `params`'s encoding, before
+/// any candidate overrides are enforced.
+fn random_genome<const N: usize, R: Rng>(
+    params: &GAParams,
+    base: &Board<N>,
+    rng: &mut R,
+) -> Board<N> {
+    if params.encoding == Encoding::Permutation {
+        return permutation::random_board(rng, base);
+    }
+
+    let max_digit = u8::try_from(N).expect("digit size exceeds 255");
+    let values_range = Uniform::from(1..=max_digit);
+    let mut board: ArrayVec<Row<N>, N> = ArrayVec::new_const();
+
+    for _ in 0..N {
+        let mut row: ArrayVec<u8, N> = ArrayVec::new_const();
+
+        for _ in 0..N {
+            row.push(rng.sample(values_range));
+        }
+
+        board.push(Row(row.into_inner().unwrap()));
+    }
+
+    Board(board.into_inner().unwrap())
+}
+
+/// Replaces every candidate identical to one before it, once the givens of
+/// `base` are overlaid, as `params`'s dedup setting asks, and returns how
+/// many were replaced.
+///
+/// A replacement that is itself a duplicate is drawn again, up to
+/// `DEDUP_ATTEMPTS` times, and kept after that. Candidates are compared by
+/// the hash of their cells and replaced in place, so an arena is never
+/// copied out as a whole.
+pub(crate) fn dedup_population<const N: usize>(
+    params: &GAParams,
+    base: &Board<N>,
+    generation: u64,
+    population: &mut impl Population<N>,
+) -> usize {
+    const DEDUP_ATTEMPTS: usize = 4;
+
+    let Some(dedup) = params.dedup else {
+        return 0;
+    };
+    let operators = Operators::new(params, generation, None);
+    let mut seen = Seen::with_capacity(population.len());
+    let mut replaced = 0;
+
+    for i in 0..population.len() {
+        let mut candidate = population.board(i);
+        if seen.insert(base, &*population, i, &candidate) {
+            continue;
+        }
+
+        let mut rng: DefaultRng = params.seed.map_or_else(
+            || DefaultRng::from_rng(OsRng).unwrap(),
+            |seed| seeded_rng(seed, [generation, DEDUP_STREAM, i as u64]),
+        );
+        for _ in 0..DEDUP_ATTEMPTS {
+            candidate = match dedup {
+                Dedup::Random => {
+                    let mut board = random_genome(params, base, &mut rng);
+                    enforce_overrides(&mut rng, base, &mut board, operators, |_, _| {});
+                    board
+                }
+                Dedup::Mutate => make_child(
+                    &mut rng,
+                    base,
+                    &(candidate, candidate),
+                    operators,
+                    |_, _, _| {},
+                ),
+            };
+            if seen.insert(base, &*population, i, &candidate) {
+                break;
+            }
+        }
+        population.set(i, &candidate);
+        replaced += 1;
+    }
+
+    replaced
+}
+
+/// The candidates of a population kept so far by `dedup_population`, by
+/// the hash of their cells once the givens are overlaid.
+struct Seen {
+    hasher: RandomState,
+    slots: HashMap<u64, Vec<usize>>,
+}
+
+impl Seen {
+    fn with_capacity(capacity: usize) -> Self {
+        Self {
+            hasher: RandomState::new(),
+            slots: HashMap::with_capacity(capacity),
+        }
+    }
+
+    /// Keeps `board` as the `i`th candidate of `population`, unless it is
+    /// identical to one kept before once the givens of `base` are overlaid,
+    /// and returns whether it was kept.
+    fn insert<const N: usize>(
+        &mut self,
+        base: &Board<N>,
+        population: &impl Population<N>,
+        i: usize,
+        board: &Board<N>,
+    ) -> bool {
+        let overlay = base.overlay(board);
+        let slots = self.slots.entry(self.hasher.hash_one(overlay)).or_default();
+        if slots
+            .iter()
+            .any(|&j| base.overlay(&population.board(j)) == overlay)
+        {
+            return false;
+        }
+
+        slots.push(i);
+        true
+    }
+}
+
+/// Replaces every child that `archive` holds with a mutated copy of it,
+/// marking its `lineage` as mutated, and returns how many were replaced.
+/// The children are the first `lineage.len()` candidates of `population`.
+/// A replacement that is itself archived is drawn again, up to
+/// `TABU_ATTEMPTS` times, and kept after that.
+pub(crate) fn reject_tabu<const N: usize>(
+    params: &GAParams,
+    base: &Board<N>,
+    generation: u64,
+    archive: &TabuArchive<N>,
+    population: &mut impl Population<N>,
+    lineage: &mut [Lineage],
+) -> usize {
+    const TABU_ATTEMPTS: usize = 4;
+
+    if archive.is_empty() {
+        return 0;
+    }
+    let operators = Operators::new(params, generation, None);
+    let mut replaced = 0;
+
+    for (i, lineage) in lineage.iter_mut().enumerate() {
+        let mut child = population.board(i);
+        if !archive.contains(base, &child) {
+            continue;
+        }
+
+        let mut rng: DefaultRng = params.seed.map_or_else(
+            || DefaultRng::from_rng(OsRng).unwrap(),
+            |seed| seeded_rng(seed, [generation, TABU_STREAM, i as u64]),
+        );
+        for _ in 0..TABU_ATTEMPTS {
+            child = make_child(&mut rng, base, &(child, child), operators, |_, _, _| {});
+            if !archive.contains(base, &child) {
+                break;
+            }
+        }
+        population.set(i, &child);
+        lineage.mutated = true;
+        replaced += 1;
+    }
+
+    replaced
+}
+
+/// Scores a population in place.
+///
+/// Overlays the `base` Board on top of every candidate in `population` and
+/// writes each candidate's fitness score into `scores`, which is cleared first
+/// and afterwards runs parallel to `population`. Returns the index of a valid
+/// solution if one was found.
+///
+/// # Arguments
+///
+/// * `base` - The base Board to find solutions for
+/// * `population` - The population to evaluate fitness for
+/// * `scores` - Receives the fitness score of each candidate
+#[inline]
+pub fn score_population<const N: usize>(
+    base: &Board<N>,
+    population: &mut [Board<N>],
+    scores: &mut Vec<Fitness>,
+) -> Option<usize> {
+    score_encoded(Encoding::Cells, base, population, scores)
+}
+
+/// Scores a population like `score_population`, for candidates bred under
+/// `encoding`.
+fn score_encoded<const N: usize>(
+    encoding: Encoding,
+    base: &Board<N>,
+    population: &mut [Board<N>],
+    scores: &mut Vec<Fitness>,
+) -> Option<usize> {
+    scores.clear();
+    scores.par_extend(population.par_iter_mut().map(|candidate| {
+        *candidate = base.overlay(candidate);
+        encoding.fitness(candidate)
+    }));
+
+    scores.par_iter().position_any(|score| score.is_solution())
+}
+
+/// Returns the encoding candidates bred under `params` are scored as.
+/// Penalties from overrides or a script are added on top of the score, so
+/// rows are always scored in full alongside them rather than trusted to be
+/// permutations.
+const fn scoring_encoding(params: &GAParams) -> Encoding {
+    #[cfg(feature = "scripting")]
+    let penalised = params.candidate_overrides.is_some() || params.script.is_some();
+    #[cfg(not(feature = "scripting"))]
+    let penalised = params.candidate_overrides.is_some();
+
+    if penalised {
+        Encoding::Cells
+    } else {
+        params.encoding
+    }
+}
+
+/// Scores a population like `score_population`, adding the penalties of the
+/// candidate overrides set with `GAParams::with_candidate_overrides` and of
+/// the script set with `GAParams::with_script`, if any. The unit weights of
+/// `GAParams::with_weights` and `GAParams::with_curriculum` are left out;
+/// they only steer selection.
+pub(crate) fn score_population_with<const N: usize>(
+    params: &GAParams,
+    base: &Board<N>,
+    population: &mut [Board<N>],
+    scores: &mut Vec<Fitness>,
+) -> Option<usize> {
+    let solved = score_encoded(scoring_encoding(params), base, population, scores);
+    let solved = params
+        .candidate_overrides
+        .as_ref()
+        .map_or(solved, |overrides| {
+            add_override_penalties(overrides, population.par_iter().copied(), scores)
+        });
+
+    #[cfg(feature = "scripting")]
+    let solved = params.script.as_ref().map_or(solved, |script| {
+        add_penalties(script, population.par_iter().copied(), scores)
+    });
+    solved
+}
+
+/// Scores a population like `score_population_with`, `size` candidates at
+/// a time, and calls `checkpoint` with the number of candidates scored so
+/// far between chunks. Scoring stops as soon as `checkpoint` breaks, leaving
+/// `scores` holding only the chunks scored.
+pub(crate) fn score_population_chunked<const N: usize, B>(
+    params: &GAParams,
+    base: &Board<N>,
+    population: &mut [Board<N>],
+    scores: &mut Vec<Fitness>,
+    size: usize,
+    mut checkpoint: impl FnMut(usize) -> ControlFlow<B>,
+) -> ControlFlow<B, Option<usize>> {
+    let mut chunk_scores = Vec::with_capacity(size);
+    let mut solved = None;
+
+    scores.clear();
+    for (k, chunk) in population.chunks_mut(size).enumerate() {
+        if k > 0 {
+            checkpoint(scores.len())?;
+        }
+        let found = score_population_with(params, base, chunk, &mut chunk_scores);
+        solved = solved.or_else(|| found.map(|i| scores.len() + i));
+        scores.extend_from_slice(&chunk_scores);
+    }
+
+    ControlFlow::Continue(solved)
+}
+
+/// Behaves like `score_population_with`, but for a `PopulationArena`.
+pub(crate) fn score_arena_with<const N: usize>(
+    params: &GAParams,
+    base: &Board<N>,
+    population: &mut PopulationArena<N>,
+    scores: &mut Vec<Fitness>,
+) -> Option<usize> {
+    let solved = score_arena_encoded(scoring_encoding(params), base, population, scores);
+    let solved = params
+        .candidate_overrides
+        .as_ref()
+        .map_or(solved, |overrides| {
+            add_override_penalties(overrides, population.par_iter().map(to_board::<N>), scores)
+        });
+
+    #[cfg(feature = "scripting")]
+    let solved = params.script.as_ref().map_or(solved, |script| {
+        add_penalties(script, population.par_iter().map(to_board::<N>), scores)
+    });
+    solved
+}
+
+/// Returns `scores` with the fitness within the score of each candidate, as
+/// `board` returns it, weighted by `weights`, keeping any penalty. Weighted
+/// scores rank candidates for selection; they are not their fitness.
+fn weigh_scores<const N: usize>(
+    weights: UnitWeights,
+    board: impl Fn(usize) -> Board<N> + Sync,
+    scores: &[Fitness],
+) -> Cow<'_, [Fitness]> {
+    if weights == UnitWeights::FULL {
+        return Cow::Borrowed(scores);
+    }
+
+    Cow::Owned(
+        scores
+            .par_iter()
+            .enumerate()
+            .map(|(i, &score)| {
+                let board = board(i);
+                (score - board.fitness()) + board.weighted_fitness(weights)
+            })
+            .collect(),
+    )
+}
+
+/// Adds the number of cells of each of `boards` that `overrides` rules out
+/// to its score, and returns the index of a solution if there still is one.
+fn add_override_penalties<const N: usize>(
+    overrides: &CandidateOverrides,
+    boards: impl IndexedParallelIterator<Item = Board<N>>,
+    scores: &mut [Fitness],
+) -> Option<usize> {
+    scores
+        .par_iter_mut()
+        .zip(boards)
+        .for_each(|(score, board)| *score += overrides.penalty(&board));
+
+    scores.par_iter().position_any(|score| score.is_solution())
+}
+
+/// Adds the penalty `script` gives each of `boards` to its score, and
+/// returns the index of a solution if there still is one.
+#[cfg(feature = "scripting")]
+fn add_penalties<const N: usize>(
+    script: &FitnessScript,
+    boards: impl IndexedParallelIterator<Item = Board<N>>,
+    scores: &mut [Fitness],
+) -> Option<usize> {
+    scores
+        .par_iter_mut()
+        .zip(boards)
+        .for_each(|(score, board)| *score += script.penalty(&board).unwrap_or(Fitness::MAX));
+
+    scores.par_iter().position_any(|score| score.is_solution())
+}
+
+/// Overlays the `base` Board on every candidate and repairs its columns with
+/// up to `max_swaps` swaps.
+pub(crate) fn repair_population<const N: usize>(
+    base: &Board<N>,
+    population: &mut [Board<N>],
+    max_swaps: usize,
+) {
+    population.par_iter_mut().for_each(|candidate| {
+        *candidate = base.overlay(candidate);
+        candidate.repair_columns(base, max_swaps);
+    });
+}
+
+/// Behaves like `repair_population`, but for a `PopulationArena`.
+pub(crate) fn repair_arena<const N: usize>(
+    base: &Board<N>,
+    population: &mut PopulationArena<N>,
+    max_swaps: usize,
+) {
+    population.par_iter_mut().for_each(|cells| {
+        let mut candidate = base.overlay(&to_board(cells));
+        candidate.repair_columns(base, max_swaps);
+
+        for (cell, digit) in cells
+            .iter_mut()
+            .zip(candidate.0.iter().flat_map(|row| row.0))
+        {
+            *cell = digit;
+        }
+    });
+}
+
+/// Scores a compact population in place.
+///
+/// Behaves like `score_population`, but for a `PopulationArena`.
+///
+/// # Arguments
+///
+/// * `base` - The base Board to find solutions for
+/// * `population` - The population to evaluate fitness for
+/// * `scores` - Receives the fitness score of each candidate
+#[inline]
+pub fn score_arena<const N: usize>(
+    base: &Board<N>,
+    population: &mut PopulationArena<N>,
+    scores: &mut Vec<Fitness>,
+) -> Option<usize> {
+    score_arena_encoded(Encoding::Cells, base, population, scores)
+}
+
+/// Scores a compact population like `score_arena`, for candidates bred
+/// under `encoding`.
+fn score_arena_encoded<const N: usize>(
+    encoding: Encoding,
+    base: &Board<N>,
+    population: &mut PopulationArena<N>,
+    scores: &mut Vec<Fitness>,
+) -> Option<usize> {
+    scores.clear();
+    scores.par_extend(population.par_iter_mut().map(|candidate| {
+        let givens = base.0.iter().flat_map(|row| row.0);
+        for (cell, given) in candidate.iter_mut().zip(givens) {
+            if given != 0 {
+                *cell = given;
+            }
+        }
+        encoding.fitness(&to_board::<N>(candidate))
+    }));
+
+    scores.par_iter().position_any(|score| score.is_solution())
+}
+
+/// Where a child's cell value came from.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum Inheritance {
+    /// Inherited from the first parent.
+    ParentX,
+    /// Inherited from the second parent.
+    ParentY,
+    /// Changed by the mutation operator.
+    Mutation,
+}
+
+/// A variation operator used to breed children.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum Operator {
+    /// Crossover of the two parents, which every child goes through.
+    Crossover,
+    /// The mutation operator, which only some children go through.
+    Mutation,
+}
+
+impl Operator {
+    pub const ALL: [Self; 2] = [Self::Crossover, Self::Mutation];
+}
+
+impl Display for Operator {
+    #[inline]
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Crossover => write!(f, "crossover"),
+            Self::Mutation => write!(f, "mutation"),
+        }
+    }
+}
+
+/// How a child of the current generation was produced.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct Lineage {
+    /// The indices of the child's parents in the generation it was bred
+    /// from.
+    pub parents: [usize; 2],
+    /// The fitness scores of the child's parents.
+    pub parent_scores: [Fitness; 2],
+    /// Whether any of the child's cells mutated.
+    pub mutated: bool,
+}
+
+impl Lineage {
+    /// Returns the operators that produced the child.
+    #[inline]
+    pub fn operators(&self) -> impl Iterator<Item = Operator> {
+        let mutated = self.mutated;
+
+        Operator::ALL
+            .into_iter()
+            .filter(move |&operator| operator != Operator::Mutation || mutated)
+    }
+}
+
+/// How often each operator produced a child fitter than both its parents.
+#[derive(Debug, Copy, Clone, Default, Eq, PartialEq)]
+pub struct OperatorStats {
+    children: [u64; 2],
+    improved: [u64; 2],
+}
+
+impl OperatorStats {
+    /// Records a child once it has been scored.
+    #[inline]
+    pub fn record(&mut self, lineage: &Lineage, score: Fitness) {
+        let improved = lineage
+            .parent_scores
+            .iter()
+            .all(|&parent| score.is_fitter_than(parent));
+
+        for operator in lineage.operators() {
+            self.children[operator as usize] += 1;
+            self.improved[operator as usize] += u64::from(improved);
+        }
+    }
+
+    /// Returns the number of children `operator` produced.
+    #[inline]
+    #[must_use]
+    pub const fn children(&self, operator: Operator) -> u64 {
+        self.children[operator as usize]
+    }
+
+    /// Returns the number of children `operator` produced that were fitter
+    /// than both their parents.
+    #[inline]
+    #[must_use]
+    pub const fn improved(&self, operator: Operator) -> u64 {
+        self.improved[operator as usize]
+    }
+
+    /// Returns the fraction of children `operator` produced that were fitter
+    /// than both their parents, or `None` if it produced none.
+    #[inline]
+    #[must_use]
+    pub fn success_rate(&self, operator: Operator) -> Option<f64> {
+        #[allow(clippy::cast_precision_loss)]
+        let rate = |improved: u64, children: u64| improved as f64 / children as f64;
+
+        (self.children(operator) > 0)
+            .then(|| rate(self.improved(operator), self.children(operator)))
+    }
+}
+
+/// A child together with a record of how each of its cells was produced.
+#[derive(Debug, Clone)]
+pub struct ChildTrace<const N: usize> {
+    pub child: Board<N>,
+    pub mask: [[Inheritance; N]; N],
+}
+
+/// Makes a single child and records how it was produced.
+///
+/// Uses the same crossover and mutation as the simulation, but also returns
+/// the crossover mask and mutated cells so they can be inspected. Mutation
+/// uses the rate of the first generation of any `MutationSchedule`.
+///
+/// # Arguments
+///
+/// * `params` - GA parameters
+/// * `base` - The base Board the parents are candidates for
+/// * `parents` - The pair of parents to breed
+///
+/// # Panics
+///
+/// Panics if N exceeds 255.
+#[inline]
+#[must_use]
+pub fn make_traced_child<const N: usize>(
+    params: &GAParams,
+    base: &Board<N>,
+    parents: &(Board<N>, Board<N>),
+) -> ChildTrace<N> {
+    let operators = Operators::new(params, 0, None);
+    let mut rng = DefaultRng::from_rng(OsRng).unwrap();
+    let mut mask = [[Inheritance::ParentX; N]; N];
+
+    let child = make_child(&mut rng, base, parents, operators, |i, j, inheritance| {
+        mask[i][j] = inheritance;
+    });
+
+    ChildTrace { child, mask }
+}
+
+/// Breeds the audit children of `generation`, as described by
+/// `GAParams::with_audit`, each from two of the `size` candidates of the
+/// population drawn at random and looked up with `board`.
+///
+/// They are bred with the generation's operators and mutation rate, from
+/// RNGs provided as for the generation's children: one per child, seeded
+/// from the OS, or derived from the seed of a seeded run.
+pub(crate) fn audit_children<const N: usize>(
+    params: &GAParams,
+    base: &Board<N>,
+    generation: u64,
+    size: usize,
+    board: impl Fn(usize) -> Board<N>,
+) -> Vec<AuditSample<N>> {
+    if size == 0 {
+        return Vec::new();
+    }
+    let operators = Operators::new(params, generation, None);
+    let mutation_rate = params.scheduled_mutation_rate(generation);
+
+    (0..params.audit)
+        .map(|sample| {
+            let mut rng: DefaultRng = params.seed.map_or_else(
+                || DefaultRng::from_rng(OsRng).unwrap(),
+                |seed| seeded_rng(seed, [generation, AUDIT_STREAM, sample as u64]),
+            );
+            let candidates = Uniform::from(0..size);
+            let parents = (board(rng.sample(candidates)), board(rng.sample(candidates)));
+            let mut crossover = [[Inheritance::ParentX; N]; N];
+            let mut mutated = [[false; N]; N];
+            let child = make_child(&mut rng, base, &parents, operators, |i, j, inheritance| {
+                match inheritance {
+                    Inheritance::Mutation => mutated[i][j] = true,
+                    parent => crossover[i][j] = parent,
+                }
+            });
+
+            AuditSample {
+                generation,
+                mutation_rate,
+                parents,
+                child,
+                crossover,
+                mutated,
+            }
+        })
+        .collect()
+}
+
+/// Runs the simulation.
+///
+/// Evaluates the given `population` fitness against the `base` Board to find
+/// the closest to correct solutions. Returns a valid solution.
+///
+/// # Arguments
+///
+/// * `params` - GA parameters
+/// * `generation` - the current generation counter
+/// * `base` - The base Board to find solutions for
+/// * `population` - The population to evaluate fitness for
+/// * `scores` - Receives the fitness score of each evaluated candidate
+///
+/// The next generation is bred with `R`s, provided as described by
+/// `RngStrategy`, or derived from the seed of a seeded run: one per child,
+/// seeded from the generation, the pair of parents and the child's place
+/// among their children. A counter-based `R` therefore breeds the same
+/// generations however the work is split between threads.
+///
+/// # Errors
+///
+/// Will return `Err(NoSolutionFound)` if a valid solution was not found. In
+/// that case `population` has been replaced in place by the next generation,
+/// while `scores` still holds the scores of the generation just evaluated.
+#[inline]
+pub fn run_simulation<const N: usize, const M: usize, R: Rng + SeedableRng>(
+    params: &GAParams,
+    generation: u64,
+    base: &Board<N>,
+    population: &mut Vec<Board<N>>,
+    scores: &mut Vec<Fitness>,
+) -> Result<Board<N>, NoSolutionFound> {
+    if let Some(i) = score_population_with(params, base, population, scores) {
+        return Ok(population[i]);
+    }
+
+    next_generation::<N, M, R>(
+        params,
+        base,
+        generation,
+        population,
+        scores,
+        None,
+        None,
+        &mut PhaseTimer::new(false),
+    );
+
+    Err(NoSolutionFound)
+}
+
+/// What `run_generation` found in the generation it evaluated.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct GenerationResult<const N: usize> {
+    /// A valid solution, if the generation held one.
+    pub solution: Option<Board<N>>,
+    /// The statistics of the generation evaluated, or `None` if its
+    /// population was empty.
+    pub stats: Option<PopulationStats>,
+}
+
+/// Runs the simulation like `run_simulation`, and also returns the fitness
+/// and diversity statistics of the generation evaluated.
+///
+/// Unless the generation held a solution, `population` is replaced in place
+/// by the next generation, while `scores` still holds the scores of the
+/// generation just evaluated.
+#[inline]
+pub fn run_generation<const N: usize, const M: usize, R: Rng + SeedableRng>(
+    params: &GAParams,
+    generation: u64,
+    base: &Board<N>,
+    population: &mut Vec<Board<N>>,
+    scores: &mut Vec<Fitness>,
+) -> GenerationResult<N> {
+    let solved = score_population_with(params, base, population, scores);
+    let stats = PopulationStats::of(generation, population, scores);
+    if let Some(i) = solved {
+        return GenerationResult {
+            solution: Some(population[i]),
+            stats,
+        };
+    }
+
+    next_generation::<N, M, R>(
+        params,
+        base,
+        generation,
+        population,
+        scores,
+        None,
+        None,
+        &mut PhaseTimer::new(false),
+    );
+
+    GenerationResult {
+        solution: None,
+        stats,
+    }
+}
+
+/// Replaces a scored population with its next generation.
+///
+/// Random immigrants topping up too few children come after the children,
+/// and elites carried over by `GAParams::with_elitism`, and survivors
+/// carried over by `GAParams::with_plus_selection`, after those. If
+/// `lineage` is given, it receives how each child was produced, or is left
+/// empty if the population restarted; immigrants and candidates carried
+/// over have no lineage. `timer` receives the time spent in each phase.
+///
+/// Survivors and elites are chosen by `scores` weighted as scheduled for
+/// `generation` by `GAParams::with_weights` and `GAParams::with_curriculum`.
+///
+/// `cutoff` is what the previous call returned, if it bred the population
+/// being replaced: the score of the least fit survivor under truncation
+/// selection, or `None`. Candidates scoring worse are left out before
+/// ranking, which changes nothing but the time spent sorting.
+///
+/// The population may be a `Vec<Board<N>>` or a `PopulationArena<N>`; a
+/// seeded run breeds the same boards in either.
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn next_generation<const N: usize, const M: usize, R: Rng + SeedableRng>(
+    params: &GAParams,
+    base: &Board<N>,
+    generation: u64,
+    population: &mut impl Population<N>,
+    scores: &[Fitness],
+    cutoff: Option<Fitness>,
+    lineage: Option<&mut Vec<Lineage>>,
+    timer: &mut PhaseTimer,
+) -> Option<Fitness> {
+    if let Some(restart) = params.restart {
+        if generation % restart == 0 && (generation != 0) {
+            population.assign(&initial_population::<N, M, R>(params, base, generation));
+            if let Some(lineage) = lineage {
+                lineage.clear();
+            }
+            timer.lap(Phase::Bookkeeping);
+            return None;
+        }
+    }
+
+    let best = scores.iter().copied().min().unwrap_or_default();
+    let target = params.scheduled_population(generation, best);
+    let num_survivors = survivors(population.len(), params.selection_rate);
+    let weighted = weigh_scores(
+        params.unit_weights(generation),
+        |i| population.board(i),
+        scores,
+    );
+    let ranking = select::<R>(params, generation, num_survivors, &weighted, cutoff, |i| {
+        population.board(i).objectives()
+    });
+    let carried: Vec<Board<N>> = carried_over(params, target, &ranking, &weighted)
+        .iter()
+        .map(|&i| population.board(i))
+        .collect();
+    let num_children = target - carried.len();
+    let brood = params.brood(num_children, num_survivors);
+    timer.lap(Phase::Selection);
+
+    let pairing = pair_survivors::<R>(params, generation, &ranking, |i, j| {
+        population.board(i).distance(&population.board(j))
+    });
+    timer.lap(Phase::Pairing);
+
+    let clock = timer.breed_clock();
+    let operators = Operators::new(params, generation, clock.as_ref());
+    let make = |rng: &mut R, i: usize| {
+        let (pair, _) = brood.parents_of(i);
+        let parents = (
+            population.board(pairing[2 * pair]),
+            population.board(pairing[2 * pair + 1]),
+        );
+        let mut mutated = false;
+        let child = make_child(rng, base, &parents, operators, |_, _, inheritance| {
+            mutated |= inheritance == Inheritance::Mutation;
+        });
+        (child, mutated)
+    };
+    let (mut children, mutated) = match (params.seed, params.rng_strategy) {
+        (Some(seed), _) => population.par_make(
+            brood.len(),
+            || (),
+            |(), i| {
+                let (pair, child) = brood.parents_of(i);
+                let stream = [generation, pair as u64, child as u64];
+                make(&mut seeded_rng(seed, stream), i)
+            },
+        ),
+        (None, RngStrategy::PerChild) => population.par_make(
+            brood.len(),
+            || (),
+            |(), i| make(&mut R::from_rng(OsRng).unwrap(), i),
+        ),
+        (None, RngStrategy::PerWorker) => population.par_make(
+            brood.len(),
+            || R::from_rng(OsRng).unwrap(),
+            |rng, i| make(rng, i),
+        ),
+    };
+    timer.lap_breeding(clock.as_ref());
+
+    children.truncate(num_children);
+    let missing = num_children - children.len();
+    for board in &immigrants::<N, R>(params, base, generation, missing) {
+        children.push(board);
+    }
+    for board in &carried {
+        children.push(board);
+    }
+    *population = children;
+    if let Some(lineage) = lineage {
+        trace_lineage(
+            lineage,
+            &pairing,
+            scores,
+            brood,
+            &mutated[..mutated.len().min(num_children)],
+        );
+    }
+    timer.lap(Phase::Bookkeeping);
+    worst_survivor(params, &ranking, &weighted)
+}
+
+/// Fills `lineage` from whether each child mutated, given that children
+/// come in runs per pair of consecutive parents in `pairing` as `brood`
+/// describes.
+fn trace_lineage(
+    lineage: &mut Vec<Lineage>,
+    pairing: &[usize],
+    scores: &[Fitness],
+    brood: Brood,
+    mutated: &[bool],
+) {
+    lineage.clear();
+    lineage.extend(mutated.iter().enumerate().map(|(i, &mutated)| {
+        let (pair, _) = brood.parents_of(i);
+        let parents = [pairing[2 * pair], pairing[2 * pair + 1]];
+
+        Lineage {
+            parents,
+            parent_scores: parents.map(|parent| scores[parent]),
+            mutated,
+        }
+    }));
+}
+
+/// Returns the indices of the candidates carried over unchanged into the
+/// next generation, at most `target` of them: the elites, then under plus
+/// selection every survivor in `ranking` not already among them.
+fn carried_over(
+    params: &GAParams,
+    target: usize,
+    ranking: &[usize],
+    scores: &[Fitness],
+) -> Vec<usize> {
+    let mut carried = rank(params.elitism.min(target), scores);
+
+    if params.plus_selection {
+        let mut seen = vec![false; scores.len()];
+        for &i in &carried {
+            seen[i] = true;
+        }
+        // Tournaments may select a candidate more than once.
+        for &i in ranking {
+            if !std::mem::replace(&mut seen[i], true) {
+                carried.push(i);
+            }
+        }
+        carried.truncate(target);
+    }
+
+    carried
+}
+
+/// Returns the indices of the `num_survivors` candidates selected to breed,
+/// as consecutive pairs of parents. Truncation selection leaves out the
+/// candidates scoring worse than `cutoff` before ranking, and Pareto
+/// selection looks each candidate's objectives up with `objectives`.
+fn select<R: Rng + SeedableRng>(
+    params: &GAParams,
+    generation: u64,
+    num_survivors: usize,
+    scores: &[Fitness],
+    cutoff: Option<Fitness>,
+    objectives: impl Fn(usize) -> Objectives + Send + Sync,
+) -> Vec<usize> {
+    let rng = || -> R {
+        params.seed.map_or_else(
+            || R::from_rng(OsRng).unwrap(),
+            |seed| seeded_rng(seed, [generation, SELECTION_STREAM, u64::MAX]),
+        )
+    };
+
+    match params.selection {
+        Selection::Truncation => rank_within(num_survivors, scores, cutoff),
+        Selection::Tournament { size } => tournaments(&mut rng(), size, num_survivors, scores),
+        Selection::Rank => rank_weighted(&mut rng(), num_survivors, scores),
+        Selection::Pareto => {
+            let objectives: Vec<Objectives> =
+                (0..scores.len()).into_par_iter().map(objectives).collect();
+            pareto(num_survivors, &objectives)
+        }
+    }
+}
+
+/// Returns the indices of `num_survivors` candidates selected front by
+/// front, as described by `Selection::Pareto`: each front by decreasing
+/// crowding distance, ties broken by index.
+///
+/// Fronts are sorted over the distinct breakdowns rather than the
+/// candidates, which are far fewer once a population converges.
+fn pareto(num_survivors: usize, objectives: &[Objectives]) -> Vec<usize> {
+    let mut candidates: BTreeMap<Objectives, Vec<usize>> = BTreeMap::new();
+    for (i, &breakdown) in objectives.iter().enumerate() {
+        candidates.entry(breakdown).or_default().push(i);
+    }
+
+    let mut left: Vec<Objectives> = candidates.keys().copied().collect();
+    let mut ranking = Vec::with_capacity(num_survivors);
+    while ranking.len() < num_survivors && !left.is_empty() {
+        let (front, rest): (Vec<Objectives>, Vec<Objectives>) = left
+            .iter()
+            .partition(|&&a| !left.iter().any(|&b| b.dominates(a)));
+        let distances = crowding_distances(&front);
+        let mut members: Vec<(f64, usize)> = front
+            .iter()
+            .zip(&distances)
+            .flat_map(|(breakdown, &distance)| {
+                candidates[breakdown].iter().map(move |&i| (distance, i))
+            })
+            .collect();
+        members.sort_unstable_by(|a, b| b.0.total_cmp(&a.0).then(a.1.cmp(&b.1)));
+        ranking.extend(members.into_iter().map(|(_, i)| i));
+        left = rest;
+    }
+
+    ranking.truncate(num_survivors);
+    ranking
+}
+
+/// Returns the NSGA-II crowding distance of each breakdown in `front`: the
+/// sum over the objectives of the gap between its neighbours on either
+/// side, relative to the objective's range, or infinity for the breakdowns
+/// at either end.
+fn crowding_distances(front: &[Objectives]) -> Vec<f64> {
+    let mut distances = vec![0.0; front.len()];
+    let mut order: Vec<usize> = (0..front.len()).collect();
+
+    for objective in 0..3 {
+        let value = |i: usize| f64::from(front[i].to_array()[objective]);
+        order.sort_unstable_by_key(|&i| (front[i].to_array()[objective], i));
+        let (Some(&first), Some(&last)) = (order.first(), order.last()) else {
+            return distances;
+        };
+        let range = value(last) - value(first);
+        distances[first] = f64::INFINITY;
+        distances[last] = f64::INFINITY;
+        if range > 0.0 {
+            for k in order.windows(3) {
+                distances[k[1]] += (value(k[2]) - value(k[0])) / range;
+            }
+        }
+    }
+
+    distances
+}
+
+/// Returns the indices of `num_survivors` candidates drawn with linear rank
+/// weights, as described by `Selection::Rank`, in the order they were drawn.
+fn rank_weighted<R: Rng>(rng: &mut R, num_survivors: usize, scores: &[Fitness]) -> Vec<usize> {
+    let ranking = rank(scores.len(), scores);
+    let weights = WeightedIndex::new((1..=ranking.len()).rev()).unwrap();
+
+    (0..num_survivors)
+        .map(|_| ranking[rng.sample(&weights)])
+        .collect()
+}
+
+/// Returns the indices of the winners of `num_survivors` tournaments of
+/// `size` candidates each, in the order they were held.
+fn tournaments<R: Rng>(
+    rng: &mut R,
+    size: usize,
+    num_survivors: usize,
+    scores: &[Fitness],
+) -> Vec<usize> {
+    let candidates = Uniform::from(0..scores.len());
+
+    (0..num_survivors)
+        .map(|_| {
+            (0..size)
+                .map(|_| rng.sample(candidates))
+                .min_by_key(|&i| scores[i])
+                .unwrap()
+        })
+        .collect()
+}
+
+/// Returns the indices of the `num_survivors` fittest candidates, fittest
+/// first.
+fn rank(num_survivors: usize, scores: &[Fitness]) -> Vec<usize> {
+    rank_within(num_survivors, scores, None)
+}
+
+/// Ranks like `rank`, but first leaves out the candidates scoring worse than
+/// `cutoff`, so that fewer are sorted. If that would leave fewer than
+/// `num_survivors`, every candidate is ranked. Ties are broken by index, so
+/// the cutoff never changes the ranking.
+fn rank_within(num_survivors: usize, scores: &[Fitness], cutoff: Option<Fitness>) -> Vec<usize> {
+    let mut ranking: Vec<usize> = cutoff.map_or_else(Vec::new, |cutoff| {
+        (0..scores.len())
+            .into_par_iter()
+            .filter(|&i| scores[i] <= cutoff)
+            .collect()
+    });
+    if ranking.len() < num_survivors {
+        ranking = (0..scores.len()).collect();
+    }
+
+    ranking.par_sort_unstable_by_key(|&i| (scores[i], i));
+    ranking.truncate(num_survivors);
+    ranking
+}
+
+/// Returns the score of the least fit survivor in `ranking`, the cutoff for
+/// ranking the next generation, under truncation selection, or `None` under
+/// any other.
+fn worst_survivor(params: &GAParams, ranking: &[usize], scores: &[Fitness]) -> Option<Fitness> {
+    match params.selection {
+        Selection::Truncation => ranking.last().map(|&i| scores[i]),
+        _ => None,
+    }
+}
+
+/// How many of the next unpaired survivors are considered as mates for
+/// each survivor under incest prevention.
+pub const MATE_WINDOW: usize = 8;
+
+/// Orders the survivors of `ranking` so that consecutive pairs are the
+/// parents to breed, by the pairing policy of `params` and then under its
+/// incest prevention. `distance` returns the Hamming distance between two
+/// candidates.
+fn pair_survivors<R: Rng + SeedableRng>(
+    params: &GAParams,
+    generation: u64,
+    ranking: &[usize],
+    distance: impl Fn(usize, usize) -> usize,
+) -> Vec<usize> {
+    let order = match params.pairing {
+        Pairing::Adjacent => ranking.to_vec(),
+        Pairing::RoundRobin => round_robin(generation, ranking),
+        Pairing::Shuffle | Pairing::BestWithRandom => {
+            let mut rng: R = params.seed.map_or_else(
+                || R::from_rng(OsRng).unwrap(),
+                |seed| seeded_rng(seed, [generation, PAIRING_STREAM, u64::MAX]),
+            );
+            if params.pairing == Pairing::Shuffle {
+                let mut order = ranking.to_vec();
+                order.shuffle(&mut rng);
+                order
+            } else {
+                best_with_random(&mut rng, ranking)
+            }
+        }
+    };
+
+    avoid_incest(params.incest_prevention, &order, distance)
+}
+
+/// Orders `ranking` so that consecutive pairs are those of round
+/// `generation` of a round-robin tournament between its survivors, by the
+/// circle method: the fittest stays put while the others rotate one place
+/// per round. With an odd number of survivors, the least fit sits out.
+fn round_robin(generation: u64, ranking: &[usize]) -> Vec<usize> {
+    let n = ranking.len() & !1;
+    if n < 4 {
+        return ranking.to_vec();
+    }
+
+    let round = usize::try_from(generation % (n as u64 - 1)).unwrap();
+    let seat = |k: usize| match k {
+        0 => ranking[0],
+        _ => ranking[1 + (k - 1 + round) % (n - 1)],
+    };
+    let mut order: Vec<usize> = (0..n / 2)
+        .flat_map(|k| [seat(k), seat(n - 1 - k)])
+        .collect();
+    order.extend(&ranking[n..]);
+    order
+}
+
+/// Orders `ranking` so that each survivor of its fitter half is followed by
+/// a random one of the rest.
+fn best_with_random<R: Rng>(rng: &mut R, ranking: &[usize]) -> Vec<usize> {
+    let (best, rest) = ranking.split_at(ranking.len() / 2);
+    let mut rest = rest.to_vec();
+    rest.shuffle(rng);
+    let mut order: Vec<usize> = best.iter().zip(&rest).flat_map(|(&x, &y)| [x, y]).collect();
+    order.extend(&rest[best.len()..]);
+    order
+}
+
+/// Orders `order` so that consecutive pairs are at least `min_distance`
+/// cells apart where possible, as described at
+/// `GAParams::with_incest_prevention`.
+fn avoid_incest(
+    min_distance: usize,
+    order: &[usize],
+    distance: impl Fn(usize, usize) -> usize,
+) -> Vec<usize> {
+    if min_distance == 0 {
+        return order.to_vec();
+    }
+
+    let mut unpaired: VecDeque<usize> = order.iter().copied().collect();
+    let mut pairing = Vec::with_capacity(order.len());
+    while let Some(x) = unpaired.pop_front() {
+        let distances: Vec<usize> = unpaired
+            .iter()
+            .take(MATE_WINDOW)
+            .map(|&y| distance(x, y))
+            .collect();
+        let mate = distances
+            .iter()
+            .position(|&d| d >= min_distance)
+            .or_else(|| {
+                // The first of the most distant, to favor fitter mates.
+                (0..distances.len()).rev().max_by_key(|&k| distances[k])
+            });
+
+        pairing.push(x);
+        pairing.extend(mate.and_then(|k| unpaired.remove(k)));
+    }
+    pairing
+}
+
+// The streams of a seeded run other than those of the children. A child's
+// stream is numbered by its pair of parents, and no pair is numbered this
+// high. Each must differ from the others, or two draws would be correlated.
+const INITIAL_STREAM: u64 = u64::MAX;
+const SELECTION_STREAM: u64 = u64::MAX - 1;
+const DEDUP_STREAM: u64 = u64::MAX - 2;
+const PAIRING_STREAM: u64 = u64::MAX - 3;
+const IMMIGRANT_STREAM: u64 = u64::MAX - 4;
+const TABU_STREAM: u64 = u64::MAX - 5;
+const CATACLYSM_STREAM: u64 = u64::MAX - 6;
+const AUDIT_STREAM: u64 = u64::MAX - 7;
+
+/// Derives an independent RNG for one stream of a seeded run.
+fn seeded_rng<R: SeedableRng>(seed: u64, stream: [u64; 3]) -> R {
+    // SplitMix64 finalizer, so that nearby streams get unrelated seeds.
+    let mix = |mut z: u64| {
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    };
+    let state = stream
+        .iter()
+        .fold(mix(seed), |state, &value| mix(state ^ mix(value)));
+
+    R::seed_from_u64(state)
+}
+
+fn make_child<const N: usize, R: Rng, F: FnMut(usize, usize, Inheritance)>(
+    rng: &mut R,
+    base: &Board<N>,
+    parents: &(Board<N>, Board<N>),
+    operators: Operators,
+    mut record: F,
+) -> Board<N> {
+    let (Board(parent_x), Board(parent_y)) = parents;
+    let mut child = parents.0;
+    let mut timer = BreedTimer::start(operators.clock);
+
+    if operators.encoding == Encoding::Permutation {
+        for (i, row) in child.0.iter_mut().enumerate() {
+            permutation::breed_row(
+                rng,
+                operators.mutation_rate,
+                &base.0[i].0,
+                &parent_x[i].0,
+                &parent_y[i].0,
+                &mut row.0,
+                |j, inheritance| record(i, j, inheritance),
+            );
+        }
+        timer.lap(Phase::Crossover);
+        enforce_overrides(rng, base, &mut child, operators, |i, j| {
+            record(i, j, Inheritance::Mutation);
+        });
+        local_search(rng, base, &mut child, operators.local_search, |i, j| {
+            record(i, j, Inheritance::Mutation);
+        });
+        timer.lap(Phase::Mutation);
+        return child;
+    }
+
+    CrossoverOperator::<N>::crossover(&operators.crossover, rng, |_, cell, parent| {
+        let (i, j) = (cell / N, cell % N);
+        child.0[i].0[j] = inherit(parent_x[i].0[j], parent_y[i].0[j], parent);
+        record(i, j, parent);
+    });
+    timer.lap(Phase::Crossover);
+    for (i, row) in child.0.iter_mut().enumerate() {
+        operators
+            .mutation
+            .mutate_row(rng, operators.mutation_rate, &mut row.0, |j| {
+                record(i, j, Inheritance::Mutation);
+            });
+    }
+    enforce_overrides(rng, base, &mut child, operators, |i, j| {
+        record(i, j, Inheritance::Mutation);
+    });
+    local_search(rng, base, &mut child, operators.local_search, |i, j| {
+        record(i, j, Inheritance::Mutation);
+    });
+    timer.lap(Phase::Mutation);
+
+    child
+}
+
+/// Brings the cells of `child` within the candidate overrides, if there are
+/// any, calling `changed` with the row and column of each cell changed.
+fn enforce_overrides<const N: usize, R: Rng>(
+    rng: &mut R,
+    base: &Board<N>,
+    child: &mut Board<N>,
+    operators: Operators,
+    mut changed: impl FnMut(usize, usize),
+) {
+    if let Some(overrides) = operators.overrides {
+        let permutation = operators.encoding == Encoding::Permutation;
+        for (i, row) in child.0.iter_mut().enumerate() {
+            overrides.enforce_row(rng, i, &base.0[i].0, &mut row.0, permutation, |j| {
+                changed(i, j);
+            });
+        }
+    }
+}
+
+/// Hill-climbs `child` for up to `steps` steps, as described by
+/// `GAParams::with_local_search`, calling `changed` with the row and column
+/// of both cells of each swap kept. Givens of `base` never move.
+fn local_search<const N: usize, R: Rng>(
+    rng: &mut R,
+    base: &Board<N>,
+    child: &mut Board<N>,
+    steps: usize,
+    mut changed: impl FnMut(usize, usize),
+) {
+    for _ in 0..steps {
+        let i = rng.gen_range(0..N);
+        let free = || (0..N).filter(|&j| base.0[i].0[j] == 0);
+        let Some(a) = free().choose(rng) else {
+            continue;
+        };
+        let Some(b) = free().filter(|&b| b != a).choose(rng) else {
+            continue;
+        };
+        if child.0[i].0[a] == child.0[i].0[b] {
+            continue;
+        }
+
+        let before = swap_duplicates(child, i, a, b);
+        child.0[i].0.swap(a, b);
+        if swap_duplicates(child, i, a, b) < before {
+            changed(i, a);
+            changed(i, b);
+        } else {
+            child.0[i].0.swap(a, b);
+        }
+    }
+}
+
+/// Counts the duplicates in the units a swap of cells `a` and `b` of row
+/// `i` can change: both columns, and the boxes of both cells.
+fn swap_duplicates<const N: usize>(board: &Board<N>, i: usize, a: usize, b: usize) -> u32 {
+    let (rows, cols) = box_dims::<N>();
+    let duplicates = |cells: &mut dyn Iterator<Item = u8>| {
+        let mut scorer = UnitScorer::<N>::new();
+        cells.for_each(|digit| scorer.check(digit));
+        u32::from(scorer.score())
+    };
+    let column = |j: usize| duplicates(&mut board.0.iter().map(|row| row.0[j]));
+    let unit_box = |j: usize| {
+        let (top, left) = (i / rows * rows, j / cols * cols);
+        duplicates(
+            &mut board.0[top..top + rows]
+                .iter()
+                .flat_map(|row| row.0[left..left + cols].iter().copied()),
+        )
+    };
+
+    let boxes = if a / cols == b / cols {
+        unit_box(a)
+    } else {
+        unit_box(a) + unit_box(b)
+    };
+    column(a) + column(b) + boxes
+}
+
+/// How many children the pairs of parents breed in one generation: each
+/// breeds `per_pair`, and the first `extra` pairs one more.
+#[derive(Debug, Copy, Clone)]
+struct Brood {
+    pairs: usize,
+    per_pair: usize,
+    extra: usize,
+}
+
+impl Brood {
+    /// Returns the number of children bred by every pair.
+    const fn len(self) -> usize {
+        self.pairs * self.per_pair + self.extra
+    }
+
+    /// Returns the pair whose child the `i`th child is, and which of their
+    /// children it is.
+    const fn parents_of(self, i: usize) -> (usize, usize) {
+        let larger = (self.per_pair + 1) * self.extra;
+        if i < larger {
+            (i / (self.per_pair + 1), i % (self.per_pair + 1))
+        } else {
+            let i = i - larger;
+            (self.extra + i / self.per_pair, i % self.per_pair)
+        }
+    }
+}
+
+/// The operators used to breed children, taken from `GAParams` before
+/// breeding starts rather than per child, and the clock to time them with
+/// if the run is profiled. The permutation encoding breeds rows in a single
+/// pass, which counts as crossover. Enforcing candidate overrides and local
+/// search count as mutation.
+#[derive(Debug, Copy, Clone)]
+struct Operators<'a> {
+    mutation_rate: Bernoulli,
+    crossover: Crossover,
+    mutation: Mutation,
+    encoding: Encoding,
+    overrides: Option<&'a CandidateOverrides>,
+    local_search: usize,
+    clock: Option<&'a BreedClock>,
+}
+
+impl<'a> Operators<'a> {
+    fn new(params: &'a GAParams, generation: u64, clock: Option<&'a BreedClock>) -> Self {
+        Self {
+            mutation_rate: params.scheduled_mutation(generation),
+            crossover: params.crossover,
+            mutation: params.mutation_operator,
+            encoding: params.encoding,
+            overrides: params.candidate_overrides.as_deref(),
+            local_search: params.local_search,
+            clock,
+        }
+    }
+}
+
+/// Picks the value of one cell of a child from the parent crossover chose
+/// for it.
+const fn inherit(x: u8, y: u8, parent: Inheritance) -> u8 {
+    match parent {
+        Inheritance::ParentY => y,
+        _ => x,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::SOLVED_9;
+    use std::collections::HashSet;
+
+    fn measured_mutation_rate(mutation_rate: f32) -> f64 {
+        const CHILDREN: u32 = 1000;
+
+        let params = GAParams::new(10, 0.5, mutation_rate, None).unwrap();
+        let mutations: u32 = (0..CHILDREN)
+            .map(|_| {
+                let trace = make_traced_child(&params, &SOLVED_9, &(SOLVED_9, SOLVED_9));
+                let mutated = trace.mask.iter().flatten();
+                u32::try_from(
+                    mutated
+                        .filter(|&&cell| cell == Inheritance::Mutation)
+                        .count(),
+                )
+                .unwrap()
+            })
+            .sum();
+
+        f64::from(mutations) / f64::from(CHILDREN * 81)
+    }
+
+    #[test]
+    // Rates of exactly 0.0 and 1.0 must hold exactly, not within noise.
+    #[allow(clippy::float_cmp)]
+    fn test_mutation_rate() {
+        assert!(measured_mutation_rate(0.0) == 0.0);
+        assert!(measured_mutation_rate(1.0) == 1.0);
+
+        // 81,000 cells put the standard deviation of the measured rate
+        // below 0.002 for any rate.
+        for rate in [0.01, 0.05, 0.5, 0.9] {
+            let measured = measured_mutation_rate(rate);
+            assert!(
+                (measured - f64::from(rate)).abs() < 0.01,
+                "rate {rate} measured as {measured}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_param_errors() {
+        assert_eq!(
+            Some(ParamError::PopulationTooLarge {
+                population: MAX_POPULATION + 1,
+                max: MAX_POPULATION,
+            }),
+            GAParams::new(MAX_POPULATION + 1, 0.5, 0.05, None).err()
+        );
+        assert_eq!(
+            Some(ParamError::MutationRate(1.5)),
+            GAParams::new(10, 0.5, 1.5, None).err()
+        );
+        assert!(matches!(
+            GAParams::new(10, f32::NAN, 0.05, None),
+            Err(ParamError::SelectionRate(rate)) if rate.is_nan()
+        ));
+        assert_eq!(
+            Some(ParamError::NoParentPairs {
+                population: 3,
+                selection_rate: 0.5,
+                survivors: 1,
+            }),
+            GAParams::new(3, 0.5, 0.05, None).err()
+        );
+        assert_eq!(
+            2,
+            GAParams::new(4, 0.5, 0.05, None).unwrap().num_survivors()
+        );
+
+        // Builders reject what they would otherwise panic on.
+        let params = GAParams::new(100, 0.5, 0.05, None).unwrap();
+        let schedule = PopulationSchedule::Linear {
+            min: 20,
+            generations: 10,
+        };
+        assert_eq!(
+            Some(ParamError::ScheduleMinimum {
+                population: 10,
+                min: 20,
+            }),
+            params
+                .clone()
+                .with_population_schedule(schedule)
+                .and_then(|params| params.with_population(10))
+                .err()
+        );
+        assert_eq!(
+            Some(ParamError::NoParentPairs {
+                population: 3,
+                selection_rate: 0.5,
+                survivors: 1,
+            }),
+            params
+                .clone()
+                .with_population_schedule(PopulationSchedule::Linear {
+                    min: 3,
+                    generations: 10,
+                })
+                .err()
+        );
+        assert_eq!(
+            Some(ParamError::ChunkSize),
+            params.clone().with_chunk_size(Some(0)).err()
+        );
+        assert_eq!(
+            Some(ParamError::TournamentSize),
+            params
+                .clone()
+                .with_selection(Selection::Tournament { size: 0 })
+                .err()
+        );
+        assert_eq!(
+            Some(ParamError::ChildrenPerPair),
+            params.clone().with_children_per_pair(Some(0)).err()
+        );
+        assert_eq!(
+            Some(ParamError::CataclysmRate(1.5)),
+            params
+                .clone()
+                .with_cataclysm(Some(Cataclysm {
+                    rate: 1.5,
+                    ..Cataclysm::default()
+                }))
+                .err()
+        );
+        assert!(matches!(
+            params.with_hypermutation(Some(Hypermutation {
+                factor: f32::NAN,
+                ..Hypermutation::default()
+            })),
+            Err(ParamError::HypermutationFactor(factor)) if factor.is_nan()
+        ));
+    }
+
+    #[test]
+    fn test_hypermutation_factor() {
+        let params = GAParams::new(10, 0.5, 0.05, None).unwrap();
+
+        assert!(params
+            .clone()
+            .with_hypermutation(Some(Hypermutation {
+                factor: 0.0,
+                ..Hypermutation::default()
+            }))
+            .is_ok());
+        for factor in [-1.0, f32::INFINITY, f32::NEG_INFINITY] {
+            assert_eq!(
+                Some(ParamError::HypermutationFactor(factor)),
+                params
+                    .clone()
+                    .with_hypermutation(Some(Hypermutation {
+                        factor,
+                        ..Hypermutation::default()
+                    }))
+                    .err()
+            );
+        }
+    }
+
+    #[test]
+    fn test_tournaments() {
+        let scores: Vec<Fitness> = (0..20).map(Fitness::new).collect();
+        let mut rng = Pcg64Mcg::seed_from_u64(1);
+
+        let winners = tournaments(&mut rng, 3, 10, &scores);
+        assert_eq!(10, winners.len());
+        assert!(winners.iter().all(|&i| i < scores.len()));
+
+        // A tournament as big as the population almost always finds the
+        // fittest, while one of a single candidate is a random draw.
+        let strong = tournaments(&mut rng, 20, 1000, &scores);
+        let weak = tournaments(&mut rng, 1, 1000, &scores);
+        let mean = |winners: &[usize]| winners.iter().sum::<usize>() / winners.len();
+        assert!(mean(&strong) < 2);
+        assert!(mean(&weak) > 5);
+
+        let params = GAParams::new(20, 0.5, 0.05, None)
+            .unwrap()
+            .with_seed(3)
+            .with_selection(Selection::Tournament { size: 2 })
+            .unwrap();
+        assert_eq!(
+            select::<DefaultRng>(&params, 4, 10, &scores, None, |_| Objectives::default()),
+            select::<DefaultRng>(&params, 4, 10, &scores, None, |_| Objectives::default())
+        );
+        assert_eq!(
+            (0..10).collect::<Vec<usize>>(),
+            select::<DefaultRng>(
+                &params.with_selection(Selection::Truncation).unwrap(),
+                4,
+                10,
+                &scores,
+                None,
+                |_| Objectives::default()
+            )
+        );
+    }
+
+    #[test]
+    fn test_rank_within() {
+        let mut rng = Pcg64Mcg::seed_from_u64(2);
+        let scores: Vec<Fitness> = (0..1000)
+            .map(|_| Fitness::new(rng.gen_range(0..40)))
+            .collect();
+        let ranking = rank(500, &scores);
+        assert!(ranking
+            .windows(2)
+            .all(|pair| (scores[pair[0]], pair[0]) < (scores[pair[1]], pair[1])));
+
+        // Whether the cutoff leaves out many, none or too many, the ranking
+        // is the same.
+        for cutoff in [0, 10, 19, 20, 39, 100] {
+            assert_eq!(
+                ranking,
+                rank_within(500, &scores, Some(Fitness::new(cutoff))),
+                "{cutoff}"
+            );
+        }
+
+        let params = GAParams::new(1000, 0.5, 0.05, None).unwrap();
+        assert_eq!(
+            Some(scores[ranking[499]]),
+            worst_survivor(&params, &ranking, &scores)
+        );
+        let tournament = params
+            .with_selection(Selection::Tournament { size: 2 })
+            .unwrap();
+        assert_eq!(None, worst_survivor(&tournament, &ranking, &scores));
+    }
+
+    #[test]
+    fn test_avoid_incest() {
+        let pair = |min_distance, values: &[usize]| {
+            let order: Vec<usize> = (0..values.len()).collect();
+            avoid_incest(min_distance, &order, |i, j| values[i].abs_diff(values[j]))
+        };
+
+        assert_eq!(vec![0, 1, 2, 3], pair(0, &[0, 0, 0, 0]));
+        assert_eq!(vec![0, 3, 1, 4, 2, 5], pair(3, &[0, 0, 0, 5, 5, 9]));
+        // Without a mate far enough away, the most distant one will do.
+        assert_eq!(vec![0, 2, 1, 3], pair(5, &[0, 0, 1, 1]));
+        assert_eq!(vec![0, 1, 2], pair(1, &[0, 0, 0]));
+        // Mates are only looked for within the window.
+        let mut values = vec![0; MATE_WINDOW + 2];
+        values[MATE_WINDOW + 1] = 1;
+        assert_eq!(1, pair(1, &values)[1]);
+    }
+
+    #[test]
+    fn test_pairing_policies() {
+        let ranking: Vec<usize> = (0..6).collect();
+
+        assert_eq!(vec![0, 5, 1, 4, 2, 3], round_robin(0, &ranking));
+        assert_eq!(vec![0, 1, 2, 5, 3, 4], round_robin(1, &ranking));
+        assert_eq!(round_robin(0, &ranking), round_robin(5, &ranking));
+        // Over a full tournament, every survivor meets every other once.
+        let mut pairs: Vec<(usize, usize)> = (0..5)
+            .flat_map(|generation| {
+                let order = round_robin(generation, &ranking);
+                (0..3)
+                    .map(|k| {
+                        (
+                            order[2 * k].min(order[2 * k + 1]),
+                            order[2 * k].max(order[2 * k + 1]),
+                        )
+                    })
+                    .collect::<Vec<_>>()
+            })
+            .collect();
+        pairs.sort_unstable();
+        pairs.dedup();
+        assert_eq!(15, pairs.len());
+        // The least fit of an odd number sits out.
+        assert_eq!(6, round_robin(3, &(0..7).collect::<Vec<_>>())[6]);
+
+        let mut rng = DefaultRng::seed_from_u64(1);
+        let order = best_with_random(&mut rng, &(0..7).collect::<Vec<_>>());
+        assert_eq!([0, 1, 2], [order[0], order[2], order[4]]);
+        let mut rest = vec![order[1], order[3], order[5], order[6]];
+        rest.sort_unstable();
+        assert_eq!(vec![3, 4, 5, 6], rest);
+
+        let params = GAParams::new(100, 0.5, 0.05, None)
+            .unwrap()
+            .with_seed(1)
+            .with_pairing(Pairing::Shuffle);
+        let pair =
+            |params: &GAParams| pair_survivors::<DefaultRng>(params, 3, &ranking, usize::abs_diff);
+        let mut shuffled = pair(&params);
+        assert_eq!(shuffled, pair(&params));
+        shuffled.sort_unstable();
+        assert_eq!(ranking, shuffled);
+        assert_eq!(ranking, pair(&params.with_pairing(Pairing::Adjacent)));
+    }
+
+    #[test]
+    fn test_pareto() {
+        let objectives = [
+            (2, 0, 0),
+            (1, 1, 1),
+            (0, 0, 2),
+            (1, 1, 1),
+            (3, 3, 3),
+            (2, 2, 2),
+            (0, 1, 2),
+        ]
+        .map(|(rows, columns, boxes)| Objectives {
+            rows: Fitness::new(rows),
+            columns: Fitness::new(columns),
+            boxes: Fitness::new(boxes),
+        });
+
+        // The first front is 0, 1, 2 and 3, since (0, 1, 2) is dominated by
+        // (0, 0, 2). Each of its breakdowns is at an end of one objective,
+        // so all are as isolated as can be and keep their order.
+        let ranking = pareto(7, &objectives);
+        assert_eq!(vec![0, 1, 2, 3, 6, 5, 4], ranking);
+        // Along a line, the middle breakdown spans each objective's range.
+        assert_eq!(
+            vec![f64::INFINITY, 3.0, f64::INFINITY],
+            crowding_distances(&[objectives[4], objectives[5], objectives[1]])
+        );
+        assert_eq!(ranking[..3], pareto(3, &objectives));
+
+        let scores: Vec<Fitness> = objectives.iter().map(|o| o.total()).collect();
+        let params = GAParams::new(7, 0.5, 0.05, None)
+            .unwrap()
+            .with_selection(Selection::Pareto)
+            .unwrap();
+        assert_eq!(
+            pareto(4, &objectives),
+            select::<DefaultRng>(&params, 0, 4, &scores, None, |i| objectives[i])
+        );
+    }
+
+    #[test]
+    fn test_rank_weighted() {
+        // Scores in a narrow range, in no particular order.
+        let scores = [3, 1, 2, 0, 3, 1].map(Fitness::new);
+        let mut rng = Pcg64Mcg::seed_from_u64(1);
+        let mut counts = [0_i32; 6];
+        for i in rank_weighted(&mut rng, 21_000, &scores) {
+            counts[i] += 1;
+        }
+
+        // Expected weights, fittest first: 6, 5, 4, 3, 2, 1 out of 21.
+        for (i, expected) in [(3, 6000), (1, 5000), (5, 4000), (2, 3000)] {
+            assert!((counts[i] - expected).abs() < 400, "{i}: {counts:?}");
+        }
+        assert!((counts[0] + counts[4] - 3000).abs() < 400, "{counts:?}");
+
+        let params = GAParams::new(20, 0.5, 0.05, None)
+            .unwrap()
+            .with_seed(3)
+            .with_selection(Selection::Rank)
+            .unwrap();
+        let scores: Vec<Fitness> = (0..20).map(Fitness::new).collect();
+        assert_eq!(
+            select::<DefaultRng>(&params, 4, 10, &scores, None, |_| Objectives::default()),
+            select::<DefaultRng>(&params, 4, 10, &scores, None, |_| Objectives::default())
+        );
+    }
+
+    #[test]
+    fn test_elitism() {
+        let mut base = SOLVED_9;
+        for row in &mut base.0 {
+            row.0[..6].fill(0);
+        }
+
+        for compact in [false, true] {
+            // Every cell mutates, so without elites the best would be lost.
+            let params = GAParams::new(20, 0.5, 1.0, None)
+                .unwrap()
+                .with_seed(2)
+                .with_elitism(2);
+            let mut population = initial_population::<9, 20, DefaultRng>(&params, &base, 0);
+            let mut scores = Vec::new();
+            score_population(&base, &mut population, &mut scores);
+
+            for generation in 0..10 {
+                let best = *scores.iter().min().unwrap();
+                let mut lineage = Vec::new();
+                if compact {
+                    let mut arena = PopulationArena::from_boards(&population);
+                    next_generation::<9, 20, DefaultRng>(
+                        &params,
+                        &base,
+                        generation,
+                        &mut arena,
+                        &scores,
+                        None,
+                        Some(&mut lineage),
+                        &mut PhaseTimer::new(false),
+                    );
+                    population = arena.to_boards();
+                } else {
+                    next_generation::<9, 20, DefaultRng>(
+                        &params,
+                        &base,
+                        generation,
+                        &mut population,
+                        &scores,
+                        None,
+                        Some(&mut lineage),
+                        &mut PhaseTimer::new(false),
+                    );
+                }
+                assert_eq!(20, population.len());
+                assert_eq!(18, lineage.len());
+
+                score_population(&base, &mut population, &mut scores);
+                assert!(*scores.iter().min().unwrap() <= best);
+                assert_eq!(best, scores[18]);
+            }
+        }
+    }
+
+    #[test]
+    fn test_plus_selection() {
+        let mut base = SOLVED_9;
+        for row in &mut base.0 {
+            row.0[..6].fill(0);
+        }
+
+        for compact in [false, true] {
+            // Every cell mutates, so only carried over survivors keep their
+            // boards.
+            let params = GAParams::new(20, 0.5, 1.0, None)
+                .unwrap()
+                .with_seed(3)
+                .with_elitism(1)
+                .with_plus_selection(true);
+            let mut population = initial_population::<9, 20, DefaultRng>(&params, &base, 0);
+            let mut scores = Vec::new();
+            score_population(&base, &mut population, &mut scores);
+            let survivors: Vec<Board<9>> =
+                rank(10, &scores).iter().map(|&i| population[i]).collect();
+
+            let mut lineage = Vec::new();
+            if compact {
+                let mut arena = PopulationArena::from_boards(&population);
+                next_generation::<9, 20, DefaultRng>(
+                    &params,
+                    &base,
+                    0,
+                    &mut arena,
+                    &scores,
+                    None,
+                    Some(&mut lineage),
+                    &mut PhaseTimer::new(false),
+                );
+                population = arena.to_boards();
+            } else {
+                next_generation::<9, 20, DefaultRng>(
+                    &params,
+                    &base,
+                    0,
+                    &mut population,
+                    &scores,
+                    None,
+                    Some(&mut lineage),
+                    &mut PhaseTimer::new(false),
+                );
+            }
+
+            // The elite is also the fittest survivor, and is carried once.
+            assert_eq!(20, population.len());
+            assert_eq!(10, lineage.len());
+            assert_eq!(survivors, population[10..]);
+        }
+    }
+
+    #[test]
+    fn test_children_per_pair() {
+        let mut base = SOLVED_9;
+        for row in &mut base.0 {
+            row.0[..6].fill(0);
+        }
+
+        // 6 survivors make 3 pairs, which share 20 children 7, 7 and 6.
+        for (children, bred) in [(None, 20), (Some(2), 6), (Some(10), 20)] {
+            let params = GAParams::new(20, 0.3, 0.05, None)
+                .unwrap()
+                .with_seed(4)
+                .with_children_per_pair(children)
+                .unwrap();
+            let mut population = initial_population::<9, 20, DefaultRng>(&params, &base, 0);
+            let mut scores = Vec::new();
+            score_population(&base, &mut population, &mut scores);
+
+            let mut arena = PopulationArena::from_boards(&population);
+            let mut compact_lineage = Vec::new();
+            next_generation::<9, 20, DefaultRng>(
+                &params,
+                &base,
+                0,
+                &mut arena,
+                &scores,
+                None,
+                Some(&mut compact_lineage),
+                &mut PhaseTimer::new(false),
+            );
+            let mut lineage = Vec::new();
+            next_generation::<9, 20, DefaultRng>(
+                &params,
+                &base,
+                0,
+                &mut population,
+                &scores,
+                None,
+                Some(&mut lineage),
+                &mut PhaseTimer::new(false),
+            );
+
+            // The rest are immigrants.
+            assert_eq!(20, population.len());
+            assert_eq!(bred, lineage.len());
+            if children.is_none() {
+                assert_eq!(lineage[0].parents, lineage[6].parents);
+                assert_ne!(lineage[6].parents, lineage[7].parents);
+                assert_eq!(lineage[14].parents, lineage[19].parents);
+            }
+            assert_eq!(lineage, compact_lineage);
+            assert_eq!(population, arena.to_boards());
+        }
+    }
+
+    #[test]
+    fn test_local_search() {
+        let mut rng = Pcg64Mcg::seed_from_u64(1);
+        let empty = Board([Row::default(); 9]);
+
+        // The duplicates counted around a swap change by as much as the
+        // whole board's fitness score.
+        for _ in 0..100 {
+            let mut board = SOLVED_9;
+            for row in &mut board.0 {
+                row.0.swap(rng.gen_range(0..9), rng.gen_range(0..9));
+            }
+            let (i, a, b) = (
+                rng.gen_range(0..9),
+                rng.gen_range(0..9),
+                rng.gen_range(0..9),
+            );
+            let before = (board.fitness(), swap_duplicates(&board, i, a, b));
+            board.0[i].0.swap(a, b);
+            let after = (board.fitness(), swap_duplicates(&board, i, a, b));
+            assert_eq!(
+                i64::from(after.0.get()) - i64::from(before.0.get()),
+                i64::from(after.1) - i64::from(before.1)
+            );
+        }
+
+        let mut child = SOLVED_9;
+        child.0[4].0.swap(1, 7);
+        let mut changed = Vec::new();
+        local_search(&mut rng, &empty, &mut child, 5000, |i, j| {
+            changed.push((i, j));
+        });
+        assert_eq!(SOLVED_9, child);
+        changed.sort_unstable();
+        assert_eq!(vec![(4, 1), (4, 7)], changed);
+
+        // Givens never move, so a swap between them is left alone.
+        let mut base = empty;
+        base.0[4].0 = SOLVED_9.0[4].0;
+        let mut child = SOLVED_9;
+        child.0[4].0.swap(1, 7);
+        local_search(&mut rng, &base, &mut child, 5000, |_, _| {
+            panic!("given moved")
+        });
+        assert_ne!(SOLVED_9, child);
+    }
+
+    #[test]
+    fn test_restart_keeping() {
+        let mut base = SOLVED_9;
+        for row in &mut base.0 {
+            row.0[..6].fill(0);
+        }
+        let params = GAParams::new(20, 0.5, 0.05, None)
+            .unwrap()
+            .with_seed(4)
+            .with_stall_restart(Some(StallRestart {
+                generations: 10,
+                keep: 3,
+            }));
+        let mut population = initial_population::<9, 20, DefaultRng>(&params, &base, 0);
+        let mut scores = Vec::new();
+        score_population(&base, &mut population, &mut scores);
+        let fittest: Vec<Board<9>> = rank(3, &scores).iter().map(|&i| population[i]).collect();
+
+        restart_keeping(&params, &base, 5, &mut population, &scores);
+        assert_eq!(20, population.len());
+        assert_eq!(fittest, population[17..]);
+        assert_eq!(
+            initial_population::<9, 20, DefaultRng>(&params, &base, 5)[..17],
+            population[..17]
+        );
+    }
+
+    #[test]
+    fn test_cataclysm() {
+        let mut base = SOLVED_9;
+        for row in &mut base.0 {
+            row.0[..6].fill(0);
+        }
+        let params = GAParams::new(20, 0.5, 0.05, None)
+            .unwrap()
+            .with_seed(4)
+            .with_stall_restart(Some(StallRestart {
+                generations: 10,
+                keep: 3,
+            }))
+            .with_cataclysm(Some(Cataclysm {
+                generations: 5,
+                rate: 0.5,
+            }))
+            .unwrap();
+        assert_eq!(Some(5), params.stall_generations());
+        let mut population = initial_population::<9, 20, DefaultRng>(&params, &base, 0);
+        let mut scores = Vec::new();
+        score_population(&base, &mut population, &mut scores);
+        let champion = population[rank(1, &scores)[0]];
+        let mut again = population.clone();
+
+        restart_keeping(&params, &base, 5, &mut population, &scores);
+        assert_eq!(20, population.len());
+        assert_eq!(champion, population[19]);
+        // Half the 81 cells of each copy mutate, a ninth of them to the digit
+        // they held.
+        let distance: usize = population[..19].iter().map(|b| b.distance(&champion)).sum();
+        assert!((19 * 25..19 * 45).contains(&distance));
+
+        restart_keeping(&params, &base, 5, &mut again, &scores);
+        assert_eq!(population, again);
+    }
+
+    #[test]
+    fn test_candidate_overrides() {
+        let overrides = CandidateOverrides::parse("1,1,2\n5,5,5/9").unwrap();
+        let params = GAParams::new(2, 1.0, 0.05, None)
+            .unwrap()
+            .with_candidate_overrides(Some(overrides.into()));
+        let mut population = vec![SOLVED_9; 2];
+        let mut scores = Vec::new();
+
+        // Only the first cell breaks an override, so the solution no longer
+        // counts as one.
+        assert_eq!(
+            Some(0),
+            score_population(&SOLVED_9, &mut population, &mut scores)
+        );
+        assert_eq!(
+            None,
+            score_population_with(&params, &SOLVED_9, &mut population, &mut scores)
+        );
+        assert_eq!(vec![Fitness::new(1); 2], scores);
+
+        let mut arena = PopulationArena::from_boards(&population);
+        assert_eq!(
+            None,
+            score_arena_with(&params, &SOLVED_9, &mut arena, &mut scores)
+        );
+        assert_eq!(vec![Fitness::new(1); 2], scores);
+
+        let mut base = SOLVED_9;
+        base.0[0].0.fill(0);
+        let population = initial_population::<9, 2, DefaultRng>(&params.with_seed(1), &base, 0);
+        assert!(population.iter().all(|board| board.0[0].0[0] == 2));
+    }
+
+    #[test]
+    // A clamped rate is exactly 1.0, and the rate outside bursts untouched.
+    #[allow(clippy::float_cmp)]
+    fn test_bursts() {
+        let params = GAParams::new(10, 0.5, 0.05, None).unwrap();
+        let mut bursts = Bursts::new(Hypermutation {
+            stall: 2,
+            factor: 10.0,
+            generations: 3,
+        });
+        let rates: Vec<Option<f32>> = [9, 8, 8, 8, 8, 8, 8, 7, 7, 7]
+            .into_iter()
+            .map(|best| {
+                bursts
+                    .record(&params, &[Fitness::new(best)])
+                    .map(|burst| burst.mutation_rate())
+            })
+            .collect();
+        // A stall during a burst does not extend it.
+        let burst = Some(0.5);
+        assert_eq!(
+            vec![None, None, None, burst, burst, burst, None, None, None, burst],
+            rates
+        );
+
+        let mut bursts = Bursts::new(Hypermutation {
+            stall: 1,
+            factor: 100.0,
+            generations: 1,
+        });
+        assert!(bursts.record(&params, &[Fitness::new(1)]).is_none());
+        let burst = bursts.record(&params, &[Fitness::new(1)]).unwrap();
+        assert_eq!(1.0, burst.mutation_rate());
+        assert_eq!(0.05, params.mutation_rate());
+    }
+
+    #[test]
+    fn test_dedup_population() {
+        let mut base = SOLVED_9;
+        base.0[0].0.fill(0);
+        let distinct = |population: &[Board<9>]| {
+            let boards: HashSet<_> = population.iter().map(|b| base.overlay(b)).collect();
+            boards.len()
+        };
+        let mut population = vec![SOLVED_9; 10];
+        // Givens are overlaid first, so this is still a duplicate.
+        population[1].0[1].0[0] = 9;
+
+        let params = GAParams::new(10, 0.5, 0.2, None).unwrap().with_seed(6);
+        assert_eq!(0, dedup_population(&params, &base, 0, &mut population));
+        assert_eq!(1, distinct(&population));
+
+        for dedup in [Dedup::Random, Dedup::Mutate] {
+            let params = params.clone().with_dedup(Some(dedup));
+            let mut deduped = population.clone();
+            assert_eq!(9, dedup_population(&params, &base, 0, &mut deduped));
+            assert_eq!(10, distinct(&deduped), "{dedup}");
+            assert_eq!(SOLVED_9, deduped[0]);
+
+            let mut arena = PopulationArena::from_boards(&population);
+            assert_eq!(9, dedup_population(&params, &base, 0, &mut arena));
+            assert_eq!(deduped, arena.to_boards());
+        }
+    }
+
+    #[test]
+    fn test_reject_tabu() {
+        let mut base = SOLVED_9;
+        base.0[0].0.fill(0);
+        let mut archive = TabuArchive::new(4);
+        archive.insert(&base, &SOLVED_9);
+        let params = GAParams::new(10, 0.5, 0.2, None).unwrap().with_seed(6);
+        let lineage = vec![
+            Lineage {
+                parents: [0, 1],
+                parent_scores: [Fitness::ZERO; 2],
+                mutated: false,
+            };
+            3
+        ];
+        let mut other = SOLVED_9;
+        other.0[0].0.swap(0, 1);
+        // The last candidate is carried over, so it is no child.
+        let population = vec![SOLVED_9, other, SOLVED_9, SOLVED_9];
+
+        let mut children = population.clone();
+        let mut traced = lineage.clone();
+        let replaced = reject_tabu(&params, &base, 0, &archive, &mut children, &mut traced);
+        assert_eq!(2, replaced);
+        assert!(!archive.contains(&base, &children[0]));
+        assert!(!archive.contains(&base, &children[2]));
+        assert_eq!(other, children[1]);
+        assert_eq!(SOLVED_9, children[3]);
+        let mutated: Vec<bool> = traced.iter().map(|lineage| lineage.mutated).collect();
+        assert_eq!(vec![true, false, true], mutated);
+
+        let mut arena = PopulationArena::from_boards(&population);
+        let mut arena_traced = lineage;
+        reject_tabu(&params, &base, 0, &archive, &mut arena, &mut arena_traced);
+        assert_eq!(children, arena.to_boards());
+        assert_eq!(traced, arena_traced);
+    }
+
+    #[test]
+    fn test_run_generation() {
+        let mut base = SOLVED_9;
+        base.0[0].0.fill(0);
+        let params = GAParams::new(20, 0.5, 0.1, None).unwrap().with_seed(4);
+        let mut population = generate_initial_population::<9, 20, DefaultRng>(&params, &base);
+        let mut expected = population.clone();
+        let (mut scores, mut expected_scores) = (Vec::new(), Vec::new());
+
+        for generation in 0..3 {
+            let mut evaluated = population.clone();
+            score_population(&base, &mut evaluated, &mut Vec::new());
+            let result = run_generation::<9, 20, DefaultRng>(
+                &params,
+                generation,
+                &base,
+                &mut population,
+                &mut scores,
+            );
+            let solution = run_simulation::<9, 20, DefaultRng>(
+                &params,
+                generation,
+                &base,
+                &mut expected,
+                &mut expected_scores,
+            );
+
+            // It breeds the same generations as `run_simulation`.
+            assert_eq!(solution.ok(), result.solution);
+            assert_eq!(expected, population);
+            assert_eq!(
+                PopulationStats::of(generation, &evaluated, &scores),
+                result.stats
+            );
+        }
+    }
+
+    #[test]
+    fn test_streams() {
+        let streams = [
+            INITIAL_STREAM,
+            SELECTION_STREAM,
+            DEDUP_STREAM,
+            PAIRING_STREAM,
+            IMMIGRANT_STREAM,
+            TABU_STREAM,
+            CATACLYSM_STREAM,
+            AUDIT_STREAM,
+        ];
+        let distinct: HashSet<_> = streams.iter().collect();
+
+        assert_eq!(streams.len(), distinct.len());
+    }
+
+    #[test]
+    fn test_rng_backend() {
+        use rand::rngs::StdRng;
+
+        let mut base = SOLVED_9;
+        base.0[0].0.fill(0);
+        let params = GAParams::new(20, 0.5, 0.1, None).unwrap().with_seed(4);
+        let run = |generations| {
+            let mut population = generate_initial_population::<9, 20, StdRng>(&params, &base);
+            let mut scores = Vec::new();
+            for generation in 0..generations {
+                let _ = run_simulation::<9, 20, StdRng>(
+                    &params,
+                    generation,
+                    &base,
+                    &mut population,
+                    &mut scores,
+                );
+            }
+            population
+        };
+
+        // A seeded run is as reproducible with any backend, and draws
+        // different candidates from each.
+        assert_eq!(run(3), run(3));
+        assert_ne!(
+            generate_initial_population::<9, 20, DefaultRng>(&params, &base),
+            run(0)
+        );
+    }
+
+    #[test]
+    fn test_score_population_chunked() {
+        let mut base = SOLVED_9;
+        base.0[0].0.fill(0);
+        let params = GAParams::new(10, 0.5, 0.05, None).unwrap().with_seed(2);
+        let mut population = generate_initial_population::<9, 10, DefaultRng>(&params, &base);
+        population[7] = SOLVED_9;
+        let mut whole = Vec::new();
+        let solved = score_population_with(&params, &base, &mut population, &mut whole);
+
+        let mut checkpoints = Vec::new();
+        let mut scores = Vec::new();
+        let chunked =
+            score_population_chunked(&params, &base, &mut population, &mut scores, 3, |scored| {
+                checkpoints.push(scored);
+                ControlFlow::<()>::Continue(())
+            });
+        assert_eq!(ControlFlow::Continue(solved), chunked);
+        assert_eq!(Some(7), solved);
+        assert_eq!(whole, scores);
+        assert_eq!(vec![3, 6, 9], checkpoints);
+
+        let stopped =
+            score_population_chunked(&params, &base, &mut population, &mut scores, 4, |_| {
+                ControlFlow::Break("stop")
+            });
+        assert_eq!(ControlFlow::Break("stop"), stopped);
+        assert_eq!(&whole[..4], &scores[..]);
+    }
+
+    #[test]
+    fn test_score_permutations() {
+        let mut base = SOLVED_9;
+        for row in &mut base.0 {
+            row.0[..4].fill(0);
+        }
+        let cells = GAParams::new(10, 0.5, 0.05, None).unwrap().with_seed(3);
+        let permutations = cells.clone().with_encoding(Encoding::Permutation);
+        let mut population = generate_initial_population::<9, 10, DefaultRng>(&permutations, &base);
+
+        let mut skipped = Vec::new();
+        let mut full = Vec::new();
+        score_population_with(&permutations, &base, &mut population, &mut skipped);
+        score_population_with(&cells, &base, &mut population, &mut full);
+        assert_eq!(full, skipped);
+
+        let mut arena = PopulationArena::from_boards(&population);
+        score_arena_with(&permutations, &base, &mut arena, &mut skipped);
+        assert_eq!(full, skipped);
+    }
+
+    #[test]
+    fn test_scheduled_population() {
+        let fixed = GAParams::new(1000, 0.5, 0.05, None).unwrap();
+        assert_eq!(1000, fixed.scheduled_population(10, Fitness::new(3)));
+
+        let linear = GAParams::new(1000, 0.5, 0.05, Some(100))
+            .unwrap()
+            .with_population_schedule(PopulationSchedule::Linear {
+                min: 200,
+                generations: 10,
+            })
+            .unwrap();
+        assert_eq!(920, linear.scheduled_population(0, Fitness::new(50)));
+        assert_eq!(200, linear.scheduled_population(9, Fitness::new(50)));
+        assert_eq!(200, linear.scheduled_population(50, Fitness::new(50)));
+        assert_eq!(920, linear.scheduled_population(100, Fitness::new(50)));
+
+        // 25 pairs of survivors breed the 92 children scheduled between
+        // them, rather than 3 each and immigrants for the other 17.
+        let mut base = SOLVED_9;
+        for row in &mut base.0 {
+            row.0[..6].fill(0);
+        }
+        let params = GAParams::new(100, 0.5, 0.05, Some(100))
+            .unwrap()
+            .with_seed(5)
+            .with_population_schedule(PopulationSchedule::Linear {
+                min: 20,
+                generations: 10,
+            })
+            .unwrap();
+        let mut population = initial_population::<9, 100, DefaultRng>(&params, &base, 0);
+        let mut scores = Vec::new();
+        score_population(&base, &mut population, &mut scores);
+        let mut lineage = Vec::new();
+        next_generation::<9, 100, DefaultRng>(
+            &params,
+            &base,
+            0,
+            &mut population,
+            &scores,
+            None,
+            Some(&mut lineage),
+            &mut PhaseTimer::new(false),
+        );
+        assert_eq!(92, population.len());
+        assert_eq!(92, lineage.len());
+
+        let fitness = GAParams::new(1000, 0.5, 0.05, None)
+            .unwrap()
+            .with_population_schedule(PopulationSchedule::Fitness {
+                min: 200,
+                fitness: Fitness::new(40),
+            })
+            .unwrap();
+        assert_eq!(1000, fitness.scheduled_population(0, Fitness::new(60)));
+        assert_eq!(600, fitness.scheduled_population(0, Fitness::new(20)));
+        assert_eq!(200, fitness.scheduled_population(0, Fitness::new(0)));
+    }
+
+    #[test]
+    fn test_scheduled_mutation_rate() {
+        let constant = GAParams::new(100, 0.5, 0.1, Some(200)).unwrap();
+        assert!((constant.scheduled_mutation_rate(150) - 0.1).abs() < f32::EPSILON);
+
+        let rate = |schedule, generation| {
+            constant
+                .clone()
+                .with_mutation_schedule(schedule)
+                .scheduled_mutation_rate(generation)
+        };
+        let close = |expected: f32, rate: f32| (expected - rate).abs() < 1e-6;
+        for schedule in [
+            MutationSchedule::Exponential { generations: 100 },
+            MutationSchedule::Linear { generations: 100 },
+            MutationSchedule::Cosine { generations: 100 },
+        ] {
+            assert!(close(0.1, rate(schedule, 0)));
+            assert!(close(0.001, rate(schedule, 100)));
+            assert!(close(0.001, rate(schedule, 199)));
+            // Restarts start the schedule over.
+            assert!(close(rate(schedule, 50), rate(schedule, 250)));
+        }
+
+        let exponential = MutationSchedule::Exponential { generations: 100 };
+        assert!(close(0.01, rate(exponential, 50)));
+        let linear = MutationSchedule::Linear { generations: 100 };
+        assert!(close(0.0505, rate(linear, 50)));
+        let cosine = MutationSchedule::Cosine { generations: 100 };
+        assert!(close(0.0505, rate(cosine, 50)));
+        assert!(rate(cosine, 25) > rate(linear, 25));
+        assert!(rate(cosine, 75) < rate(linear, 75));
+
+        // Once the schedule has decayed, children of two copies of a
+        // solution mostly keep it.
+        let params = GAParams::new(20, 0.5, 1.0, None)
+            .unwrap()
+            .with_seed(1)
+            .with_mutation_schedule(MutationSchedule::Linear { generations: 1 });
+        let scores = vec![Fitness::ZERO; 20];
+        for (generation, unchanged) in [(0, 0..1), (1, 8..21)] {
+            let mut population = vec![SOLVED_9; 20];
+            next_generation::<9, 20, DefaultRng>(
+                &params,
+                &SOLVED_9,
+                generation,
+                &mut population,
+                &scores,
+                None,
+                None,
+                &mut PhaseTimer::new(false),
+            );
+            let solved = population.iter().filter(|&&child| child == SOLVED_9);
+            assert!(unchanged.contains(&solved.count()));
+        }
+    }
+
+    #[test]
+    fn test_unit_weights() {
+        let full = GAParams::new(100, 0.5, 0.05, Some(20)).unwrap();
+        assert_eq!(UnitWeights::FULL, full.unit_weights(0));
+
+        let curriculum = full
+            .clone()
+            .with_curriculum(Some(Curriculum { generations: 10 }));
+        let boxes = |generation| curriculum.unit_weights(generation).boxes;
+        assert!(boxes(0).abs() < f32::EPSILON);
+        assert!((boxes(5) - 0.5).abs() < f32::EPSILON);
+        assert_eq!(UnitWeights::FULL, curriculum.unit_weights(10));
+        assert_eq!(UnitWeights::FULL, curriculum.unit_weights(19));
+        // Restarts start the curriculum over.
+        assert!(boxes(20).abs() < f32::EPSILON);
+
+        // A curriculum phases boxes in up to their weight.
+        let weights = UnitWeights {
+            rows: 0.5,
+            columns: 1.0,
+            boxes: 2.0,
+        };
+        let weighted = curriculum.clone().with_weights(weights);
+        assert_eq!(weights, weighted.unit_weights(10));
+        assert!((weighted.unit_weights(5).boxes - 1.0).abs() < f32::EPSILON);
+        assert!((weighted.unit_weights(5).rows - 0.5).abs() < f32::EPSILON);
+        assert_eq!(weights, full.with_weights(weights).unit_weights(0));
+
+        // Boxes weigh nothing at first, but only in selection: scores are
+        // the candidates' fitness, and only full solutions are solutions.
+        let mut base = SOLVED_9;
+        base.0[0].0.fill(0);
+        let mut population = vec![SOLVED_9; 2];
+        population[1].0[0].0.swap(2, 3);
+        let mut scores = Vec::new();
+        assert_eq!(
+            Some(0),
+            score_population_with(&curriculum, &base, &mut population, &mut scores)
+        );
+        // The swap repeats two digits in columns and two in boxes.
+        assert_eq!(vec![Fitness::ZERO, Fitness::new(4)], scores);
+        let weighted = weigh_scores(curriculum.unit_weights(0), |i| population[i], &scores);
+        assert_eq!([Fitness::ZERO, Fitness::new(2)], *weighted);
+        population[0] = population[1];
+        assert_eq!(
+            None,
+            score_population_with(&curriculum, &base, &mut population, &mut scores)
+        );
+    }
 }
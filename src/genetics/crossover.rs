@@ -0,0 +1,263 @@
+#![warn(
+    clippy::all,
+    // clippy::restriction,
+    clippy::pedantic,
+    clippy::nursery,
+    clippy::cargo
+)]
+
+use super::{Inheritance, CROSSOVER_RATE};
+use crate::sudoku::box_index;
+use rand::distributions::Bernoulli;
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+
+/// Decides which parent each cell of a child is inherited from.
+///
+/// Cells are numbered from 0 to N * N - 1, row by row. Mutation is decided
+/// separately, by the caller, once a cell's parent is known.
+pub trait CrossoverOperator<const N: usize> {
+    /// Calls `inherit` once per cell of one child, in order, with the cell
+    /// and the parent it inherits from: `Inheritance::ParentX` or
+    /// `Inheritance::ParentY`. `inherit` is handed `rng` back so that it can
+    /// draw from it too.
+    fn crossover<R: Rng + ?Sized>(
+        &self,
+        rng: &mut R,
+        inherit: impl FnMut(&mut R, usize, Inheritance),
+    );
+}
+
+/// Inherits each cell independently, from the first parent with
+/// probability `CROSSOVER_RATE`.
+#[derive(Debug, Copy, Clone, Default, Eq, PartialEq)]
+pub struct UniformCrossover;
+
+impl<const N: usize> CrossoverOperator<N> for UniformCrossover {
+    #[inline]
+    fn crossover<R: Rng + ?Sized>(
+        &self,
+        rng: &mut R,
+        mut inherit: impl FnMut(&mut R, usize, Inheritance),
+    ) {
+        let parent_x = Bernoulli::new(CROSSOVER_RATE).unwrap();
+
+        for cell in 0..N * N {
+            let parent = if rng.sample(parent_x) {
+                Inheritance::ParentX
+            } else {
+                Inheritance::ParentY
+            };
+            inherit(rng, cell, parent);
+        }
+    }
+}
+
+/// Inherits the cells before a random cut from the first parent and the
+/// rest from the second.
+#[derive(Debug, Copy, Clone, Default, Eq, PartialEq)]
+pub struct SinglePointCrossover;
+
+impl<const N: usize> CrossoverOperator<N> for SinglePointCrossover {
+    #[inline]
+    fn crossover<R: Rng + ?Sized>(
+        &self,
+        rng: &mut R,
+        mut inherit: impl FnMut(&mut R, usize, Inheritance),
+    ) {
+        let cut = rng.gen_range(0..=N * N);
+
+        for cell in 0..N * N {
+            let parent = if cell < cut {
+                Inheritance::ParentX
+            } else {
+                Inheritance::ParentY
+            };
+            inherit(rng, cell, parent);
+        }
+    }
+}
+
+/// Inherits the cells between two random cuts from the second parent and
+/// the rest from the first.
+#[derive(Debug, Copy, Clone, Default, Eq, PartialEq)]
+pub struct TwoPointCrossover;
+
+impl<const N: usize> CrossoverOperator<N> for TwoPointCrossover {
+    #[inline]
+    fn crossover<R: Rng + ?Sized>(
+        &self,
+        rng: &mut R,
+        mut inherit: impl FnMut(&mut R, usize, Inheritance),
+    ) {
+        let (a, b) = (rng.gen_range(0..=N * N), rng.gen_range(0..=N * N));
+        let cuts = a.min(b)..a.max(b);
+
+        for cell in 0..N * N {
+            let parent = if cuts.contains(&cell) {
+                Inheritance::ParentY
+            } else {
+                Inheritance::ParentX
+            };
+            inherit(rng, cell, parent);
+        }
+    }
+}
+
+/// Inherits each row whole, from the first parent with probability
+/// `CROSSOVER_RATE`, so that rows which have settled in a parent survive.
+#[derive(Debug, Copy, Clone, Default, Eq, PartialEq)]
+pub struct RowCrossover;
+
+impl<const N: usize> CrossoverOperator<N> for RowCrossover {
+    #[inline]
+    fn crossover<R: Rng + ?Sized>(
+        &self,
+        rng: &mut R,
+        mut inherit: impl FnMut(&mut R, usize, Inheritance),
+    ) {
+        let parent_x = Bernoulli::new(CROSSOVER_RATE).unwrap();
+
+        for row in 0..N {
+            let parent = if rng.sample(parent_x) {
+                Inheritance::ParentX
+            } else {
+                Inheritance::ParentY
+            };
+            for col in 0..N {
+                inherit(rng, row * N + col, parent);
+            }
+        }
+    }
+}
+
+/// Inherits each box whole, from the first parent with probability
+/// `CROSSOVER_RATE`, so that boxes which have settled in a parent survive.
+///
+/// # Panics
+///
+/// Panics if N has no box dimensions (see [`crate::sudoku::box_dims`]).
+#[derive(Debug, Copy, Clone, Default, Eq, PartialEq)]
+pub struct BoxCrossover;
+
+impl<const N: usize> CrossoverOperator<N> for BoxCrossover {
+    #[inline]
+    fn crossover<R: Rng + ?Sized>(
+        &self,
+        rng: &mut R,
+        mut inherit: impl FnMut(&mut R, usize, Inheritance),
+    ) {
+        let parent_x = Bernoulli::new(CROSSOVER_RATE).unwrap();
+        let mut parents = [Inheritance::ParentX; N];
+        for parent in &mut parents {
+            if !rng.sample(parent_x) {
+                *parent = Inheritance::ParentY;
+            }
+        }
+
+        for cell in 0..N * N {
+            let (row, col) = (cell / N, cell % N);
+            inherit(rng, cell, parents[box_index::<N>(row, col)]);
+        }
+    }
+}
+
+/// The crossover operator used to breed children.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Serialize, Deserialize)]
+pub enum Crossover {
+    /// `UniformCrossover`.
+    Uniform,
+    /// `SinglePointCrossover`.
+    SinglePoint,
+    /// `TwoPointCrossover`.
+    TwoPoint,
+    /// `RowCrossover`.
+    Row,
+    /// `BoxCrossover`.
+    Box,
+}
+
+impl Default for Crossover {
+    #[inline]
+    fn default() -> Self {
+        Self::Uniform
+    }
+}
+
+impl<const N: usize> CrossoverOperator<N> for Crossover {
+    #[inline]
+    fn crossover<R: Rng + ?Sized>(
+        &self,
+        rng: &mut R,
+        inherit: impl FnMut(&mut R, usize, Inheritance),
+    ) {
+        match self {
+            Self::Uniform => CrossoverOperator::<N>::crossover(&UniformCrossover, rng, inherit),
+            Self::SinglePoint => {
+                CrossoverOperator::<N>::crossover(&SinglePointCrossover, rng, inherit);
+            }
+            Self::TwoPoint => CrossoverOperator::<N>::crossover(&TwoPointCrossover, rng, inherit),
+            Self::Row => CrossoverOperator::<N>::crossover(&RowCrossover, rng, inherit),
+            Self::Box => CrossoverOperator::<N>::crossover(&BoxCrossover, rng, inherit),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::SeedableRng;
+    use rand_pcg::Pcg64Mcg;
+
+    /// Returns the parent of each cell of one child.
+    fn mask<C: CrossoverOperator<4>>(crossover: &C, rng: &mut Pcg64Mcg) -> Vec<Inheritance> {
+        let mut mask = Vec::new();
+        crossover.crossover(rng, |_, cell, parent| {
+            assert_eq!(mask.len(), cell);
+            mask.push(parent);
+        });
+        mask
+    }
+
+    /// Returns the number of times the parent changes along the mask.
+    fn switches(mask: &[Inheritance]) -> usize {
+        mask.windows(2).filter(|pair| pair[0] != pair[1]).count()
+    }
+
+    #[test]
+    fn test_crossover() {
+        let mut rng = Pcg64Mcg::seed_from_u64(1);
+
+        for _ in 0..100 {
+            let single = mask(&Crossover::SinglePoint, &mut rng);
+            assert_eq!(16, single.len());
+            assert!(switches(&single) <= 1);
+            assert!(single.first() == Some(&Inheritance::ParentX) || switches(&single) == 0);
+
+            let two = mask(&Crossover::TwoPoint, &mut rng);
+            assert!(switches(&two) <= 2);
+            assert!(!two.contains(&Inheritance::Mutation));
+
+            let rows = mask(&Crossover::Row, &mut rng);
+            assert_eq!(16, rows.len());
+            for row in rows.chunks(4) {
+                assert_eq!(0, switches(row));
+            }
+
+            // Boxes of a 4x4 board are 2x2.
+            let boxes = mask(&Crossover::Box, &mut rng);
+            for (row, col) in [(0, 0), (0, 2), (2, 0), (2, 2)] {
+                let parent = boxes[row * 4 + col];
+                assert_eq!(parent, boxes[row * 4 + col + 1]);
+                assert_eq!(parent, boxes[(row + 1) * 4 + col]);
+                assert_eq!(parent, boxes[(row + 1) * 4 + col + 1]);
+            }
+        }
+
+        // Uniform crossover switches parents at about every other cell.
+        let switched: usize = (0..100)
+            .map(|_| switches(&mask(&Crossover::Uniform, &mut rng)))
+            .sum();
+        assert!((600..900).contains(&switched), "{switched}");
+    }
+}
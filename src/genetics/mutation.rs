@@ -0,0 +1,195 @@
+#![warn(
+    clippy::all,
+    // clippy::restriction,
+    clippy::pedantic,
+    clippy::nursery,
+    clippy::cargo
+)]
+
+use rand::distributions::{Bernoulli, Uniform};
+use rand::seq::SliceRandom;
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+
+/// Mutates a child once crossover has filled it in.
+///
+/// Children are mutated a row at a time. Givens need no special care: they
+/// are restored from the puzzle before every candidate is scored.
+pub trait MutationOperator<const N: usize> {
+    /// Mutates `row`, one row of a child, at the mutation rate `rate`, and
+    /// calls `mutated` with the column of each cell it changes. A cell may
+    /// be reported more than once, or keep its digit.
+    fn mutate_row<R: Rng + ?Sized>(
+        &self,
+        rng: &mut R,
+        rate: Bernoulli,
+        row: &mut [u8; N],
+        mutated: impl FnMut(usize),
+    );
+}
+
+/// Replaces each cell with a random digit, with probability `rate`.
+#[derive(Debug, Copy, Clone, Default, Eq, PartialEq)]
+pub struct RandomResetting;
+
+impl<const N: usize> MutationOperator<N> for RandomResetting {
+    #[inline]
+    fn mutate_row<R: Rng + ?Sized>(
+        &self,
+        rng: &mut R,
+        rate: Bernoulli,
+        row: &mut [u8; N],
+        mut mutated: impl FnMut(usize),
+    ) {
+        let max_digit = u8::try_from(N).expect("digit size exceeds 255");
+        let values = Uniform::from(1..=max_digit);
+
+        for (col, cell) in row.iter_mut().enumerate() {
+            if rng.sample(rate) {
+                *cell = rng.sample(values);
+                mutated(col);
+            }
+        }
+    }
+}
+
+/// Swaps each cell with a random cell of the same row, with probability
+/// `rate`, so that rows keep their digits.
+#[derive(Debug, Copy, Clone, Default, Eq, PartialEq)]
+pub struct SwapWithinRow;
+
+impl<const N: usize> MutationOperator<N> for SwapWithinRow {
+    #[inline]
+    fn mutate_row<R: Rng + ?Sized>(
+        &self,
+        rng: &mut R,
+        rate: Bernoulli,
+        row: &mut [u8; N],
+        mut mutated: impl FnMut(usize),
+    ) {
+        for col in 0..N {
+            if rng.sample(rate) {
+                let other = rng.gen_range(0..N);
+                row.swap(col, other);
+                mutated(col);
+                mutated(other);
+            }
+        }
+    }
+}
+
+/// Shuffles the cells between two random cuts of the row, with probability
+/// `rate`, so that rows keep their digits.
+///
+/// The rate applies per row rather than per cell, so the same rate
+/// disturbs far more cells than the other operators do.
+#[derive(Debug, Copy, Clone, Default, Eq, PartialEq)]
+pub struct ScrambleRow;
+
+impl<const N: usize> MutationOperator<N> for ScrambleRow {
+    #[inline]
+    fn mutate_row<R: Rng + ?Sized>(
+        &self,
+        rng: &mut R,
+        rate: Bernoulli,
+        row: &mut [u8; N],
+        mutated: impl FnMut(usize),
+    ) {
+        if rng.sample(rate) {
+            let (a, b) = (rng.gen_range(0..=N), rng.gen_range(0..=N));
+            let cuts = a.min(b)..a.max(b);
+
+            row[cuts.clone()].shuffle(rng);
+            cuts.for_each(mutated);
+        }
+    }
+}
+
+/// The mutation operator used to breed children.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Serialize, Deserialize)]
+pub enum Mutation {
+    /// `RandomResetting`.
+    RandomResetting,
+    /// `SwapWithinRow`.
+    SwapWithinRow,
+    /// `ScrambleRow`.
+    ScrambleRow,
+}
+
+impl Default for Mutation {
+    #[inline]
+    fn default() -> Self {
+        Self::RandomResetting
+    }
+}
+
+impl<const N: usize> MutationOperator<N> for Mutation {
+    #[inline]
+    fn mutate_row<R: Rng + ?Sized>(
+        &self,
+        rng: &mut R,
+        rate: Bernoulli,
+        row: &mut [u8; N],
+        mutated: impl FnMut(usize),
+    ) {
+        match self {
+            Self::RandomResetting => RandomResetting.mutate_row(rng, rate, row, mutated),
+            Self::SwapWithinRow => SwapWithinRow.mutate_row(rng, rate, row, mutated),
+            Self::ScrambleRow => ScrambleRow.mutate_row(rng, rate, row, mutated),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::SeedableRng;
+    use rand_pcg::Pcg64Mcg;
+
+    const ROW: [u8; 9] = [1, 2, 3, 4, 5, 6, 7, 8, 9];
+
+    /// Mutates a copy of `ROW`, and returns it with the columns reported.
+    fn mutate(mutation: Mutation, rate: f64, rng: &mut Pcg64Mcg) -> ([u8; 9], Vec<usize>) {
+        let mut row = ROW;
+        let mut columns = Vec::new();
+        mutation.mutate_row(rng, Bernoulli::new(rate).unwrap(), &mut row, |col| {
+            columns.push(col);
+        });
+        (row, columns)
+    }
+
+    #[test]
+    fn test_mutation() {
+        let mut rng = Pcg64Mcg::seed_from_u64(1);
+
+        for mutation in [
+            Mutation::RandomResetting,
+            Mutation::SwapWithinRow,
+            Mutation::ScrambleRow,
+        ] {
+            assert_eq!((ROW, Vec::new()), mutate(mutation, 0.0, &mut rng));
+        }
+
+        for _ in 0..100 {
+            let (row, columns) = mutate(Mutation::RandomResetting, 0.5, &mut rng);
+            assert!(row.iter().all(|digit| (1..=9).contains(digit)));
+            for col in 0..9 {
+                assert!(row[col] == ROW[col] || columns.contains(&col));
+            }
+
+            for mutation in [Mutation::SwapWithinRow, Mutation::ScrambleRow] {
+                let (mut row, columns) = mutate(mutation, 1.0, &mut rng);
+                for col in 0..9 {
+                    assert!(row[col] == ROW[col] || columns.contains(&col));
+                }
+                row.sort_unstable();
+                assert_eq!(ROW, row);
+            }
+        }
+
+        // Resetting every cell leaves few of them as they were.
+        let (row, columns) = mutate(Mutation::RandomResetting, 1.0, &mut rng);
+        assert_eq!((0..9).collect::<Vec<_>>(), columns);
+        assert!(row != ROW);
+    }
+}
@@ -0,0 +1,171 @@
+#![warn(
+    clippy::all,
+    // clippy::restriction,
+    clippy::pedantic,
+    clippy::nursery,
+    clippy::cargo
+)]
+
+use super::Inheritance;
+use crate::sudoku::Board;
+use arrayvec::ArrayVec;
+use rand::distributions::Bernoulli;
+use rand::seq::SliceRandom;
+use rand::Rng;
+
+/// Returns `base` with the free cells of each row, those without a given,
+/// filled with a random permutation of the digits the row is missing.
+///
+/// # Panics
+///
+/// Panics if N exceeds 255.
+#[inline]
+#[must_use]
+pub fn random_board<const N: usize, R: Rng + ?Sized>(rng: &mut R, base: &Board<N>) -> Board<N> {
+    let max_digit = u8::try_from(N).expect("digit size exceeds 255");
+    let mut board = *base;
+
+    for row in &mut board.0 {
+        let mut missing: ArrayVec<u8, N> = (1..=max_digit)
+            .filter(|digit| !row.0.contains(digit))
+            .collect();
+        missing.shuffle(rng);
+        let mut missing = missing.into_iter();
+
+        // A row with repeated givens is missing fewer digits than it has
+        // free cells; the rest get random digits.
+        for cell in row.0.iter_mut().filter(|cell| **cell == 0) {
+            *cell = missing
+                .next()
+                .unwrap_or_else(|| rng.gen_range(1..=max_digit));
+        }
+    }
+
+    board
+}
+
+/// Breeds one row of a child from the same row of two parents, leaving the
+/// givens of `base_row` in place, and calls `inherit` with the column of
+/// each cell and where its digit came from.
+///
+/// Order crossover copies the free cells between two random cuts from
+/// `parent_x`, then fills the other free cells with the digits still
+/// missing, in the order they appear in `parent_y`. Swap mutation then
+/// swaps each free cell with a random free cell, with probability `rate`.
+/// A child of two rows that permute the same digits permutes them too.
+#[inline]
+pub fn breed_row<const N: usize, R: Rng + ?Sized>(
+    rng: &mut R,
+    rate: Bernoulli,
+    base_row: &[u8; N],
+    parent_x: &[u8; N],
+    parent_y: &[u8; N],
+    child: &mut [u8; N],
+    mut inherit: impl FnMut(usize, Inheritance),
+) {
+    let free: ArrayVec<usize, N> = (0..N).filter(|&col| base_row[col] == 0).collect();
+    *child = *parent_x;
+    for col in (0..N).filter(|&col| base_row[col] != 0) {
+        inherit(col, Inheritance::ParentX);
+    }
+
+    let (a, b) = (rng.gen_range(0..=free.len()), rng.gen_range(0..=free.len()));
+    let cuts = a.min(b)..a.max(b);
+    let mut needed = [0_u8; 256];
+    for &col in &free {
+        needed[usize::from(parent_x[col])] += 1;
+    }
+    for &col in &free[cuts.clone()] {
+        needed[usize::from(parent_x[col])] -= 1;
+        inherit(col, Inheritance::ParentX);
+    }
+
+    let mut order = free.iter().map(|&col| parent_y[col]);
+    for &col in free[..cuts.start].iter().chain(&free[cuts.end..]) {
+        let digit = order
+            .by_ref()
+            .find(|&digit| needed[usize::from(digit)] > 0)
+            .or_else(|| (0..=u8::MAX).find(|&digit| needed[usize::from(digit)] > 0))
+            .unwrap_or(parent_x[col]);
+        needed[usize::from(digit)] = needed[usize::from(digit)].saturating_sub(1);
+        child[col] = digit;
+        inherit(col, Inheritance::ParentY);
+    }
+
+    for &col in &free {
+        if rng.sample(rate) {
+            let other = free[rng.gen_range(0..free.len())];
+            child.swap(col, other);
+            inherit(col, Inheritance::Mutation);
+            inherit(other, Inheritance::Mutation);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sudoku::Row;
+    use crate::testing::SOLVED_9;
+    use rand::SeedableRng;
+    use rand_pcg::Pcg64Mcg;
+
+    /// Returns whether every row of `board` holds each digit once.
+    fn rows_permute(board: &Board<9>) -> bool {
+        board.0.iter().all(|row| {
+            let mut digits = row.0;
+            digits.sort_unstable();
+            digits == [1, 2, 3, 4, 5, 6, 7, 8, 9]
+        })
+    }
+
+    #[test]
+    fn test_permutation() {
+        let mut rng = Pcg64Mcg::seed_from_u64(1);
+        let mut base = SOLVED_9;
+        for (i, row) in base.0.iter_mut().enumerate() {
+            row.0[i % 3..i % 3 + 5].fill(0);
+        }
+
+        for _ in 0..100 {
+            let x = random_board(&mut rng, &base);
+            let y = random_board(&mut rng, &base);
+            assert!(rows_permute(&x));
+            assert_eq!(x, base.overlay(&x));
+
+            for rate in [0.0, 0.3, 1.0] {
+                let rate = Bernoulli::new(rate).unwrap();
+                let mut child = Board([Row([0; 9]); 9]);
+                for i in 0..9 {
+                    breed_row(
+                        &mut rng,
+                        rate,
+                        &base.0[i].0,
+                        &x.0[i].0,
+                        &y.0[i].0,
+                        &mut child.0[i].0,
+                        |col, parent| {
+                            assert!(base.0[i].0[col] == 0 || parent == Inheritance::ParentX);
+                        },
+                    );
+                }
+                assert!(rows_permute(&child));
+                assert_eq!(child, base.overlay(&child));
+            }
+        }
+
+        // Without mutation, a child of two copies of a board is that board.
+        let x = random_board(&mut rng, &base);
+        let mut child = [0; 9];
+        breed_row(
+            &mut rng,
+            Bernoulli::new(0.0).unwrap(),
+            &base.0[0].0,
+            &x.0[0].0,
+            &x.0[0].0,
+            &mut child,
+            |_, _| {},
+        );
+        assert_eq!(x.0[0].0, child);
+    }
+}
@@ -0,0 +1,181 @@
+#![warn(
+    clippy::all,
+    // clippy::restriction,
+    clippy::pedantic,
+    clippy::nursery,
+    clippy::cargo
+)]
+
+use super::genetics::GAParams;
+use super::solver::{Budget, GeneticSolver, Solver};
+use super::sudoku::{Board, Fitness};
+use super::termination::Termination;
+
+/// The result of one short GA probe.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct Probe {
+    /// The seed the probe was run with.
+    pub seed: u64,
+    /// The best fitness score reached by the probe.
+    pub best: Fitness,
+    /// The generation the probe solved the puzzle in, if it did.
+    pub solved: Option<u64>,
+}
+
+/// How hard a puzzle is for the genetic algorithm, as measured by probes.
+///
+/// This says nothing about how hard the puzzle is for a human: it only
+/// reflects how far the GA gets within a fixed number of generations.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Hardness {
+    probes: Vec<Probe>,
+}
+
+impl Hardness {
+    /// Returns every probe, in the order they were run.
+    #[inline]
+    #[must_use]
+    pub fn probes(&self) -> &[Probe] {
+        &self.probes
+    }
+
+    /// Returns the best fitness score of every probe, in ascending order.
+    #[inline]
+    #[must_use]
+    pub fn best_scores(&self) -> Vec<Fitness> {
+        let mut scores: Vec<Fitness> = self.probes.iter().map(|probe| probe.best).collect();
+        scores.sort_unstable();
+        scores
+    }
+
+    /// Returns the median best fitness score, or `None` if there were no
+    /// probes. The lower median is used for an even number of probes.
+    #[inline]
+    #[must_use]
+    pub fn median(&self) -> Option<Fitness> {
+        let scores = self.best_scores();
+        scores.get(scores.len().saturating_sub(1) / 2).copied()
+    }
+
+    /// Returns the mean best fitness score, or `None` if there were no
+    /// probes. This is the GA hardness score: 0 means every probe solved the
+    /// puzzle.
+    #[inline]
+    #[must_use]
+    pub fn mean(&self) -> Option<f64> {
+        if self.probes.is_empty() {
+            return None;
+        }
+
+        let total: u32 = self.probes.iter().map(|probe| probe.best.get()).sum();
+        let count = u32::try_from(self.probes.len()).ok()?;
+
+        Some(f64::from(total) / f64::from(count))
+    }
+
+    /// Returns the sample variance of the best fitness scores, or `None` if
+    /// there were fewer than two probes.
+    #[inline]
+    #[must_use]
+    pub fn variance(&self) -> Option<f64> {
+        let mean = self.mean()?;
+        let count = u32::try_from(self.probes.len()).ok()?;
+        if count < 2 {
+            return None;
+        }
+
+        let squares: f64 = self
+            .probes
+            .iter()
+            .map(|probe| (f64::from(probe.best) - mean).powi(2))
+            .sum();
+
+        Some(squares / f64::from(count - 1))
+    }
+
+    /// Returns the fraction of probes that solved the puzzle.
+    #[inline]
+    #[must_use]
+    pub fn solved_fraction(&self) -> f64 {
+        let solved = self
+            .probes
+            .iter()
+            .filter(|probe| probe.solved.is_some())
+            .count();
+
+        #[allow(clippy::cast_precision_loss)]
+        let fraction = solved as f64 / self.probes.len().max(1) as f64;
+        fraction
+    }
+}
+
+/// Estimates how hard `base` is for the genetic algorithm.
+///
+/// Runs `probes` short seeded GA runs of at most `generations` generations
+/// each and records the best fitness score each one reaches. Probe `k` is
+/// seeded with `seed + k`, so grading is reproducible.
+///
+/// # Arguments
+///
+/// * `base` - The puzzle to grade
+/// * `params` - GA parameters for every probe; any seed is replaced
+/// * `probes` - The number of probes to run
+/// * `generations` - The generation budget of each probe
+/// * `seed` - The seed of the first probe
+#[inline]
+#[must_use]
+pub fn grade<const N: usize>(
+    base: &Board<N>,
+    params: &GAParams,
+    probes: usize,
+    generations: u64,
+    seed: u64,
+) -> Hardness {
+    let budget = Budget {
+        generations: Some(generations),
+        duration: None,
+        target: None,
+    };
+
+    let probes = (0..probes as u64)
+        .map(|k| {
+            let seed = seed.wrapping_add(k);
+            let solver = GeneticSolver::new(params.clone().with_seed(seed));
+            let outcome = solver.solve(base, budget, &mut ());
+
+            Probe {
+                seed,
+                best: outcome.best.map_or(Fitness::MAX, |(_, score)| score),
+                solved: (outcome.termination == Termination::Solved).then_some(outcome.generation),
+            }
+        })
+        .collect();
+
+    Hardness { probes }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::SOLVED_9;
+
+    #[test]
+    fn test_grade() {
+        let params = GAParams::new(50, 0.5, 0.05, None).unwrap();
+        let mut base = SOLVED_9;
+        for i in 0..9 {
+            base.0[i].0[(i * 4) % 9] = 0;
+            base.0[i].0[(i * 7 + 2) % 9] = 0;
+        }
+
+        let hardness = grade(&base, &params, 4, 5, 42);
+        assert_eq!(4, hardness.probes().len());
+        assert_eq!(hardness, grade(&base, &params, 4, 5, 42));
+        assert!(hardness.median() <= hardness.best_scores().last().copied());
+
+        let trivial = grade(&SOLVED_9, &params, 3, 5, 0);
+        assert_eq!(Some(Fitness::ZERO), trivial.median());
+        assert_eq!(Some(0.0), trivial.mean());
+        assert!((trivial.solved_fraction() - 1.0).abs() < f64::EPSILON);
+    }
+}
@@ -0,0 +1,150 @@
+#![warn(
+    clippy::all,
+    // clippy::restriction,
+    clippy::pedantic,
+    clippy::nursery,
+    clippy::cargo
+)]
+
+use super::certificate::verify;
+use super::exact::solve;
+use super::sudoku::Board;
+
+/// The distinct solutions found for one puzzle over any number of runs.
+///
+/// Every run of the genetic algorithm stops at the first solution it finds,
+/// so runs of a puzzle with more than one solution may end on different
+/// boards. Collecting them here turns that into proof that the puzzle is
+/// not unique.
+#[derive(Debug, Clone)]
+pub struct HallOfFame<const N: usize> {
+    puzzle: Board<N>,
+    capacity: usize,
+    solutions: Vec<Board<N>>,
+}
+
+impl<const N: usize> HallOfFame<N> {
+    /// Returns an empty hall of fame for `puzzle`, which keeps up to
+    /// `capacity` solutions.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `capacity` is 0.
+    #[inline]
+    #[must_use]
+    pub fn new(puzzle: Board<N>, capacity: usize) -> Self {
+        assert!(capacity > 0);
+
+        Self {
+            puzzle,
+            capacity,
+            solutions: Vec::new(),
+        }
+    }
+
+    /// Records a solution, and returns true if it is new. Boards that do not
+    /// solve the puzzle are ignored, as are new solutions once the hall of
+    /// fame is full.
+    ///
+    /// # Panics
+    ///
+    /// Panics if N is not a supported board size.
+    #[inline]
+    pub fn record(&mut self, solution: &Board<N>) -> bool {
+        let is_new = self.solutions.len() < self.capacity
+            && !self.solutions.contains(solution)
+            && verify(&self.puzzle, solution);
+
+        if is_new {
+            self.solutions.push(*solution);
+        }
+        is_new
+    }
+
+    /// Returns the distinct solutions found, in the order they were found.
+    #[inline]
+    #[must_use]
+    pub fn solutions(&self) -> &[Board<N>] {
+        &self.solutions
+    }
+
+    /// Returns true if more than one distinct solution has been found.
+    #[inline]
+    #[must_use]
+    pub fn has_multiple_solutions(&self) -> bool {
+        self.solutions.len() > 1
+    }
+
+    /// Returns true if the puzzle has exactly one solution. This is known to
+    /// be false once two solutions have been found; otherwise the exact
+    /// solver decides.
+    ///
+    /// # Panics
+    ///
+    /// Panics if N is not a supported board size.
+    #[inline]
+    #[must_use]
+    pub fn is_unique(&self) -> bool {
+        !self.has_multiple_solutions() && solve(&self.puzzle, 2).len() == 1
+    }
+}
+
+/// Returns the cells, as `(row, col)`, in which two boards differ.
+#[inline]
+#[must_use]
+pub fn differing_cells<const N: usize>(a: &Board<N>, b: &Board<N>) -> Vec<(usize, usize)> {
+    (0..N)
+        .flat_map(|row| (0..N).map(move |col| (row, col)))
+        .filter(|&(row, col)| a.0[row].0[col] != b.0[row].0[col])
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sudoku::Row;
+    use crate::testing::SOLVED_4;
+
+    /// A puzzle with two solutions, which differ by swapping 1 and 2.
+    fn ambiguous() -> (Board<4>, Board<4>) {
+        let mut puzzle = SOLVED_4;
+        let mut other = SOLVED_4;
+        for (row, other) in puzzle.0.iter_mut().zip(&mut other.0) {
+            for (cell, swapped) in row.0.iter_mut().zip(&mut other.0) {
+                match *cell {
+                    1 | 2 => {
+                        *swapped = 3 - *cell;
+                        *cell = 0;
+                    }
+                    _ => {}
+                }
+            }
+        }
+        (puzzle, other)
+    }
+
+    #[test]
+    fn test_hall_of_fame() {
+        let (puzzle, other) = ambiguous();
+        let mut fame = HallOfFame::new(puzzle, 4);
+
+        assert!(!fame.is_unique());
+        assert!(fame.record(&SOLVED_4));
+        assert!(!fame.record(&SOLVED_4));
+        assert!(!fame.has_multiple_solutions());
+        assert!(!fame.record(&Board([Row([1, 2, 3, 4]); 4])));
+
+        assert!(fame.record(&other));
+        assert!(fame.has_multiple_solutions());
+        assert!(!fame.is_unique());
+        assert_eq!(&[SOLVED_4, other], fame.solutions());
+        assert_eq!(8, differing_cells(&SOLVED_4, &other).len());
+
+        let mut puzzle = SOLVED_4;
+        puzzle.0[0].0[0] = 0;
+        let mut fame = HallOfFame::new(puzzle, 1);
+        assert!(fame.is_unique());
+        assert!(fame.record(&SOLVED_4));
+        assert!(fame.is_unique());
+    }
+}
@@ -0,0 +1,233 @@
+#![warn(
+    clippy::all,
+    // clippy::restriction,
+    clippy::pedantic,
+    clippy::nursery,
+    clippy::cargo
+)]
+
+use super::solver::Observer;
+use super::sudoku::{Board, Fitness};
+use std::collections::VecDeque;
+
+/// The fittest board of one generation.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct Snapshot<const N: usize> {
+    pub generation: u64,
+    pub board: Board<N>,
+    pub score: Fitness,
+}
+
+/// A ring buffer of the fittest board of each of the last `capacity`
+/// generations, for replaying a run after it ends.
+///
+/// Solvers with several populations record one snapshot per generation,
+/// from the fittest of all of them.
+#[derive(Debug, Clone)]
+pub struct BestHistory<const N: usize> {
+    capacity: usize,
+    snapshots: VecDeque<Snapshot<N>>,
+}
+
+impl<const N: usize> BestHistory<N> {
+    /// Returns an empty history.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `capacity` is 0.
+    #[inline]
+    #[must_use]
+    pub fn new(capacity: usize) -> Self {
+        assert!(capacity > 0);
+
+        Self {
+            capacity,
+            snapshots: VecDeque::with_capacity(capacity),
+        }
+    }
+
+    /// Records a snapshot, dropping the oldest if the history is full. A
+    /// snapshot of the latest generation replaces it if it is fitter.
+    #[inline]
+    pub fn record(&mut self, snapshot: Snapshot<N>) {
+        if let Some(last) = self.snapshots.back_mut() {
+            if last.generation == snapshot.generation {
+                if snapshot.score < last.score {
+                    *last = snapshot;
+                }
+                return;
+            }
+        }
+
+        if self.snapshots.len() == self.capacity {
+            self.snapshots.pop_front();
+        }
+        self.snapshots.push_back(snapshot);
+    }
+
+    /// Returns the number of snapshots held.
+    #[inline]
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.snapshots.len()
+    }
+
+    #[inline]
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.snapshots.is_empty()
+    }
+
+    /// Returns the `i`th oldest snapshot held.
+    #[inline]
+    #[must_use]
+    pub fn get(&self, i: usize) -> Option<&Snapshot<N>> {
+        self.snapshots.get(i)
+    }
+
+    /// Returns the position of the snapshot of `generation`, or of the
+    /// nearest generation held if it has been dropped or not reached.
+    #[inline]
+    #[must_use]
+    pub fn position(&self, generation: u64) -> Option<usize> {
+        let after = self
+            .snapshots
+            .partition_point(|snapshot| snapshot.generation < generation);
+
+        (!self.is_empty()).then(|| after.min(self.len() - 1))
+    }
+
+    #[inline]
+    pub fn clear(&mut self) {
+        self.snapshots.clear();
+    }
+}
+
+impl<const N: usize> Observer<N> for BestHistory<N> {
+    #[inline]
+    fn on_generation(&mut self, generation: u64, population: &[Board<N>], scores: &[Fitness]) {
+        let fittest = scores.iter().enumerate().min_by_key(|(_, score)| **score);
+
+        if let Some((i, &score)) = fittest {
+            if let Some(&board) = population.get(i) {
+                self.record(Snapshot {
+                    generation,
+                    board,
+                    score,
+                });
+            }
+        }
+    }
+}
+
+/// A movement through a `BestHistory`.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum Scrub {
+    Back,
+    Forward,
+    First,
+    Last,
+    /// To the snapshot of a generation, or the nearest one held.
+    Generation(u64),
+}
+
+/// A cursor over a `BestHistory`, starting at its latest snapshot.
+#[derive(Debug)]
+pub struct Scrubber<'a, const N: usize> {
+    history: &'a BestHistory<N>,
+    position: usize,
+}
+
+impl<'a, const N: usize> Scrubber<'a, N> {
+    #[inline]
+    #[must_use]
+    pub fn new(history: &'a BestHistory<N>) -> Self {
+        Self {
+            history,
+            position: history.len().saturating_sub(1),
+        }
+    }
+
+    /// Moves the cursor, stopping at either end of the history.
+    #[inline]
+    pub fn scrub(&mut self, scrub: Scrub) {
+        let last = self.history.len().saturating_sub(1);
+
+        self.position = match scrub {
+            Scrub::Back => self.position.saturating_sub(1),
+            Scrub::Forward => (self.position + 1).min(last),
+            Scrub::First => 0,
+            Scrub::Last => last,
+            Scrub::Generation(generation) => {
+                self.history.position(generation).unwrap_or(self.position)
+            }
+        };
+    }
+
+    /// Returns the snapshot under the cursor, or `None` if the history is
+    /// empty.
+    #[inline]
+    #[must_use]
+    pub fn current(&self) -> Option<&'a Snapshot<N>> {
+        self.history.get(self.position)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::SOLVED_4;
+
+    fn snapshot(generation: u64, score: u32) -> Snapshot<4> {
+        Snapshot {
+            generation,
+            board: SOLVED_4,
+            score: Fitness::new(score),
+        }
+    }
+
+    #[test]
+    fn test_history() {
+        let mut history = BestHistory::new(3);
+        assert_eq!(None, history.position(0));
+
+        for generation in 0..5 {
+            history.record(snapshot(generation, 10));
+        }
+        history.record(snapshot(4, 12));
+        history.record(snapshot(4, 3));
+
+        assert_eq!(3, history.len());
+        assert_eq!(Some(&snapshot(2, 10)), history.get(0));
+        assert_eq!(Some(&snapshot(4, 3)), history.get(2));
+        assert_eq!(Some(0), history.position(0));
+        assert_eq!(Some(1), history.position(3));
+        assert_eq!(Some(2), history.position(9));
+    }
+
+    #[test]
+    fn test_scrubber() {
+        let mut history = BestHistory::new(10);
+        history.on_generation(0, &[SOLVED_4, SOLVED_4], &[4, 2].map(Fitness::new));
+        history.on_generation(1, &[SOLVED_4], &[Fitness::new(1)]);
+        history.on_generation(2, &[SOLVED_4], &[Fitness::ZERO]);
+
+        let mut scrubber = Scrubber::new(&history);
+        let generation = |scrubber: &Scrubber<'_, 4>| scrubber.current().map(|s| s.generation);
+        assert_eq!(Some(2), generation(&scrubber));
+
+        scrubber.scrub(Scrub::Forward);
+        assert_eq!(Some(2), generation(&scrubber));
+        scrubber.scrub(Scrub::Back);
+        assert_eq!(Some(1), generation(&scrubber));
+        scrubber.scrub(Scrub::First);
+        scrubber.scrub(Scrub::Back);
+        assert_eq!(Some(&snapshot(0, 2)), scrubber.current());
+        scrubber.scrub(Scrub::Generation(1));
+        assert_eq!(Some(1), generation(&scrubber));
+        scrubber.scrub(Scrub::Last);
+        assert_eq!(Some(2), generation(&scrubber));
+
+        assert_eq!(None, Scrubber::new(&BestHistory::<4>::new(1)).current());
+    }
+}
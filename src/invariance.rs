@@ -0,0 +1,147 @@
+#![warn(
+    clippy::all,
+    // clippy::restriction,
+    clippy::pedantic,
+    clippy::nursery,
+    clippy::cargo
+)]
+
+use super::genetics::GAParams;
+use super::grading::{grade, Hardness};
+use super::sudoku::symmetry::Symmetry;
+use super::sudoku::Board;
+use rand::Rng;
+
+/// How many standard errors two mean hardness scores may differ by before
+/// the difference is no longer put down to noise.
+const TOLERANCE: f64 = 3.0;
+
+/// A transformed copy of the puzzle and how hard the GA found it.
+#[derive(Debug, Clone)]
+pub struct Variant<const N: usize> {
+    pub symmetry: Symmetry<N>,
+    pub hardness: Hardness,
+    /// True if the hardness differs from the baseline by more than noise.
+    pub deviates: bool,
+}
+
+/// The result of an invariance check.
+#[derive(Debug, Clone)]
+pub struct InvarianceReport<const N: usize> {
+    /// The hardness of the untransformed puzzle.
+    pub baseline: Hardness,
+    pub variants: Vec<Variant<N>>,
+}
+
+impl<const N: usize> InvarianceReport<N> {
+    /// Returns true if no variant deviated from the baseline.
+    #[inline]
+    #[must_use]
+    pub fn is_invariant(&self) -> bool {
+        self.variants.iter().all(|variant| !variant.deviates)
+    }
+}
+
+/// Checks that the GA finds `base` equally hard under symmetry transforms
+/// and digit relabelings.
+///
+/// Grades the puzzle and `transforms` randomly transformed copies of it with
+/// the same probe seeds. Since every copy is the same puzzle in disguise, a
+/// variant whose mean hardness is further than `TOLERANCE` standard errors
+/// from the baseline points at an encoding or operator that depends on
+/// digit identity or orientation.
+///
+/// # Arguments
+///
+/// * `base` - The puzzle to check
+/// * `params` - GA parameters for every probe
+/// * `transforms` - The number of transformed copies to grade
+/// * `probes` - The number of probes per copy
+/// * `generations` - The generation budget of each probe
+/// * `seed` - The seed of the first probe of every copy
+/// * `rng` - The random number generator used to pick transforms
+#[inline]
+#[must_use]
+pub fn check_invariance<const N: usize, R: Rng + ?Sized>(
+    base: &Board<N>,
+    params: &GAParams,
+    transforms: usize,
+    probes: usize,
+    generations: u64,
+    seed: u64,
+    rng: &mut R,
+) -> InvarianceReport<N> {
+    let baseline = grade(base, params, probes, generations, seed);
+
+    let variants = (0..transforms)
+        .map(|_| {
+            let symmetry = Symmetry::random(rng);
+            let hardness = grade(&symmetry.apply(base), params, probes, generations, seed);
+            let deviates = deviates(&baseline, &hardness);
+
+            Variant {
+                symmetry,
+                hardness,
+                deviates,
+            }
+        })
+        .collect();
+
+    InvarianceReport { baseline, variants }
+}
+
+fn deviates(baseline: &Hardness, hardness: &Hardness) -> bool {
+    let (Some(expected), Some(actual)) = (baseline.mean(), hardness.mean()) else {
+        return false;
+    };
+    let count = u32::try_from(baseline.probes().len()).unwrap_or(u32::MAX);
+    let variance =
+        baseline.variance().unwrap_or_default() + hardness.variance().unwrap_or_default();
+    let standard_error = (variance / f64::from(count.max(1))).sqrt();
+
+    // Best fitness scores are whole numbers, so allow half a point even when
+    // every probe agreed.
+    (actual - expected).abs() > TOLERANCE.mul_add(standard_error, 0.5)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::overrides::CandidateOverrides;
+    use crate::testing::SOLVED_9;
+    use rand::SeedableRng;
+    use rand_pcg::Pcg64Mcg;
+    use std::fmt::Write;
+    use std::sync::Arc;
+
+    #[test]
+    fn test_check_invariance() {
+        let mut base = SOLVED_9;
+        for i in 0..9 {
+            base.0[i].0[(i * 4) % 9] = 0;
+            base.0[i].0[(i * 7 + 2) % 9] = 0;
+        }
+        let params = GAParams::new(50, 0.5, 0.05, None).unwrap();
+        let mut rng = Pcg64Mcg::seed_from_u64(3);
+        let report = check_invariance(&base, &params, 3, 4, 5, 7, &mut rng);
+
+        assert_eq!(3, report.variants.len());
+        assert!(report.is_invariant(), "{report:?}");
+
+        // Overrides pinning every cell to the solution are not transformed
+        // with the puzzle, so they only help the untransformed copy.
+        let mut pins = String::new();
+        for (i, row) in SOLVED_9.0.iter().enumerate() {
+            for (j, digit) in row.0.iter().enumerate() {
+                writeln!(pins, "{},{},{digit}", i + 1, j + 1).unwrap();
+            }
+        }
+        let pinned = params
+            .with_candidate_overrides(Some(Arc::new(CandidateOverrides::parse(&pins).unwrap())));
+        let report = check_invariance(&base, &pinned, 3, 4, 5, 7, &mut rng);
+
+        assert_eq!(Some(0.0), report.baseline.mean());
+        assert!(!report.is_invariant());
+        assert!(report.variants.iter().all(|variant| variant.deviates));
+    }
+}
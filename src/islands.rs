@@ -0,0 +1,99 @@
+#![warn(
+    clippy::all,
+    // clippy::restriction,
+    clippy::pedantic,
+    clippy::nursery,
+    clippy::cargo
+)]
+
+use super::sudoku::{Board, Fitness};
+
+/// Migrates the `migrants` fittest candidates of each island to the next
+/// island along a ring, where they replace its least fit candidates.
+///
+/// Migrants are chosen from every island before any arrive, so a candidate
+/// moves at most one island per migration. `scores` are moved along with
+/// the candidates, so that they still run parallel to `populations`.
+///
+/// # Panics
+///
+/// Panics if `populations` and `scores` differ in length.
+#[inline]
+pub fn migrate<const N: usize>(
+    populations: &mut [Vec<Board<N>>],
+    scores: &mut [Vec<Fitness>],
+    migrants: usize,
+) {
+    assert_eq!(populations.len(), scores.len());
+
+    // Collected so that every island's migrants leave before any arrive.
+    #[allow(clippy::needless_collect)]
+    let emigrants: Vec<Vec<(Board<N>, Fitness)>> = populations
+        .iter()
+        .zip(scores.iter())
+        .map(|(population, scores)| {
+            ranked(scores)
+                .into_iter()
+                .take(migrants)
+                .map(|i| (population[i], scores[i]))
+                .collect()
+        })
+        .collect();
+
+    for (from, emigrants) in emigrants.into_iter().enumerate() {
+        let to = (from + 1) % populations.len();
+        let worst = ranked(&scores[to]).into_iter().rev();
+
+        for (i, (candidate, score)) in worst.zip(emigrants) {
+            populations[to][i] = candidate;
+            scores[to][i] = score;
+        }
+    }
+}
+
+/// Returns the indices of `scores`, fittest first.
+fn ranked(scores: &[Fitness]) -> Vec<usize> {
+    let mut ranking: Vec<usize> = (0..scores.len()).collect();
+    ranking.sort_by_key(|&i| scores[i]);
+    ranking
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sudoku::Row;
+
+    #[test]
+    fn test_migrate() {
+        let board = |digit| Board([Row([digit; 4]); 4]);
+        let mut populations = vec![
+            vec![board(1), board(2), board(3)],
+            vec![board(4), board(5), board(6)],
+            vec![board(7), board(8), board(9)],
+        ];
+        let mut scores = vec![
+            [3, 1, 2].map(Fitness::new).to_vec(),
+            [5, 4, 6].map(Fitness::new).to_vec(),
+            [9, 8, 7].map(Fitness::new).to_vec(),
+        ];
+
+        migrate(&mut populations, &mut scores, 1);
+        // Each island's best replaced the next island's worst, and the last
+        // island's best went round to the first.
+        assert_eq!(vec![board(9), board(2), board(3)], populations[0]);
+        assert_eq!(vec![board(4), board(5), board(2)], populations[1]);
+        assert_eq!(vec![board(5), board(8), board(9)], populations[2]);
+        assert_eq!([7, 1, 2].map(Fitness::new).to_vec(), scores[0]);
+        assert_eq!([5, 4, 1].map(Fitness::new).to_vec(), scores[1]);
+        assert_eq!([4, 8, 7].map(Fitness::new).to_vec(), scores[2]);
+
+        migrate(&mut populations, &mut scores, 0);
+        assert_eq!([7, 1, 2].map(Fitness::new).to_vec(), scores[0]);
+
+        // With one island, its best replace its worst.
+        let mut island = vec![vec![board(1), board(2)]];
+        let mut scores = vec![[2, 1].map(Fitness::new).to_vec()];
+        migrate(&mut island, &mut scores, 1);
+        assert_eq!(vec![board(2), board(2)], island[0]);
+    }
+}
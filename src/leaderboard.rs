@@ -0,0 +1,182 @@
+#![warn(
+    clippy::all,
+    // clippy::restriction,
+    clippy::pedantic,
+    clippy::nursery,
+    clippy::cargo
+)]
+
+use super::solver::Observer;
+use super::sudoku::{Board, Fitness, Violation};
+
+/// A candidate board that came close to solving the puzzle.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct NearSolution<const N: usize> {
+    pub board: Board<N>,
+    /// The board's fitness score.
+    pub score: Fitness,
+    /// Every duplicated digit on the board, for post-processing.
+    pub violations: Vec<Violation>,
+}
+
+/// Keeps the best distinct boards seen over a run.
+///
+/// When a budget runs out before a solution is found, the single best board
+/// of the `Outcome` is often not the most useful result: an exact solver
+/// that repairs candidates wants several promising ones. Pass a leaderboard
+/// as the observer of a run and ask it for the `top_k` boards afterwards.
+#[derive(Debug, Clone)]
+pub struct Leaderboard<const N: usize> {
+    capacity: usize,
+    /// The best boards so far, by increasing score and then by when they
+    /// were first seen.
+    entries: Vec<(Board<N>, Fitness)>,
+}
+
+impl<const N: usize> Leaderboard<N> {
+    /// Returns an empty leaderboard.
+    ///
+    /// # Arguments
+    ///
+    /// * `capacity` - the number of boards to keep
+    #[inline]
+    #[must_use]
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            entries: Vec::with_capacity(capacity),
+        }
+    }
+
+    /// Offers a scored board, which is kept if it is new and among the
+    /// `capacity` best seen.
+    #[inline]
+    pub fn offer(&mut self, board: &Board<N>, score: Fitness) {
+        if self.is_full() && self.worst().is_some_and(|worst| score >= worst) {
+            return;
+        }
+        if self.entries.iter().any(|(kept, _)| kept == board) {
+            return;
+        }
+
+        let at = self.entries.partition_point(|&(_, kept)| kept <= score);
+        self.entries.insert(at, (*board, score));
+        self.entries.truncate(self.capacity);
+    }
+
+    /// Returns the `k` best distinct boards seen, best first, with their
+    /// violations. Returns fewer if fewer were seen, or if `k` is larger
+    /// than the capacity.
+    #[inline]
+    #[must_use]
+    pub fn top_k(&self, k: usize) -> Vec<NearSolution<N>> {
+        self.entries
+            .iter()
+            .take(k)
+            .map(|(board, score)| NearSolution {
+                board: *board,
+                score: *score,
+                violations: board.violations(),
+            })
+            .collect()
+    }
+
+    /// Forgets every board seen.
+    #[inline]
+    pub fn clear(&mut self) {
+        self.entries.clear();
+    }
+
+    fn is_full(&self) -> bool {
+        self.entries.len() >= self.capacity
+    }
+
+    fn worst(&self) -> Option<Fitness> {
+        self.entries.last().map(|&(_, score)| score)
+    }
+}
+
+impl<const N: usize> Observer<N> for Leaderboard<N> {
+    #[inline]
+    fn on_generation(&mut self, _: u64, population: &[Board<N>], scores: &[Fitness]) {
+        for (board, &score) in population.iter().zip(scores) {
+            self.offer(board, score);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::genetics::GAParams;
+    use crate::solver::{Budget, GeneticSolver, Solver};
+    use crate::sudoku::Unit;
+    use crate::termination::Termination;
+    use crate::testing::SOLVED_9;
+
+    #[test]
+    fn test_offer() {
+        let mut boards = [SOLVED_9; 4];
+        for (i, board) in boards.iter_mut().enumerate() {
+            board.0[0].0[0] = u8::try_from(i + 1).unwrap();
+        }
+        let mut leaderboard = Leaderboard::new(3);
+
+        leaderboard.offer(&boards[0], Fitness::new(5));
+        leaderboard.offer(&boards[1], Fitness::new(2));
+        leaderboard.offer(&boards[1], Fitness::new(2));
+        leaderboard.offer(&boards[2], Fitness::new(5));
+        leaderboard.offer(&boards[3], Fitness::new(9));
+        let scores: Vec<u32> = leaderboard
+            .top_k(5)
+            .iter()
+            .map(|near| near.score.get())
+            .collect();
+        assert_eq!(vec![2, 5, 5], scores);
+
+        leaderboard.offer(&boards[3], Fitness::new(1));
+        let top = leaderboard.top_k(2);
+        assert_eq!(2, top.len());
+        assert_eq!((boards[3], Fitness::new(1)), (top[0].board, top[0].score));
+        assert_eq!((boards[1], Fitness::new(2)), (top[1].board, top[1].score));
+        assert_eq!(
+            vec![Unit::Row, Unit::Column, Unit::Box],
+            top[0]
+                .violations
+                .iter()
+                .map(|violation| violation.unit)
+                .collect::<Vec<_>>()
+        );
+
+        leaderboard.clear();
+        assert!(leaderboard.top_k(3).is_empty());
+    }
+
+    #[test]
+    fn test_budget_exhausted() {
+        let mut base = SOLVED_9;
+        for row in &mut base.0 {
+            row.0[..6].fill(0);
+        }
+        let budget = Budget {
+            generations: Some(2),
+            duration: None,
+            target: None,
+        };
+        let mut leaderboard = Leaderboard::new(5);
+        let outcome = GeneticSolver::new(GAParams::new(100, 0.5, 0.05, None).unwrap()).solve(
+            &base,
+            budget,
+            &mut leaderboard,
+        );
+        let top = leaderboard.top_k(5);
+
+        if outcome.termination == Termination::BudgetExhausted {
+            assert_eq!(5, top.len());
+            assert_eq!(outcome.best.map(|(_, score)| score), Some(top[0].score));
+            assert!(top.windows(2).all(|w| w[0].score <= w[1].score));
+            assert!(top.windows(2).all(|w| w[0].board != w[1].board));
+            assert!(top.iter().all(|near| !near.violations.is_empty()));
+        }
+    }
+}
@@ -6,6 +6,56 @@
     clippy::cargo
 )]
 
+pub mod ancestry;
+pub mod anytime;
+pub mod arena;
+pub mod audit;
+pub mod bench;
+pub mod cancel;
+pub mod certificate;
+pub mod comparison;
+#[cfg(feature = "db")]
+pub mod corpus;
+pub mod diversity;
+pub mod duel;
 pub mod errors;
+pub mod exact;
+pub mod fingerprint;
+pub mod formats;
 pub mod genetics;
+pub mod grading;
+pub mod hall_of_fame;
+pub mod history;
+pub mod invariance;
+pub mod islands;
+pub mod leaderboard;
+pub mod movie;
+pub mod overrides;
+#[cfg(feature = "plugins")]
+pub mod plugin;
+pub mod portfolio;
+pub mod presets;
+pub mod profile;
+pub mod quickstart;
+pub mod restarts;
+#[cfg(feature = "db")]
+pub mod results;
+#[cfg(feature = "scripting")]
+pub mod scripting;
+pub mod simulation;
+pub mod sinks;
+pub mod sizes;
+pub mod solver;
+pub mod stats;
+pub mod stream;
 pub mod sudoku;
+pub mod tabu;
+pub mod tempering;
+pub mod termination;
+#[cfg(any(test, feature = "testing"))]
+pub mod testing;
+#[cfg(feature = "watch")]
+pub mod watch;
+pub mod webhook;
+
+pub use quickstart::{solve_str, Solution, SolveOptions};
@@ -6,46 +6,996 @@
     clippy::cargo
 )]
 
-use clap::{App, Arg};
+use clap::{App, AppSettings, Arg, ArgMatches, SubCommand};
+#[cfg(feature = "tui")]
+use crossterm::{
+    cursor::{Hide, MoveTo, Show},
+    event::{self, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers},
+    execute, queue,
+    style::{Attribute, Color, Print, ResetColor, SetAttribute, SetForegroundColor},
+    terminal::{self, Clear, ClearType, EnterAlternateScreen, LeaveAlternateScreen},
+};
+#[cfg(feature = "db")]
+use genetic_sudoku::corpus::{self, Change};
+#[cfg(feature = "tui")]
+use genetic_sudoku::duel::{full_size, Duel, Layout, Move, Viewport, MIN_SIZE};
+#[cfg(feature = "movie")]
+use genetic_sudoku::movie;
+#[cfg(feature = "plugins")]
+use genetic_sudoku::plugin::discover;
+#[cfg(feature = "tui")]
+use genetic_sudoku::profile::PhaseTimes;
+#[cfg(feature = "db")]
+use genetic_sudoku::results::{ResultsDb, RunRecord};
+#[cfg(feature = "scripting")]
+use genetic_sudoku::scripting::FitnessScript;
+#[cfg(feature = "tui")]
+use genetic_sudoku::stats::PopulationStats;
+#[cfg(feature = "watch")]
+use genetic_sudoku::watch::FileWatcher;
 use genetic_sudoku::{
-    genetics::{generate_initial_population, run_simulation, GAParams, MAX_POPULATION},
-    sudoku::Board,
+    ancestry::{LineageRecorder, Origin, Step},
+    audit::{p_value, AuditStats},
+    bench::{BenchReport, BenchRun, EvaluationCounter},
+    board_sizes,
+    cancel::CancellationToken,
+    certificate::{self, Certificate},
+    comparison::{align_by_generation, align_by_time, diff_configs, FieldDiff, StatsLog},
+    diversity::DiversityHistory,
+    errors::{GivenConflict, ParamError},
+    exact::{find_single, reveal},
+    fingerprint::RunConfig,
+    formats::{
+        self,
+        document::PuzzleDocument,
+        pretty::{cell_name, Pretty},
+    },
+    genetics::{
+        is_rate, make_traced_child, Crossover, Curriculum, Dedup, Encoding, GAParams,
+        Hypermutation, Inheritance, Mutation, MutationSchedule, Operator, Pairing,
+        PopulationSchedule, RngStrategy, Selection, MAX_POPULATION,
+    },
+    grading::Hardness,
+    hall_of_fame::{differing_cells, HallOfFame},
+    history::{BestHistory, Scrub, Scrubber},
+    invariance::check_invariance,
+    movie::Recording,
+    overrides::CandidateOverrides,
+    presets::Preset,
+    profile::Phase,
+    restarts::{AutoRestart, Cataclysm, RestartDecision, StallRestart},
+    simulation::Simulation,
+    sinks::{SinkSpec, Telemetry},
+    sizes::{BoardSizes, SizedDriver},
+    solver::{
+        backtrack::Hybrid, Budget, GeneticSolver, IslandSolver, Metrics, Observer, PortfolioSolver,
+        Solver, TemperingSolver,
+    },
+    stats::StatsAggregator,
+    sudoku::{
+        propagate::{propagate as propagate_singles, Propagation},
+        Board, CellChange, Fitness, GivensMask, UnitWeights,
+    },
+    termination::Termination,
+    webhook::Webhook,
 };
+use std::io::ErrorKind;
+#[cfg(feature = "tui")]
+use std::io::Write;
 use std::path::{Path, PathBuf};
-use std::time::Instant;
+use std::process::ExitCode;
+use std::str::FromStr;
+use std::sync::Arc;
+use std::thread::sleep;
+use std::time::{Duration, Instant};
 
 // The board size for puzzles. Change this for larger or smaller boards.
-const BOARD_SIZE: usize = 9;
+board_sizes! {
+    /// The board sizes the binary can solve. Supporting another size only
+    /// takes adding it here.
+    struct Sizes = [4, 6, 9, 12, 16, 25, 36];
+}
+
+/// The board size assumed when there is no board to detect it from.
+const DEFAULT_BOARD_SIZE: usize = 9;
+
+// How long to pause after narrating each generation in teaching mode.
+const TEACH_DELAY: Duration = Duration::from_secs(1);
+
+// How many generations a --mutation-schedule decays over unless given.
+const DEFAULT_DECAY_GENERATIONS: u64 = 1000;
+
+// How many generations to sample for the fitness curve printed by --stats.
+const STATS_CAPACITY: usize = 20;
+
+// How many recent generations --stats computes rolling percentiles over.
+const STATS_WINDOW: usize = 100;
+
+// How many generations of best boards --scrub keeps for replay.
+const SCRUB_CAPACITY: usize = 1000;
+
+// How often --record keeps a frame, unless --record-every says otherwise.
+const RECORD_EVERY: u64 = 10;
+
+// How long movie shows each frame, in hundredths of a second, unless
+// --delay says otherwise.
+#[cfg(feature = "movie")]
+const MOVIE_DELAY: u16 = 20;
+
+// How many generations of populations --lineage keeps to trace the
+// winning lineage through.
+const LINEAGE_CAPACITY: usize = 1000;
+
+// How many distinct solutions of one puzzle --bench keeps to report.
+const FAME_CAPACITY: usize = 16;
+
+// How many evenly spaced times diff-runs compares fitness curves at.
+const DIFF_STEPS: u32 = 10;
+
+// How often --duel redraws the GA's pane while the human is idle.
+#[cfg(feature = "tui")]
+const DUEL_FRAME: Duration = Duration::from_millis(50);
+
+#[allow(clippy::struct_excessive_bools)]
+struct Args {
+    path: PathBuf,
+    params: GAParams,
+    benchmark: bool,
+    bench_json: Option<PathBuf>,
+    bench_runs: Option<u32>,
+    budget: Budget,
+    teach: bool,
+    coordinates: bool,
+    stats: bool,
+    diversity: Option<usize>,
+    scrub: bool,
+    record: Option<(PathBuf, u64)>,
+    lineage: bool,
+    pins: Vec<(usize, usize, u8)>,
+    propagate: bool,
+    certificate: Option<PathBuf>,
+    sign_key: Option<PathBuf>,
+    sinks: Vec<SinkSpec>,
+    versus: Option<GAParams>,
+    replicas: Vec<GAParams>,
+    swap_interval: u64,
+    islands: Option<usize>,
+    migration_interval: u64,
+    portfolio: Vec<GAParams>,
+    threads: usize,
+    reallocation_interval: u64,
+    #[cfg(feature = "db")]
+    db: Option<PathBuf>,
+    #[cfg(feature = "http")]
+    webhook: Option<(String, Duration)>,
+    #[cfg(feature = "watch")]
+    watch: bool,
+    #[cfg(feature = "tui")]
+    duel: bool,
+}
+
+enum Command {
+    Solve(Box<Args>),
+    Hint {
+        board: PathBuf,
+        reveal: bool,
+        coordinates: bool,
+        candidates: bool,
+    },
+    Analyze {
+        board: PathBuf,
+        params: Box<GAParams>,
+        transforms: usize,
+        probes: usize,
+        generations: u64,
+        seed: Option<u64>,
+    },
+    CheckCertificate {
+        certificate: PathBuf,
+        key: Option<PathBuf>,
+    },
+    Normalize {
+        input: String,
+    },
+    ExportParams {
+        preset: Box<Preset>,
+        output: Option<PathBuf>,
+    },
+    ImportParams {
+        preset: PathBuf,
+    },
+    DiffRuns {
+        runs: [String; 2],
+        #[cfg(feature = "db")]
+        db: Option<PathBuf>,
+    },
+    #[cfg(feature = "db")]
+    History {
+        db: PathBuf,
+        board: Option<PathBuf>,
+    },
+    #[cfg(feature = "db")]
+    RecordSeeds {
+        db: PathBuf,
+        board: PathBuf,
+        params: Box<GAParams>,
+        probes: usize,
+        generations: u64,
+        seed: Option<u64>,
+    },
+    #[cfg(feature = "db")]
+    ReplaySeeds {
+        db: PathBuf,
+        board: Option<PathBuf>,
+        params: Box<GAParams>,
+    },
+    #[cfg(feature = "plugins")]
+    Plugins {
+        directory: PathBuf,
+        board: Option<PathBuf>,
+    },
+    #[cfg(feature = "movie")]
+    Movie {
+        recording: PathBuf,
+        output: PathBuf,
+        delay: u16,
+    },
+}
+
+fn parse_params(
+    spec: &str,
+    rng_strategy: RngStrategy,
+) -> Result<GAParams, Box<dyn std::error::Error>> {
+    let fields: Vec<&str> = spec.split(',').collect();
+    if !(3..=4).contains(&fields.len()) {
+        return Err(format!("expected N,S,F or N,S,F,R but got {spec:?}").into());
+    }
+
+    let restart = fields.get(3).copied().map(parse_restart).transpose()?;
+    let population = parse_population(fields[0])?;
+    let selection_rate = parse_rate(fields[1])?;
+    let params = GAParams::new(population, selection_rate, parse_rate(fields[2])?, restart)
+        .map_err(describe_param_error)?
+        .with_rng_strategy(rng_strategy);
+
+    Ok(params)
+}
+
+fn parse_number<T: FromStr>(number: &str) -> Result<T, Box<dyn std::error::Error>> {
+    number
+        .parse()
+        .map_err(|_| format!("expected a whole number but got {number:?}").into())
+}
+
+/// Parses the value of `arg` if it was given, or returns `default`.
+fn parse_or<T>(
+    matches: &ArgMatches,
+    arg: &str,
+    parse: fn(&str) -> Result<T, Box<dyn std::error::Error>>,
+    default: T,
+) -> Result<T, Box<dyn std::error::Error>> {
+    matches.value_of(arg).map_or(Ok(default), parse)
+}
+
+fn parse_population(population: &str) -> Result<usize, Box<dyn std::error::Error>> {
+    let parsed = parse_number(population)?;
+    if parsed > MAX_POPULATION {
+        return Err(format!(
+            "expected a population of at most {MAX_POPULATION} but got {population:?}"
+        )
+        .into());
+    }
+
+    Ok(parsed)
+}
+
+fn parse_capacity(capacity: &str) -> Result<usize, Box<dyn std::error::Error>> {
+    match parse_number(capacity)? {
+        0 => Err("expected at least 1 generation but got \"0\"".into()),
+        parsed => Ok(parsed),
+    }
+}
+
+fn parse_stall_restart(spec: &str) -> Result<StallRestart, Box<dyn std::error::Error>> {
+    let (generations, keep) = spec.split_once(':').unwrap_or((spec, "1"));
+    Ok(StallRestart {
+        generations: parse_restart(generations)?,
+        keep: parse_number(keep)?,
+    })
+}
+
+fn parse_cataclysm(spec: &str) -> Result<Cataclysm, Box<dyn std::error::Error>> {
+    let (generations, rate) = spec
+        .split_once(':')
+        .map_or((spec, None), |(n, rate)| (n, Some(rate)));
+    Ok(Cataclysm {
+        generations: parse_restart(generations)?,
+        rate: rate.map_or(Ok(Cataclysm::default().rate), parse_rate)?,
+    })
+}
+
+fn parse_hypermutation(spec: &str) -> Result<Hypermutation, Box<dyn std::error::Error>> {
+    let defaults = Hypermutation::default();
+    let mut fields = spec.split(':');
+    let hypermutation = Hypermutation {
+        stall: parse_restart(fields.next().unwrap_or_default())?,
+        factor: fields.next().map_or(Ok(defaults.factor), parse_number)?,
+        generations: fields
+            .next()
+            .map_or(Ok(defaults.generations), parse_number)?,
+    };
+
+    if fields.next().is_some() {
+        return Err(format!("expected K[:FACTOR[:GENERATIONS]] but got {spec:?}").into());
+    }
+    if !(hypermutation.factor >= 0.0 && hypermutation.factor.is_finite()) {
+        return Err(format!(
+            "expected a non-negative factor but got \"{}\"",
+            hypermutation.factor
+        )
+        .into());
+    }
+    Ok(hypermutation)
+}
+
+fn parse_hybrid(spec: &str) -> Result<Hybrid, Box<dyn std::error::Error>> {
+    let defaults = Hybrid::default();
+    let mut fields = spec.split(':');
+    let hybrid = Hybrid {
+        threshold: parse_number(fields.next().unwrap_or_default())?,
+        stall: fields.next().map_or(Ok(defaults.stall), parse_number)?,
+    };
+
+    if fields.next().is_some() {
+        return Err(format!("expected THRESHOLD[:STALL] but got {spec:?}").into());
+    }
+    Ok(hybrid)
+}
+
+fn parse_restart(restart: &str) -> Result<u64, Box<dyn std::error::Error>> {
+    match parse_number(restart)? {
+        0 => Err("expected a restart interval of at least 1 generation but got \"0\"".into()),
+        parsed => Ok(parsed),
+    }
+}
+
+/// Parses a 1-based `ROW,COL,DIGIT` pin into 0-based coordinates.
+fn parse_pin(pin: &str) -> Result<(usize, usize, u8), Box<dyn std::error::Error>> {
+    let fields: Vec<&str> = pin.split(',').collect();
+    let [row, col, digit] = fields[..] else {
+        return Err(format!("expected a pin ROW,COL,DIGIT but got {pin:?}").into());
+    };
+    let (row, col): (usize, usize) = (parse_number(row)?, parse_number(col)?);
+    let digit: u8 = parse_number(digit)?;
+
+    // The upper bounds depend on the board, and are checked once it is read.
+    if row == 0 || col == 0 || digit == 0 {
+        return Err(
+            format!("expected a row, column and digit of at least 1 but got {pin:?}").into(),
+        );
+    }
+
+    Ok((row - 1, col - 1, digit))
+}
+
+fn parse_rates(rates: &str) -> Result<Vec<f32>, Box<dyn std::error::Error>> {
+    rates.split(',').map(parse_rate).collect()
+}
+
+/// Parses the mutation rates of parallel tempering replicas, which otherwise
+/// share the parameters of `params`.
+fn parse_replicas(
+    rates: &str,
+    params: &GAParams,
+) -> Result<Vec<GAParams>, Box<dyn std::error::Error>> {
+    (0..)
+        .zip(parse_rates(rates)?)
+        .map(|(i, rate)| {
+            let replica = GAParams::new(
+                params.population(),
+                params.selection_rate(),
+                rate,
+                params.restart(),
+            )
+            .map_err(describe_param_error)?
+            .with_rng_strategy(params.rng_strategy())
+            .with_population_schedule(params.population_schedule())
+            .map_err(describe_param_error)?
+            .with_mutation_schedule(params.mutation_schedule())
+            .with_selection(params.selection())
+            .map_err(describe_param_error)?
+            .with_crossover(params.crossover())
+            .with_mutation_operator(params.mutation_operator())
+            .with_encoding(params.encoding())
+            .with_weights(params.weights())
+            .with_curriculum(params.curriculum())
+            .with_elitism(params.elitism())
+            .with_plus_selection(params.plus_selection())
+            .with_pairing(params.pairing())
+            .with_incest_prevention(params.incest_prevention())
+            .with_children_per_pair(params.children_per_pair())
+            .map_err(describe_param_error)?
+            .with_local_search(params.local_search())
+            .with_tabu(params.tabu())
+            .with_candidate_overrides(params.candidate_overrides().cloned());
+            #[cfg(feature = "scripting")]
+            let replica = replica.with_script(params.script().cloned());
+            Ok(match params.seed() {
+                Some(seed) => replica.with_seed(seed.wrapping_add(i)),
+                None => replica,
+            })
+        })
+        .collect()
+}
+
+/// Describes why GA parameters cannot be used, in terms of the options that
+/// set them.
+fn describe_param_error(err: ParamError) -> String {
+    match err {
+        ParamError::PopulationTooLarge { population, max } => {
+            format!("expected a population of at most {max} but got {population}")
+        }
+        ParamError::SelectionRate(rate) => {
+            format!("expected a selection fraction between 0.0 and 1.0 but got {rate}")
+        }
+        ParamError::MutationRate(rate) => {
+            format!("expected a mutation rate between 0.0 and 1.0 but got {rate}")
+        }
+        ParamError::NoParentPairs {
+            population,
+            selection_rate,
+            survivors,
+        } => format!(
+            "a population of {population} with a selection fraction of {selection_rate} \
+             keeps {survivors} survivor(s), but breeding needs at least 2; \
+             try a larger population or fraction"
+        ),
+        ParamError::ScheduleMinimum { population, min } => format!(
+            "the schedule's minimum population {min} is larger than the population \
+             {population}; try a minimum of at most {population}"
+        ),
+        ParamError::ChunkSize => "expected a chunk size of at least 1 but got 0".to_owned(),
+        ParamError::TournamentSize => {
+            "expected a tournament of at least 1 candidate but got 0".to_owned()
+        }
+        ParamError::ChildrenPerPair => {
+            "expected at least 1 child per pair of parents but got 0".to_owned()
+        }
+        ParamError::CataclysmRate(rate) => {
+            format!("expected a cataclysm rate between 0.0 and 1.0 but got {rate}")
+        }
+        ParamError::HypermutationFactor(factor) => {
+            format!("expected a finite hypermutation factor of at least 0.0 but got {factor}")
+        }
+        ParamError::NoIslands => "expected at least 1 island but got 0".to_owned(),
+        ParamError::EmptyPortfolio => {
+            "expected at least 1 parameter set in the portfolio".to_owned()
+        }
+        ParamError::PortfolioThreads { members, threads } => format!(
+            "a portfolio of {members} parameter sets needs at least {members} threads but got \
+             {threads}"
+        ),
+    }
+}
+
+/// Parses the members of a portfolio, the parameters given first, and the
+/// number of threads they share, or returns no members if no portfolio was
+/// asked for. Exits if there are fewer threads than members. The members
+/// share the parameters' constraints, and a seeded run gives each its own
+/// seed derived from the parameters' seed.
+fn parse_portfolio(
+    matches: &ArgMatches,
+    params: &GAParams,
+) -> Result<(Vec<GAParams>, usize), Box<dyn std::error::Error>> {
+    if !matches.is_present("portfolio") {
+        return Ok((Vec::new(), 0));
+    }
+
+    let mut members = vec![params.clone()];
+    for (i, spec) in (1..).zip(matches.values_of("portfolio").into_iter().flatten()) {
+        let member = parse_params(spec, params.rng_strategy())?
+            .with_candidate_overrides(params.candidate_overrides().cloned());
+        #[cfg(feature = "scripting")]
+        let member = member.with_script(params.script().cloned());
+        members.push(match params.seed() {
+            Some(seed) => member.with_seed(seed.wrapping_add(i)),
+            None => member,
+        });
+    }
+
+    let threads = match matches.value_of("threads") {
+        Some(threads) => parse_number(threads)?,
+        None => rayon::current_num_threads().max(members.len()),
+    };
+    if threads < members.len() {
+        invalid_arguments(&describe_param_error(ParamError::PortfolioThreads {
+            members: members.len(),
+            threads,
+        }));
+    }
+    Ok((members, threads))
+}
+
+/// Parses the number of islands, exiting if an island's share of the
+/// population leaves too few survivors to breed.
+fn parse_island_count(
+    matches: &ArgMatches,
+    params: &GAParams,
+) -> Result<Option<usize>, Box<dyn std::error::Error>> {
+    let Some(islands) = matches.value_of("islands").map(parse_islands).transpose()? else {
+        return Ok(None);
+    };
+
+    let population = params.population() / islands;
+    if let Err(err) = params.clone().with_population(population) {
+        invalid_arguments(&format!(
+            "on each of {islands} islands, {}",
+            describe_param_error(err)
+        ));
+    }
+    Ok(Some(islands))
+}
+
+/// Adapts a parser into a clap validator that reports the parse error.
+fn validator<T>(
+    parse: fn(&str) -> Result<T, Box<dyn std::error::Error>>,
+) -> impl Fn(String) -> Result<(), String> {
+    move |value| parse(&value).map(|_| ()).map_err(|err| err.to_string())
+}
+
+/// Reports an invalid combination of arguments the way clap reports invalid
+/// values, and exits.
+fn invalid_arguments(message: &str) -> ! {
+    clap::Error::with_description(message, clap::ErrorKind::ValueValidation).exit()
+}
+
+fn parse_islands(islands: &str) -> Result<usize, Box<dyn std::error::Error>> {
+    match parse_number(islands)? {
+        0 => Err("expected at least 1 island but got \"0\"".into()),
+        islands => Ok(islands),
+    }
+}
+
+fn parse_chunk_size(size: &str) -> Result<usize, Box<dyn std::error::Error>> {
+    match parse_number(size)? {
+        0 => Err("expected a chunk of at least 1 candidate but got \"0\"".into()),
+        size => Ok(size),
+    }
+}
+
+fn parse_children_per_pair(children: &str) -> Result<usize, Box<dyn std::error::Error>> {
+    match parse_number(children)? {
+        0 => Err("expected at least 1 child per pair but got \"0\"".into()),
+        children => Ok(children),
+    }
+}
+
+fn parse_rate(rate: &str) -> Result<f32, Box<dyn std::error::Error>> {
+    match rate.parse() {
+        Ok(parsed) if is_rate(parsed) => Ok(parsed),
+        _ => Err(format!("expected a rate between 0.0 and 1.0 but got {rate:?}").into()),
+    }
+}
+
+fn parse_schedule(spec: &str) -> Result<PopulationSchedule, Box<dyn std::error::Error>> {
+    let fields: Vec<&str> = spec.split(':').collect();
+
+    match fields[..] {
+        ["fixed"] => Ok(PopulationSchedule::Fixed),
+        ["linear", min, generations] => Ok(PopulationSchedule::Linear {
+            min: parse_number(min)?,
+            generations: parse_number(generations)?,
+        }),
+        ["fitness", min, fitness] => Ok(PopulationSchedule::Fitness {
+            min: parse_number(min)?,
+            fitness: parse_number(fitness)?,
+        }),
+        _ => Err(format!(
+            "expected fixed, linear:MIN:GENERATIONS or fitness:MIN:SCORE but got {spec:?}"
+        )
+        .into()),
+    }
+}
+
+fn parse_mutation_schedule(spec: &str) -> Result<MutationSchedule, Box<dyn std::error::Error>> {
+    let (kind, generations) = match spec.split_once(':') {
+        Some((kind, generations)) => (kind, parse_number(generations)?),
+        None => (spec, DEFAULT_DECAY_GENERATIONS),
+    };
+
+    match kind {
+        "constant" if !spec.contains(':') => Ok(MutationSchedule::Constant),
+        "exp" => Ok(MutationSchedule::Exponential { generations }),
+        "linear" => Ok(MutationSchedule::Linear { generations }),
+        "cosine" => Ok(MutationSchedule::Cosine { generations }),
+        _ => Err(format!(
+            "expected constant, or exp, linear or cosine with an optional :GENERATIONS, \
+             but got {spec:?}"
+        )
+        .into()),
+    }
+}
+
+fn parse_selection(spec: &str) -> Result<Selection, Box<dyn std::error::Error>> {
+    let fields: Vec<&str> = spec.split(':').collect();
+
+    match fields[..] {
+        ["truncation"] => Ok(Selection::Truncation),
+        ["tournament", size] => match parse_number(size)? {
+            0 => Err("expected a tournament of at least 1 candidate but got \"0\"".into()),
+            size => Ok(Selection::Tournament { size }),
+        },
+        ["rank"] => Ok(Selection::Rank),
+        ["pareto"] => Ok(Selection::Pareto),
+        _ => Err(
+            format!("expected truncation, tournament:SIZE, rank or pareto but got {spec:?}").into(),
+        ),
+    }
+}
+
+fn parse_mutation_operator(spec: &str) -> Result<Mutation, Box<dyn std::error::Error>> {
+    match spec {
+        "random-resetting" => Ok(Mutation::RandomResetting),
+        "swap-within-row" => Ok(Mutation::SwapWithinRow),
+        "scramble-row" => Ok(Mutation::ScrambleRow),
+        _ => Err(format!(
+            "expected random-resetting, swap-within-row or scramble-row but got {spec:?}"
+        )
+        .into()),
+    }
+}
+
+fn parse_encoding(spec: &str) -> Result<Encoding, Box<dyn std::error::Error>> {
+    match spec {
+        "cells" => Ok(Encoding::Cells),
+        "permutation" => Ok(Encoding::Permutation),
+        _ => Err(format!("expected cells or permutation but got {spec:?}").into()),
+    }
+}
+
+fn parse_pairing(spec: &str) -> Result<Pairing, Box<dyn std::error::Error>> {
+    match spec {
+        "adjacent" => Ok(Pairing::Adjacent),
+        "shuffle" => Ok(Pairing::Shuffle),
+        "best-with-random" => Ok(Pairing::BestWithRandom),
+        "round-robin" => Ok(Pairing::RoundRobin),
+        _ => Err(format!(
+            "expected adjacent, shuffle, best-with-random or round-robin but got {spec:?}"
+        )
+        .into()),
+    }
+}
+
+fn parse_weights(spec: &str) -> Result<UnitWeights, Box<dyn std::error::Error>> {
+    let weights = spec
+        .split(',')
+        .map(parse_number::<f32>)
+        .collect::<Result<Vec<_>, _>>()?;
+    let &[rows, columns, boxes] = weights.as_slice() else {
+        return Err(format!("expected ROWS,COLUMNS,BOXES but got {spec:?}").into());
+    };
+
+    let weights = UnitWeights {
+        rows,
+        columns,
+        boxes,
+    };
+    if !weights.is_valid() {
+        return Err("weights must not be negative, and one must be positive".into());
+    }
+    Ok(weights)
+}
+
+fn parse_dedup(spec: &str) -> Result<Dedup, Box<dyn std::error::Error>> {
+    match spec {
+        "random" => Ok(Dedup::Random),
+        "mutate" => Ok(Dedup::Mutate),
+        _ => Err(format!("expected random or mutate but got {spec:?}").into()),
+    }
+}
+
+fn parse_crossover(spec: &str) -> Result<Crossover, Box<dyn std::error::Error>> {
+    match spec {
+        "uniform" => Ok(Crossover::Uniform),
+        "single-point" => Ok(Crossover::SinglePoint),
+        "two-point" => Ok(Crossover::TwoPoint),
+        "row" => Ok(Crossover::Row),
+        "box" => Ok(Crossover::Box),
+        _ => Err(
+            format!("expected uniform, single-point, two-point, row or box but got {spec:?}")
+                .into(),
+        ),
+    }
+}
+
+fn parse_preset(path: &str) -> Result<Preset, Box<dyn std::error::Error>> {
+    Ok(Preset::parse(&std::fs::read_to_string(path)?)?)
+}
 
-fn parse_args() -> Result<(PathBuf, GAParams, bool), Box<dyn std::error::Error>> {
-    let matches = App::new("genetic-sudoku")
+fn parse_candidates(path: &str) -> Result<Arc<CandidateOverrides>, Box<dyn std::error::Error>> {
+    Ok(Arc::new(CandidateOverrides::parse(
+        &std::fs::read_to_string(path)?,
+    )?))
+}
+
+#[cfg(feature = "scripting")]
+fn parse_script(path: &str) -> Result<Arc<FitnessScript>, Box<dyn std::error::Error>> {
+    Ok(Arc::new(FitnessScript::compile(&std::fs::read_to_string(
+        path,
+    )?)?))
+}
+
+#[allow(clippy::too_many_lines)]
+fn build_app() -> App<'static, 'static> {
+    let app = App::new("genetic-sudoku")
         .arg(
             Arg::with_name("population")
                 .help("population per generation")
                 .long("population")
                 .takes_value(true)
-                .value_name("N"),
+                .value_name("N")
+                .validator(validator(parse_population)),
         )
         .arg(
             Arg::with_name("selection")
                 .help("fraction of population selected")
                 .long("fraction")
                 .takes_value(true)
-                .value_name("S"),
+                .value_name("S")
+                .validator(validator(parse_rate)),
         )
         .arg(
             Arg::with_name("mutation")
                 .help("mutation rate as fraction")
                 .long("mutation")
                 .takes_value(true)
-                .value_name("F"),
+                .value_name("F")
+                .validator(validator(parse_rate)),
         )
         .arg(
             Arg::with_name("restart")
                 .help("number of generations to restart population")
                 .long("restart")
                 .takes_value(true)
-                .value_name("R"),
+                .value_name("R")
+                .validator(validator(parse_restart)),
+        )
+        .arg(
+            Arg::with_name("auto-restart")
+                .help("restarts the population when a fresh one is expected to solve sooner")
+                .long("auto-restart")
+                .takes_value(false)
+                .conflicts_with("restart"),
+        )
+        .arg(
+            Arg::with_name("stall-restart")
+                .help(
+                    "restarts all but the KEEP (default 1) fittest candidates after N \
+                     generations without a better best score",
+                )
+                .long("stall-restart")
+                .value_name("N[:KEEP]")
+                .validator(validator(parse_stall_restart))
+                .conflicts_with_all(&["restart", "auto-restart"]),
+        )
+        .arg(
+            Arg::with_name("cataclysm")
+                .help(
+                    "restarts all but the fittest candidate as copies of it mutated at RATE \
+                     (default 0.35) after N generations without a better best score",
+                )
+                .long("cataclysm")
+                .value_name("N[:RATE]")
+                .validator(validator(parse_cataclysm))
+                .conflicts_with_all(&["restart", "auto-restart", "stall-restart"]),
+        )
+        .arg(
+            Arg::with_name("hypermutation")
+                .help(
+                    "multiplies the mutation rate by FACTOR (default 10) for GENERATIONS \
+                     (default 5) generations after K generations without a better best score",
+                )
+                .long("hypermutation")
+                .value_name("K[:FACTOR[:GENERATIONS]]")
+                .validator(validator(parse_hypermutation)),
+        )
+        .arg(
+            Arg::with_name("hybrid")
+                .help(
+                    "hands the fittest candidate to a backtracking finisher once the best score \
+                     is THRESHOLD (default 4) or less and has not improved for STALL (default \
+                     20) generations; a STALL of 0 hands it off every generation",
+                )
+                .long("hybrid")
+                .value_name("THRESHOLD[:STALL]")
+                .min_values(0)
+                .require_equals(true)
+                .validator(validator(parse_hybrid)),
+        )
+        .arg(
+            Arg::with_name("candidates")
+                .help(
+                    "restricts cells to the digits listed in FILE, one ROW,COL,DIGITS line per \
+                     cell such as 3,4,1/5/7",
+                )
+                .long("candidates")
+                .value_name("FILE")
+                .validator(validator(parse_candidates)),
+        )
+        .arg(
+            Arg::with_name("preset")
+                .help(
+                    "starts from the GA parameters of the preset in FILE; options given \
+                     alongside it override its parameters",
+                )
+                .long("preset")
+                .value_name("FILE")
+                .validator(validator(parse_preset)),
+        )
+        .arg(
+            Arg::with_name("schedule")
+                .help("population schedule: fixed, linear:MIN:GENERATIONS or fitness:MIN:SCORE")
+                .long("schedule")
+                .takes_value(true)
+                .value_name("SCHEDULE")
+                .validator(validator(parse_schedule)),
+        )
+        .arg(
+            Arg::with_name("mutation-schedule")
+                .help("decays the mutation rate: constant, exp, linear or cosine[:GENERATIONS]")
+                .long("mutation-schedule")
+                .value_name("SCHEDULE")
+                .validator(validator(parse_mutation_schedule)),
+        )
+        .arg(
+            Arg::with_name("repair")
+                .help("swaps up to SWAPS column duplicates away within their rows before scoring")
+                .long("repair")
+                .value_name("SWAPS")
+                .validator(validator(parse_number::<usize>)),
+        )
+        .arg(
+            Arg::with_name("dedup")
+                .help("replaces duplicate candidates before scoring: random or mutate")
+                .long("dedup")
+                .value_name("REPLACEMENT")
+                .validator(validator(parse_dedup)),
+        )
+        .arg(
+            Arg::with_name("selection-scheme")
+                .help("survivor selection: truncation, tournament:SIZE, rank or pareto")
+                .long("selection")
+                .value_name("SELECTION")
+                .validator(validator(parse_selection)),
+        )
+        .arg(
+            Arg::with_name("crossover")
+                .help("crossover operator: uniform, single-point, two-point, row or box")
+                .long("crossover")
+                .value_name("CROSSOVER")
+                .validator(validator(parse_crossover)),
+        )
+        .arg(
+            Arg::with_name("mutation-operator")
+                .help("mutation operator: random-resetting, swap-within-row or scramble-row")
+                .long("mutation-operator")
+                .value_name("OPERATOR")
+                .validator(validator(parse_mutation_operator)),
+        )
+        .arg(
+            Arg::with_name("encoding")
+                .help("candidate encoding: cells or permutation")
+                .long("encoding")
+                .value_name("ENCODING")
+                .validator(validator(parse_encoding)),
+        )
+        .arg(
+            Arg::with_name("weights")
+                .help("weighs the duplicates in rows, columns and boxes when selecting candidates")
+                .long("weights")
+                .value_name("ROWS,COLUMNS,BOXES")
+                .validator(validator(parse_weights)),
+        )
+        .arg(
+            Arg::with_name("curriculum")
+                .help("phases box constraints into fitness scores over the first GENERATIONS generations")
+                .long("curriculum")
+                .value_name("GENERATIONS")
+                .validator(validator(parse_number::<u64>)),
+        )
+        .arg(
+            Arg::with_name("elitism")
+                .help("carries the K fittest candidates of each generation over unchanged")
+                .long("elitism")
+                .value_name("K")
+                .validator(validator(parse_number::<usize>)),
+        )
+        .arg(
+            Arg::with_name("plus")
+                .help("carries survivors over to compete with their children, as in (μ+λ) evolution")
+                .long("plus")
+                .takes_value(false),
+        )
+        .arg(
+            Arg::with_name("pairing")
+                .help("pairs survivors as parents: adjacent, shuffle, best-with-random or round-robin")
+                .long("pairing")
+                .value_name("POLICY")
+                .validator(validator(parse_pairing)),
+        )
+        .arg(
+            Arg::with_name("incest-prevention")
+                .help("pairs survivors with mates that differ from them in at least CELLS cells")
+                .long("incest-prevention")
+                .value_name("CELLS")
+                .validator(validator(parse_number::<usize>)),
+        )
+        .arg(
+            Arg::with_name("children-per-pair")
+                .help("breeds K children per pair of parents, topping the population up with random immigrants")
+                .long("children-per-pair")
+                .value_name("K")
+                .validator(validator(parse_children_per_pair)),
+        )
+        .arg(
+            Arg::with_name("tabu")
+                .help("keeps children from revisiting the best boards of the last SIZE generations")
+                .long("tabu")
+                .value_name("SIZE")
+                .validator(validator(parse_number::<usize>)),
+        )
+        .arg(
+            Arg::with_name("local-search")
+                .help("hill-climbs each child with up to STEPS swaps within its rows")
+                .long("local-search")
+                .value_name("STEPS")
+                .validator(validator(parse_number::<usize>)),
+        )
+        .arg(
+            Arg::with_name("compact")
+                .help("stores the population in one contiguous buffer")
+                .long("compact")
+                .takes_value(false),
+        )
+        .arg(
+            Arg::with_name("profile")
+                .help("measures the time spent in each phase of a generation")
+                .long("profile")
+                .takes_value(false),
+        )
+        .arg(
+            Arg::with_name("audit")
+                .help(
+                    "breeds SAMPLES extra children per generation to test crossover and mutation \
+                     against their configured rates",
+                )
+                .long("audit")
+                .value_name("SAMPLES")
+                .validator(validator(parse_number::<usize>)),
+        )
+        .arg(
+            Arg::with_name("chunk")
+                .help(
+                    "scores each generation SIZE candidates at a time, so that runs can be \
+                     stopped between chunks",
+                )
+                .long("chunk")
+                .value_name("SIZE")
+                .validator(validator(parse_chunk_size))
+                .conflicts_with("compact"),
+        )
+        .arg(
+            Arg::with_name("worker-rng")
+                .help("reuses one RNG per worker when making children")
+                .long("worker-rng")
+                .takes_value(false),
+        )
+        .arg(
+            Arg::with_name("seed")
+                .help("derives every random choice of the run from SEED, making it reproducible")
+                .long("seed")
+                .value_name("SEED")
+                .validator(validator(parse_number::<u64>)),
         )
         .arg(
             Arg::with_name("bench")
@@ -54,72 +1004,2976 @@ fn parse_args() -> Result<(PathBuf, GAParams, bool), Box<dyn std::error::Error>>
                 .takes_value(false),
         )
         .arg(
-            Arg::with_name("BOARD")
-                .help("board to solve")
-                .required(true),
+            Arg::with_name("bench-json")
+                .help("writes benchmark results to PATH as Bencher Metric Format JSON")
+                .long("bench-json")
+                .value_name("PATH")
+                .requires("bench"),
         )
-        .get_matches();
-
-    let path = Path::new(matches.value_of("BOARD").unwrap()).to_owned();
-    let population = matches.value_of("population").unwrap_or("100").parse()?;
-    let selection_rate = matches.value_of("selection").unwrap_or("0.5").parse()?;
-    let mutation_rate = matches.value_of("mutation").unwrap_or("0.05").parse()?;
-    let restart = match matches.value_of("restart") {
-        None => None,
-        Some(restart) => Some(restart.parse()?),
-    };
-    let benchmark = matches.is_present("bench");
-    let params = GAParams::new(population, selection_rate, mutation_rate, restart);
-
-    Ok((path, params, benchmark))
-}
-
-fn main() -> Result<(), Box<dyn std::error::Error>> {
-    let (path, params, benchmark) = parse_args()?;
-    let board = Board::read(path)?;
-
-    let start = Instant::now();
-    let mut runs: u32 = 0;
-    let mut total_generations: u64 = 0;
-
-    loop {
-        runs += 1;
-
-        let now = Instant::now();
-        let mut generation: u64 = 0;
-        let mut population = generate_initial_population::<BOARD_SIZE, MAX_POPULATION>(&params);
-
-        loop {
-            population = match run_simulation::<BOARD_SIZE, MAX_POPULATION>(
-                &params, generation, &board, population,
-            ) {
-                Ok(solution) => {
-                    total_generations += generation;
-
-                    print!(
-                        "Solution: Generation: {} | Duration: {:?}",
-                        generation,
-                        now.elapsed(),
-                    );
-
-                    if !benchmark {
-                        println!("\n{}", solution);
-                        return Ok(());
-                    }
-
-                    println!(
-                        " | Average Generation: {} | Average Duration: {:?}",
-                        total_generations / u64::from(runs),
-                        start.elapsed() / runs
-                    );
-
-                    break;
-                }
-                Err(no_solution_found) => {
-                    generation += 1;
-                    no_solution_found.next_generation
-                }
-            };
+        .arg(
+            Arg::with_name("bench-runs")
+                .help("stops benchmark mode after RUNS solved runs")
+                .long("bench-runs")
+                .value_name("RUNS")
+                .requires("bench")
+                .validator(validator(parse_number::<u32>)),
+        )
+        .arg(
+            Arg::with_name("max-generations")
+                .help("stops the solve after N generations without a solution")
+                .long("max-generations")
+                .value_name("N")
+                .validator(validator(parse_number::<u64>)),
+        )
+        .arg(
+            Arg::with_name("time-limit")
+                .help("stops the solve after SECONDS without a solution")
+                .long("time-limit")
+                .value_name("SECONDS")
+                .validator(validator(parse_seconds)),
+        )
+        .arg(
+            Arg::with_name("target-fitness")
+                .help("stops the solve once a candidate scores F or better")
+                .long("target-fitness")
+                .value_name("F")
+                .validator(validator(parse_fitness)),
+        )
+        .arg(
+            Arg::with_name("teach")
+                .help("narrates one sample child per generation")
+                .long("teach")
+                .takes_value(false),
+        )
+        .arg(
+            Arg::with_name("coordinates")
+                .help("draws boards with row and column coordinates when teaching")
+                .long("coordinates")
+                .requires("teach"),
+        )
+        .arg(
+            Arg::with_name("stats")
+                .help("prints a sampled fitness curve of each run")
+                .long("stats")
+                .takes_value(false),
+        )
+        .arg(
+            Arg::with_name("diversity")
+                .help("prints the diversity of the last GENERATIONS generations of each run")
+                .long("diversity")
+                .value_name("GENERATIONS")
+                .validator(validator(parse_capacity)),
+        )
+        .arg(
+            Arg::with_name("scrub")
+                .help("steps through the best board of each generation after solving")
+                .long("scrub")
+                .takes_value(false)
+                .conflicts_with_all(&["bench", "versus"]),
+        )
+        .arg(
+            Arg::with_name("record")
+                .help("records the best board of every --record-every'th generation to FILE, for the movie subcommand")
+                .long("record")
+                .value_name("FILE")
+                .conflicts_with_all(&["bench", "versus"]),
+        )
+        .arg(
+            Arg::with_name("record-every")
+                .help("records a frame every GENERATIONS generations [default: 10]")
+                .long("record-every")
+                .value_name("GENERATIONS")
+                .requires("record")
+                .validator(validator(parse_frame_interval)),
+        )
+        .arg(
+            Arg::with_name("lineage")
+                .help("steps through the ancestors of the solution after solving, showing how each was bred")
+                .long("lineage")
+                .takes_value(false)
+                .conflicts_with_all(&["bench", "versus", "temperatures", "islands", "portfolio"]),
+        )
+        .arg(
+            Arg::with_name("pin")
+                .help("fixes a cell to a digit before solving; may be repeated")
+                .long("pin")
+                .value_name("ROW,COL,DIGIT")
+                .multiple(true)
+                .number_of_values(1)
+                .validator(validator(parse_pin)),
+        )
+        .arg(
+            Arg::with_name("propagate")
+                .help("fills in the cells naked and hidden singles force before solving")
+                .long("propagate")
+                .takes_value(false),
+        )
+        .arg(
+            Arg::with_name("certificate")
+                .help("writes a solution certificate to PATH")
+                .long("certificate")
+                .value_name("PATH")
+                .conflicts_with_all(&["bench", "versus"]),
+        )
+        .arg(
+            Arg::with_name("sign-key")
+                .help("signs the certificate with the key in FILE")
+                .long("sign-key")
+                .value_name("FILE")
+                .requires("certificate"),
+        )
+        .arg(
+            Arg::with_name("sink")
+                .help(
+                    "sends each generation's telemetry to SINK: stdout, csv:PATH or jsonl:PATH; \
+                     may be repeated",
+                )
+                .long("sink")
+                .value_name("SINK")
+                .multiple(true)
+                .number_of_values(1)
+                .validator(|spec| spec.parse::<SinkSpec>().map(|_| ()))
+                .conflicts_with("versus"),
+        )
+        .arg(
+            Arg::with_name("versus")
+                .help("races against a second parameter set N,S,F[,R]")
+                .long("versus")
+                .takes_value(true)
+                .value_name("PARAMS")
+                .validator(|spec| {
+                    parse_params(&spec, RngStrategy::default())
+                        .map(|_| ())
+                        .map_err(|err| err.to_string())
+                })
+                .conflicts_with("temperatures"),
+        )
+        .arg(
+            Arg::with_name("temperatures")
+                .help("runs parallel tempering with these comma-separated mutation rates")
+                .long("temperatures")
+                .takes_value(true)
+                .value_name("RATES")
+                .validator(validator(parse_rates)),
+        )
+        .arg(
+            Arg::with_name("swap-interval")
+                .help("generations between elite migrations in parallel tempering")
+                .long("swap-interval")
+                .takes_value(true)
+                .value_name("K")
+                .validator(validator(parse_number::<u64>)),
+        )
+        .arg(
+            Arg::with_name("islands")
+                .help("splits the population between M islands that exchange their best candidates")
+                .long("islands")
+                .value_name("M")
+                .validator(validator(parse_islands))
+                .conflicts_with_all(&["temperatures", "versus"]),
+        )
+        .arg(
+            Arg::with_name("migration-interval")
+                .help("generations between migrations between islands")
+                .long("migration-interval")
+                .value_name("K")
+                .validator(validator(parse_number::<u64>)),
+        )
+        .arg(
+            Arg::with_name("portfolio")
+                .help("races the parameters against parameter set N,S,F[,R] on shared threads; may be repeated")
+                .long("portfolio")
+                .value_name("PARAMS")
+                .multiple(true)
+                .number_of_values(1)
+                .validator(|spec| {
+                    parse_params(&spec, RngStrategy::default())
+                        .map(|_| ())
+                        .map_err(|err| err.to_string())
+                })
+                .conflicts_with_all(&["temperatures", "versus", "islands"]),
+        )
+        .arg(
+            Arg::with_name("threads")
+                .help("worker threads shared by the portfolio")
+                .long("threads")
+                .value_name("T")
+                .validator(validator(parse_number::<usize>)),
+        )
+        .arg(
+            Arg::with_name("reallocation-interval")
+                .help("generations between reallocations of threads to the most promising parameters")
+                .long("reallocation-interval")
+                .value_name("K")
+                .validator(validator(parse_number::<u64>)),
+        )
+        .arg(
+            Arg::with_name("BOARD")
+                .help("board file or URL to solve")
+                .required(true),
+        );
+    let app = app
+        .setting(AppSettings::SubcommandsNegateReqs)
+        .subcommand(
+            SubCommand::with_name("hint")
+                .about("suggests the next cell to fill in a puzzle")
+                .arg(
+                    Arg::with_name("reveal")
+                        .help("fills one cell from the exact solution instead")
+                        .long("reveal"),
+                )
+                .arg(
+                    Arg::with_name("coordinates")
+                        .help("prints the board with the hint filled in, with row and column coordinates")
+                        .long("coordinates"),
+                )
+                .arg(
+                    Arg::with_name("candidates")
+                        .help("prints the candidates of empty cells; implies --coordinates")
+                        .long("candidates"),
+                )
+                .arg(
+                    Arg::with_name("BOARD")
+                        .help("board file or URL to give a hint for")
+                        .required(true),
+                ),
+        )
+        .subcommand(
+            SubCommand::with_name("analyze")
+                .about("analyzes how the solver behaves on a puzzle")
+                .arg(
+                    Arg::with_name("invariance")
+                        .help("checks the GA finds transformed copies of the puzzle equally hard")
+                        .long("invariance")
+                        .required(true),
+                )
+                .arg(
+                    Arg::with_name("transforms")
+                        .help("number of transformed copies to check")
+                        .long("transforms")
+                        .takes_value(true)
+                        .value_name("T")
+                        .validator(validator(parse_number::<usize>)),
+                )
+                .arg(
+                    Arg::with_name("probes")
+                        .help("number of GA probes per copy")
+                        .long("probes")
+                        .takes_value(true)
+                        .value_name("K")
+                        .validator(validator(parse_number::<usize>)),
+                )
+                .arg(
+                    Arg::with_name("generations")
+                        .help("generations per GA probe")
+                        .long("generations")
+                        .takes_value(true)
+                        .value_name("G")
+                        .validator(validator(parse_number::<u64>)),
+                )
+                .arg(
+                    Arg::with_name("seed")
+                        .help("seed of the first GA probe")
+                        .long("seed")
+                        .takes_value(true)
+                        .value_name("SEED")
+                        .validator(validator(parse_number::<u64>)),
+                )
+                .arg(
+                    Arg::with_name("BOARD")
+                        .help("board file or URL to analyze")
+                        .required(true),
+                ),
+        )
+        .subcommand(
+            SubCommand::with_name("check-certificate")
+                .about("checks a solution certificate")
+                .arg(
+                    Arg::with_name("key")
+                        .help("also checks the signature against the key in FILE")
+                        .long("key")
+                        .value_name("FILE"),
+                )
+                .arg(
+                    Arg::with_name("CERTIFICATE")
+                        .help("certificate file to check")
+                        .required(true),
+                ),
+        )
+        .subcommand(
+            SubCommand::with_name("normalize")
+                .about("rewrites the boards of a file as lines of digits, one board per line")
+                .arg(
+                    Arg::with_name("BOARD")
+                        .help("board file or URL to normalize; standard input if - or omitted"),
+                ),
+        )
+        .subcommand(diff_runs_command())
+        .subcommand(params_command());
+    #[cfg(feature = "db")]
+    let app = app
+        .arg(
+            Arg::with_name("db")
+                .help("records the run in a results database")
+                .long("db")
+                .takes_value(true)
+                .value_name("PATH"),
+        )
+        .subcommand(
+            SubCommand::with_name("history")
+                .about("lists past runs from a results database")
+                .arg(
+                    Arg::with_name("DB")
+                        .help("results database to read")
+                        .required(true),
+                )
+                .arg(Arg::with_name("BOARD").help("only list runs of this board")),
+        )
+        .subcommand(corpus_command());
+    #[cfg(feature = "plugins")]
+    let app = app.subcommand(
+        SubCommand::with_name("plugins")
+            .about("lists the plugins in a directory and the board layout they are given")
+            .arg(
+                Arg::with_name("DIR")
+                    .help("directory of plugin libraries")
+                    .required(true),
+            )
+            .arg(Arg::with_name("BOARD").help("also scores this board with each constraint")),
+    );
+    #[cfg(feature = "movie")]
+    let app = app.subcommand(
+        SubCommand::with_name("movie")
+            .about("renders a run recorded with --record as an animated GIF")
+            .arg(
+                Arg::with_name("delay")
+                    .help("shows each frame for CENTISECONDS hundredths of a second [default: 20]")
+                    .long("delay")
+                    .value_name("CENTISECONDS")
+                    .validator(validator(parse_number::<u16>)),
+            )
+            .arg(
+                Arg::with_name("RECORDING")
+                    .help("recorded run to render")
+                    .required(true),
+            )
+            .arg(
+                Arg::with_name("OUTPUT")
+                    .help("GIF file to write")
+                    .required(true),
+            ),
+    );
+    #[cfg(feature = "http")]
+    let app = app
+        .arg(
+            Arg::with_name("webhook")
+                .help("POSTs the progress and result of each run as JSON to URL")
+                .long("webhook")
+                .value_name("URL")
+                .conflicts_with("versus"),
+        )
+        .arg(
+            Arg::with_name("webhook-interval")
+                .help("sends progress to the webhook at most once every SECS seconds [default: 60]")
+                .long("webhook-interval")
+                .value_name("SECS")
+                .requires("webhook")
+                .validator(validator(parse_number::<u64>)),
+        );
+    #[cfg(feature = "watch")]
+    let app = app.arg(
+        Arg::with_name("watch")
+            .help("restarts with the new puzzle whenever the board file changes")
+            .long("watch")
+            .conflicts_with_all(&["bench", "versus", "scrub", "lineage"]),
+    );
+    #[cfg(feature = "tui")]
+    let app = app.arg(
+        Arg::with_name("duel")
+            .help("races you, filling in the puzzle, against the solver in the terminal")
+            .long("duel")
+            .conflicts_with_all(&[
+                "bench",
+                "teach",
+                "stats",
+                "scrub",
+                "lineage",
+                "versus",
+                "temperatures",
+            ]),
+    );
+    #[cfg(feature = "scripting")]
+    let app = app.arg(
+        Arg::with_name("script")
+            .help("adds the penalty a Rhai script gives each board to its fitness")
+            .long("script")
+            .value_name("FILE")
+            .validator(validator(parse_script)),
+    );
+
+    app
+}
+
+fn diff_runs_command() -> App<'static, 'static> {
+    let command = SubCommand::with_name("diff-runs")
+        .about("compares the --stats output of two runs")
+        .arg(
+            Arg::with_name("A")
+                .help("stats log of the first run")
+                .required(true),
+        )
+        .arg(
+            Arg::with_name("B")
+                .help("stats log of the second run")
+                .required(true),
+        );
+    #[cfg(feature = "db")]
+    let command = command.arg(
+        Arg::with_name("db")
+            .help("compares the runs with ids A and B in a results database instead")
+            .long("db")
+            .takes_value(true)
+            .value_name("PATH"),
+    );
+
+    command
+}
+
+/// Parses the limits a solve stops at without a solution.
+fn parse_budget(matches: &ArgMatches) -> Result<Budget, Box<dyn std::error::Error>> {
+    Ok(Budget {
+        generations: matches
+            .value_of("max-generations")
+            .map(parse_number)
+            .transpose()?,
+        duration: matches
+            .value_of("time-limit")
+            .map(parse_seconds)
+            .transpose()?,
+        target: matches
+            .value_of("target-fitness")
+            .map(parse_fitness)
+            .transpose()?,
+    })
+}
+
+fn parse_seconds(seconds: &str) -> Result<Duration, Box<dyn std::error::Error>> {
+    seconds
+        .parse()
+        .ok()
+        .and_then(|seconds| Duration::try_from_secs_f64(seconds).ok())
+        .ok_or_else(|| format!("expected a number of seconds but got {seconds:?}").into())
+}
+
+fn parse_fitness(fitness: &str) -> Result<Fitness, Box<dyn std::error::Error>> {
+    fitness
+        .parse()
+        .map_err(|_| format!("expected a fitness score but got {fitness:?}").into())
+}
+
+fn parse_frame_interval(every: &str) -> Result<u64, Box<dyn std::error::Error>> {
+    match parse_number(every)? {
+        0 => Err("expected a frame every 1 generation or more but got \"0\"".into()),
+        parsed => Ok(parsed),
+    }
+}
+
+fn parse_record(
+    matches: &ArgMatches,
+) -> Result<Option<(PathBuf, u64)>, Box<dyn std::error::Error>> {
+    let Some(path) = matches.value_of("record") else {
+        return Ok(None);
+    };
+    let every = matches
+        .value_of("record-every")
+        .map(parse_frame_interval)
+        .transpose()?
+        .unwrap_or(RECORD_EVERY);
+
+    Ok(Some((PathBuf::from(path), every)))
+}
+
+#[cfg(feature = "http")]
+fn parse_webhook(
+    matches: &ArgMatches,
+) -> Result<Option<(String, Duration)>, Box<dyn std::error::Error>> {
+    let Some(url) = matches.value_of("webhook") else {
+        return Ok(None);
+    };
+    let interval = matches
+        .value_of("webhook-interval")
+        .map(parse_number)
+        .transpose()?
+        .map_or(Webhook::DEFAULT_INTERVAL, Duration::from_secs);
+
+    Ok(Some((url.to_owned(), interval)))
+}
+
+fn params_command() -> App<'static, 'static> {
+    SubCommand::with_name("params")
+        .about("shares GA parameters as presets")
+        .setting(AppSettings::SubcommandRequiredElseHelp)
+        .subcommand(
+            SubCommand::with_name("export")
+                .about("writes the GA options given before the subcommand as a preset")
+                .arg(
+                    Arg::with_name("NAME")
+                        .help("name of the preset, such as the puzzle class it was tuned for")
+                        .required(true),
+                )
+                .arg(
+                    Arg::with_name("description")
+                        .help("describes the preset to whoever uses it")
+                        .long("description")
+                        .value_name("TEXT"),
+                )
+                .arg(
+                    Arg::with_name("output")
+                        .help("writes the preset to PATH instead of standard output")
+                        .long("output")
+                        .value_name("PATH"),
+                ),
+        )
+        .subcommand(
+            SubCommand::with_name("import")
+                .about("checks a preset and describes its parameters")
+                .arg(
+                    Arg::with_name("PRESET")
+                        .help("preset file to check")
+                        .required(true),
+                ),
+        )
+}
+
+#[cfg(feature = "db")]
+fn corpus_command() -> App<'static, 'static> {
+    let db = || {
+        Arg::with_name("DB")
+            .help("results database holding the corpus")
+            .required(true)
+    };
+
+    SubCommand::with_name("corpus")
+        .about("keeps a corpus of seeds that failed to solve a puzzle, to replay after changes")
+        .setting(AppSettings::SubcommandRequiredElseHelp)
+        .subcommand(
+            SubCommand::with_name("record")
+                .about("probes a puzzle and adds the seeds that fail to solve it to the corpus")
+                .arg(db())
+                .arg(
+                    Arg::with_name("BOARD")
+                        .help("board file or URL to probe")
+                        .required(true),
+                )
+                .arg(
+                    Arg::with_name("probes")
+                        .help("number of GA probes to run")
+                        .long("probes")
+                        .value_name("K")
+                        .validator(validator(parse_number::<usize>)),
+                )
+                .arg(
+                    Arg::with_name("generations")
+                        .help("generations per GA probe")
+                        .long("generations")
+                        .value_name("G")
+                        .validator(validator(parse_number::<u64>)),
+                )
+                .arg(
+                    Arg::with_name("seed")
+                        .help("seed of the first GA probe")
+                        .long("seed")
+                        .value_name("SEED")
+                        .validator(validator(parse_number::<u64>)),
+                ),
+        )
+        .subcommand(
+            SubCommand::with_name("replay")
+                .about("replays the corpus and reports whether its seeds improved")
+                .arg(db())
+                .arg(Arg::with_name("BOARD").help("only replay seeds of this board")),
+        )
+}
+
+/// Returns the subcommand given, unless it is `analyze`, `corpus`,
+/// `normalize` or `params`, or there is none. Only `analyze`, `corpus` and
+/// `params` take the GA options, and `normalize` reads its input while it
+/// is parsed.
+fn parse_subcommand(matches: &ArgMatches<'_>) -> Option<Command> {
+    if let Some(check) = matches.subcommand_matches("check-certificate") {
+        return Some(Command::CheckCertificate {
+            certificate: PathBuf::from(check.value_of("CERTIFICATE").unwrap()),
+            key: check.value_of("key").map(PathBuf::from),
+        });
+    }
+
+    if let Some(diff) = matches.subcommand_matches("diff-runs") {
+        return Some(Command::DiffRuns {
+            runs: ["A", "B"].map(|run| diff.value_of(run).unwrap().to_owned()),
+            #[cfg(feature = "db")]
+            db: diff.value_of("db").map(PathBuf::from),
+        });
+    }
+
+    if let Some(hint) = matches.subcommand_matches("hint") {
+        return Some(Command::Hint {
+            board: Path::new(hint.value_of("BOARD").unwrap()).to_owned(),
+            reveal: hint.is_present("reveal"),
+            coordinates: hint.is_present("coordinates"),
+            candidates: hint.is_present("candidates"),
+        });
+    }
+
+    #[cfg(feature = "db")]
+    if let Some(history) = matches.subcommand_matches("history") {
+        return Some(Command::History {
+            db: Path::new(history.value_of("DB").unwrap()).to_owned(),
+            board: history.value_of("BOARD").map(PathBuf::from),
+        });
+    }
+
+    #[cfg(feature = "plugins")]
+    if let Some(plugins) = matches.subcommand_matches("plugins") {
+        return Some(Command::Plugins {
+            directory: PathBuf::from(plugins.value_of("DIR").unwrap()),
+            board: plugins.value_of("BOARD").map(PathBuf::from),
+        });
+    }
+
+    #[cfg(feature = "movie")]
+    if let Some(movie) = matches.subcommand_matches("movie") {
+        return Some(Command::Movie {
+            recording: PathBuf::from(movie.value_of("RECORDING").unwrap()),
+            output: PathBuf::from(movie.value_of("OUTPUT").unwrap()),
+            delay: movie
+                .value_of("delay")
+                .map_or(MOVIE_DELAY, |delay| delay.parse().unwrap()),
+        });
+    }
+
+    None
+}
+
+/// Parses the parameters of the genetic algorithm, starting from those of a
+/// preset if one was given, and exiting if they leave too few survivors to
+/// breed.
+///
+/// Options given alongside a preset override its parameters. Flags and
+/// optional settings can only be added to a preset's parameters, not taken
+/// away.
+fn parse_ga_params(
+    matches: &ArgMatches,
+    rng_strategy: RngStrategy,
+) -> Result<GAParams, Box<dyn std::error::Error>> {
+    let preset = match matches.value_of("preset") {
+        Some(path) => parse_preset(path)?.params(),
+        None => GAParams::new(100, 0.5, 0.05, None).map_err(describe_param_error)?,
+    };
+    let population = parse_or(matches, "population", parse_population, preset.population())?;
+    let selection_rate = parse_or(matches, "selection", parse_rate, preset.selection_rate())?;
+    let mutation_rate = parse_or(matches, "mutation", parse_rate, preset.mutation_rate())?;
+    let restart = matches.value_of("restart").map(parse_restart).transpose()?;
+    let population_schedule = parse_or(
+        matches,
+        "schedule",
+        parse_schedule,
+        preset.population_schedule(),
+    )?;
+    let params = GAParams::new(
+        population,
+        selection_rate,
+        mutation_rate,
+        restart.or_else(|| preset.restart()),
+    )
+    .and_then(|params| params.with_population_schedule(population_schedule))
+    .unwrap_or_else(|err| invalid_arguments(&describe_param_error(err)));
+    let params = params
+        .with_rng_strategy(rng_strategy)
+        .with_mutation_schedule(parse_or(
+            matches,
+            "mutation-schedule",
+            parse_mutation_schedule,
+            preset.mutation_schedule(),
+        )?)
+        .with_selection(parse_or(
+            matches,
+            "selection-scheme",
+            parse_selection,
+            preset.selection(),
+        )?)
+        .map_err(describe_param_error)?
+        .with_crossover(parse_or(
+            matches,
+            "crossover",
+            parse_crossover,
+            preset.crossover(),
+        )?)
+        .with_mutation_operator(parse_or(
+            matches,
+            "mutation-operator",
+            parse_mutation_operator,
+            preset.mutation_operator(),
+        )?)
+        .with_encoding(parse_or(
+            matches,
+            "encoding",
+            parse_encoding,
+            preset.encoding(),
+        )?)
+        .with_weights(parse_or(
+            matches,
+            "weights",
+            parse_weights,
+            preset.weights(),
+        )?)
+        .with_curriculum(
+            matches
+                .value_of("curriculum")
+                .map(parse_number)
+                .transpose()?
+                .map(|generations| Curriculum { generations })
+                .or_else(|| preset.curriculum()),
+        )
+        .with_local_search(parse_or(
+            matches,
+            "local-search",
+            parse_number,
+            preset.local_search(),
+        )?)
+        .with_column_repair(parse_or(
+            matches,
+            "repair",
+            parse_number,
+            preset.column_repair(),
+        )?)
+        .with_dedup(
+            matches
+                .value_of("dedup")
+                .map(parse_dedup)
+                .transpose()?
+                .or_else(|| preset.dedup()),
+        )
+        .with_tabu(parse_or(matches, "tabu", parse_number, preset.tabu())?);
+    let params = parse_survivor_settings(matches, &preset, params)?;
+    let params = parse_stall_responses(matches, &preset, params)?;
+    parse_run_settings(matches, params)
+}
+
+/// Adds how survivors are carried over and paired to `params`: as given on
+/// the command line, or else as in `preset`.
+fn parse_survivor_settings(
+    matches: &ArgMatches,
+    preset: &GAParams,
+    params: GAParams,
+) -> Result<GAParams, Box<dyn std::error::Error>> {
+    params
+        .with_elitism(parse_or(
+            matches,
+            "elitism",
+            parse_number,
+            preset.elitism(),
+        )?)
+        .with_plus_selection(matches.is_present("plus") || preset.plus_selection())
+        .with_pairing(parse_or(
+            matches,
+            "pairing",
+            parse_pairing,
+            preset.pairing(),
+        )?)
+        .with_incest_prevention(parse_or(
+            matches,
+            "incest-prevention",
+            parse_number,
+            preset.incest_prevention(),
+        )?)
+        .with_children_per_pair(
+            matches
+                .value_of("children-per-pair")
+                .map(parse_children_per_pair)
+                .transpose()?
+                .or_else(|| preset.children_per_pair()),
+        )
+        .map_err(|err| describe_param_error(err).into())
+}
+
+/// Adds the responses to a stalled run to `params`: those given on the
+/// command line, or else those of `preset`.
+fn parse_stall_responses(
+    matches: &ArgMatches,
+    preset: &GAParams,
+    params: GAParams,
+) -> Result<GAParams, Box<dyn std::error::Error>> {
+    let params = params
+        .with_auto_restart(
+            matches
+                .is_present("auto-restart")
+                .then(AutoRestart::default)
+                .or_else(|| preset.auto_restart()),
+        )
+        .with_stall_restart(
+            matches
+                .value_of("stall-restart")
+                .map(parse_stall_restart)
+                .transpose()?
+                .or_else(|| preset.stall_restart()),
+        )
+        .with_cataclysm(
+            matches
+                .value_of("cataclysm")
+                .map(parse_cataclysm)
+                .transpose()?
+                .or_else(|| preset.cataclysm()),
+        )
+        .map_err(describe_param_error)?
+        .with_hypermutation(
+            matches
+                .value_of("hypermutation")
+                .map(parse_hypermutation)
+                .transpose()?
+                .or_else(|| preset.hypermutation()),
+        )
+        .map_err(describe_param_error)?
+        .with_hybrid(
+            matches
+                .is_present("hybrid")
+                .then(|| {
+                    matches
+                        .value_of("hybrid")
+                        .map_or(Ok(Hybrid::default()), parse_hybrid)
+                })
+                .transpose()?
+                .or_else(|| preset.hybrid()),
+        );
+    Ok(params)
+}
+
+/// Adds the settings that belong to a single run, rather than to a preset,
+/// to `params`.
+fn parse_run_settings(
+    matches: &ArgMatches,
+    params: GAParams,
+) -> Result<GAParams, Box<dyn std::error::Error>> {
+    let params = params
+        .with_compact_population(matches.is_present("compact"))
+        .with_profiling(matches.is_present("profile"))
+        .with_audit(parse_number(matches.value_of("audit").unwrap_or("0"))?)
+        .with_chunk_size(
+            matches
+                .value_of("chunk")
+                .map(parse_chunk_size)
+                .transpose()?,
+        )
+        .map_err(describe_param_error)?
+        .with_candidate_overrides(
+            matches
+                .value_of("candidates")
+                .map(parse_candidates)
+                .transpose()?,
+        );
+    let params = match matches.value_of("seed") {
+        Some(seed) => params.with_seed(parse_number(seed)?),
+        None => params,
+    };
+    #[cfg(feature = "scripting")]
+    let params = params.with_script(matches.value_of("script").map(parse_script).transpose()?);
+    Ok(params)
+}
+
+fn parse_analyze(
+    analyze: &ArgMatches,
+    params: GAParams,
+) -> Result<Command, Box<dyn std::error::Error>> {
+    Ok(Command::Analyze {
+        board: Path::new(analyze.value_of("BOARD").unwrap()).to_owned(),
+        params: Box::new(params),
+        transforms: analyze.value_of("transforms").unwrap_or("8").parse()?,
+        probes: analyze.value_of("probes").unwrap_or("10").parse()?,
+        generations: analyze.value_of("generations").unwrap_or("50").parse()?,
+        seed: analyze.value_of("seed").map(str::parse).transpose()?,
+    })
+}
+
+fn parse_presets(presets: &ArgMatches, params: &GAParams) -> Command {
+    if let Some(export) = presets.subcommand_matches("export") {
+        return Command::ExportParams {
+            preset: Box::new(Preset::new(
+                export.value_of("NAME").unwrap(),
+                export.value_of("description").unwrap_or_default(),
+                params,
+            )),
+            output: export.value_of("output").map(PathBuf::from),
+        };
+    }
+
+    let import = presets.subcommand_matches("import").unwrap();
+    Command::ImportParams {
+        preset: PathBuf::from(import.value_of("PRESET").unwrap()),
+    }
+}
+
+#[cfg(feature = "db")]
+fn parse_corpus(
+    corpus: &ArgMatches,
+    params: GAParams,
+) -> Result<Command, Box<dyn std::error::Error>> {
+    if let Some(record) = corpus.subcommand_matches("record") {
+        return Ok(Command::RecordSeeds {
+            db: PathBuf::from(record.value_of("DB").unwrap()),
+            board: PathBuf::from(record.value_of("BOARD").unwrap()),
+            params: Box::new(params),
+            probes: record.value_of("probes").unwrap_or("10").parse()?,
+            generations: record.value_of("generations").unwrap_or("500").parse()?,
+            seed: record.value_of("seed").map(str::parse).transpose()?,
+        });
+    }
+
+    let replay = corpus.subcommand_matches("replay").unwrap();
+    Ok(Command::ReplaySeeds {
+        db: PathBuf::from(replay.value_of("DB").unwrap()),
+        board: replay.value_of("BOARD").map(PathBuf::from),
+        params: Box::new(params),
+    })
+}
+
+fn parse_args() -> Result<Command, Box<dyn std::error::Error>> {
+    let matches = build_app().get_matches();
+
+    if let Some(command) = parse_subcommand(&matches) {
+        return Ok(command);
+    }
+
+    if let Some(normalize) = matches.subcommand_matches("normalize") {
+        // Standard input can only be read once, so it is read here rather
+        // than by each of the size detection and the command.
+        let input = match normalize.value_of("BOARD") {
+            None | Some("-") => std::io::read_to_string(std::io::stdin())?,
+            Some(path) => read_board(Path::new(path))?,
+        };
+        return Ok(Command::Normalize { input });
+    }
+
+    let benchmark = matches.is_present("bench");
+    let teach = matches.is_present("teach");
+    let coordinates = matches.is_present("coordinates");
+    let stats = matches.is_present("stats");
+    let diversity = matches
+        .value_of("diversity")
+        .map(parse_capacity)
+        .transpose()?;
+    let scrub = matches.is_present("scrub");
+    let pins = matches
+        .values_of("pin")
+        .into_iter()
+        .flatten()
+        .map(parse_pin)
+        .collect::<Result<_, _>>()?;
+    let rng_strategy = if matches.is_present("worker-rng") {
+        RngStrategy::PerWorker
+    } else {
+        RngStrategy::PerChild
+    };
+    let params = parse_ga_params(&matches, rng_strategy)?;
+    let replicas = match matches.value_of("temperatures") {
+        None => Vec::new(),
+        Some(rates) => parse_replicas(rates, &params)?,
+    };
+    let swap_interval = matches.value_of("swap-interval").unwrap_or("10").parse()?;
+    let islands = parse_island_count(&matches, &params)?;
+    let (portfolio, threads) = parse_portfolio(&matches, &params)?;
+    let versus = match matches.value_of("versus") {
+        None => None,
+        Some(spec) => Some(parse_params(spec, rng_strategy)?),
+    };
+
+    if let Some(analyze) = matches.subcommand_matches("analyze") {
+        return parse_analyze(analyze, params);
+    }
+
+    if let Some(presets) = matches.subcommand_matches("params") {
+        return Ok(parse_presets(presets, &params));
+    }
+
+    #[cfg(feature = "db")]
+    if let Some(corpus) = matches.subcommand_matches("corpus") {
+        return parse_corpus(corpus, params);
+    }
+
+    let path = Path::new(matches.value_of("BOARD").unwrap()).to_owned();
+
+    Ok(Command::Solve(Box::new(Args {
+        path,
+        params,
+        benchmark,
+        bench_json: matches.value_of("bench-json").map(PathBuf::from),
+        bench_runs: matches
+            .value_of("bench-runs")
+            .map(parse_number)
+            .transpose()?,
+        budget: parse_budget(&matches)?,
+        teach,
+        coordinates,
+        stats,
+        diversity,
+        scrub,
+        record: parse_record(&matches)?,
+        lineage: matches.is_present("lineage"),
+        pins,
+        propagate: matches.is_present("propagate"),
+        certificate: matches.value_of("certificate").map(PathBuf::from),
+        sign_key: matches.value_of("sign-key").map(PathBuf::from),
+        sinks: matches
+            .values_of("sink")
+            .into_iter()
+            .flatten()
+            .map(str::parse)
+            .collect::<Result<_, _>>()?,
+        versus,
+        replicas,
+        swap_interval,
+        islands,
+        migration_interval: parse_number(matches.value_of("migration-interval").unwrap_or("10"))?,
+        portfolio,
+        threads,
+        reallocation_interval: parse_number(
+            matches.value_of("reallocation-interval").unwrap_or("10"),
+        )?,
+        #[cfg(feature = "db")]
+        db: matches.value_of("db").map(PathBuf::from),
+        #[cfg(feature = "http")]
+        webhook: parse_webhook(&matches)?,
+        #[cfg(feature = "watch")]
+        watch: matches.is_present("watch"),
+        #[cfg(feature = "tui")]
+        duel: matches.is_present("duel"),
+    })))
+}
+
+struct Teacher<'a, const N: usize> {
+    params: &'a GAParams,
+    board: &'a Board<N>,
+    coordinates: bool,
+    introduced: bool,
+}
+
+impl<const N: usize> Observer<N> for Teacher<'_, N> {
+    fn on_generation(&mut self, generation: u64, population: &[Board<N>], scores: &[Fitness]) {
+        if self.coordinates && !self.introduced {
+            println!(
+                "Puzzle (candidates of empty cells in braces):\n{}\n",
+                Pretty::new(self.board).with_candidates()
+            );
+            self.introduced = true;
+        }
+        narrate_generation(
+            self.params,
+            generation,
+            self.board,
+            population,
+            scores,
+            self.coordinates,
+        );
+    }
+}
+
+fn narrate_generation<const N: usize>(
+    params: &GAParams,
+    generation: u64,
+    board: &Board<N>,
+    population: &[Board<N>],
+    scores: &[Fitness],
+    coordinates: bool,
+) {
+    if population.len() < 2 || scores.contains(&Fitness::ZERO) {
+        return;
+    }
+    let draw = |board: &Board<N>| {
+        if coordinates {
+            Pretty::new(board).to_string()
+        } else {
+            board.to_string()
+        }
+    };
+
+    let mut ranking: Vec<usize> = (0..population.len()).collect();
+    ranking.sort_unstable_by_key(|&i| scores[i]);
+    let parents = (population[ranking[0]], population[ranking[1]]);
+    let trace = make_traced_child(params, board, &parents);
+    let child = board.overlay(&trace.child);
+
+    println!("Generation {generation}: breeding the two fittest candidates");
+    println!(
+        "Parent X (fitness {}):\n{}",
+        scores[ranking[0]],
+        draw(&parents.0)
+    );
+    println!(
+        "Parent Y (fitness {}):\n{}",
+        scores[ranking[1]],
+        draw(&parents.1)
+    );
+    print_mask(board, &trace.mask, &child, coordinates);
+    println!("Child (fitness {}):\n{}", child.fitness(), draw(&child));
+
+    let x = parents.0.objectives().to_array();
+    let y = parents.1.objectives().to_array();
+    let c = child.objectives().to_array();
+    for (k, name) in ["rows", "columns", "boxes"].iter().enumerate() {
+        println!(
+            "Duplicates in {name}: parent X {}, parent Y {}, child {}",
+            x[k], y[k], c[k]
+        );
+    }
+
+    println!();
+    sleep(TEACH_DELAY);
+}
+
+/// Prints where each cell of `child`, a candidate for `board`, came from,
+/// then the digit each mutated cell became.
+fn print_mask<const N: usize>(
+    board: &Board<N>,
+    mask: &[[Inheritance; N]; N],
+    child: &Board<N>,
+    coordinates: bool,
+) {
+    println!("Crossover mask (x/y = inherited from parent, * = mutated, . = given):");
+
+    let mut mutations = Vec::new();
+    for (i, row) in mask.iter().enumerate() {
+        let line: Vec<&str> = row
+            .iter()
+            .enumerate()
+            .map(|(j, inheritance)| {
+                if board.0[i].0[j] != 0 {
+                    return ".";
+                }
+                match inheritance {
+                    Inheritance::ParentX => "x",
+                    Inheritance::ParentY => "y",
+                    Inheritance::Mutation => {
+                        mutations.push((i, j, child.0[i].0[j]));
+                        "*"
+                    }
+                }
+            })
+            .collect();
+        println!("{}", line.join(" "));
+    }
+
+    for &(i, j, digit) in &mutations {
+        if coordinates {
+            println!("Mutation: cell {} became {digit}", cell_name(i, j));
+        } else {
+            println!("Mutation: cell ({i}, {j}) became {digit}");
+        }
+    }
+}
+
+/// Races two parameter sets on `board` until either solves it, or until
+/// `observer` cancels the race.
+fn race<const N: usize>(
+    board: &Board<N>,
+    contenders: [&GAParams; 2],
+    observer: &dyn Observer<N>,
+) -> ExitCode {
+    let start = Instant::now();
+    let mut simulations = contenders.map(|params| Simulation::<N>::new(params.clone(), *board));
+
+    loop {
+        if observer.is_cancelled() {
+            return terminate(Termination::Cancelled, "race cancelled");
+        }
+        let outcomes = simulations.each_mut().map(Simulation::step);
+        let generation = outcomes[0].generation;
+        let solutions = outcomes.map(|outcome| outcome.solution);
+        let best = outcomes.map(|outcome| outcome.stats.map_or(Fitness::ZERO, |stats| stats.best));
+
+        println!(
+            "Generation: {generation:>6} | A: {:>3} | B: {:>3}",
+            best[0], best[1]
+        );
+
+        let winners: Vec<(&str, Board<N>)> = ["A", "B"]
+            .into_iter()
+            .zip(solutions)
+            .filter_map(|(name, solution)| solution.map(|solution| (name, solution)))
+            .collect();
+
+        if !winners.is_empty() {
+            for (name, solution) in winners {
+                println!(
+                    "Solution: {name} | Generation: {generation} | Duration: {:?}\n{solution}",
+                    start.elapsed()
+                );
+            }
+            return ExitCode::from(Termination::Solved.exit_code());
+        }
+    }
+}
+
+/// Races the human against a genetic solver on `board`. The human fills in
+/// the left pane while the right pane shows the solver's fittest candidate,
+/// until either solves the puzzle or the human quits.
+#[cfg(feature = "tui")]
+fn play_duel<const N: usize>(
+    board: &Board<N>,
+    params: &GAParams,
+) -> Result<ExitCode, Box<dyn std::error::Error>> {
+    let mut screen = DuelScreen {
+        human: Duel::new(board),
+        best: None,
+        stats: None,
+        phases: None,
+        drawn: None,
+        quit: false,
+        error: None,
+    };
+
+    terminal::enable_raw_mode()?;
+    execute!(std::io::stdout(), EnterAlternateScreen, Hide)?;
+    let outcome = GeneticSolver::new(params.clone()).solve(board, Budget::unlimited(), &mut screen);
+    execute!(std::io::stdout(), Show, LeaveAlternateScreen)?;
+    terminal::disable_raw_mode()?;
+
+    if let Some(err) = screen.error {
+        return Err(err.into());
+    }
+    let (winner, solution) = if screen.human.is_solved() {
+        ("you", screen.human.board())
+    } else if let Some(solution) = outcome.solution() {
+        ("GA", solution)
+    } else {
+        return Ok(terminate(Termination::Cancelled, "gave up the duel"));
+    };
+
+    println!(
+        "Winner: {winner} | Generation: {} | Duration: {:?}\n{solution}",
+        outcome.generation, outcome.duration
+    );
+    Ok(ExitCode::from(Termination::Solved.exit_code()))
+}
+
+/// The terminal screen of a duel, which reads the human's keys and redraws
+/// both panes between generations.
+#[cfg(feature = "tui")]
+struct DuelScreen<const N: usize> {
+    human: Duel<N>,
+    best: Option<(Board<N>, Fitness)>,
+    stats: Option<PopulationStats>,
+    phases: Option<PhaseTimes>,
+    drawn: Option<Instant>,
+    quit: bool,
+    error: Option<std::io::Error>,
+}
+
+#[cfg(feature = "tui")]
+impl<const N: usize> DuelScreen<N> {
+    fn update(&mut self, generation: u64) -> std::io::Result<()> {
+        let mut changed = false;
+        while event::poll(Duration::ZERO)? {
+            match event::read()? {
+                Event::Key(key) => self.press(key),
+                Event::Resize(..) => {}
+                _ => continue,
+            }
+            changed = true;
+        }
+
+        if changed || self.drawn.is_none_or(|drawn| drawn.elapsed() >= DUEL_FRAME) {
+            self.draw(generation)?;
+            self.drawn = Some(Instant::now());
+        }
+        Ok(())
+    }
+
+    fn press(&mut self, key: KeyEvent) {
+        if key.kind != KeyEventKind::Press {
+            return;
+        }
+
+        match key.code {
+            KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => self.quit = true,
+            KeyCode::Esc | KeyCode::Char('q') => self.quit = true,
+            KeyCode::Up | KeyCode::Char('k') => self.human.move_cursor(Move::Up),
+            KeyCode::Down | KeyCode::Char('j') => self.human.move_cursor(Move::Down),
+            KeyCode::Left | KeyCode::Char('h') => self.human.move_cursor(Move::Left),
+            KeyCode::Right | KeyCode::Char('l') => self.human.move_cursor(Move::Right),
+            KeyCode::Backspace | KeyCode::Delete | KeyCode::Char(' ' | '.') => {
+                self.human.enter(0);
+            }
+            KeyCode::Char(ch) => {
+                if let Some(digit) = ch.to_digit(10).and_then(|digit| u8::try_from(digit).ok()) {
+                    self.human.enter(digit);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Redraws the screen in whichever layout the terminal has room for.
+    fn draw(&self, generation: u64) -> std::io::Result<()> {
+        let mut out = std::io::stdout().lock();
+        let (width, height) = terminal::size()?;
+        let (width, height) = (usize::from(width), usize::from(height));
+
+        queue!(out, Clear(ClearType::All))?;
+        match self.human.layout(width, height) {
+            Layout::Full => self.draw_full(&mut out, generation, (width, height))?,
+            Layout::Compact(view) => self.draw_compact(&mut out, generation, view, width)?,
+            Layout::TooSmall => draw_too_small(&mut out, width, height)?,
+        }
+        out.flush()
+    }
+
+    /// Returns the color of a cell of the human's board, and whether the
+    /// cursor is on it.
+    fn human_style(&self, conflicts: &GivensMask<N>, row: usize, col: usize) -> (Color, bool) {
+        let color = if conflicts.contains(row, col) {
+            Color::Red
+        } else if self.human.givens().contains(row, col) {
+            Color::Reset
+        } else {
+            Color::Cyan
+        };
+        (color, (row, col) == self.human.cursor())
+    }
+
+    /// Draws both boards side by side, and the statistics of the GA's
+    /// population and its phase times, if it is profiled, below them if they
+    /// fit in a terminal of `size`.
+    fn draw_full(
+        &self,
+        out: &mut impl Write,
+        generation: u64,
+        (width, height): (usize, usize),
+    ) -> std::io::Result<()> {
+        let conflicts = self.human.conflicts();
+        let title = format!("You: {} empty", self.human.remaining());
+        draw_pane(out, 0, &title, self.human.board(), |row, col| {
+            self.human_style(&conflicts, row, col)
+        })?;
+        if let Some((best, score)) = &self.best {
+            let title = format!(
+                "GA: generation {generation}, fitness {score} ({})",
+                best.objectives()
+            );
+            draw_pane(out, 2 * N + 10, &title, best, |row, col| {
+                let color = if self.human.givens().contains(row, col) {
+                    Color::Reset
+                } else {
+                    Color::Yellow
+                };
+                (color, false)
+            })?;
+        }
+
+        let help = "Arrows or hjkl move, 1-9 fill in, Space or Backspace clears, q gives up";
+        queue!(
+            out,
+            MoveTo(0, pane_offset::<N>(N)),
+            Print(clip(help, width))
+        )?;
+        let stats_line = pane_offset::<N>(N) + 1;
+        if let Some(stats) = &self.stats {
+            if usize::from(stats_line) < height {
+                let text = format!(
+                    "GA population: best {}, mean {:.1}, median {}, worst {}, diversity {:.1}",
+                    stats.best, stats.mean, stats.median, stats.worst, stats.diversity
+                );
+                queue!(out, MoveTo(0, stats_line), Print(clip(&text, width)))?;
+            }
+        }
+        let phases_top = stats_line + 2;
+        if let Some(phases) = &self.phases {
+            if usize::from(phases_top) + Phase::ALL.len() < height {
+                draw_phases(out, phases_top, phases)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Draws the part of the human's board in `view` under a status line
+    /// with the GA's score, for terminals too small for both boards.
+    fn draw_compact(
+        &self,
+        out: &mut impl Write,
+        generation: u64,
+        view: Viewport,
+        width: usize,
+    ) -> std::io::Result<()> {
+        let conflicts = self.human.conflicts();
+        let ga = self.best.as_ref().map_or_else(String::new, |(_, score)| {
+            format!(" | GA: generation {generation}, fitness {score}")
+        });
+        let status = format!("You: {} empty{ga}", self.human.remaining());
+        queue!(out, MoveTo(0, 0), Print(clip(&status, width)))?;
+
+        for (line, row) in (2..).zip(view.top..view.top + view.rows) {
+            for (x, col) in (0..).step_by(2).zip(view.left..view.left + view.cols) {
+                let (color, highlight) = self.human_style(&conflicts, row, col);
+                draw_cell(
+                    out,
+                    x,
+                    line,
+                    self.human.board().0[row].0[col],
+                    color,
+                    highlight,
+                )?;
+            }
+        }
+
+        let (full_width, full_height) = full_size::<N>();
+        let help = format!(
+            "Rows {}-{}, columns {}-{} of {N} | q gives up | {full_width}x{full_height} shows both boards",
+            view.top + 1,
+            view.top + view.rows,
+            view.left + 1,
+            view.left + view.cols,
+        );
+        let bottom = u16::try_from(view.rows + 2).unwrap_or(u16::MAX);
+        queue!(out, MoveTo(0, bottom), Print(clip(&help, width)))
+    }
+}
+
+/// Warns, in the middle of a `width` by `height` terminal, that it is too
+/// small to draw a duel in.
+#[cfg(feature = "tui")]
+fn draw_too_small(out: &mut impl Write, width: usize, height: usize) -> std::io::Result<()> {
+    let lines = [
+        "Too small".to_owned(),
+        format!("{width}x{height}"),
+        format!("need {}x{}", MIN_SIZE.0, MIN_SIZE.1),
+    ];
+    let top = height.saturating_sub(lines.len()) / 2;
+
+    for (y, line) in (top..).zip(lines) {
+        let line = clip(&line, width);
+        let x = width.saturating_sub(line.chars().count()) / 2;
+        queue!(
+            out,
+            MoveTo(
+                u16::try_from(x).unwrap_or(u16::MAX),
+                u16::try_from(y).unwrap_or(u16::MAX)
+            ),
+            SetForegroundColor(Color::Red),
+            Print(line),
+            ResetColor
+        )?;
+    }
+
+    Ok(())
+}
+
+/// Cuts `text` down to its first `width` characters, so that it cannot
+/// wrap onto the next line.
+#[cfg(feature = "tui")]
+fn clip(text: &str, width: usize) -> &str {
+    text.char_indices()
+        .nth(width)
+        .map_or(text, |(end, _)| &text[..end])
+}
+
+/// Draws one cell at column `x` of line `y`, with digits past 9 as letters
+/// so that every cell takes one column.
+#[cfg(feature = "tui")]
+fn draw_cell(
+    out: &mut impl Write,
+    x: u16,
+    y: u16,
+    digit: u8,
+    color: Color,
+    highlight: bool,
+) -> std::io::Result<()> {
+    let text = match digit {
+        0 => '.',
+        digit => char::from_digit(u32::from(digit), 36).map_or('?', |ch| ch.to_ascii_uppercase()),
+    };
+
+    queue!(out, MoveTo(x, y), SetForegroundColor(color))?;
+    if highlight {
+        queue!(out, SetAttribute(Attribute::Reverse))?;
+    }
+    queue!(out, Print(text), SetAttribute(Attribute::Reset), ResetColor)
+}
+
+#[cfg(feature = "tui")]
+impl<const N: usize> Observer<N> for DuelScreen<N> {
+    fn on_generation(&mut self, generation: u64, population: &[Board<N>], scores: &[Fitness]) {
+        let fittest = scores.iter().enumerate().min_by_key(|(_, score)| **score);
+        if let Some((i, &score)) = fittest {
+            self.best = population.get(i).map(|&board| (board, score));
+        }
+        self.stats = PopulationStats::of(generation, population, scores);
+
+        if let Err(err) = self.update(generation) {
+            self.error = Some(err);
+        }
+    }
+
+    fn on_metrics(&mut self, _: u64, metrics: &Metrics) {
+        if let Metrics::Phases(phases) = metrics {
+            self.phases = Some(*phases);
+        }
+    }
+
+    fn is_cancelled(&self) -> bool {
+        self.quit || self.error.is_some() || self.human.is_solved()
+    }
+}
+
+/// Returns how far down a duel pane the `row`th row of a board is drawn,
+/// leaving a line for the title and a blank line between bands of boxes.
+#[cfg(feature = "tui")]
+fn pane_offset<const N: usize>(row: usize) -> u16 {
+    u16::try_from(2 + row + row / genetic_sudoku::sudoku::box_dims::<N>().0).unwrap_or(u16::MAX)
+}
+
+/// Draws the time the GA spends in each phase per generation, `top` lines
+/// from the top of the screen.
+#[cfg(feature = "tui")]
+fn draw_phases(out: &mut impl Write, top: u16, phases: &PhaseTimes) -> std::io::Result<()> {
+    queue!(out, MoveTo(0, top), Print("GA time per generation:"))?;
+    for (line, phase) in (top + 1..).zip(Phase::ALL) {
+        let share = phases.share(phase).unwrap_or_default() * 100.0;
+        let text = format!(
+            "  {:<12}{:>12.1?}{:>6.1}%",
+            phase.to_string(),
+            phases.per_generation(phase),
+            share
+        );
+        queue!(out, MoveTo(0, line), Print(text))?;
+    }
+
+    Ok(())
+}
+
+/// Draws `board` under `title`, `left` columns from the left edge of the
+/// screen. `style` gives the color of each cell and whether to highlight it.
+#[cfg(feature = "tui")]
+fn draw_pane<const N: usize>(
+    out: &mut impl Write,
+    left: usize,
+    title: &str,
+    board: &Board<N>,
+    style: impl Fn(usize, usize) -> (Color, bool),
+) -> std::io::Result<()> {
+    let column = |col: usize| {
+        u16::try_from(left + 2 * col + 2 * (col / genetic_sudoku::sudoku::box_dims::<N>().1))
+            .unwrap_or(u16::MAX)
+    };
+
+    queue!(out, MoveTo(column(0), 0), Print(title))?;
+    for (row, digits) in board.0.iter().enumerate() {
+        for (col, &digit) in digits.0.iter().enumerate() {
+            let (color, highlight) = style(row, col);
+            draw_cell(
+                out,
+                column(col),
+                pane_offset::<N>(row),
+                digit,
+                color,
+                highlight,
+            )?;
+        }
+    }
+
+    Ok(())
+}
+
+fn print_stats(stats: &StatsAggregator, config: &RunConfig) {
+    println!("Stats: Config: {}", config.canonical());
+    for (sample, elapsed) in stats.timed_history() {
+        println!(
+            "Stats: Generation: {} | Best: {} | Median: {} | Worst: {} | Elapsed: {:?}",
+            sample.generation, sample.best, sample.median, sample.worst, elapsed,
+        );
+    }
+
+    let percentile = |p| {
+        stats
+            .rolling_percentile(p)
+            .map_or_else(|| "-".to_owned(), |score| score.to_string())
+    };
+    let percent = |rate: Option<f64>| {
+        rate.map_or_else(|| "-".to_owned(), |rate| format!("{:.0}%", rate * 100.0))
+    };
+    for metrics in stats.metrics() {
+        match metrics {
+            Metrics::Tempering(replicas) => {
+                for (i, replica) in replicas.iter().enumerate() {
+                    println!(
+                        "Stats: Replica: {} | Temperature: {} | Energy: {} | Acceptance: {}",
+                        i,
+                        replica.temperature,
+                        replica.energy,
+                        percent(replica.acceptance),
+                    );
+                }
+            }
+            Metrics::Portfolio(members) => {
+                for (i, member) in members.iter().enumerate() {
+                    println!(
+                        "Stats: Member: {} | Threads: {} | Best: {}",
+                        i, member.threads, member.best,
+                    );
+                }
+            }
+            Metrics::Restarts(restarts) => {
+                for decision in restarts.restarts.iter().chain([&restarts.latest]) {
+                    print_restart(decision);
+                }
+            }
+            Metrics::Operators(operators) => {
+                for operator in Operator::ALL {
+                    println!(
+                        "Stats: Operator: {} | Children: {} | Fitter Than Parents: {} | Success: {}",
+                        operator,
+                        operators.children(operator),
+                        operators.improved(operator),
+                        percent(operators.success_rate(operator)),
+                    );
+                }
+            }
+            Metrics::Phases(phases) => {
+                for phase in Phase::ALL {
+                    println!(
+                        "Stats: Phase: {} | Total: {:?} | Per Generation: {:?} | Share: {}",
+                        phase,
+                        phases.total(phase),
+                        phases.per_generation(phase),
+                        percent(phases.share(phase)),
+                    );
+                }
+            }
+            _ => {}
+        }
+    }
+
+    println!(
+        "Stats: Generations: {} | Last {} Best p10: {} | p50: {} | p90: {}",
+        stats.generations(),
+        STATS_WINDOW,
+        percentile(10),
+        percentile(50),
+        percentile(90),
+    );
+}
+
+fn print_restart(decision: &RestartDecision) {
+    let generations = |expected: Option<f64>| {
+        expected.map_or_else(|| "never".to_owned(), |expected| format!("{expected:.0}"))
+    };
+
+    println!(
+        "Stats: Restart: Generation: {} | Restarted: {} | Entropy: {:.2} | Expected If Continued: {} | Expected If Restarted: {}",
+        decision.generation,
+        if decision.restarted { "yes" } else { "no" },
+        decision.entropy,
+        generations(decision.continued),
+        generations(decision.restarted_from_scratch),
+    );
+}
+
+fn print_diversity(diversity: &DiversityHistory) {
+    for snapshot in diversity.snapshots() {
+        let histogram: Vec<String> = snapshot
+            .histogram
+            .iter()
+            .map(|(score, count)| format!("{score}:{count}"))
+            .collect();
+        println!(
+            "Diversity: Generation: {} | Diversity: {:.2} | Entropy: {:.2} | Scores: {}",
+            snapshot.generation,
+            snapshot.diversity,
+            snapshot.entropy,
+            histogram.join(" "),
+        );
+    }
+}
+
+/// Prints the chi-squared tests of the children audited over a run, with
+/// their p-values.
+fn print_audit(audit: &AuditStats) {
+    let test = |chi_squared: Option<f64>| {
+        chi_squared.map_or_else(
+            || "-".to_owned(),
+            |chi_squared| format!("{chi_squared:.2} (p = {:.3})", p_value(chi_squared)),
+        )
+    };
+
+    println!(
+        "Audit: Children: {} | Crossover Chi-Squared: {} | Mutation Chi-Squared: {}",
+        audit.children(),
+        test(audit.crossover_chi_squared()),
+        test(audit.mutation_chi_squared()),
+    );
+}
+
+/// Reads the text of the board file or URL at `path`.
+fn read_board(path: &Path) -> Result<String, std::io::Error> {
+    let source = path.to_string_lossy();
+
+    if source.starts_with("http://") || source.starts_with("https://") {
+        #[cfg(feature = "http")]
+        return ureq::get(&source)
+            .call()
+            .map_err(std::io::Error::other)?
+            .into_string();
+        #[cfg(not(feature = "http"))]
+        return Err(std::io::Error::new(
+            ErrorKind::Unsupported,
+            "reading boards from URLs requires the http feature",
+        ));
+    }
+
+    std::fs::read_to_string(path)
+}
+
+/// Parses the first board of `text`, as read by `read_board`.
+fn load_board<const N: usize>(
+    text: Result<String, std::io::Error>,
+) -> Result<Board<N>, std::io::Error> {
+    // Collections are accepted too; only their first board is used.
+    formats::parse(&text?)?
+        .into_iter()
+        .next()
+        .ok_or_else(|| std::io::Error::new(ErrorKind::InvalidData, "no boards found"))
+}
+
+/// Returns the path or URL of the board `command` works on, if any.
+fn board_path(command: &Command) -> Option<&Path> {
+    match command {
+        Command::Solve(args) => Some(&args.path),
+        Command::Hint { board, .. } | Command::Analyze { board, .. } => Some(board),
+        #[cfg(feature = "db")]
+        Command::RecordSeeds { board, .. } => Some(board),
+        #[cfg(feature = "db")]
+        Command::History { board, .. } | Command::ReplaySeeds { board, .. } => board.as_deref(),
+        #[cfg(feature = "plugins")]
+        Command::Plugins { board, .. } => board.as_deref(),
+        #[cfg(feature = "movie")]
+        Command::Movie { .. } => None,
+        Command::CheckCertificate { .. }
+        | Command::Normalize { .. }
+        | Command::DiffRuns { .. }
+        | Command::ExportParams { .. }
+        | Command::ImportParams { .. } => None,
+    }
+}
+
+/// Returns the size of the board `command` works on, detected from `board`,
+/// the text of the board it reads, or `DEFAULT_BOARD_SIZE` if it reads none.
+/// A board that is unreadable, or of none of the supported sizes, is left
+/// for the command to report as it would at the default size.
+fn board_size(command: &Command, board: Option<&Result<String, std::io::Error>>) -> usize {
+    let size = match command {
+        #[cfg(feature = "movie")]
+        Command::Movie { recording, .. } => std::fs::read_to_string(recording)
+            .ok()
+            .and_then(|text| movie::size(&text)),
+        Command::CheckCertificate { certificate, .. } => std::fs::read_to_string(certificate)
+            .ok()
+            .and_then(|text| certificate::size(&text)),
+        Command::Normalize { input } => Sizes::detect(input),
+        _ => board
+            .and_then(|text| text.as_ref().ok())
+            .and_then(|text| Sizes::detect(text)),
+    };
+
+    size.unwrap_or(DEFAULT_BOARD_SIZE)
+}
+
+/// Parses one line of scrubber input into the moves it asks for, or `None`
+/// to stop. Arrow keys arrive as escape sequences, since the terminal is
+/// left in line mode, so several may be typed before pressing Enter.
+fn parse_scrub(line: &str) -> Option<Vec<Scrub>> {
+    let line = line.trim_end_matches(['\r', '\n']);
+
+    match line.trim() {
+        "q" => return None,
+        "" => return Some(vec![Scrub::Forward]),
+        number => {
+            if let Ok(generation) = number.parse() {
+                return Some(vec![Scrub::Generation(generation)]);
+            }
+        }
+    }
+
+    let keys = [
+        ("\x1b[C", Scrub::Forward),
+        ("\x1b[B", Scrub::Forward),
+        ("\x1b[D", Scrub::Back),
+        ("\x1b[A", Scrub::Back),
+        ("\x1b[H", Scrub::First),
+        ("\x1b[F", Scrub::Last),
+        ("n", Scrub::Forward),
+        ("p", Scrub::Back),
+        ("f", Scrub::First),
+        ("l", Scrub::Last),
+    ];
+    let mut scrubs = Vec::new();
+    let mut rest = line;
+
+    while let Some(next) = rest.chars().next() {
+        if let Some((key, scrub)) = keys.iter().find(|(key, _)| rest.starts_with(key)) {
+            scrubs.push(*scrub);
+            rest = &rest[key.len()..];
+        } else {
+            rest = &rest[next.len_utf8()..];
+        }
+    }
+
+    Some(scrubs)
+}
+
+fn scrub_history<const N: usize>(history: &BestHistory<N>) -> std::io::Result<()> {
+    let mut scrubber = Scrubber::new(history);
+    let mut line = String::new();
+
+    println!(
+        "Scrubbing the last {} generations: Enter or n/right for next, p/left for previous, \
+         f/l for first/last, a generation number to jump, q to quit",
+        history.len()
+    );
+
+    loop {
+        if let Some(snapshot) = scrubber.current() {
+            println!(
+                "Generation: {} | Best: {}\n{}",
+                snapshot.generation, snapshot.score, snapshot.board
+            );
+        }
+
+        line.clear();
+        if std::io::stdin().read_line(&mut line)? == 0 {
+            return Ok(());
+        }
+        let Some(scrubs) = parse_scrub(&line) else {
+            return Ok(());
+        };
+        for scrub in scrubs {
+            scrubber.scrub(scrub);
+        }
+    }
+}
+
+/// Steps through the winning lineage of a run on `board`, one ancestor at a
+/// time, showing the parents each was bred from and where its cells came
+/// from. Takes the same input as the scrubber.
+fn step_lineage<const N: usize>(
+    board: &Board<N>,
+    steps: &[Step<N>],
+    coordinates: bool,
+) -> std::io::Result<()> {
+    let draw = |candidate: &Board<N>| {
+        let candidate = board.overlay(candidate);
+        if coordinates {
+            Pretty::new(&candidate).to_string()
+        } else {
+            candidate.to_string()
+        }
+    };
+    let mut position = 0;
+    let mut line = String::new();
+
+    println!(
+        "Stepping through the {} ancestors of the solution: Enter or n/right for next, \
+         p/left for previous, f/l for first/last, a generation number to jump, q to quit",
+        steps.len()
+    );
+
+    while let Some(step) = steps.get(position) {
+        println!("Generation: {} | Fitness: {}", step.generation, step.score);
+        match step.origin {
+            Origin::Random => println!("Generated at random"),
+            Origin::CarriedOver => println!("Carried over from generation {}", step.generation - 1),
+            Origin::Bred {
+                parents,
+                parent_scores,
+                mask,
+            } => {
+                println!(
+                    "Parent X (fitness {}):\n{}",
+                    parent_scores[0],
+                    draw(&parents.0)
+                );
+                println!(
+                    "Parent Y (fitness {}):\n{}",
+                    parent_scores[1],
+                    draw(&parents.1)
+                );
+                print_mask(board, &mask, &board.overlay(&step.board), coordinates);
+            }
+        }
+        println!("Candidate:\n{}", draw(&step.board));
+
+        line.clear();
+        if std::io::stdin().read_line(&mut line)? == 0 {
+            return Ok(());
+        }
+        let Some(scrubs) = parse_scrub(&line) else {
+            return Ok(());
+        };
+        let last = steps.len() - 1;
+        for scrub in scrubs {
+            position = match scrub {
+                Scrub::Back => position.saturating_sub(1),
+                Scrub::Forward => (position + 1).min(last),
+                Scrub::First => 0,
+                Scrub::Last => last,
+                Scrub::Generation(generation) => steps
+                    .partition_point(|step| step.generation < generation)
+                    .min(last),
+            };
+        }
+    }
+
+    Ok(())
+}
+
+/// Explains why a pin could not be placed. For a pin that repeats a digit,
+/// the pin is forced onto the board and its violation is shown with the
+/// clashing cells in brackets.
+fn report_pin_conflict<const N: usize>(
+    board: &Board<N>,
+    pins: &[(usize, usize, u8)],
+    conflict: GivenConflict,
+) {
+    let GivenConflict::Duplicate {
+        row,
+        col,
+        digit,
+        unit,
+    } = conflict
+    else {
+        eprintln!("Pin conflict: {conflict}");
+        return;
+    };
+    eprintln!(
+        "Pin conflict: Row {} | Column {} | Digit {digit} is already in its {unit:?}",
+        row + 1,
+        col + 1,
+    );
+
+    // The pins before the conflicting one were all accepted.
+    let earlier = pins
+        .iter()
+        .position(|&pin| pin == (row, col, digit))
+        .unwrap_or(pins.len());
+    let Ok(mut forced) = board.with_extra_givens(&pins[..earlier]) else {
+        return;
+    };
+    if forced.apply(&[CellChange { row, col, digit }]).is_err() {
+        return;
+    }
+
+    let clashing = forced.violations().into_iter().find(|violation| {
+        violation.unit == unit && violation.digit == digit && violation.cells.contains(&(row, col))
+    });
+    let clashing = clashing
+        .map(|violation| violation.cells)
+        .unwrap_or_default();
+
+    for (i, cells) in forced.0.iter().enumerate() {
+        let line: Vec<String> = cells
+            .0
+            .iter()
+            .enumerate()
+            .map(|(j, value)| {
+                if clashing.contains(&(i, j)) {
+                    format!("[{value}]")
+                } else {
+                    format!(" {value} ")
+                }
+            })
+            .collect();
+        eprintln!("{}", line.concat());
+    }
+}
+
+/// Returns true if two givens of `board` repeat a digit within a unit.
+fn conflicting_givens<const N: usize>(board: &Board<N>) -> bool {
+    board
+        .violations()
+        .iter()
+        .any(|violation| violation.digit != 0)
+}
+
+fn terminate(termination: Termination, detail: &str) -> ExitCode {
+    eprintln!("Termination: {termination}: {detail}");
+    ExitCode::from(termination.exit_code())
+}
+
+/// Prints every board in `input` in the canonical line format.
+fn normalize<const N: usize>(input: &str) -> Result<ExitCode, Box<dyn std::error::Error>> {
+    match formats::normalize::<N>(input) {
+        Ok(lines) => {
+            println!("{lines}");
+            Ok(ExitCode::SUCCESS)
+        }
+        Err(err) if err.kind() == ErrorKind::InvalidData => {
+            Ok(terminate(Termination::InvalidPuzzle, &err.to_string()))
+        }
+        Err(err) => Err(err.into()),
+    }
+}
+
+fn hint<const N: usize>(
+    text: Result<String, std::io::Error>,
+    show_solution: bool,
+    coordinates: bool,
+    candidates: bool,
+) -> Result<ExitCode, Box<dyn std::error::Error>> {
+    let board = match load_board::<N>(text) {
+        Ok(board) => board,
+        Err(err) if err.kind() == ErrorKind::InvalidData => {
+            return Ok(terminate(Termination::InvalidPuzzle, &err.to_string()));
+        }
+        Err(err) => return Err(err.into()),
+    };
+    if conflicting_givens(&board) {
+        return Ok(terminate(Termination::InvalidPuzzle, "conflicting givens"));
+    }
+
+    let hint = if show_solution {
+        match reveal(&board) {
+            Ok(hint) => hint,
+            Err(0) => {
+                return Ok(terminate(
+                    Termination::NoUniqueSolution,
+                    "puzzle has no solution",
+                ))
+            }
+            Err(_) => {
+                return Ok(terminate(
+                    Termination::NoUniqueSolution,
+                    "puzzle has more than one solution",
+                ))
+            }
+        }
+    } else {
+        find_single(&board)
+    };
+
+    let Some(hint) = hint else {
+        if show_solution {
+            println!("Puzzle is already solved");
+            return Ok(ExitCode::SUCCESS);
+        }
+        eprintln!("No naked or hidden singles left; try --reveal");
+        return Ok(ExitCode::FAILURE);
+    };
+
+    let cell = if coordinates || candidates {
+        format!(" | Cell: {}", cell_name(hint.row, hint.col))
+    } else {
+        String::new()
+    };
+    println!(
+        "Hint: Row {} | Column {}{} | Digit: {} ({})",
+        hint.row + 1,
+        hint.col + 1,
+        cell,
+        hint.digit,
+        hint.technique,
+    );
+
+    let mut board = board;
+    board.apply_around(
+        &GivensMask::of(&board),
+        &[CellChange {
+            row: hint.row,
+            col: hint.col,
+            digit: hint.digit,
+        }],
+    )?;
+    if coordinates || candidates {
+        let pretty = Pretty::new(&board);
+        if candidates {
+            println!("{}", pretty.with_candidates());
+        } else {
+            println!("{pretty}");
+        }
+    } else if show_solution {
+        println!("{board}");
+    }
+
+    Ok(ExitCode::SUCCESS)
+}
+
+fn analyze<const N: usize>(
+    text: Result<String, std::io::Error>,
+    params: &GAParams,
+    transforms: usize,
+    probes: usize,
+    generations: u64,
+    seed: Option<u64>,
+) -> Result<ExitCode, Box<dyn std::error::Error>> {
+    let board = match load_board::<N>(text) {
+        Ok(board) => board,
+        Err(err) if err.kind() == ErrorKind::InvalidData => {
+            return Ok(terminate(Termination::InvalidPuzzle, &err.to_string()));
+        }
+        Err(err) => return Err(err.into()),
+    };
+    let seed = seed.unwrap_or_else(rand::random);
+    let report = check_invariance(
+        &board,
+        params,
+        transforms,
+        probes,
+        generations,
+        seed,
+        &mut rand::thread_rng(),
+    );
+    let summary = |hardness: &Hardness| {
+        format!(
+            "Mean: {:.2} | Median: {} | Solved: {:.0}%",
+            hardness.mean().unwrap_or_default(),
+            hardness.median().unwrap_or_default(),
+            hardness.solved_fraction() * 100.0,
+        )
+    };
+
+    println!("Seed: {seed}");
+    println!("Baseline | {}", summary(&report.baseline));
+    for (i, variant) in report.variants.iter().enumerate() {
+        println!(
+            "Variant {} | {} | {}{}",
+            i + 1,
+            summary(&variant.hardness),
+            variant.symmetry,
+            if variant.deviates { " | DEVIATES" } else { "" },
+        );
+    }
+
+    if report.is_invariant() {
+        println!("Invariant: every variant is within noise of the baseline");
+        Ok(ExitCode::SUCCESS)
+    } else {
+        println!("Not invariant: the GA depends on digit identity or orientation");
+        Ok(ExitCode::FAILURE)
+    }
+}
+
+/// Renders the run recorded in `recording` as an animated GIF in `output`.
+#[cfg(feature = "movie")]
+fn render_movie<const N: usize>(
+    recording: &Path,
+    output: &Path,
+    delay: u16,
+) -> Result<ExitCode, Box<dyn std::error::Error>> {
+    let recording = Recording::<N>::from_json(&std::fs::read_to_string(recording)?)?;
+    let file = std::io::BufWriter::new(std::fs::File::create(output)?);
+    movie::write_gif(&recording, file, delay)?;
+
+    println!("Movie: {} frames", recording.frames().len());
+    Ok(ExitCode::SUCCESS)
+}
+
+fn check_certificate<const N: usize>(
+    path: &Path,
+    key: Option<&Path>,
+) -> Result<ExitCode, Box<dyn std::error::Error>> {
+    let key = key.map(std::fs::read).transpose()?;
+
+    match certificate::check::<N>(&std::fs::read_to_string(path)?, key.as_deref()) {
+        Ok(()) => {
+            println!("Certificate: valid");
+            Ok(ExitCode::SUCCESS)
+        }
+        Err(err) => {
+            println!("Certificate: invalid: {err}");
+            Ok(ExitCode::FAILURE)
+        }
+    }
+}
+
+/// Writes `preset` to `output`, or to standard output if none is given.
+fn export_params(
+    preset: &Preset,
+    output: Option<&Path>,
+) -> Result<ExitCode, Box<dyn std::error::Error>> {
+    match output {
+        Some(path) => std::fs::write(path, preset.to_json() + "\n")?,
+        None => println!("{}", preset.to_json()),
+    }
+
+    Ok(ExitCode::SUCCESS)
+}
+
+/// Checks that the preset at `path` can be read and run, and describes it.
+fn import_params(path: &Path) -> Result<ExitCode, Box<dyn std::error::Error>> {
+    let preset = match Preset::parse(&std::fs::read_to_string(path)?) {
+        Ok(preset) => preset,
+        Err(err) => {
+            println!("Preset: invalid: {err}");
+            return Ok(ExitCode::FAILURE);
+        }
+    };
+    let params = preset.params();
+
+    println!("Preset: {}", preset.name());
+    if !preset.description().is_empty() {
+        println!("Description: {}", preset.description());
+    }
+    println!(
+        "Population: {} | Survivors: {} | Mutation Rate: {} | Selection: {:?} | Crossover: {:?}",
+        params.population(),
+        params.num_survivors(),
+        params.mutation_rate(),
+        params.selection(),
+        params.crossover(),
+    );
+    println!("Use with: --preset {}", path.display());
+
+    Ok(ExitCode::SUCCESS)
+}
+
+fn diff_runs(
+    runs: &[String; 2],
+    #[cfg(feature = "db")] db: Option<&Path>,
+) -> Result<ExitCode, Box<dyn std::error::Error>> {
+    #[cfg(feature = "db")]
+    if let Some(db) = db {
+        return diff_db_runs(db, runs);
+    }
+
+    let mut logs = Vec::with_capacity(2);
+    for run in runs {
+        let log = StatsLog::parse(&std::fs::read_to_string(run)?);
+        if log.samples.is_empty() {
+            return Err(format!("expected a --stats log but got {run:?}").into());
+        }
+        logs.push(log);
+    }
+    let logs = [&logs[0], &logs[1]];
+
+    if let [Some(a), Some(b)] = logs.map(|log| log.config.as_ref()) {
+        print_field_diffs(&diff_configs(a, b));
+    }
+    for (name, log) in ["A", "B"].into_iter().zip(logs) {
+        match log.solution {
+            Some((generation, duration)) => println!(
+                "Outcome: {name} | {} | Generation: {generation} | Duration: {duration:?}",
+                Termination::Solved,
+            ),
+            None => println!("Outcome: {name} | unsolved"),
+        }
+    }
+
+    let score =
+        |best: Option<Fitness>| best.map_or_else(|| "-".to_owned(), |best| best.to_string());
+    for (generation, [a, b]) in align_by_generation(logs) {
+        println!(
+            "Generation: {generation} | A: {} | B: {}",
+            score(a),
+            score(b)
+        );
+    }
+    for (elapsed, [a, b]) in align_by_time(logs, DIFF_STEPS) {
+        println!("Elapsed: {elapsed:?} | A: {} | B: {}", score(a), score(b));
+    }
+
+    Ok(ExitCode::SUCCESS)
+}
+
+fn print_field_diffs(diffs: &[FieldDiff]) {
+    let value = |value: &Option<String>| value.clone().unwrap_or_else(|| "-".to_owned());
+
+    for diff in diffs {
+        println!(
+            "Param: {} | A: {} | B: {}",
+            diff.path,
+            value(&diff.values[0]),
+            value(&diff.values[1]),
+        );
+    }
+}
+
+/// Compares two recorded runs. The database keeps no fitness curves, so
+/// only their parameters and outcomes are compared.
+#[cfg(feature = "db")]
+fn diff_db_runs(db: &Path, runs: &[String; 2]) -> Result<ExitCode, Box<dyn std::error::Error>> {
+    let db = ResultsDb::open(db)?;
+    let mut records = Vec::with_capacity(2);
+    for run in runs {
+        let id = parse_number::<i64>(run)?;
+        records.push(db.run(id)?.ok_or_else(|| format!("no run with id {id}"))?);
+    }
+
+    let [a, b] = [&records[0], &records[1]].map(|run| {
+        let hex = |value: Option<u64>| value.map(|value| format!("{value:016x}"));
+        serde_json::json!({
+            "puzzle_hash": format!("{:016x}", run.puzzle_hash),
+            "fingerprint": hex(run.fingerprint),
+            "population": run.population,
+            "survivors": run.survivors,
+            "mutation_rate": run.mutation_rate,
+            "restart": run.restart,
+            "seed": hex(run.seed),
+        })
+    });
+    print_field_diffs(&diff_configs(&a, &b));
+    for (name, run) in ["A", "B"].into_iter().zip(&records) {
+        println!(
+            "Outcome: {name} | {} | Generation: {} | Duration: {:?}",
+            run.outcome, run.generations, run.duration,
+        );
+    }
+
+    Ok(ExitCode::SUCCESS)
+}
+
+#[cfg(feature = "db")]
+fn history<const N: usize>(
+    db: &Path,
+    board: Option<Result<String, std::io::Error>>,
+) -> Result<ExitCode, Box<dyn std::error::Error>> {
+    let puzzle_hash = match board {
+        None => None,
+        Some(board) => Some(load_board::<N>(board)?.puzzle_hash()),
+    };
+
+    for (id, run) in ResultsDb::open(db)?.entries(puzzle_hash)? {
+        println!(
+            "#{} | {} | Puzzle: {:016x} | Fingerprint: {} | Population: {} | Survivors: {} | \
+             Mutation: {} | Restart: {} | Outcome: {} | Generations: {} | Duration: {:?}",
+            id,
+            run.recorded_at,
+            run.puzzle_hash,
+            run.fingerprint.map_or_else(
+                || "-".to_owned(),
+                |fingerprint| format!("{fingerprint:016x}")
+            ),
+            run.population,
+            run.survivors,
+            run.mutation_rate,
+            run.restart
+                .map_or_else(|| "-".to_owned(), |restart| restart.to_string()),
+            run.outcome,
+            run.generations,
+            run.duration,
+        );
+    }
+
+    Ok(ExitCode::SUCCESS)
+}
+
+/// Probes `path` and adds the seeds that fail to solve it within
+/// `generations` generations to the seed corpus of `db`.
+#[cfg(feature = "db")]
+fn record_seeds<const N: usize>(
+    db: &Path,
+    text: Result<String, std::io::Error>,
+    params: &GAParams,
+    probes: usize,
+    generations: u64,
+    seed: Option<u64>,
+) -> Result<ExitCode, Box<dyn std::error::Error>> {
+    let board = match load_board::<N>(text) {
+        Ok(board) => board,
+        Err(err) if err.kind() == ErrorKind::InvalidData => {
+            return Ok(terminate(Termination::InvalidPuzzle, &err.to_string()));
+        }
+        Err(err) => return Err(err.into()),
+    };
+    let seed = seed.unwrap_or_else(rand::random);
+    let db = ResultsDb::open(db)?;
+    let hard_seeds = corpus::record(&db, &board, params, probes, generations, seed)?;
+
+    println!("Seed: {seed}");
+    for hard_seed in &hard_seeds {
+        println!("Hard seed: {} | Best: {}", hard_seed.seed, hard_seed.best);
+    }
+    println!(
+        "Recorded {} of {probes} seeds that did not solve the puzzle within {generations} \
+         generations",
+        hard_seeds.len()
+    );
+
+    Ok(ExitCode::SUCCESS)
+}
+
+/// Replays the seed corpus of `db`, or only the seeds of `board` if given,
+/// and fails if any seed did worse than when it was recorded.
+#[cfg(feature = "db")]
+fn replay_seeds<const N: usize>(
+    db: &Path,
+    board: Option<Result<String, std::io::Error>>,
+    params: &GAParams,
+) -> Result<ExitCode, Box<dyn std::error::Error>> {
+    let puzzle_hash = match board {
+        None => None,
+        Some(board) => Some(load_board::<N>(board)?.puzzle_hash()),
+    };
+    let (mut improved, mut unchanged, mut worse) = (0, 0, 0);
+
+    for replay in corpus::replay::<N>(&ResultsDb::open(db)?, params, puzzle_hash)? {
+        let now = replay.probe.solved.map_or_else(
+            || replay.probe.best.to_string(),
+            |generation| format!("solved in generation {generation}"),
+        );
+        let change = replay.change();
+        match change {
+            Change::Improved => improved += 1,
+            Change::Unchanged => unchanged += 1,
+            Change::Worse => worse += 1,
+        }
+
+        println!(
+            "Puzzle: {:016x} | Seed: {} | Generations: {} | Before: {} | Now: {} | {}{}",
+            replay.hard_seed.puzzle_hash,
+            replay.hard_seed.seed,
+            replay.hard_seed.generations,
+            replay.hard_seed.best,
+            now,
+            change,
+            if replay.same_config {
+                ""
+            } else {
+                " | DIFFERENT CONFIG"
+            },
+        );
+    }
+
+    println!("Improved: {improved} | Unchanged: {unchanged} | Worse: {worse}");
+    Ok(if worse == 0 {
+        ExitCode::SUCCESS
+    } else {
+        ExitCode::FAILURE
+    })
+}
+
+/// Runs a command for boards of the size it was detected to work on.
+struct Run {
+    command: Command,
+    /// The text of the board `command` reads, read once by `main`.
+    board: Option<Result<String, std::io::Error>>,
+}
+
+impl SizedDriver for Run {
+    type Output = Result<ExitCode, Box<dyn std::error::Error>>;
+
+    fn run<const N: usize>(self) -> Self::Output {
+        let Self { command, board } = self;
+        let read =
+            |board: Option<_>| board.expect("main reads the board of each command naming one");
+        let args = match command {
+            Command::Solve(args) => args,
+            Command::Hint {
+                reveal,
+                coordinates,
+                candidates,
+                ..
+            } => return hint::<N>(read(board), reveal, coordinates, candidates),
+            Command::CheckCertificate { certificate, key } => {
+                return check_certificate::<N>(&certificate, key.as_deref())
+            }
+            Command::Normalize { input } => return normalize::<N>(&input),
+            Command::ExportParams { preset, output } => {
+                return export_params(&preset, output.as_deref())
+            }
+            Command::ImportParams { preset } => return import_params(&preset),
+            Command::DiffRuns {
+                runs,
+                #[cfg(feature = "db")]
+                db,
+            } => {
+                return diff_runs(
+                    &runs,
+                    #[cfg(feature = "db")]
+                    db.as_deref(),
+                )
+            }
+            Command::Analyze {
+                params,
+                transforms,
+                probes,
+                generations,
+                seed,
+                ..
+            } => return analyze::<N>(read(board), &params, transforms, probes, generations, seed),
+            #[cfg(feature = "db")]
+            Command::History { db, .. } => return history::<N>(&db, board),
+            #[cfg(feature = "db")]
+            Command::RecordSeeds {
+                db,
+                params,
+                probes,
+                generations,
+                seed,
+                ..
+            } => return record_seeds::<N>(&db, read(board), &params, probes, generations, seed),
+            #[cfg(feature = "db")]
+            Command::ReplaySeeds { db, params, .. } => {
+                return replay_seeds::<N>(&db, board, &params)
+            }
+            #[cfg(feature = "plugins")]
+            Command::Plugins { directory, .. } => return plugins::<N>(&directory, board),
+            #[cfg(feature = "movie")]
+            Command::Movie {
+                recording,
+                output,
+                delay,
+            } => return render_movie::<N>(&recording, &output, delay),
+        };
+        let mut interrupt = cancel_on_ctrl_c();
+        #[cfg(feature = "watch")]
+        if args.watch {
+            return watch::<N>(&args, read(board), &interrupt);
+        }
+
+        solve_file::<N>(&args, read(board), &mut interrupt)
+    }
+}
+
+/// Returns a token that Ctrl+C cancels, so that the run in progress ends
+/// with the best board found so far. A second Ctrl+C exits at once.
+#[cfg(unix)]
+fn cancel_on_ctrl_c() -> CancellationToken {
+    use signal_hook::consts::SIGINT;
+    use signal_hook::flag;
+    use std::sync::atomic::AtomicBool;
+
+    let interrupted = Arc::new(AtomicBool::new(false));
+    // Registered first, so that it only sees the flag set by an earlier
+    // Ctrl+C.
+    let exit_code = i32::from(Termination::Cancelled.exit_code());
+    let installed =
+        flag::register_conditional_shutdown(SIGINT, exit_code, Arc::clone(&interrupted))
+            .and_then(|_| flag::register(SIGINT, Arc::clone(&interrupted)));
+    if let Err(err) = installed {
+        eprintln!("Ctrl+C: {err}");
+    }
+    CancellationToken::from(interrupted)
+}
+
+/// Returns a token nothing cancels, where Ctrl+C keeps its default handling.
+#[cfg(not(unix))]
+fn cancel_on_ctrl_c() -> CancellationToken {
+    CancellationToken::new()
+}
+
+fn main() -> Result<ExitCode, Box<dyn std::error::Error>> {
+    let command = parse_args()?;
+    // Read once, so that a board from a URL is downloaded once and its size
+    // is detected from the very text the command parses.
+    let board = board_path(&command).map(read_board);
+    let size = board_size(&command, board.as_ref());
+
+    Sizes::dispatch(size, Run { command, board }).expect("the default board size is supported")
+}
+
+/// Lists the plugins in `directory`, scoring `board` with each constraint if
+/// one is given.
+#[cfg(feature = "plugins")]
+fn plugins<const N: usize>(
+    directory: &Path,
+    board: Option<Result<String, std::io::Error>>,
+) -> Result<ExitCode, Box<dyn std::error::Error>> {
+    let board = board.map(load_board::<N>).transpose()?;
+
+    println!("Layout: {}", Board::<N>::layout());
+    for (path, plugin) in discover(directory)? {
+        let plugin = match plugin {
+            Ok(plugin) => plugin,
+            Err(error) => {
+                println!("Plugin: {} | {error}", path.display());
+                continue;
+            }
+        };
+        let penalty = board
+            .and_then(|board| plugin.penalty(&board))
+            .map_or_else(|| "-".to_owned(), |penalty| penalty.to_string());
+        println!(
+            "Plugin: {} | {} | Supported: {} | Constraint: {} | Operator: {} | Penalty: {penalty}",
+            path.display(),
+            plugin.name(),
+            plugin.supports(N),
+            plugin.has_constraint(),
+            plugin.has_operator(),
+        );
+    }
+
+    Ok(ExitCode::SUCCESS)
+}
+
+/// Solves `text`, the board at `args.path`, then again each time the file
+/// changes, cancelling the run in progress. Runs until `interrupt` is
+/// cancelled.
+#[cfg(feature = "watch")]
+fn watch<const N: usize>(
+    args: &Args,
+    text: Result<String, std::io::Error>,
+    interrupt: &CancellationToken,
+) -> Result<ExitCode, Box<dyn std::error::Error>> {
+    let source = args.path.to_string_lossy();
+    if source.starts_with("http://") || source.starts_with("https://") {
+        invalid_arguments("--watch needs a board file, not a URL");
+    }
+
+    let mut watcher = FileWatcher::new(&args.path)?;
+    let mut text = Some(text);
+    loop {
+        let text = text.take().unwrap_or_else(|| read_board(&args.path));
+        let code = solve_file::<N>(args, text, &mut (&mut watcher, interrupt.clone()))?;
+        if interrupt.is_cancelled() {
+            return Ok(code);
+        }
+        if !watcher.take_change() {
+            println!("Watch: waiting for {} to change", args.path.display());
+            if !watcher.wait_for_change(interrupt) {
+                return Ok(terminate(Termination::Cancelled, "watch interrupted"));
+            }
+        }
+        println!("Watch: {} changed; restarting", args.path.display());
+    }
+}
+
+/// Loads `text`, the board at `args.path`, and solves it, or races or
+/// benchmarks it, as `args` asks. `extra` observes every run too.
+#[allow(clippy::too_many_lines)]
+fn solve_file<const N: usize>(
+    args: &Args,
+    text: Result<String, std::io::Error>,
+    extra: &mut dyn Observer<N>,
+) -> Result<ExitCode, Box<dyn std::error::Error>> {
+    let &Args {
+        ref path,
+        ref params,
+        benchmark,
+        ref bench_json,
+        bench_runs,
+        budget,
+        teach,
+        coordinates,
+        stats,
+        diversity,
+        scrub,
+        ref record,
+        lineage,
+        ref pins,
+        propagate,
+        ref certificate,
+        ref sign_key,
+        ref sinks,
+        ref versus,
+        ref replicas,
+        swap_interval,
+        islands,
+        migration_interval,
+        ref portfolio,
+        threads,
+        reallocation_interval,
+        #[cfg(feature = "db")]
+        ref db,
+        #[cfg(feature = "http")]
+        ref webhook,
+        #[cfg(feature = "watch")]
+            watch: _,
+        #[cfg(feature = "tui")]
+        duel,
+    } = args;
+    let board = match load_board::<N>(text) {
+        Ok(board) => board,
+        Err(err) if err.kind() == ErrorKind::InvalidData => {
+            return Ok(terminate(Termination::InvalidPuzzle, &err.to_string()));
+        }
+        Err(err) => return Err(err.into()),
+    };
+
+    if conflicting_givens(&board) {
+        return Ok(terminate(Termination::InvalidPuzzle, "conflicting givens"));
+    }
+
+    let board = match board.with_extra_givens(pins) {
+        Ok(pinned) => pinned,
+        Err(conflict) => {
+            report_pin_conflict(&board, pins, conflict);
+            return Ok(terminate(Termination::InvalidPuzzle, "conflicting pin"));
+        }
+    };
+    let board = if propagate {
+        match propagate_singles(&board) {
+            Ok(propagation) => {
+                print_propagation(&propagation);
+                propagation.board
+            }
+            Err(contradiction) => {
+                return Ok(terminate(
+                    Termination::InvalidPuzzle,
+                    &contradiction.to_string(),
+                ));
+            }
+        }
+    } else {
+        board
+    };
+    if let Some(Err(err)) = params
+        .candidate_overrides()
+        .map(|overrides| overrides.check(&board))
+    {
+        return Ok(terminate(Termination::InvalidPuzzle, &err.to_string()));
+    }
+
+    if let Some(versus) = versus {
+        return Ok(race(&board, [params, versus], &*extra));
+    }
+    #[cfg(feature = "tui")]
+    if duel {
+        return play_duel(&board, params);
+    }
+
+    #[cfg(feature = "db")]
+    let db = db.as_ref().map(ResultsDb::open).transpose()?;
+
+    let puzzle = PuzzleDocument::from_board(&board);
+    let (solver, config): (Box<dyn Solver<N>>, _) = if !portfolio.is_empty() {
+        (
+            Box::new(
+                PortfolioSolver::new(portfolio.clone(), threads, reallocation_interval)
+                    .map_err(describe_param_error)?,
+            ),
+            RunConfig::portfolio(puzzle, portfolio, threads, reallocation_interval),
+        )
+    } else if let Some(islands) = islands {
+        (
+            Box::new(
+                IslandSolver::new(params, islands, migration_interval)
+                    .map_err(describe_param_error)?,
+            ),
+            RunConfig::islands(puzzle, params, islands, migration_interval),
+        )
+    } else if replicas.is_empty() {
+        (
+            Box::new(GeneticSolver::new(params.clone())),
+            RunConfig::genetic(puzzle, params),
+        )
+    } else {
+        let config = RunConfig::tempering(puzzle, replicas, swap_interval);
+        (
+            Box::new(TemperingSolver::new(replicas.clone(), swap_interval)),
+            config,
+        )
+    };
+    let fingerprint = config.fingerprint();
+    let mut teacher = Teacher {
+        params,
+        board: &board,
+        coordinates,
+        introduced: false,
+    };
+    let mut quiet = ();
+    let observer: &mut dyn Observer<N> = if teach { &mut teacher } else { &mut quiet };
+    let mut stats = stats.then(|| StatsAggregator::new(STATS_CAPACITY, STATS_WINDOW));
+    let mut history = scrub.then(|| BestHistory::new(SCRUB_CAPACITY));
+    let mut recording = record
+        .as_ref()
+        .map(|&(_, every)| Recording::new(board, every));
+    let mut lineage = lineage.then(|| LineageRecorder::new(LINEAGE_CAPACITY));
+    let mut diversity = diversity.map(DiversityHistory::new);
+    let mut audit = (params.audit() > 0).then(AuditStats::default);
+    let mut telemetry = if sinks.is_empty() {
+        None
+    } else {
+        let sinks = sinks.iter().map(SinkSpec::open).collect::<Result<_, _>>()?;
+        Some(Telemetry::new(sinks))
+    };
+    #[cfg(feature = "http")]
+    let mut webhook = webhook
+        .as_ref()
+        .map(|(url, interval)| Webhook::new(url, *interval));
+    #[cfg(not(feature = "http"))]
+    let mut webhook: Option<Webhook> = None;
+
+    let mut fame = HallOfFame::new(board, FAME_CAPACITY);
+    let start = Instant::now();
+    let mut report = BenchReport::new();
+    let mut evaluations = EvaluationCounter::default();
+    let bench_name = format!(
+        "solve/{}",
+        path.file_stem().unwrap_or_default().to_string_lossy()
+    );
+
+    loop {
+        let outcome = solver.solve(
+            &board,
+            budget,
+            &mut (
+                (&mut *observer, (&mut *extra, &mut evaluations)),
+                (
+                    (stats.as_mut(), audit.as_mut()),
+                    (
+                        ((history.as_mut(), recording.as_mut()), lineage.as_mut()),
+                        (diversity.as_mut(), (telemetry.as_mut(), webhook.as_mut())),
+                    ),
+                ),
+            ),
+        );
+        if let Some(telemetry) = &mut telemetry {
+            telemetry.finish_run()?;
+        }
+        if let (Some(recording), Some((path, _))) = (&recording, record) {
+            std::fs::write(path, recording.to_json() + "\n")?;
+        }
+        if let Some(webhook) = &mut webhook {
+            if let Err(err) = webhook.finish_run(&outcome) {
+                eprintln!("Webhook: {err}");
+            }
+        }
+        let solution = match outcome.clone().into_result() {
+            Ok(solution) => solution,
+            Err(stopped) => {
+                if let Some((best, score)) = stopped.best {
+                    println!(
+                        "Best: Generation: {} | Fitness: {score} | Duration: {:?}\n{best}",
+                        stopped.generation, outcome.duration
+                    );
+                }
+                let detail = match stopped.termination {
+                    Termination::Cancelled => "run cancelled",
+                    Termination::TargetReached => "target fitness reached",
+                    _ => "no solution found",
+                };
+                return Ok(terminate(stopped.termination, detail));
+            }
+        };
+        let generation = outcome.generation;
+        report.record(BenchRun {
+            generations: generation,
+            evaluations: evaluations.take(),
+            duration: outcome.duration,
+        });
+
+        #[cfg(feature = "db")]
+        if let Some(db) = &db {
+            db.record(&RunRecord {
+                recorded_at: RunRecord::now(),
+                puzzle_hash: board.puzzle_hash(),
+                population: params.population(),
+                survivors: params.num_survivors(),
+                mutation_rate: params.mutation_rate(),
+                restart: params.restart(),
+                seed: params.seed(),
+                fingerprint: Some(fingerprint),
+                outcome: outcome.termination,
+                generations: generation,
+                duration: outcome.duration,
+            })?;
+        }
+
+        if let Some(stats) = &mut stats {
+            print_stats(stats, &config);
+            stats.clear();
+        }
+        if let Some(diversity) = &mut diversity {
+            print_diversity(diversity);
+            diversity.clear();
+        }
+        if let Some(audit) = &mut audit {
+            print_audit(audit);
+            *audit = AuditStats::default();
+        }
+
+        print!(
+            "Solution: Generation: {} | Duration: {:?} | Fingerprint: {:016x}",
+            generation, outcome.duration, fingerprint,
+        );
+
+        if !benchmark {
+            println!("\n{solution}");
+            if let Some(path) = &certificate {
+                let key = sign_key.as_ref().map(std::fs::read).transpose()?;
+                let certificate =
+                    Certificate::new(config, params.seed(), &board, &solution, generation);
+                std::fs::write(path, certificate.to_json(key.as_deref()) + "\n")?;
+            }
+            if let Some(history) = &history {
+                scrub_history(history)?;
+            }
+            if let Some(lineage) = &lineage {
+                step_lineage(&board, &lineage.winning_lineage(), coordinates)?;
+            }
+            return Ok(ExitCode::from(Termination::Solved.exit_code()));
+        }
+
+        print_bench_line(&report, start.elapsed());
+        if let Some(path) = bench_json {
+            std::fs::write(path, report.to_bmf(&bench_name).to_string() + "\n")?;
+        }
+        if fame.record(&solution) && fame.has_multiple_solutions() {
+            report_solutions(&fame);
+        }
+        if bench_runs.is_some_and(|runs| report.runs().len() >= runs as usize) {
+            return Ok(ExitCode::from(Termination::Solved.exit_code()));
+        }
+    }
+}
+
+/// Finishes a benchmark mode solution line with the averages over every
+/// run so far, which took `elapsed` in all.
+fn print_bench_line(report: &BenchReport, elapsed: Duration) {
+    let runs = report.runs();
+    let count = u32::try_from(runs.len()).unwrap_or(u32::MAX);
+    let generations: u64 = runs.iter().map(|run| run.generations).sum();
+
+    println!(
+        " | Average Generation: {} | Median Generation: {} | Average Duration: {:?} | Evaluations/s: {:.0}",
+        generations / u64::from(count),
+        report.median_generations().unwrap_or_default(),
+        elapsed / count,
+        report.evaluations_per_second().unwrap_or_default(),
+    );
+}
+
+/// Reports how many cells propagation filled in, and how many are left for
+/// the solver.
+fn print_propagation<const N: usize>(propagation: &Propagation<N>) {
+    let empty = propagation
+        .board
+        .0
+        .iter()
+        .flat_map(|row| row.0)
+        .filter(|&digit| digit == 0)
+        .count();
+
+    println!(
+        "Propagation: Cells Filled: {} | Cells Left: {}",
+        propagation.placed.len(),
+        empty
+    );
+}
+
+/// Reports that a puzzle has more than one solution, listing each solution
+/// not listed before: both of the first two, and then each new one.
+fn report_solutions<const N: usize>(fame: &HallOfFame<N>) {
+    let solutions = fame.solutions();
+    let first = &solutions[0];
+    let unlisted = if solutions.len() == 2 {
+        0
+    } else {
+        solutions.len() - 1
+    };
+
+    println!(
+        "Uniqueness: Puzzle has multiple solutions | Distinct Solutions Found: {}",
+        solutions.len()
+    );
+    for (i, solution) in solutions.iter().enumerate().skip(unlisted) {
+        let cells: Vec<String> = differing_cells(first, solution)
+            .into_iter()
+            .map(|(row, col)| cell_name(row, col))
+            .collect();
+        if i == 0 {
+            println!("Uniqueness: Solution: 1");
+        } else {
+            println!(
+                "Uniqueness: Solution: {} | Differs From Solution 1 In: {}",
+                i + 1,
+                cells.join(" ")
+            );
         }
+        println!("{solution}");
     }
 }
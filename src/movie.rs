@@ -0,0 +1,483 @@
+#![warn(
+    clippy::all,
+    // clippy::restriction,
+    clippy::pedantic,
+    clippy::nursery,
+    clippy::cargo
+)]
+
+use super::arena::to_board;
+use super::errors::RecordingError;
+use super::history::Snapshot;
+use super::solver::Observer;
+use super::sudoku::{Board, Fitness};
+use serde::{Deserialize, Serialize};
+
+/// A recorded run: the puzzle, and the fittest board of every `every`th
+/// generation along with that of the latest generation, for rendering a
+/// movie of the solve once the run is over.
+///
+/// Boards are recorded with the givens of the puzzle overlaid. Solvers with
+/// several populations record one frame per generation, from the fittest of
+/// all of them.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Recording<const N: usize> {
+    base: Board<N>,
+    every: u64,
+    frames: Vec<Snapshot<N>>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct RecordingDocument {
+    puzzle: Vec<Vec<u8>>,
+    every: u64,
+    frames: Vec<FrameDocument>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct FrameDocument {
+    generation: u64,
+    fitness: Fitness,
+    board: Vec<Vec<u8>>,
+}
+
+impl<const N: usize> Recording<N> {
+    /// Returns an empty recording of a run on `base`, keeping a frame every
+    /// `every` generations.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `every` is 0.
+    #[inline]
+    #[must_use]
+    pub fn new(base: Board<N>, every: u64) -> Self {
+        assert!(every > 0);
+
+        Self {
+            base,
+            every,
+            frames: Vec::new(),
+        }
+    }
+
+    #[inline]
+    #[must_use]
+    pub const fn base(&self) -> &Board<N> {
+        &self.base
+    }
+
+    #[inline]
+    #[must_use]
+    pub const fn every(&self) -> u64 {
+        self.every
+    }
+
+    /// Returns the frames recorded, oldest first. The last one is of the
+    /// latest generation recorded, even if it is not an `every`th one.
+    #[inline]
+    #[must_use]
+    pub fn frames(&self) -> &[Snapshot<N>] {
+        &self.frames
+    }
+
+    /// Records a snapshot. A snapshot of the latest generation replaces it
+    /// if it is fitter, and one of a later generation replaces the latest
+    /// unless that is an `every`th one.
+    #[inline]
+    pub fn record(&mut self, snapshot: Snapshot<N>) {
+        if let Some(last) = self.frames.last_mut() {
+            if last.generation == snapshot.generation {
+                if snapshot.score < last.score {
+                    *last = snapshot;
+                }
+                return;
+            }
+            if last.generation % self.every != 0 {
+                *last = snapshot;
+                return;
+            }
+        }
+
+        self.frames.push(snapshot);
+    }
+
+    /// Returns the recording as compact JSON.
+    ///
+    /// # Panics
+    ///
+    /// Does not panic in practice: every field serializes to JSON.
+    #[inline]
+    #[must_use]
+    pub fn to_json(&self) -> String {
+        let document = RecordingDocument {
+            puzzle: rows(&self.base),
+            every: self.every,
+            frames: self
+                .frames
+                .iter()
+                .map(|frame| FrameDocument {
+                    generation: frame.generation,
+                    fitness: frame.score,
+                    board: rows(&frame.board),
+                })
+                .collect(),
+        };
+
+        serde_json::to_string(&document).expect("recording is serializable")
+    }
+
+    /// Reads a recording written by `to_json`.
+    ///
+    /// # Errors
+    ///
+    /// Fails if `text` is not a recording of boards of size N.
+    #[inline]
+    pub fn from_json(text: &str) -> Result<Self, RecordingError> {
+        let document: RecordingDocument =
+            serde_json::from_str(text).map_err(|_| RecordingError::Malformed("JSON"))?;
+        let base = grid(&document.puzzle).ok_or(RecordingError::Malformed("puzzle"))?;
+        if document.every == 0 {
+            return Err(RecordingError::Malformed("every"));
+        }
+
+        let frames = document
+            .frames
+            .into_iter()
+            .map(|frame| {
+                Some(Snapshot {
+                    generation: frame.generation,
+                    board: grid(&frame.board)?,
+                    score: frame.fitness,
+                })
+            })
+            .collect::<Option<_>>()
+            .ok_or(RecordingError::Malformed("board"))?;
+
+        Ok(Self {
+            base,
+            every: document.every,
+            frames,
+        })
+    }
+}
+
+impl<const N: usize> Observer<N> for Recording<N> {
+    #[inline]
+    fn on_generation(&mut self, generation: u64, population: &[Board<N>], scores: &[Fitness]) {
+        let fittest = scores.iter().enumerate().min_by_key(|(_, score)| **score);
+
+        if let Some((i, &score)) = fittest {
+            if let Some(board) = population.get(i) {
+                self.record(Snapshot {
+                    generation,
+                    board: self.base.overlay(board),
+                    score,
+                });
+            }
+        }
+    }
+}
+
+/// Returns the size of the boards a recording is of, going by its puzzle,
+/// or `None` if it has none. The size is not checked; `Recording::from_json`
+/// does that.
+#[inline]
+#[must_use]
+pub fn size(text: &str) -> Option<usize> {
+    let value: serde_json::Value = serde_json::from_str(text).ok()?;
+    value["puzzle"].as_array().map(Vec::len)
+}
+
+fn rows<const N: usize>(board: &Board<N>) -> Vec<Vec<u8>> {
+    board.0.iter().map(|row| row.0.to_vec()).collect()
+}
+
+fn grid<const N: usize>(rows: &[Vec<u8>]) -> Option<Board<N>> {
+    let max = u8::try_from(N).ok()?;
+    let valid = rows.len() == N
+        && rows
+            .iter()
+            .all(|row| row.len() == N && row.iter().all(|&digit| digit <= max));
+
+    valid.then(|| to_board(&rows.concat()))
+}
+
+#[cfg(feature = "movie")]
+pub use render::write_gif;
+
+#[cfg(feature = "movie")]
+mod render {
+    use super::Recording;
+    use crate::sudoku::{box_dims, Board};
+    use gif::{Encoder, EncodingError, Frame, Repeat};
+    use std::io::Write;
+
+    /// The side of a cell, in pixels.
+    const CELL: usize = 16;
+    /// How many pixels each pixel of a glyph is drawn as.
+    const SCALE: usize = 2;
+    const LINE: usize = 1;
+    const BOX_LINE: usize = 2;
+
+    const BACKGROUND: u8 = 0;
+    const GRID: u8 = 1;
+    const GIVEN: u8 = 2;
+    const FILLED: u8 = 3;
+    const CONFLICT: u8 = 4;
+    const PALETTE: [u8; 15] = [
+        255, 255, 255, // background
+        192, 192, 192, // lines between cells
+        0, 0, 0, // lines between boxes, and givens
+        32, 96, 192, // digits filled in
+        208, 32, 32, // digits filled in that repeat within a unit
+    ];
+
+    /// Glyphs of 3 by 5 pixels for the digits 1 to 25, drawn past 9 as the
+    /// letters A to P. Each row is 3 bits, leftmost pixel highest.
+    const GLYPHS: [[u8; 5]; 25] = [
+        [0b010, 0b110, 0b010, 0b010, 0b111],
+        [0b111, 0b001, 0b111, 0b100, 0b111],
+        [0b111, 0b001, 0b111, 0b001, 0b111],
+        [0b101, 0b101, 0b111, 0b001, 0b001],
+        [0b111, 0b100, 0b111, 0b001, 0b111],
+        [0b111, 0b100, 0b111, 0b101, 0b111],
+        [0b111, 0b001, 0b001, 0b001, 0b001],
+        [0b111, 0b101, 0b111, 0b101, 0b111],
+        [0b111, 0b101, 0b111, 0b001, 0b111],
+        [0b010, 0b101, 0b111, 0b101, 0b101],
+        [0b110, 0b101, 0b110, 0b101, 0b110],
+        [0b011, 0b100, 0b100, 0b100, 0b011],
+        [0b110, 0b101, 0b101, 0b101, 0b110],
+        [0b111, 0b100, 0b110, 0b100, 0b111],
+        [0b111, 0b100, 0b110, 0b100, 0b100],
+        [0b011, 0b100, 0b101, 0b101, 0b011],
+        [0b101, 0b101, 0b111, 0b101, 0b101],
+        [0b111, 0b010, 0b010, 0b010, 0b111],
+        [0b001, 0b001, 0b001, 0b101, 0b010],
+        [0b101, 0b101, 0b110, 0b101, 0b101],
+        [0b100, 0b100, 0b100, 0b100, 0b111],
+        [0b101, 0b111, 0b111, 0b101, 0b101],
+        [0b110, 0b101, 0b101, 0b101, 0b101],
+        [0b010, 0b101, 0b101, 0b101, 0b010],
+        [0b110, 0b101, 0b110, 0b100, 0b100],
+    ];
+
+    /// Writes `recording` as an animated GIF that loops forever, showing
+    /// each frame for `delay` hundredths of a second and the last for four
+    /// times as long.
+    ///
+    /// Givens are drawn in black, other digits in blue, or in red where they
+    /// repeat within a row, column or box.
+    ///
+    /// # Errors
+    ///
+    /// Fails if the GIF cannot be written.
+    ///
+    /// # Panics
+    ///
+    /// Panics if N is not a supported board size.
+    #[inline]
+    pub fn write_gif<const N: usize, W: Write>(
+        recording: &Recording<N>,
+        writer: W,
+        delay: u16,
+    ) -> Result<(), EncodingError> {
+        let (rows, cols) = box_dims::<N>();
+        let axes = (axis(N, rows), axis(N, cols));
+        let size = |axis: &[Span]| u16::try_from(axis.len()).expect("image fits in a GIF");
+        let (width, height) = (size(&axes.1), size(&axes.0));
+
+        let mut encoder = Encoder::new(writer, width, height, &PALETTE)?;
+        encoder.set_repeat(Repeat::Infinite)?;
+
+        let frames = recording.frames();
+        for (i, frame) in frames.iter().enumerate() {
+            let pixels = render(recording.base(), &frame.board, &axes);
+            let mut image = Frame::from_indexed_pixels(width, height, pixels, None);
+            image.delay = if i + 1 == frames.len() {
+                delay.saturating_mul(4)
+            } else {
+                delay
+            };
+            encoder.write_frame(&image)?;
+        }
+
+        Ok(())
+    }
+
+    /// What a pixel is part of, along one side of the board.
+    #[derive(Copy, Clone)]
+    enum Span {
+        /// The given cell, with how far into it the pixel is.
+        Cell(usize, usize),
+        Line,
+        BoxLine,
+    }
+
+    /// Lays out `count` cells along one side of the board, with a box line
+    /// every `box_len` cells and around the board.
+    fn axis(count: usize, box_len: usize) -> Vec<Span> {
+        let mut spans = vec![Span::BoxLine; BOX_LINE];
+
+        for i in 0..count {
+            spans.extend((0..CELL).map(|offset| Span::Cell(i, offset)));
+            if (i + 1) % box_len == 0 {
+                spans.extend([Span::BoxLine; BOX_LINE]);
+            } else {
+                spans.extend([Span::Line; LINE]);
+            }
+        }
+
+        spans
+    }
+
+    fn render<const N: usize>(
+        base: &Board<N>,
+        board: &Board<N>,
+        (rows, cols): &(Vec<Span>, Vec<Span>),
+    ) -> Vec<u8> {
+        let mut conflicts = [[false; N]; N];
+        for violation in board.violations() {
+            for (row, col) in violation.cells {
+                conflicts[row][col] = true;
+            }
+        }
+
+        rows.iter()
+            .flat_map(|&y| cols.iter().map(move |&x| (y, x)))
+            .map(|spans| match spans {
+                (Span::Cell(row, dy), Span::Cell(col, dx)) => {
+                    let color = if base.0[row].0[col] != 0 {
+                        GIVEN
+                    } else if conflicts[row][col] {
+                        CONFLICT
+                    } else {
+                        FILLED
+                    };
+                    if is_inked(board.0[row].0[col], dy, dx) {
+                        color
+                    } else {
+                        BACKGROUND
+                    }
+                }
+                (Span::BoxLine, _) | (_, Span::BoxLine) => GIVEN,
+                _ => GRID,
+            })
+            .collect()
+    }
+
+    /// Returns true if the pixel `dy` down and `dx` across a cell holding
+    /// `digit` is part of its glyph, which is centered in the cell. Empty
+    /// cells have no glyph.
+    fn is_inked(digit: u8, dy: usize, dx: usize) -> bool {
+        let Some(glyph) = usize::from(digit)
+            .checked_sub(1)
+            .and_then(|i| GLYPHS.get(i))
+        else {
+            return false;
+        };
+        let y = dy.checked_sub((CELL - 5 * SCALE) / 2).map(|y| y / SCALE);
+        let x = dx.checked_sub((CELL - 3 * SCALE) / 2).map(|x| x / SCALE);
+
+        match (y, x) {
+            (Some(y), Some(x)) if y < 5 && x < 3 => glyph[y] & (0b100 >> x) != 0,
+            _ => false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::SOLVED_4;
+
+    fn snapshot(generation: u64, score: u32) -> Snapshot<4> {
+        Snapshot {
+            generation,
+            board: SOLVED_4,
+            score: Fitness::new(score),
+        }
+    }
+
+    #[test]
+    fn test_recording() {
+        let mut base = SOLVED_4;
+        base.0[0].0[..2].fill(0);
+        let mut recording = Recording::new(base, 5);
+        for generation in 0..12 {
+            recording.record(snapshot(
+                generation,
+                20 - u32::try_from(generation).unwrap(),
+            ));
+        }
+        recording.record(snapshot(11, 3));
+        recording.record(snapshot(11, 4));
+
+        let frames: Vec<_> = recording
+            .frames()
+            .iter()
+            .map(|frame| frame.generation)
+            .collect();
+        assert_eq!(vec![0, 5, 10, 11], frames);
+        assert_eq!(Fitness::new(3), recording.frames()[3].score);
+
+        // Givens are overlaid on the boards observed.
+        let mut observed = Recording::new(base, 5);
+        let mut candidate = SOLVED_4;
+        candidate.0[0].0[2..].fill(0);
+        observed.on_generation(
+            0,
+            &[candidate, candidate],
+            &[Fitness::new(2), Fitness::new(1)],
+        );
+        assert_eq!([1, 2, 3, 4], observed.frames()[0].board.0[0].0);
+
+        let text = recording.to_json();
+        assert_eq!(Some(4), size(&text));
+        assert_eq!(Ok(recording), Recording::from_json(&text));
+        assert_eq!(
+            Err(RecordingError::Malformed("puzzle")),
+            Recording::<9>::from_json(&text)
+        );
+        assert_eq!(
+            Err(RecordingError::Malformed("JSON")),
+            Recording::<4>::from_json("{}")
+        );
+    }
+
+    #[cfg(feature = "movie")]
+    #[test]
+    fn test_write_gif() {
+        let mut base = SOLVED_4;
+        base.0[0].0[0] = 0;
+        let mut recording = Recording::new(base, 1);
+        let mut conflicting = SOLVED_4;
+        conflicting.0[0].0[0] = 2;
+        recording.record(Snapshot {
+            generation: 0,
+            board: conflicting,
+            score: Fitness::new(3),
+        });
+        recording.record(snapshot(1, 0));
+
+        let mut gif = Vec::new();
+        write_gif(&recording, &mut gif, 20).unwrap();
+
+        let mut decoder = gif::DecodeOptions::new();
+        decoder.set_color_output(gif::ColorOutput::Indexed);
+        let mut decoder = decoder.read_info(gif.as_slice()).unwrap();
+        // Four 16 pixel cells, a line between them and box lines around
+        // each pair.
+        let side = 2 + 16 + 1 + 16 + 2 + 16 + 1 + 16 + 2;
+        assert_eq!((side, side), (decoder.width(), decoder.height()));
+
+        let first = decoder.read_next_frame().unwrap().unwrap();
+        assert_eq!(20, first.delay);
+        assert!(first.buffer.contains(&4));
+        let last = decoder.read_next_frame().unwrap().unwrap();
+        assert_eq!(80, last.delay);
+        assert!(!last.buffer.contains(&4));
+        assert!(last.buffer.contains(&3));
+        assert!(decoder.read_next_frame().unwrap().is_none());
+    }
+}
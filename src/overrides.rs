@@ -0,0 +1,337 @@
+#![warn(
+    clippy::all,
+    // clippy::restriction,
+    clippy::pedantic,
+    clippy::nursery,
+    clippy::cargo
+)]
+
+use super::errors::OverrideError;
+use super::sudoku::{fnv1a, Board, Fitness};
+use rand::seq::IteratorRandom;
+use rand::Rng;
+
+/// The digits one cell is restricted to, as a mask with bit `d` set for
+/// each allowed digit `d`.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct CellOverride {
+    pub row: usize,
+    pub col: usize,
+    pub digits: u64,
+}
+
+impl CellOverride {
+    /// Returns whether the cell may hold `digit`.
+    #[inline]
+    #[must_use]
+    pub const fn allows(&self, digit: u8) -> bool {
+        digit < 64 && self.digits & 1 << digit != 0
+    }
+}
+
+/// Partial deductions fed in by the user: cells that must hold one of a
+/// given subset of digits.
+///
+/// An overrides file lists one cell per line as `ROW,COL,DIGITS`, with a
+/// 1-based row and column as for `--pin`, and the digits the cell may hold
+/// separated by `/`, such as `3,4,1/5/7`. Blank lines and lines starting
+/// with `#` are skipped.
+///
+/// The GA keeps candidates within the overrides as it initializes and
+/// mutates them, and adds a point to a candidate's fitness for every cell
+/// holding a digit they rule out, so that only boards that respect them
+/// are solutions.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct CandidateOverrides {
+    cells: Vec<CellOverride>,
+    hash: u64,
+}
+
+impl CandidateOverrides {
+    /// Parses an overrides file.
+    ///
+    /// # Errors
+    ///
+    /// Fails if a line is not of the form `ROW,COL,DIGITS`, a row, column or
+    /// digit is 0, a digit is too large for any board, or a cell is listed
+    /// twice.
+    #[inline]
+    pub fn parse(text: &str) -> Result<Self, OverrideError> {
+        let mut cells: Vec<CellOverride> = Vec::new();
+
+        for (index, line) in text.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let cell = parse_line(line).ok_or(OverrideError::Syntax { line: index + 1 })?;
+            if cells.iter().any(|c| (c.row, c.col) == (cell.row, cell.col)) {
+                return Err(OverrideError::DuplicateCell {
+                    row: cell.row,
+                    col: cell.col,
+                });
+            }
+            cells.push(cell);
+        }
+
+        Ok(Self {
+            cells,
+            hash: fnv1a(text.bytes()),
+        })
+    }
+
+    /// Returns the overridden cells, in the order they were listed.
+    #[inline]
+    #[must_use]
+    pub fn cells(&self) -> &[CellOverride] {
+        &self.cells
+    }
+
+    /// Returns a stable hash of the overrides file.
+    #[inline]
+    #[must_use]
+    pub const fn hash(&self) -> u64 {
+        self.hash
+    }
+
+    /// Checks the overrides against `base`, the puzzle they are for.
+    ///
+    /// # Errors
+    ///
+    /// Fails if a cell lies outside the board, a digit is greater than N, or
+    /// a given is not among the digits its cell is restricted to.
+    #[inline]
+    pub fn check<const N: usize>(&self, base: &Board<N>) -> Result<(), OverrideError> {
+        for cell in &self.cells {
+            let (row, col) = (cell.row, cell.col);
+            let too_large = (1..64).find(|&digit| usize::from(digit) > N && cell.allows(digit));
+            if row >= N || col >= N || too_large.is_some() {
+                let digit = too_large.unwrap_or(0);
+                return Err(OverrideError::OutOfRange { row, col, digit });
+            }
+
+            let given = base.0[row].0[col];
+            if given != 0 && !cell.allows(given) {
+                return Err(OverrideError::Given { row, col, given });
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Returns the number of cells of `board` holding a digit their
+    /// override rules out.
+    #[inline]
+    #[must_use]
+    pub fn penalty<const N: usize>(&self, board: &Board<N>) -> Fitness {
+        let broken = self
+            .cells
+            .iter()
+            .filter(|cell| !cell.allows(board.0[cell.row].0[cell.col]))
+            .count();
+
+        Fitness::new(u32::try_from(broken).unwrap_or(u32::MAX))
+    }
+
+    /// Brings the overridden cells of `row`, row `index` of a candidate,
+    /// within their overrides, and calls `changed` with the column of each
+    /// cell it changes. Cells holding givens in `base_row` are left alone.
+    ///
+    /// A cell holding a digit its override rules out swaps digits with
+    /// another free cell of the row where possible, so that rows keep their
+    /// digits, and otherwise gets a random allowed digit. With `keep_digits`
+    /// set, as for rows that must stay permutations, such a cell is left to
+    /// the override penalty instead.
+    #[inline]
+    pub fn enforce_row<const N: usize, R: Rng + ?Sized>(
+        &self,
+        rng: &mut R,
+        index: usize,
+        base_row: &[u8; N],
+        row: &mut [u8; N],
+        keep_digits: bool,
+        mut changed: impl FnMut(usize),
+    ) {
+        let overrides: Vec<&CellOverride> =
+            self.cells.iter().filter(|cell| cell.row == index).collect();
+        let allows = |col: usize, digit: u8| {
+            overrides
+                .iter()
+                .find(|cell| cell.col == col)
+                .is_none_or(|cell| cell.allows(digit))
+        };
+
+        for cell in &overrides {
+            let col = cell.col;
+            if base_row[col] != 0 || cell.allows(row[col]) {
+                continue;
+            }
+
+            let partner = (0..N)
+                .filter(|&other| {
+                    other != col
+                        && base_row[other] == 0
+                        && cell.allows(row[other])
+                        && allows(other, row[col])
+                })
+                .choose(rng);
+            if let Some(other) = partner {
+                row.swap(col, other);
+                changed(other);
+            } else if keep_digits {
+                continue;
+            } else if let Some(digit) = (1..=u8::try_from(N).unwrap_or(u8::MAX))
+                .filter(|&digit| cell.allows(digit))
+                .choose(rng)
+            {
+                row[col] = digit;
+            }
+            changed(col);
+        }
+    }
+}
+
+/// Parses one `ROW,COL,DIGITS` line into 0-based coordinates.
+fn parse_line(line: &str) -> Option<CellOverride> {
+    let (row, rest) = line.split_once(',')?;
+    let (col, digits) = rest.split_once(',')?;
+    let (row, col): (usize, usize) = (row.trim().parse().ok()?, col.trim().parse().ok()?);
+    let digits = digits.split('/').try_fold(0_u64, |mask, digit| {
+        let digit: u32 = digit.trim().parse().ok()?;
+        (1..64).contains(&digit).then(|| mask | 1 << digit)
+    })?;
+
+    Some(CellOverride {
+        row: row.checked_sub(1)?,
+        col: col.checked_sub(1)?,
+        digits,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sudoku::Row;
+    use crate::testing::SOLVED_4;
+    use rand::SeedableRng;
+    use rand_pcg::Pcg64Mcg;
+
+    #[test]
+    fn test_parse() {
+        let overrides =
+            CandidateOverrides::parse("# deductions\n1,2,1/2\n\n 4 , 4 , 3 \n").unwrap();
+        assert_eq!(
+            &[
+                CellOverride {
+                    row: 0,
+                    col: 1,
+                    digits: 0b110
+                },
+                CellOverride {
+                    row: 3,
+                    col: 3,
+                    digits: 0b1000
+                },
+            ],
+            overrides.cells()
+        );
+        assert!(overrides.cells()[0].allows(2));
+        assert!(!overrides.cells()[0].allows(3));
+
+        for (text, line) in [
+            ("1,2", 1),
+            ("1,1,1\n0,1,2", 2),
+            ("1,1,1/x", 1),
+            ("1,1,64", 1),
+        ] {
+            assert_eq!(
+                Err(OverrideError::Syntax { line }),
+                CandidateOverrides::parse(text)
+            );
+        }
+        assert_eq!(
+            Err(OverrideError::DuplicateCell { row: 0, col: 0 }),
+            CandidateOverrides::parse("1,1,1\n1,1,2")
+        );
+    }
+
+    #[test]
+    fn test_check() {
+        let overrides = CandidateOverrides::parse("1,2,1/2").unwrap();
+        assert_eq!(Ok(()), overrides.check(&Board([Row::default(); 4])));
+        assert_eq!(
+            Err(OverrideError::Given {
+                row: 0,
+                col: 1,
+                given: 2
+            }),
+            CandidateOverrides::parse("1,2,1/3")
+                .unwrap()
+                .check(&SOLVED_4)
+        );
+        assert_eq!(
+            Err(OverrideError::OutOfRange {
+                row: 0,
+                col: 0,
+                digit: 5
+            }),
+            CandidateOverrides::parse("1,1,1/5")
+                .unwrap()
+                .check(&SOLVED_4)
+        );
+        assert_eq!(
+            Err(OverrideError::OutOfRange {
+                row: 4,
+                col: 0,
+                digit: 0
+            }),
+            CandidateOverrides::parse("5,1,1").unwrap().check(&SOLVED_4)
+        );
+    }
+
+    #[test]
+    fn test_enforce_row() {
+        let mut rng = Pcg64Mcg::seed_from_u64(1);
+        let overrides = CandidateOverrides::parse("1,1,3\n1,2,1/4\n2,1,2").unwrap();
+        assert_eq!(Fitness::new(3), overrides.penalty(&SOLVED_4));
+
+        // A swap brings the 3 into the first cell, then the second cell
+        // swaps its digit for the 4.
+        let mut row = [1, 2, 3, 4];
+        let mut changed = Vec::new();
+        overrides.enforce_row(&mut rng, 0, &[0; 4], &mut row, false, |col| {
+            changed.push(col);
+        });
+        assert_eq!(3, row[0]);
+        assert!([1, 4].contains(&row[1]));
+        let mut digits = row;
+        digits.sort_unstable();
+        assert_eq!([1, 2, 3, 4], digits);
+        assert!(changed.contains(&0));
+
+        // Givens are left alone, and a cell whose allowed digits are all
+        // given elsewhere in the row is reset.
+        let mut row = [1, 2, 3, 4];
+        overrides.enforce_row(&mut rng, 0, &[1, 0, 3, 0], &mut row, false, |_| {});
+        assert_eq!([1, 4, 3, 2], row);
+        let mut row = [1, 1, 3, 4];
+        overrides.enforce_row(&mut rng, 1, &[0, 1, 0, 0], &mut row, false, |_| {});
+        assert_eq!([2, 1, 3, 4], row);
+
+        // Rows that must keep their digits never have one overwritten.
+        let mut row = [1, 3, 2, 4];
+        let mut changed = Vec::new();
+        overrides.enforce_row(&mut rng, 1, &[0, 0, 2, 0], &mut row, true, |col| {
+            changed.push(col);
+        });
+        assert_eq!([1, 3, 2, 4], row);
+        assert!(changed.is_empty());
+
+        let mut board = SOLVED_4;
+        for (i, row) in board.0.iter_mut().enumerate() {
+            overrides.enforce_row(&mut rng, i, &[0; 4], &mut row.0, false, |_| {});
+        }
+        assert_eq!(Fitness::ZERO, overrides.penalty(&board));
+    }
+}
@@ -0,0 +1,264 @@
+#![warn(
+    clippy::all,
+    // clippy::restriction,
+    clippy::pedantic,
+    clippy::nursery,
+    clippy::cargo
+)]
+
+use super::errors::PluginError;
+use super::sudoku::{Board, BoardLayout, Fitness};
+use libloading::Library;
+use std::ffi::{c_char, CStr};
+use std::path::{Path, PathBuf};
+
+/// The version of the plugin interface. Plugins that declare any other
+/// version are refused.
+pub const PLUGIN_ABI_VERSION: u32 = 1;
+
+/// The name of the function every plugin exports. It takes no arguments
+/// and returns the plugin's `PluginDeclaration`.
+pub const PLUGIN_ENTRY_POINT: &str = "genetic_sudoku_plugin";
+
+/// Scores a board against a custom constraint, returning the number of
+/// violations, which is added to its fitness. The board is laid out as
+/// `layout` describes.
+pub type PenaltyFn = unsafe extern "C" fn(board: *const u8, layout: *const BoardLayout) -> u32;
+
+/// Mutates a board in place. `givens` is the puzzle, laid out like the
+/// board; its non-zero cells are restored after the call. Any randomness
+/// should come from `seed`.
+pub type MutateFn =
+    unsafe extern "C" fn(board: *mut u8, givens: *const u8, layout: *const BoardLayout, seed: u64);
+
+/// What a plugin provides, as returned by its entry point.
+///
+/// A plugin built as a `cdylib` declares itself with:
+///
+/// ```ignore
+/// #[no_mangle]
+/// pub extern "C" fn genetic_sudoku_plugin() -> PluginDeclaration {
+///     PluginDeclaration {
+///         abi_version: PLUGIN_ABI_VERSION,
+///         name: c"diagonal".as_ptr(),
+///         size: 9,
+///         penalty: Some(diagonal_duplicates),
+///         mutate: None,
+///     }
+/// }
+/// ```
+#[derive(Debug, Copy, Clone)]
+#[repr(C)]
+pub struct PluginDeclaration {
+    /// The `PLUGIN_ABI_VERSION` the plugin was built against.
+    pub abi_version: u32,
+    /// The plugin's name, NUL-terminated and valid for as long as the
+    /// library is loaded, or null to name it after its file.
+    pub name: *const c_char,
+    /// The board size the plugin supports, or 0 for any size.
+    pub size: usize,
+    /// A custom constraint, if the plugin has one.
+    pub penalty: Option<PenaltyFn>,
+    /// A custom mutation operator, if the plugin has one.
+    pub mutate: Option<MutateFn>,
+}
+
+/// A plugin loaded from a shared library.
+#[derive(Debug)]
+pub struct Plugin {
+    path: PathBuf,
+    name: String,
+    size: usize,
+    penalty: Option<PenaltyFn>,
+    mutate: Option<MutateFn>,
+    // Declared last so that it is dropped last: the function pointers above
+    // point into it.
+    _library: Library,
+}
+
+impl Plugin {
+    /// Loads the plugin in the shared library at `path`.
+    ///
+    /// Loading a library runs its initialisers, and calling into it runs
+    /// its code, so only load plugins you trust.
+    ///
+    /// # Errors
+    ///
+    /// Fails if the file cannot be loaded as a shared library, does not
+    /// export `PLUGIN_ENTRY_POINT`, or was built for another version of
+    /// the plugin interface.
+    #[inline]
+    pub fn load(path: &Path) -> Result<Self, PluginError> {
+        // SAFETY: the user asked for this library to be loaded.
+        let library =
+            unsafe { Library::new(path) }.map_err(|error| PluginError::Load(error.to_string()))?;
+
+        // SAFETY: the entry point has the signature the plugin interface
+        // documents for it.
+        let declaration = unsafe {
+            let entry = library
+                .get::<unsafe extern "C" fn() -> PluginDeclaration>(PLUGIN_ENTRY_POINT.as_bytes())
+                .map_err(|_| PluginError::MissingEntryPoint)?;
+            entry()
+        };
+
+        if declaration.abi_version != PLUGIN_ABI_VERSION {
+            return Err(PluginError::AbiMismatch {
+                expected: PLUGIN_ABI_VERSION,
+                found: declaration.abi_version,
+            });
+        }
+
+        let name = if declaration.name.is_null() {
+            path.file_stem()
+                .map_or_else(String::new, |stem| stem.to_string_lossy().into_owned())
+        } else {
+            // SAFETY: the name is NUL-terminated and lives as long as the
+            // library.
+            unsafe { CStr::from_ptr(declaration.name) }
+                .to_string_lossy()
+                .into_owned()
+        };
+
+        Ok(Self {
+            path: path.to_owned(),
+            name,
+            size: declaration.size,
+            penalty: declaration.penalty,
+            mutate: declaration.mutate,
+            _library: library,
+        })
+    }
+
+    #[inline]
+    #[must_use]
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    #[inline]
+    #[must_use]
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Returns true if the plugin supports boards of `size`.
+    #[inline]
+    #[must_use]
+    pub const fn supports(&self, size: usize) -> bool {
+        self.size == 0 || self.size == size
+    }
+
+    /// Returns true if the plugin has a custom constraint.
+    #[inline]
+    #[must_use]
+    pub const fn has_constraint(&self) -> bool {
+        self.penalty.is_some()
+    }
+
+    /// Returns true if the plugin has a custom mutation operator.
+    #[inline]
+    #[must_use]
+    pub const fn has_operator(&self) -> bool {
+        self.mutate.is_some()
+    }
+
+    /// Scores `board` against the plugin's constraint, or returns `None` if
+    /// it has none or does not support boards of this size.
+    #[inline]
+    #[must_use]
+    pub fn penalty<const N: usize>(&self, board: &Board<N>) -> Option<Fitness> {
+        let penalty = self.penalty.filter(|_| self.supports(N))?;
+        let layout = Board::<N>::layout();
+
+        // SAFETY: `Board` is `repr(C)` and laid out as `layout` says, and
+        // the library is still loaded.
+        let violations = unsafe { penalty(std::ptr::from_ref(board).cast(), &raw const layout) };
+        Some(Fitness::new(violations))
+    }
+
+    /// Mutates `board` with the plugin's operator, keeping the givens of
+    /// `base`, and returns true, or returns false if it has no operator or
+    /// does not support boards of this size.
+    #[inline]
+    pub fn mutate<const N: usize>(&self, board: &mut Board<N>, base: &Board<N>, seed: u64) -> bool {
+        let Some(mutate) = self.mutate.filter(|_| self.supports(N)) else {
+            return false;
+        };
+        let layout = Board::<N>::layout();
+
+        // SAFETY: as for `penalty`; `board` is borrowed mutably for the
+        // duration of the call.
+        unsafe {
+            mutate(
+                std::ptr::from_mut(board).cast(),
+                std::ptr::from_ref(base).cast(),
+                &raw const layout,
+                seed,
+            );
+        }
+        *board = base.overlay(board);
+        true
+    }
+}
+
+/// Loads every shared library in `directory` as a plugin, in order of
+/// path.
+///
+/// Other files are skipped. Libraries that fail to load are returned with
+/// the error, so that one bad plugin does not hide the rest.
+///
+/// # Errors
+///
+/// Fails if the directory cannot be read.
+#[inline]
+pub fn discover(directory: &Path) -> std::io::Result<Vec<(PathBuf, Result<Plugin, PluginError>)>> {
+    let mut paths = Vec::new();
+    for entry in std::fs::read_dir(directory)? {
+        let path = entry?.path();
+        if path.extension() == Some(std::env::consts::DLL_EXTENSION.as_ref()) {
+            paths.push(path);
+        }
+    }
+    paths.sort();
+
+    Ok(paths
+        .into_iter()
+        .map(|path| {
+            let plugin = Plugin::load(&path);
+            (path, plugin)
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_discover() {
+        let directory = std::env::temp_dir().join(format!("plugins-{}", std::process::id()));
+        std::fs::create_dir_all(&directory).unwrap();
+        let library = directory.join(format!("broken.{}", std::env::consts::DLL_EXTENSION));
+        std::fs::write(&library, "not a library").unwrap();
+        std::fs::write(directory.join("notes.txt"), "not a plugin").unwrap();
+
+        let found = discover(&directory).unwrap();
+        assert_eq!(1, found.len());
+        assert_eq!(library, found[0].0);
+        assert!(matches!(found[0].1, Err(PluginError::Load(_))));
+
+        std::fs::remove_dir_all(directory).unwrap();
+        assert!(discover(Path::new("/nonexistent/plugins")).is_err());
+    }
+
+    #[test]
+    #[cfg(target_os = "linux")]
+    fn test_missing_entry_point() {
+        // Any shared library that is not a plugin will do.
+        assert_eq!(
+            PluginError::MissingEntryPoint,
+            Plugin::load(Path::new("libc.so.6")).unwrap_err()
+        );
+    }
+}
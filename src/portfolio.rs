@@ -0,0 +1,69 @@
+#![warn(
+    clippy::all,
+    // clippy::restriction,
+    clippy::pedantic,
+    clippy::nursery,
+    clippy::cargo
+)]
+
+use super::sudoku::Fitness;
+
+/// Splits `threads` worker threads between the members of a portfolio, one
+/// share per entry of `bests`, the best score each member has found so far.
+///
+/// Every member keeps at least one thread, so that none is starved. The rest
+/// go to the most promising members: ranked fittest first, each member is
+/// weighted by how many members it ranks above or level with, and threads
+/// left over from rounding go to the fittest. Ties are ranked in order.
+///
+/// # Panics
+///
+/// Panics if `threads` is less than the number of members.
+#[inline]
+#[must_use]
+pub fn allocate_threads(threads: usize, bests: &[Fitness]) -> Vec<usize> {
+    let members = bests.len();
+    assert!(
+        threads >= members,
+        "a portfolio of {members} members needs at least {members} threads"
+    );
+
+    let mut ranking: Vec<usize> = (0..members).collect();
+    ranking.sort_by_key(|&i| bests[i]);
+
+    let spare = threads - members;
+    let total_weight = members * (members + 1) / 2;
+    let mut shares = vec![1; members];
+    let mut given = 0;
+    for (rank, &i) in ranking.iter().enumerate() {
+        let extra = spare * (members - rank) / total_weight;
+        shares[i] += extra;
+        given += extra;
+    }
+    for &i in ranking.iter().cycle().take(spare - given) {
+        shares[i] += 1;
+    }
+
+    shares
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_allocate_threads() {
+        let bests = [5, 1, 3].map(Fitness::new);
+        assert_eq!(vec![1, 4, 3], allocate_threads(8, &bests));
+        assert_eq!(vec![1, 1, 1], allocate_threads(3, &bests));
+        assert_eq!(vec![3, 1], allocate_threads(4, &[Fitness::ZERO; 2]));
+        assert_eq!(vec![7], allocate_threads(7, &[Fitness::MAX]));
+        assert!(allocate_threads(0, &[]).is_empty());
+    }
+
+    #[test]
+    #[should_panic(expected = "needs at least 3 threads")]
+    fn test_allocate_too_few_threads() {
+        let _ = allocate_threads(2, &[Fitness::ZERO; 3]);
+    }
+}
@@ -0,0 +1,386 @@
+#![warn(
+    clippy::all,
+    // clippy::restriction,
+    clippy::pedantic,
+    clippy::nursery,
+    clippy::cargo
+)]
+
+use super::errors::{ParamError, PresetError};
+use super::genetics::{
+    Crossover, Curriculum, Dedup, Encoding, GAParams, Hypermutation, Mutation, MutationSchedule,
+    Pairing, PopulationSchedule, Selection,
+};
+use super::restarts::{AutoRestart, Cataclysm, StallRestart};
+use super::solver::backtrack::Hybrid;
+use super::sudoku::UnitWeights;
+use serde::{Deserialize, Serialize};
+
+/// The version of the preset format this build writes, and the only one it
+/// reads.
+pub const PRESET_VERSION: u32 = 1;
+
+/// A named set of GA parameters, such as one tuned for a class of puzzles,
+/// that can be saved to a file and shared.
+///
+/// The JSON form is an object with the format `"version"`, a `"name"`, an
+/// optional `"description"` and the `"params"`, of which only the
+/// population and the selection and mutation rates are required:
+///
+/// ```json
+/// {
+///   "version": 1,
+///   "name": "17-clue 9x9",
+///   "params": {
+///     "population": 500,
+///     "selection_rate": 0.3,
+///     "mutation_rate": 0.02,
+///     "elitism": 2
+///   }
+/// }
+/// ```
+///
+/// Settings that belong to a single run rather than to a puzzle class are
+/// left out: the seed and RNG strategy, candidate overrides and fitness
+/// scripts, and whether the population is compact, profiled, audited or
+/// scored in chunks.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Preset {
+    version: u32,
+    name: String,
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    description: String,
+    params: PresetParams,
+}
+
+/// The parameters of a preset, with the same names as the fields of a run
+/// configuration.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct PresetParams {
+    population: usize,
+    selection_rate: f32,
+    mutation_rate: f32,
+    #[serde(default, skip_serializing_if = "is_default")]
+    mutation_schedule: MutationSchedule,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    restart: Option<u64>,
+    #[serde(default, skip_serializing_if = "is_default")]
+    population_schedule: PopulationSchedule,
+    #[serde(default, skip_serializing_if = "is_default")]
+    column_repair: usize,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    dedup: Option<Dedup>,
+    #[serde(default, skip_serializing_if = "is_default")]
+    selection: Selection,
+    #[serde(default, skip_serializing_if = "is_default")]
+    crossover: Crossover,
+    #[serde(default, skip_serializing_if = "is_default")]
+    mutation_operator: Mutation,
+    #[serde(default, skip_serializing_if = "is_default")]
+    encoding: Encoding,
+    #[serde(default, skip_serializing_if = "is_default")]
+    weights: UnitWeights,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    curriculum: Option<Curriculum>,
+    #[serde(default, skip_serializing_if = "is_default")]
+    elitism: usize,
+    #[serde(default, skip_serializing_if = "is_default")]
+    plus_selection: bool,
+    #[serde(default, skip_serializing_if = "is_default")]
+    pairing: Pairing,
+    #[serde(default, skip_serializing_if = "is_default")]
+    incest_prevention: usize,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    children_per_pair: Option<usize>,
+    #[serde(default, skip_serializing_if = "is_default")]
+    local_search: usize,
+    #[serde(default, skip_serializing_if = "is_default")]
+    tabu: usize,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    auto_restart: Option<AutoRestart>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    stall_restart: Option<StallRestart>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    cataclysm: Option<Cataclysm>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    hypermutation: Option<Hypermutation>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    hybrid: Option<Hybrid>,
+}
+
+fn is_default<T: Default + PartialEq>(value: &T) -> bool {
+    *value == T::default()
+}
+
+impl From<&GAParams> for PresetParams {
+    #[inline]
+    fn from(params: &GAParams) -> Self {
+        Self {
+            population: params.population(),
+            selection_rate: params.selection_rate(),
+            mutation_rate: params.mutation_rate(),
+            mutation_schedule: params.mutation_schedule(),
+            restart: params.restart(),
+            population_schedule: params.population_schedule(),
+            column_repair: params.column_repair(),
+            dedup: params.dedup(),
+            selection: params.selection(),
+            crossover: params.crossover(),
+            mutation_operator: params.mutation_operator(),
+            encoding: params.encoding(),
+            weights: params.weights(),
+            curriculum: params.curriculum(),
+            elitism: params.elitism(),
+            plus_selection: params.plus_selection(),
+            pairing: params.pairing(),
+            incest_prevention: params.incest_prevention(),
+            children_per_pair: params.children_per_pair(),
+            local_search: params.local_search(),
+            tabu: params.tabu(),
+            auto_restart: params.auto_restart(),
+            stall_restart: params.stall_restart(),
+            cataclysm: params.cataclysm(),
+            hypermutation: params.hypermutation(),
+            hybrid: params.hybrid(),
+        }
+    }
+}
+
+impl Preset {
+    /// Returns a preset of the parameters of `params` that are not specific
+    /// to a single run.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - what the preset is called, such as the puzzle class it
+    ///   was tuned for
+    /// * `description` - notes for whoever uses the preset, or an empty
+    ///   string for none
+    /// * `params` - the parameters to save
+    #[inline]
+    #[must_use]
+    pub fn new(name: &str, description: &str, params: &GAParams) -> Self {
+        Self {
+            version: PRESET_VERSION,
+            name: name.to_owned(),
+            description: description.to_owned(),
+            params: params.into(),
+        }
+    }
+
+    /// Parses a preset from its JSON form.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `json` is not a preset, was written in another
+    /// version of the format, or holds parameters that cannot be run.
+    #[inline]
+    pub fn parse(json: &str) -> Result<Self, PresetError> {
+        #[derive(Deserialize)]
+        struct Version {
+            version: u32,
+        }
+
+        let version = serde_json::from_str::<Version>(json)
+            .map_err(|err| PresetError::Malformed(err.to_string()))?
+            .version;
+        if version != PRESET_VERSION {
+            return Err(PresetError::UnsupportedVersion(version));
+        }
+        let preset: Self =
+            serde_json::from_str(json).map_err(|err| PresetError::Malformed(err.to_string()))?;
+
+        preset.build()?;
+        if !preset.params.weights.is_valid() {
+            return Err(PresetError::Invalid(
+                "weights must not be negative, and one must be positive",
+            ));
+        }
+        Ok(preset)
+    }
+
+    /// Returns the JSON form of the preset, pretty-printed for sharing.
+    ///
+    /// # Panics
+    ///
+    /// Does not panic in practice: every field serializes to JSON.
+    #[inline]
+    #[must_use]
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).expect("preset is serializable")
+    }
+
+    #[inline]
+    #[must_use]
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    #[inline]
+    #[must_use]
+    pub fn description(&self) -> &str {
+        &self.description
+    }
+
+    /// Returns the preset's parameters, with the defaults of `GAParams::new`
+    /// for the settings of a single run.
+    ///
+    /// # Panics
+    ///
+    /// Does not panic in practice: presets are either imported with `parse`,
+    /// which checks their parameters, or exported from `GAParams`.
+    #[inline]
+    #[must_use]
+    pub fn params(&self) -> GAParams {
+        self.build().expect("presets hold valid parameters")
+    }
+
+    /// Builds the preset's parameters, or returns why they cannot be built.
+    fn build(&self) -> Result<GAParams, ParamError> {
+        let params = &self.params;
+        Ok(GAParams::new(
+            params.population,
+            params.selection_rate,
+            params.mutation_rate,
+            params.restart,
+        )?
+        .with_mutation_schedule(params.mutation_schedule)
+        .with_population_schedule(params.population_schedule)?
+        .with_column_repair(params.column_repair)
+        .with_dedup(params.dedup)
+        .with_selection(params.selection)?
+        .with_crossover(params.crossover)
+        .with_mutation_operator(params.mutation_operator)
+        .with_encoding(params.encoding)
+        .with_weights(params.weights)
+        .with_curriculum(params.curriculum)
+        .with_elitism(params.elitism)
+        .with_plus_selection(params.plus_selection)
+        .with_pairing(params.pairing)
+        .with_incest_prevention(params.incest_prevention)
+        .with_children_per_pair(params.children_per_pair)?
+        .with_local_search(params.local_search)
+        .with_tabu(params.tabu)
+        .with_auto_restart(params.auto_restart)
+        .with_stall_restart(params.stall_restart)
+        .with_cataclysm(params.cataclysm)?
+        .with_hypermutation(params.hypermutation)?
+        .with_hybrid(params.hybrid))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::genetics::MAX_POPULATION;
+
+    #[test]
+    fn test_round_trip() {
+        let params = GAParams::new(500, 0.3, 0.02, Some(1000))
+            .unwrap()
+            .with_seed(7)
+            .with_compact_population(true)
+            .with_selection(Selection::Tournament { size: 3 })
+            .unwrap()
+            .with_elitism(2)
+            .with_pairing(Pairing::Shuffle)
+            .with_dedup(Some(Dedup::Mutate))
+            .with_hybrid(Some(Hybrid::default()));
+        let preset = Preset::new("17-clue 9x9", "", &params);
+
+        let json = preset.to_json();
+        assert!(!json.contains("description"));
+        assert!(!json.contains("seed"));
+        let imported = Preset::parse(&json).unwrap();
+        assert_eq!(preset, imported);
+
+        // Only the settings of the run itself are lost.
+        let params = imported.params();
+        assert_eq!(None, params.seed());
+        assert!(!params.compact());
+        assert_eq!(Selection::Tournament { size: 3 }, params.selection());
+        assert_eq!(Pairing::Shuffle, params.pairing());
+        assert_eq!(Preset::new("17-clue 9x9", "", &params), imported);
+    }
+
+    #[test]
+    fn test_parse() {
+        let preset = Preset::parse(
+            r#"{"version": 1, "name": "small", "description": "for 4x4 boards",
+                "params": {"population": 20, "selection_rate": 0.5, "mutation_rate": 0.1,
+                           "crossover": "Row"}}"#,
+        )
+        .unwrap();
+        assert_eq!("small", preset.name());
+        assert_eq!("for 4x4 boards", preset.description());
+        assert_eq!(Crossover::Row, preset.params().crossover());
+        assert_eq!(
+            Mutation::RandomResetting,
+            preset.params().mutation_operator()
+        );
+
+        let preset = |version, params| {
+            format!(r#"{{"version": {version}, "name": "p", "params": {params}}}"#)
+        };
+        let rates = r#""selection_rate": 0.5, "mutation_rate": 0.1"#;
+        assert_eq!(
+            Err(PresetError::UnsupportedVersion(2)),
+            Preset::parse(&preset(2, format!(r#"{{"population": 20, {rates}}}"#)))
+        );
+        assert!(matches!(
+            Preset::parse(&preset(1, format!(r#"{{"populaton": 20, {rates}}}"#))),
+            Err(PresetError::Malformed(_))
+        ));
+        assert_eq!(
+            Err(PresetError::Params(ParamError::PopulationTooLarge {
+                population: 1_000_000,
+                max: MAX_POPULATION,
+            })),
+            Preset::parse(&preset(1, format!(r#"{{"population": 1000000, {rates}}}"#)))
+        );
+        assert_eq!(
+            Err(PresetError::Params(ParamError::SelectionRate(2.0))),
+            Preset::parse(&preset(
+                1,
+                r#"{"population": 20, "selection_rate": 2.0, "mutation_rate": 0.1}"#.to_owned()
+            ))
+        );
+        assert_eq!(
+            Err(PresetError::Params(ParamError::ScheduleMinimum {
+                population: 20,
+                min: 40,
+            })),
+            Preset::parse(&preset(
+                1,
+                format!(
+                    r#"{{"population": 20, {rates},
+                         "population_schedule": {{"Linear": {{"min": 40, "generations": 10}}}}}}"#
+                )
+            ))
+        );
+        let weights = |boxes| {
+            preset(
+                1,
+                format!(
+                    r#"{{"population": 20, {rates},
+                         "weights": {{"rows": 1.0, "columns": 1.0, "boxes": {boxes}}}}}"#
+                ),
+            )
+        };
+        let weighted = Preset::parse(&weights(2.0)).unwrap();
+        assert!((weighted.params().weights().boxes - 2.0).abs() < f32::EPSILON);
+        assert!(matches!(
+            Preset::parse(&weights(-1.0)),
+            Err(PresetError::Invalid(_))
+        ));
+        assert!(matches!(
+            Preset::parse(&preset(
+                1,
+                format!(r#"{{"population": 20, {rates}, "children_per_pair": 0}}"#)
+            )),
+            Err(PresetError::Params(ParamError::ChildrenPerPair))
+        ));
+    }
+}
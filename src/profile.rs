@@ -0,0 +1,248 @@
+#![warn(
+    clippy::all,
+    // clippy::restriction,
+    clippy::pedantic,
+    clippy::nursery,
+    clippy::cargo
+)]
+
+use std::fmt::{Display, Formatter};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, Instant};
+
+/// A phase of a generation of the genetic algorithm.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum Phase {
+    /// Scoring every candidate.
+    Scoring,
+    /// Sorting candidates by score, and selecting survivors and elites.
+    Selection,
+    /// Gathering the survivors into pairs of parents.
+    Pairing,
+    /// Making children from their parents' cells.
+    Crossover,
+    /// Mutating children.
+    Mutation,
+    /// Everything else, such as column repair, restarts, observers and
+    /// assembling the next population.
+    Bookkeeping,
+}
+
+impl Phase {
+    /// Every phase, in the order they run.
+    pub const ALL: [Self; 6] = [
+        Self::Scoring,
+        Self::Selection,
+        Self::Pairing,
+        Self::Crossover,
+        Self::Mutation,
+        Self::Bookkeeping,
+    ];
+
+    const fn index(self) -> usize {
+        self as usize
+    }
+}
+
+impl Display for Phase {
+    #[inline]
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Scoring => write!(f, "scoring"),
+            Self::Selection => write!(f, "selection"),
+            Self::Pairing => write!(f, "pairing"),
+            Self::Crossover => write!(f, "crossover"),
+            Self::Mutation => write!(f, "mutation"),
+            Self::Bookkeeping => write!(f, "bookkeeping"),
+        }
+    }
+}
+
+/// The wall-clock time a run has spent in each phase so far.
+#[derive(Debug, Copy, Clone, Default, Eq, PartialEq)]
+pub struct PhaseTimes {
+    totals: [Duration; Phase::ALL.len()],
+    generations: u64,
+}
+
+impl PhaseTimes {
+    /// Returns the number of generations scored.
+    #[inline]
+    #[must_use]
+    pub const fn generations(&self) -> u64 {
+        self.generations
+    }
+
+    /// Returns the total time spent in `phase`.
+    #[inline]
+    #[must_use]
+    pub const fn total(&self, phase: Phase) -> Duration {
+        self.totals[phase.index()]
+    }
+
+    /// Returns the mean time spent in `phase` per generation.
+    #[inline]
+    #[must_use]
+    pub fn per_generation(&self, phase: Phase) -> Duration {
+        let generations = u32::try_from(self.generations.max(1)).unwrap_or(u32::MAX);
+        self.total(phase) / generations
+    }
+
+    /// Returns the fraction of the time profiled that was spent in `phase`,
+    /// or `None` if no time was.
+    #[inline]
+    #[must_use]
+    pub fn share(&self, phase: Phase) -> Option<f64> {
+        let total: Duration = self.totals.iter().sum();
+        (!total.is_zero()).then(|| self.total(phase).as_secs_f64() / total.as_secs_f64())
+    }
+
+    fn add(&mut self, phase: Phase, time: Duration) {
+        self.totals[phase.index()] += time;
+        if phase == Phase::Scoring {
+            self.generations += 1;
+        }
+    }
+}
+
+/// Attributes the time between successive laps to phases, or does nothing
+/// if profiling is off.
+#[derive(Debug)]
+pub(crate) struct PhaseTimer {
+    times: Option<PhaseTimes>,
+    lap: Instant,
+}
+
+impl PhaseTimer {
+    pub(crate) fn new(enabled: bool) -> Self {
+        Self {
+            times: enabled.then(PhaseTimes::default),
+            lap: Instant::now(),
+        }
+    }
+
+    /// Returns the times so far, or `None` if profiling is off.
+    pub(crate) const fn times(&self) -> Option<&PhaseTimes> {
+        self.times.as_ref()
+    }
+
+    /// Returns a clock for breeding to report to, or `None` if profiling is
+    /// off.
+    pub(crate) fn breed_clock(&self) -> Option<BreedClock> {
+        self.times.map(|_| BreedClock::default())
+    }
+
+    /// Attributes the time since the last lap to `phase`.
+    pub(crate) fn lap(&mut self, phase: Phase) {
+        if let Some(times) = &mut self.times {
+            let now = Instant::now();
+            times.add(phase, now - self.lap);
+            self.lap = now;
+        }
+    }
+
+    /// Attributes the time since the last lap, spent breeding, to crossover
+    /// and mutation in proportion to the time `clock` measured for each.
+    ///
+    /// Children are bred in parallel, so the clock sums time across threads;
+    /// only its proportions are comparable with the wall-clock phases.
+    pub(crate) fn lap_breeding(&mut self, clock: Option<&BreedClock>) {
+        let (Some(times), Some(clock)) = (&mut self.times, clock) else {
+            return;
+        };
+        let now = Instant::now();
+        let wall = now - self.lap;
+        self.lap = now;
+
+        let crossover = clock.crossover.load(Ordering::Relaxed);
+        let mutation = clock.mutation.load(Ordering::Relaxed);
+        #[allow(clippy::cast_precision_loss)]
+        let share = if crossover + mutation == 0 {
+            1.0
+        } else {
+            crossover as f64 / (crossover + mutation) as f64
+        };
+        let crossover = wall.mul_f64(share);
+        times.add(Phase::Crossover, crossover);
+        times.add(Phase::Mutation, wall.saturating_sub(crossover));
+    }
+}
+
+/// The time spent on crossover and mutation while breeding, summed across
+/// the threads breeding children.
+#[derive(Debug, Default)]
+pub(crate) struct BreedClock {
+    crossover: AtomicU64,
+    mutation: AtomicU64,
+}
+
+/// Times the breeding of one child, starting with crossover.
+pub(crate) struct BreedTimer<'a> {
+    clock: Option<&'a BreedClock>,
+    lap: Option<Instant>,
+}
+
+impl<'a> BreedTimer<'a> {
+    pub(crate) fn start(clock: Option<&'a BreedClock>) -> Self {
+        Self {
+            clock,
+            lap: clock.map(|_| Instant::now()),
+        }
+    }
+
+    /// Adds the time since the last lap to `phase`, which is either
+    /// crossover or mutation.
+    pub(crate) fn lap(&mut self, phase: Phase) {
+        let (Some(clock), Some(lap)) = (self.clock, &mut self.lap) else {
+            return;
+        };
+        let now = Instant::now();
+        let nanos = u64::try_from((now - *lap).as_nanos()).unwrap_or(u64::MAX);
+        *lap = now;
+
+        let total = match phase {
+            Phase::Mutation => &clock.mutation,
+            _ => &clock.crossover,
+        };
+        total.fetch_add(nanos, Ordering::Relaxed);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_phase_times() {
+        let mut off = PhaseTimer::new(false);
+        off.lap(Phase::Scoring);
+        assert!(off.times().is_none());
+        assert!(off.breed_clock().is_none());
+
+        let mut timer = PhaseTimer::new(true);
+        for _ in 0..3 {
+            timer.lap(Phase::Scoring);
+            timer.lap(Phase::Selection);
+
+            let clock = timer.breed_clock();
+            let mut breeding = BreedTimer::start(clock.as_ref());
+            std::thread::sleep(Duration::from_millis(2));
+            breeding.lap(Phase::Crossover);
+            breeding.lap(Phase::Mutation);
+            timer.lap_breeding(clock.as_ref());
+        }
+
+        let phases = *timer.times().unwrap();
+        assert_eq!(3, phases.generations());
+        assert!(phases.total(Phase::Crossover) > phases.total(Phase::Mutation));
+        assert!(phases.total(Phase::Crossover) >= Duration::from_millis(5));
+        assert_eq!(Duration::ZERO, phases.total(Phase::Pairing));
+        assert_eq!(
+            phases.total(Phase::Crossover) / 3,
+            phases.per_generation(Phase::Crossover)
+        );
+        let shares: f64 = Phase::ALL.iter().filter_map(|&p| phases.share(p)).sum();
+        assert!((shares - 1.0).abs() < 1e-9);
+        assert_eq!(None, PhaseTimes::default().share(Phase::Scoring));
+    }
+}
@@ -0,0 +1,275 @@
+#![warn(
+    clippy::all,
+    // clippy::restriction,
+    clippy::pedantic,
+    clippy::nursery,
+    clippy::cargo
+)]
+
+use super::errors::SolveError;
+use super::formats;
+use super::genetics::GAParams;
+use super::solver::{Budget, GeneticSolver, Solver};
+use super::sudoku::Board;
+use std::fmt::{Display, Formatter};
+use std::time::Duration;
+
+/// Options for `solve_str`. The defaults are those of the command line.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct SolveOptions {
+    population: usize,
+    selection_rate: f32,
+    mutation_rate: f32,
+    restart: Option<u64>,
+    seed: Option<u64>,
+    budget: Budget,
+}
+
+impl Default for SolveOptions {
+    #[inline]
+    fn default() -> Self {
+        Self {
+            population: 100,
+            selection_rate: 0.5,
+            mutation_rate: 0.05,
+            restart: None,
+            seed: None,
+            budget: Budget::unlimited(),
+        }
+    }
+}
+
+impl SolveOptions {
+    /// Sets the size of the population.
+    #[inline]
+    #[must_use]
+    pub const fn with_population(mut self, population: usize) -> Self {
+        self.population = population;
+        self
+    }
+
+    /// Sets the fraction of the population selected per generation.
+    #[inline]
+    #[must_use]
+    pub const fn with_selection_rate(mut self, selection_rate: f32) -> Self {
+        self.selection_rate = selection_rate;
+        self
+    }
+
+    /// Sets the rate at which values mutate.
+    #[inline]
+    #[must_use]
+    pub const fn with_mutation_rate(mut self, mutation_rate: f32) -> Self {
+        self.mutation_rate = mutation_rate;
+        self
+    }
+
+    /// Sets the number of generations before a population restart.
+    #[inline]
+    #[must_use]
+    pub const fn with_restart(mut self, restart: u64) -> Self {
+        self.restart = Some(restart);
+        self
+    }
+
+    /// Seeds every random choice of the run, making it reproducible.
+    #[inline]
+    #[must_use]
+    pub const fn with_seed(mut self, seed: u64) -> Self {
+        self.seed = Some(seed);
+        self
+    }
+
+    /// Limits how long the solver may run. By default it runs until it
+    /// finds a solution.
+    #[inline]
+    #[must_use]
+    pub const fn with_budget(mut self, budget: Budget) -> Self {
+        self.budget = budget;
+        self
+    }
+
+    fn params(&self) -> Result<GAParams, SolveError> {
+        let params = GAParams::new(
+            self.population,
+            self.selection_rate,
+            self.mutation_rate,
+            self.restart,
+        )?;
+
+        Ok(match self.seed {
+            Some(seed) => params.with_seed(seed),
+            None => params,
+        })
+    }
+}
+
+/// A solved puzzle.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Solution {
+    /// The solved grid, row by row.
+    pub grid: Vec<Vec<u8>>,
+    /// The generation the solution was found in.
+    pub generation: u64,
+    /// How long the solver ran.
+    pub duration: Duration,
+}
+
+impl Solution {
+    /// Returns the side length of the grid.
+    #[inline]
+    #[must_use]
+    pub fn size(&self) -> usize {
+        self.grid.len()
+    }
+}
+
+/// Writes the grid the way `Board` does.
+impl Display for Solution {
+    #[inline]
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let rows: Vec<String> = self
+            .grid
+            .iter()
+            .map(|row| {
+                let digits: Vec<String> = row.iter().map(ToString::to_string).collect();
+                digits.join(" ")
+            })
+            .collect();
+
+        write!(f, "{}", rows.join("\n"))
+    }
+}
+
+/// Solves a puzzle given as text, in one call.
+///
+/// The puzzle may be in any format `formats::parse` accepts, and may be
+/// 4×4, 9×9, 16×16 or 25×25; the smallest size it parses as is used. For a
+/// collection of puzzles, only the first is solved.
+///
+/// ```no_run
+/// use genetic_sudoku::{solve_str, SolveOptions};
+///
+/// let solution = solve_str(
+///     "..3.\n.4..\n...2\n.1..\n",
+///     SolveOptions::default().with_seed(1),
+/// )?;
+/// println!("{solution}");
+/// # Ok::<(), genetic_sudoku::errors::SolveError>(())
+/// ```
+///
+/// # Errors
+///
+/// Fails if the puzzle is malformed or its givens conflict, if the options
+/// are invalid, or if the budget runs out before a solution is found.
+#[inline]
+pub fn solve_str(puzzle: &str, options: SolveOptions) -> Result<Solution, SolveError> {
+    if let Ok(boards) = formats::parse::<4>(puzzle) {
+        return solve_board(&boards[0], options);
+    }
+    if let Ok(boards) = formats::parse::<9>(puzzle) {
+        return solve_board(&boards[0], options);
+    }
+    if let Ok(boards) = formats::parse::<16>(puzzle) {
+        return solve_board(&boards[0], options);
+    }
+    if let Ok(boards) = formats::parse::<25>(puzzle) {
+        return solve_board(&boards[0], options);
+    }
+
+    Err(SolveError::Malformed)
+}
+
+fn solve_board<const N: usize>(
+    board: &Board<N>,
+    options: SolveOptions,
+) -> Result<Solution, SolveError> {
+    let params = options.params()?;
+
+    if board
+        .violations()
+        .iter()
+        .any(|violation| violation.digit != 0)
+    {
+        return Err(SolveError::ConflictingGivens);
+    }
+
+    let outcome = GeneticSolver::new(params).solve(board, options.budget, &mut ());
+    let Some(solution) = outcome.solution() else {
+        return Err(SolveError::Unsolved {
+            termination: outcome.termination,
+            best: outcome.best.map(|(_, score)| score),
+        });
+    };
+
+    Ok(Solution {
+        grid: solution.0.iter().map(|row| row.0.to_vec()).collect(),
+        generation: outcome.generation,
+        duration: outcome.duration,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::errors::ParamError;
+    use crate::termination::Termination;
+    use crate::testing::{SOLVED_4, SOLVED_9};
+
+    #[test]
+    fn test_solve_str() {
+        let mut puzzle = SOLVED_9;
+        puzzle.0[0].0[0] = 0;
+        puzzle.0[8].0[8] = 0;
+
+        let solution = solve_str(&puzzle.to_string(), SolveOptions::default()).unwrap();
+        assert_eq!(9, solution.size());
+        assert_eq!(SOLVED_9.to_string(), solution.to_string());
+
+        let solution = solve_str("12.4\n3..2\n4.21\n2.43\n", SolveOptions::default()).unwrap();
+        assert_eq!(SOLVED_4.to_string(), solution.to_string());
+    }
+
+    #[test]
+    fn test_solve_str_errors() {
+        let options = SolveOptions::default();
+
+        assert_eq!(Err(SolveError::Malformed), solve_str("123", options));
+        assert_eq!(
+            Err(SolveError::ConflictingGivens),
+            solve_str("11..\n....\n....\n....\n", options)
+        );
+        assert_eq!(
+            Some(SolveError::InvalidOptions(ParamError::NoParentPairs {
+                population: 3,
+                selection_rate: 0.5,
+                survivors: 1,
+            })),
+            solve_str("....\n....\n....\n....\n", options.with_population(3)).err()
+        );
+        assert_eq!(
+            Some("invalid options: mutation rate 1.5 is not between 0.0 and 1.0".to_owned()),
+            solve_str("....\n....\n....\n....\n", options.with_mutation_rate(1.5))
+                .err()
+                .map(|err| err.to_string())
+        );
+
+        let budget = Budget {
+            generations: Some(0),
+            duration: None,
+            target: None,
+        };
+        let unsolved = solve_str("....\n....\n....\n....\n", options.with_budget(budget));
+        assert_eq!(
+            Err(SolveError::Unsolved {
+                termination: Termination::BudgetExhausted,
+                best: None,
+            }),
+            unsolved
+        );
+        assert_eq!(
+            Termination::BudgetExhausted,
+            unsolved.unwrap_err().termination()
+        );
+    }
+}
@@ -0,0 +1,311 @@
+#![warn(
+    clippy::all,
+    // clippy::restriction,
+    clippy::pedantic,
+    clippy::nursery,
+    clippy::cargo
+)]
+
+use super::diversity::entropy;
+use super::sudoku::{Board, Fitness};
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+
+/// Settings for restarting the population when a fresh one is expected to
+/// solve the puzzle sooner than the current one.
+///
+/// Each generation, the time to solve by continuing is extrapolated from
+/// the progress of the best score over the last `window` generations, and
+/// the time to solve after a restart from its progress since the population
+/// was last started. The population restarts when the second is shorter,
+/// but only once it has converged, with its entropy below `min_entropy`, and
+/// at least `window` generations after it was last started.
+#[derive(Debug, Copy, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AutoRestart {
+    /// The number of generations progress is measured over.
+    pub window: u64,
+    /// The entropy below which the population counts as converged.
+    pub min_entropy: f64,
+}
+
+impl Default for AutoRestart {
+    #[inline]
+    fn default() -> Self {
+        Self {
+            window: 50,
+            min_entropy: 0.25,
+        }
+    }
+}
+
+/// Settings for restarting the population once its best score has stopped
+/// improving, keeping its fittest candidates.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Serialize, Deserialize)]
+pub struct StallRestart {
+    /// The number of generations without a better best score before the
+    /// population restarts.
+    pub generations: u64,
+    /// The number of the fittest candidates kept through a restart.
+    pub keep: usize,
+}
+
+/// Settings for cataclysmic restarts, as in the CHC algorithm, which start
+/// a stalled population over around its champion.
+///
+/// Once the best score has not improved for `generations` generations, only
+/// the fittest candidate is kept, and the rest of the population is
+/// replaced with copies of it whose free cells each mutate with probability
+/// `rate`. Unlike a random restart, the progress of a nearly solved
+/// champion carries over to the whole new population.
+#[derive(Debug, Copy, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Cataclysm {
+    /// The number of generations without a better best score before the
+    /// population restarts.
+    pub generations: u64,
+    /// The mutation rate the champion's copies are bred with.
+    pub rate: f32,
+}
+
+impl Default for Cataclysm {
+    #[inline]
+    fn default() -> Self {
+        Self {
+            generations: 50,
+            rate: 0.35,
+        }
+    }
+}
+
+/// Counts the generations since the best score of a run last improved.
+#[derive(Debug, Clone)]
+pub(crate) struct StallCounter {
+    generations: u64,
+    best: Option<Fitness>,
+    stalled: u64,
+}
+
+impl StallCounter {
+    /// Returns a counter that reports a stall after `generations`
+    /// generations without improvement.
+    pub(crate) const fn new(generations: u64) -> Self {
+        Self {
+            generations,
+            best: None,
+            stalled: 0,
+        }
+    }
+
+    /// Records a scored generation that did not solve the puzzle, and
+    /// returns whether the best score has stalled. The count starts over
+    /// after each stall, but the best score to beat is still the best of the
+    /// whole run.
+    pub(crate) fn stalled(&mut self, scores: &[Fitness]) -> bool {
+        let best = scores.iter().copied().min().unwrap_or_default();
+        if self.best.is_none_or(|previous| best < previous) {
+            self.best = Some(best);
+            self.stalled = 0;
+            return false;
+        }
+
+        self.stalled += 1;
+        if self.stalled < self.generations {
+            return false;
+        }
+        self.stalled = 0;
+        true
+    }
+}
+
+/// Whether the population was restarted after one generation, and why.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct RestartDecision {
+    pub generation: u64,
+    pub restarted: bool,
+    /// The population's entropy.
+    pub entropy: f64,
+    /// The expected number of generations to a solution by continuing, or
+    /// `None` if the best score has stalled over the window.
+    pub continued: Option<f64>,
+    /// The expected number of generations to a solution after a restart, or
+    /// `None` if the best score has not improved since the last start.
+    pub restarted_from_scratch: Option<f64>,
+}
+
+/// The restarts made so far in a run, and the latest decision.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RestartMetrics {
+    pub restarts: Vec<RestartDecision>,
+    pub latest: RestartDecision,
+}
+
+/// Decides, generation by generation, when to restart the population.
+#[derive(Debug, Clone)]
+pub(crate) struct RestartTuner {
+    settings: AutoRestart,
+    start: u64,
+    first_best: Option<Fitness>,
+    recent: VecDeque<Fitness>,
+    restarts: Vec<RestartDecision>,
+}
+
+impl RestartTuner {
+    pub(crate) const fn new(settings: AutoRestart) -> Self {
+        Self {
+            settings,
+            start: 0,
+            first_best: None,
+            recent: VecDeque::new(),
+            restarts: Vec::new(),
+        }
+    }
+
+    /// Decides whether to restart after a scored generation that did not
+    /// solve the puzzle.
+    pub(crate) fn decide<const N: usize>(
+        &mut self,
+        generation: u64,
+        population: &[Board<N>],
+        scores: &[Fitness],
+    ) -> RestartDecision {
+        let best = scores.iter().copied().min().unwrap_or_default();
+        let first_best = *self.first_best.get_or_insert(best);
+        let window = usize::try_from(self.settings.window).unwrap_or(usize::MAX);
+        if self.recent.len() > window {
+            self.recent.pop_front();
+        }
+        self.recent.push_back(best);
+
+        let age = generation - self.start + 1;
+        let entropy = entropy(population);
+        let continued = extrapolate(self.recent[0], best, self.recent.len() as u64 - 1);
+        let restarted_from_scratch = extrapolate(first_best, best, age);
+        let cheaper = match (continued, restarted_from_scratch) {
+            (None, _) => true,
+            (Some(continued), Some(restart)) => restart < continued,
+            (Some(_), None) => false,
+        };
+
+        let decision = RestartDecision {
+            generation,
+            restarted: cheaper
+                && age >= self.settings.window
+                && entropy < self.settings.min_entropy,
+            entropy,
+            continued,
+            restarted_from_scratch,
+        };
+        if decision.restarted {
+            self.start = generation + 1;
+            self.first_best = None;
+            self.recent.clear();
+            self.restarts.push(decision);
+        }
+        decision
+    }
+
+    /// Returns the restarts so far with the latest decision.
+    pub(crate) fn metrics(&self, latest: RestartDecision) -> RestartMetrics {
+        RestartMetrics {
+            restarts: self.restarts.clone(),
+            latest,
+        }
+    }
+}
+
+/// Returns the generations it takes to get from `from` to a solution at the
+/// rate the best score went from `from` to `to` over `generations`, or
+/// `None` if it did not improve.
+fn extrapolate(from: Fitness, to: Fitness, generations: u64) -> Option<f64> {
+    #[allow(clippy::cast_precision_loss)]
+    let rate = f64::from(from - to) / generations as f64;
+
+    (rate > 0.0).then(|| f64::from(from) / rate)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::SOLVED_4;
+
+    fn settings() -> AutoRestart {
+        AutoRestart {
+            window: 4,
+            min_entropy: 0.5,
+        }
+    }
+
+    #[test]
+    fn test_extrapolate() {
+        let (ten, six) = (Fitness::new(10), Fitness::new(6));
+        assert_eq!(Some(10.0), extrapolate(ten, six, 4));
+        assert_eq!(None, extrapolate(six, six, 4));
+        assert_eq!(None, extrapolate(six, six, 0));
+    }
+
+    #[test]
+    fn test_restart_when_stalled() {
+        let mut tuner = RestartTuner::new(settings());
+        let converged = [SOLVED_4; 4];
+
+        // Steady progress: continuing beats starting over.
+        for (generation, best) in (0..6).zip([20, 18, 16, 14, 12, 10]) {
+            let decision = tuner.decide(generation, &converged, &[Fitness::new(best)]);
+            assert!(!decision.restarted, "{decision:?}");
+        }
+
+        // Stalling: a fresh population that improves as this one did on
+        // average is soon expected to solve it sooner.
+        let decision = (6..11)
+            .map(|generation| tuner.decide(generation, &converged, &[Fitness::new(10)]))
+            .find(|decision| decision.restarted)
+            .unwrap();
+        assert!(
+            decision.restarted_from_scratch.unwrap() < decision.continued.unwrap_or(f64::INFINITY)
+        );
+
+        let metrics = tuner.metrics(decision);
+        assert_eq!(vec![decision], metrics.restarts);
+
+        // The window starts over with the new population.
+        assert!(
+            !tuner
+                .decide(decision.generation + 1, &converged, &[Fitness::new(10)])
+                .restarted
+        );
+    }
+
+    #[test]
+    fn test_stall_counter() {
+        let mut counter = StallCounter::new(2);
+        let stalled: Vec<bool> = [10, 8, 8, 9, 8, 8, 7, 7]
+            .into_iter()
+            .map(|best| counter.stalled(&[Fitness::new(20), Fitness::new(best)]))
+            .collect();
+        assert_eq!(
+            vec![false, false, false, true, false, true, false, false],
+            stalled
+        );
+    }
+
+    #[test]
+    fn test_no_restart_while_diverse() {
+        let mut tuner = RestartTuner::new(settings());
+        let diverse: Vec<Board<4>> = (0..4)
+            .map(|shift| {
+                let mut board = SOLVED_4;
+                for row in &mut board.0 {
+                    row.0.rotate_left(shift);
+                }
+                board
+            })
+            .collect();
+
+        for generation in 0..20 {
+            assert!(
+                !tuner
+                    .decide(generation, &diverse, &[Fitness::new(10)])
+                    .restarted
+            );
+        }
+    }
+}
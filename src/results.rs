@@ -0,0 +1,385 @@
+#![warn(
+    clippy::all,
+    // clippy::restriction,
+    clippy::pedantic,
+    clippy::nursery,
+    clippy::cargo
+)]
+
+use super::sudoku::Fitness;
+use super::termination::Termination;
+use rusqlite::{params, Connection, Row};
+use std::path::Path;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+const SCHEMA: &str = "
+CREATE TABLE IF NOT EXISTS runs (
+    id INTEGER PRIMARY KEY,
+    recorded_at INTEGER NOT NULL,
+    puzzle_hash TEXT NOT NULL,
+    population INTEGER NOT NULL,
+    survivors INTEGER NOT NULL,
+    mutation_rate REAL NOT NULL,
+    restart INTEGER,
+    seed TEXT,
+    fingerprint TEXT,
+    outcome TEXT NOT NULL,
+    generations INTEGER NOT NULL,
+    duration_secs REAL NOT NULL
+);
+CREATE INDEX IF NOT EXISTS runs_puzzle_hash ON runs (puzzle_hash);
+CREATE TABLE IF NOT EXISTS seed_corpus (
+    puzzle_hash TEXT NOT NULL,
+    seed TEXT NOT NULL,
+    recorded_at INTEGER NOT NULL,
+    puzzle TEXT NOT NULL,
+    generations INTEGER NOT NULL,
+    best INTEGER NOT NULL,
+    fingerprint TEXT NOT NULL,
+    PRIMARY KEY (puzzle_hash, seed)
+);
+";
+
+/// Selects the columns `RunRecord::from_row` reads, followed by the id.
+const SELECT: &str = "SELECT recorded_at, puzzle_hash, population, survivors, mutation_rate, \
+                      restart, seed, fingerprint, outcome, generations, duration_secs, id \
+                      FROM runs";
+
+/// Brings databases created before run fingerprints up to date.
+const MIGRATE_FINGERPRINT: &str = "ALTER TABLE runs ADD COLUMN fingerprint TEXT";
+
+/// The outcome and parameters of a single run.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RunRecord {
+    /// Seconds since the Unix epoch at which the run was recorded.
+    pub recorded_at: u64,
+    /// The `Board::puzzle_hash` of the puzzle that was solved.
+    pub puzzle_hash: u64,
+    pub population: usize,
+    pub survivors: usize,
+    pub mutation_rate: f32,
+    pub restart: Option<u64>,
+    /// The RNG seed, if the run was seeded.
+    pub seed: Option<u64>,
+    /// The `RunConfig::fingerprint` of the run, or `None` for runs recorded
+    /// before fingerprints were.
+    pub fingerprint: Option<u64>,
+    pub outcome: Termination,
+    pub generations: u64,
+    pub duration: Duration,
+}
+
+impl RunRecord {
+    /// Returns the current time in seconds since the Unix epoch.
+    #[inline]
+    #[must_use]
+    pub fn now() -> u64 {
+        SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |elapsed| elapsed.as_secs())
+    }
+
+    fn from_row(row: &Row<'_>) -> rusqlite::Result<Self> {
+        let seed: Option<String> = row.get(6)?;
+        let fingerprint: Option<String> = row.get(7)?;
+        let outcome: String = row.get(8)?;
+
+        Ok(Self {
+            recorded_at: row.get(0)?,
+            puzzle_hash: parse_hex(row, 1)?,
+            population: row.get(2)?,
+            survivors: row.get(3)?,
+            mutation_rate: row.get(4)?,
+            restart: row.get(5)?,
+            seed: seed.map(|seed| hex(6, &seed)).transpose()?,
+            fingerprint: fingerprint
+                .map(|fingerprint| hex(7, &fingerprint))
+                .transpose()?,
+            outcome: outcome.parse().map_err(|err: String| {
+                rusqlite::Error::FromSqlConversionFailure(
+                    8,
+                    rusqlite::types::Type::Text,
+                    err.into(),
+                )
+            })?,
+            generations: row.get(9)?,
+            duration: Duration::from_secs_f64(row.get(10)?),
+        })
+    }
+}
+
+/// Parses the hexadecimal text in column `index` of a row.
+fn hex(index: usize, text: &str) -> rusqlite::Result<u64> {
+    u64::from_str_radix(text, 16).map_err(|err| {
+        rusqlite::Error::FromSqlConversionFailure(index, rusqlite::types::Type::Text, Box::new(err))
+    })
+}
+
+/// Reads the hexadecimal text in column `index` of `row`.
+fn parse_hex(row: &Row<'_>, index: usize) -> rusqlite::Result<u64> {
+    hex(index, &row.get::<_, String>(index)?)
+}
+
+/// A seed that failed to solve a puzzle within its generation budget, kept
+/// so that it can be replayed after the algorithm changes.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HardSeed {
+    /// Seconds since the Unix epoch at which the seed was recorded.
+    pub recorded_at: u64,
+    /// The `Board::puzzle_hash` of the puzzle.
+    pub puzzle_hash: u64,
+    /// The puzzle, drawn as a grid of digits with 0 for empty cells.
+    pub puzzle: String,
+    pub seed: u64,
+    /// The generation budget the seed failed to solve the puzzle within.
+    pub generations: u64,
+    /// The best fitness score the seed reached within its budget.
+    pub best: Fitness,
+    /// The `RunConfig::fingerprint` of the seeded run.
+    pub fingerprint: u64,
+}
+
+/// A SQLite-backed store of past runs.
+pub struct ResultsDb {
+    conn: Connection,
+}
+
+impl ResultsDb {
+    /// Opens the results database at `path`, creating it if needed.
+    ///
+    /// # Errors
+    ///
+    /// Fails if the database cannot be opened or its schema cannot be
+    /// created.
+    #[inline]
+    pub fn open<P: AsRef<Path>>(path: P) -> rusqlite::Result<Self> {
+        let conn = Connection::open(path)?;
+        conn.execute_batch(SCHEMA)?;
+
+        let has_fingerprint: bool = conn.query_row(
+            "SELECT COUNT(*) > 0 FROM pragma_table_info('runs') WHERE name = 'fingerprint'",
+            [],
+            |row| row.get(0),
+        )?;
+        if !has_fingerprint {
+            conn.execute_batch(MIGRATE_FINGERPRINT)?;
+        }
+
+        Ok(Self { conn })
+    }
+
+    /// Records a run.
+    ///
+    /// # Errors
+    ///
+    /// Fails if the run cannot be written to the database.
+    #[inline]
+    pub fn record(&self, run: &RunRecord) -> rusqlite::Result<()> {
+        self.conn.execute(
+            "INSERT INTO runs (recorded_at, puzzle_hash, population, survivors, mutation_rate, \
+             restart, seed, fingerprint, outcome, generations, duration_secs) \
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11)",
+            params![
+                run.recorded_at,
+                format!("{:016x}", run.puzzle_hash),
+                run.population,
+                run.survivors,
+                run.mutation_rate,
+                run.restart,
+                run.seed.map(|seed| format!("{seed:016x}")),
+                run.fingerprint
+                    .map(|fingerprint| format!("{fingerprint:016x}")),
+                run.outcome.as_str(),
+                run.generations,
+                run.duration.as_secs_f64(),
+            ],
+        )?;
+
+        Ok(())
+    }
+
+    /// Returns past runs in the order they were recorded.
+    ///
+    /// # Arguments
+    ///
+    /// * `puzzle_hash` - only return runs of this puzzle, if given
+    ///
+    /// # Errors
+    ///
+    /// Fails if the runs cannot be read from the database.
+    #[inline]
+    pub fn history(&self, puzzle_hash: Option<u64>) -> rusqlite::Result<Vec<RunRecord>> {
+        Ok(self
+            .entries(puzzle_hash)?
+            .into_iter()
+            .map(|(_, run)| run)
+            .collect())
+    }
+
+    /// Returns past runs in the order they were recorded, with their ids.
+    ///
+    /// # Arguments
+    ///
+    /// * `puzzle_hash` - only return runs of this puzzle, if given
+    ///
+    /// # Errors
+    ///
+    /// Fails if the runs cannot be read from the database.
+    #[inline]
+    pub fn entries(&self, puzzle_hash: Option<u64>) -> rusqlite::Result<Vec<(i64, RunRecord)>> {
+        self.query("puzzle_hash", puzzle_hash)
+    }
+
+    /// Returns the run with the given id, or `None` if there is none.
+    ///
+    /// # Errors
+    ///
+    /// Fails if the run cannot be read from the database.
+    #[inline]
+    pub fn run(&self, id: i64) -> rusqlite::Result<Option<RunRecord>> {
+        let mut statement = self.conn.prepare(&format!("{SELECT} WHERE id = ?1"))?;
+        let mut runs = statement.query_map([id], RunRecord::from_row)?;
+
+        runs.next().transpose()
+    }
+
+    /// Returns past runs comparable with a run of the given fingerprint, in
+    /// the order they were recorded.
+    ///
+    /// # Errors
+    ///
+    /// Fails if the runs cannot be read from the database.
+    #[inline]
+    pub fn comparable(&self, fingerprint: u64) -> rusqlite::Result<Vec<RunRecord>> {
+        Ok(self
+            .query("fingerprint", Some(fingerprint))?
+            .into_iter()
+            .map(|(_, run)| run)
+            .collect())
+    }
+
+    /// Adds a hard seed to the seed corpus, unless the corpus already holds
+    /// that seed for that puzzle. Returns whether it was added.
+    ///
+    /// # Errors
+    ///
+    /// Fails if the seed cannot be written to the database.
+    #[inline]
+    pub fn add_hard_seed(&self, hard_seed: &HardSeed) -> rusqlite::Result<bool> {
+        let added = self.conn.execute(
+            "INSERT OR IGNORE INTO seed_corpus (puzzle_hash, seed, recorded_at, puzzle, \
+             generations, best, fingerprint) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+            params![
+                format!("{:016x}", hard_seed.puzzle_hash),
+                format!("{:016x}", hard_seed.seed),
+                hard_seed.recorded_at,
+                hard_seed.puzzle,
+                hard_seed.generations,
+                hard_seed.best.get(),
+                format!("{:016x}", hard_seed.fingerprint),
+            ],
+        )?;
+
+        Ok(added > 0)
+    }
+
+    /// Returns the seed corpus in the order it was recorded.
+    ///
+    /// # Arguments
+    ///
+    /// * `puzzle_hash` - only return seeds of this puzzle, if given
+    ///
+    /// # Errors
+    ///
+    /// Fails if the seeds cannot be read from the database.
+    #[inline]
+    pub fn hard_seeds(&self, puzzle_hash: Option<u64>) -> rusqlite::Result<Vec<HardSeed>> {
+        let mut statement = self.conn.prepare(
+            "SELECT recorded_at, puzzle_hash, puzzle, seed, generations, best, fingerprint \
+             FROM seed_corpus WHERE ?1 IS NULL OR puzzle_hash = ?1 ORDER BY rowid",
+        )?;
+        let puzzle_hash = puzzle_hash.map(|hash| format!("{hash:016x}"));
+        let seeds = statement.query_map([puzzle_hash], |row| {
+            Ok(HardSeed {
+                recorded_at: row.get(0)?,
+                puzzle_hash: parse_hex(row, 1)?,
+                puzzle: row.get(2)?,
+                seed: parse_hex(row, 3)?,
+                generations: row.get(4)?,
+                best: Fitness::new(row.get(5)?),
+                fingerprint: parse_hex(row, 6)?,
+            })
+        })?;
+
+        seeds.collect()
+    }
+
+    fn query(&self, column: &str, value: Option<u64>) -> rusqlite::Result<Vec<(i64, RunRecord)>> {
+        let mut statement = self.conn.prepare(&format!(
+            "{SELECT} WHERE ?1 IS NULL OR {column} = ?1 ORDER BY id",
+        ))?;
+        let value = value.map(|value| format!("{value:016x}"));
+        let runs =
+            statement.query_map([value], |row| Ok((row.get(11)?, RunRecord::from_row(row)?)))?;
+
+        runs.collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn run(puzzle_hash: u64, outcome: Termination) -> RunRecord {
+        RunRecord {
+            recorded_at: 1_700_000_000,
+            puzzle_hash,
+            population: 100,
+            survivors: 50,
+            mutation_rate: 0.05,
+            restart: Some(500),
+            seed: Some(u64::MAX),
+            fingerprint: Some(puzzle_hash ^ 1),
+            outcome,
+            generations: 42,
+            duration: Duration::from_millis(1500),
+        }
+    }
+
+    #[test]
+    fn test_record_and_history() {
+        let db = ResultsDb::open(":memory:").unwrap();
+        let solved = run(u64::MAX, Termination::Solved);
+        let cancelled = run(7, Termination::Cancelled);
+
+        db.record(&solved).unwrap();
+        db.record(&cancelled).unwrap();
+
+        assert_eq!(vec![solved.clone(), cancelled], db.history(None).unwrap());
+        assert_eq!(vec![solved.clone()], db.history(Some(u64::MAX)).unwrap());
+        assert_eq!(vec![solved.clone()], db.comparable(u64::MAX ^ 1).unwrap());
+        assert!(db.comparable(0).unwrap().is_empty());
+
+        let (id, _) = db.entries(Some(u64::MAX)).unwrap()[0];
+        assert_eq!(Some(solved), db.run(id).unwrap());
+        assert_eq!(None, db.run(id + 100).unwrap());
+    }
+
+    #[test]
+    fn test_migrate_fingerprint() {
+        let path = std::env::temp_dir().join(format!("runs-{}.db", std::process::id()));
+        let legacy = SCHEMA.replace("    fingerprint TEXT,\n", "");
+        Connection::open(&path)
+            .unwrap()
+            .execute_batch(&legacy)
+            .unwrap();
+
+        let db = ResultsDb::open(&path).unwrap();
+        let run = run(7, Termination::Solved);
+        db.record(&run).unwrap();
+        assert_eq!(vec![run], db.history(None).unwrap());
+
+        drop(db);
+        std::fs::remove_file(path).unwrap();
+    }
+}
@@ -0,0 +1,227 @@
+#![warn(
+    clippy::all,
+    // clippy::restriction,
+    clippy::pedantic,
+    clippy::nursery,
+    clippy::cargo
+)]
+
+use super::errors::ScriptError;
+use super::sudoku::{fnv1a, Board, Fitness};
+use rhai::{Array, Dynamic, Engine, Scope, AST, INT};
+use std::collections::HashMap;
+use std::fmt::{Debug, Formatter};
+use std::sync::RwLock;
+
+/// The most operations a script may run to score one board, so that a
+/// script that never ends fails instead of stalling the run.
+const MAX_OPERATIONS: u64 = 1_000_000;
+
+/// How many boards' penalties are cached before the cache starts over.
+const CACHE_CAPACITY: usize = 1 << 16;
+
+/// A custom fitness term written in Rhai.
+///
+/// The script sees the board as `grid`, an array of rows, each an array of
+/// digits with 0 for an empty cell, and evaluates to the number of
+/// violations of its constraint, which is added to the board's fitness. For
+/// example, this script counts the duplicates on the main diagonal:
+///
+/// ```text
+/// let seen = [];
+/// let duplicates = 0;
+/// for i in 0..grid.len() {
+///     let digit = grid[i][i];
+///     if seen.contains(digit) { duplicates += 1; } else { seen.push(digit); }
+/// }
+/// duplicates
+/// ```
+///
+/// Converged populations hold many copies of the same boards, so penalties
+/// are cached by board.
+pub struct FitnessScript {
+    engine: Engine,
+    ast: AST,
+    hash: u64,
+    cache: RwLock<HashMap<Vec<u8>, u32>>,
+}
+
+impl FitnessScript {
+    /// Compiles `source`.
+    ///
+    /// # Errors
+    ///
+    /// Fails if the script does not compile.
+    #[inline]
+    pub fn compile(source: &str) -> Result<Self, ScriptError> {
+        let mut engine = Engine::new();
+        engine.set_max_operations(MAX_OPERATIONS);
+        let ast = engine
+            .compile(source)
+            .map_err(|error| ScriptError::Compile(error.to_string()))?;
+
+        Ok(Self {
+            engine,
+            ast,
+            hash: fnv1a(source.bytes()),
+            cache: RwLock::new(HashMap::new()),
+        })
+    }
+
+    /// Returns a stable hash of the script's source.
+    #[inline]
+    #[must_use]
+    pub const fn hash(&self) -> u64 {
+        self.hash
+    }
+
+    /// Returns the script's penalty for `board`.
+    ///
+    /// # Errors
+    ///
+    /// Fails if the script fails or does not evaluate to a non-negative
+    /// integer.
+    ///
+    /// # Panics
+    ///
+    /// Panics if another thread panicked while holding the cache.
+    #[inline]
+    pub fn penalty<const N: usize>(&self, board: &Board<N>) -> Result<Fitness, ScriptError> {
+        let cells: Vec<u8> = board.0.iter().flat_map(|row| row.0).collect();
+        if let Some(&penalty) = self.cache.read().unwrap().get(&cells) {
+            return Ok(Fitness::new(penalty));
+        }
+
+        let grid: Array = board
+            .0
+            .iter()
+            .map(|row| {
+                let row: Array = row
+                    .0
+                    .iter()
+                    .map(|&digit| Dynamic::from(INT::from(digit)))
+                    .collect();
+                Dynamic::from(row)
+            })
+            .collect();
+        let mut scope = Scope::new();
+        scope.push("grid", grid);
+
+        let penalty = self
+            .engine
+            .eval_ast_with_scope::<INT>(&mut scope, &self.ast)
+            .map_err(|error| ScriptError::Run(error.to_string()))?;
+        if penalty < 0 {
+            return Err(ScriptError::Negative(penalty));
+        }
+        let penalty = u32::try_from(penalty).unwrap_or(u32::MAX);
+
+        let mut cache = self.cache.write().unwrap();
+        if cache.len() >= CACHE_CAPACITY {
+            cache.clear();
+        }
+        cache.insert(cells, penalty);
+        drop(cache);
+        Ok(Fitness::new(penalty))
+    }
+
+    /// Returns the number of boards whose penalties are cached.
+    ///
+    /// # Panics
+    ///
+    /// Panics if another thread panicked while holding the cache.
+    #[inline]
+    #[must_use]
+    pub fn cached(&self) -> usize {
+        self.cache.read().unwrap().len()
+    }
+}
+
+impl Debug for FitnessScript {
+    #[inline]
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("FitnessScript")
+            .field("hash", &format_args!("{:016x}", self.hash))
+            .finish_non_exhaustive()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::arena::PopulationArena;
+    use crate::genetics::{score_arena_with, score_population_with, GAParams};
+    use crate::testing::SOLVED_4;
+
+    const DIAGONAL: &str = "
+        let seen = [];
+        let duplicates = 0;
+        for i in 0..grid.len() {
+            let digit = grid[i][i];
+            if seen.contains(digit) { duplicates += 1; } else { seen.push(digit); }
+        }
+        duplicates
+    ";
+
+    #[test]
+    fn test_fitness_script() {
+        let script = FitnessScript::compile(DIAGONAL).unwrap();
+
+        // The diagonal of SOLVED_4 is 1 4 2 3.
+        assert_eq!(Ok(Fitness::new(0)), script.penalty(&SOLVED_4));
+        assert_eq!(Ok(Fitness::new(0)), script.penalty(&SOLVED_4));
+        assert_eq!(1, script.cached());
+
+        let mut board = SOLVED_4;
+        board.0[2].0[2] = 1;
+        board.0[3].0[3] = 1;
+        assert_eq!(Ok(Fitness::new(2)), script.penalty(&board));
+        assert_eq!(2, script.cached());
+    }
+
+    #[test]
+    fn test_script_errors() {
+        assert!(matches!(
+            FitnessScript::compile("let ="),
+            Err(ScriptError::Compile(_))
+        ));
+
+        let failing = FitnessScript::compile("grid[10][0]").unwrap();
+        assert!(matches!(
+            failing.penalty(&SOLVED_4),
+            Err(ScriptError::Run(_))
+        ));
+
+        let endless = FitnessScript::compile("loop {}").unwrap();
+        assert!(matches!(
+            endless.penalty(&SOLVED_4),
+            Err(ScriptError::Run(_))
+        ));
+
+        let negative = FitnessScript::compile("-1").unwrap();
+        assert_eq!(Err(ScriptError::Negative(-1)), negative.penalty(&SOLVED_4));
+    }
+
+    #[test]
+    fn test_scripted_scores() {
+        let params = GAParams::new(4, 0.5, 0.05, None).unwrap();
+        let mut population = vec![SOLVED_4; 4];
+        let mut scores = Vec::new();
+        assert!(score_population_with(&params, &SOLVED_4, &mut population, &mut scores).is_some());
+
+        let script = FitnessScript::compile("if grid[0][0] == 1 { 3 } else { 0 }").unwrap();
+        let params = params.with_script(Some(std::sync::Arc::new(script)));
+        assert_eq!(
+            None,
+            score_population_with(&params, &SOLVED_4, &mut population, &mut scores)
+        );
+        assert_eq!(vec![Fitness::new(3); 4], scores);
+
+        let mut arena = PopulationArena::from_boards(&population);
+        assert_eq!(
+            None,
+            score_arena_with(&params, &SOLVED_4, &mut arena, &mut scores)
+        );
+        assert_eq!(vec![Fitness::new(3); 4], scores);
+    }
+}
@@ -0,0 +1,207 @@
+#![warn(
+    clippy::all,
+    // clippy::restriction,
+    clippy::pedantic,
+    clippy::nursery,
+    clippy::cargo
+)]
+
+use super::cancel::CancellationToken;
+use super::genetics::{
+    generate_initial_population, run_generation, DefaultRng, GAParams, MAX_POPULATION,
+};
+use super::stats::PopulationStats;
+use super::sudoku::{Board, Fitness};
+use rand::{Rng, SeedableRng};
+use std::marker::PhantomData;
+
+/// What one step of a `Simulation` found.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct GenerationOutcome<const N: usize> {
+    /// The generation evaluated.
+    pub generation: u64,
+    /// A valid solution, if the generation held one.
+    pub solution: Option<Board<N>>,
+    /// The statistics of the generation evaluated, or `None` if its
+    /// population was empty.
+    pub stats: Option<PopulationStats>,
+}
+
+/// A run of the genetic algorithm on one puzzle, stepped one generation at
+/// a time by the caller rather than driven to the end by a solver.
+///
+/// Each step evaluates the current generation and, unless it held a
+/// solution, breeds the next one, drawing random numbers from `R`s as
+/// `run_simulation` does. As an iterator, it yields the outcome of each
+/// generation up to and including the first one holding a solution, and
+/// otherwise never ends unless it is cancelled: bound it with `take` or
+/// `take_while`, or give it a `CancellationToken`.
+#[derive(Debug, Clone)]
+pub struct Simulation<const N: usize, R = DefaultRng> {
+    params: GAParams,
+    base: Board<N>,
+    population: Vec<Board<N>>,
+    scores: Vec<Fitness>,
+    generation: u64,
+    solution: Option<Board<N>>,
+    cancellation: Option<CancellationToken>,
+    rng: PhantomData<fn() -> R>,
+}
+
+impl<const N: usize, R: Rng + SeedableRng> Simulation<N, R> {
+    /// Returns a simulation of `base` with a random initial population.
+    #[inline]
+    #[must_use]
+    pub fn new(params: GAParams, base: Board<N>) -> Self {
+        let population = generate_initial_population::<N, MAX_POPULATION, R>(&params, &base);
+
+        Self {
+            params,
+            base,
+            population,
+            scores: Vec::new(),
+            generation: 0,
+            solution: None,
+            cancellation: None,
+            rng: PhantomData,
+        }
+    }
+
+    /// Ends the simulation as an iterator once `cancellation` is cancelled,
+    /// leaving the generation it would have evaluated next in place.
+    #[inline]
+    #[must_use]
+    pub fn with_cancellation(mut self, cancellation: CancellationToken) -> Self {
+        self.cancellation = Some(cancellation);
+        self
+    }
+
+    /// Evaluates the current generation and, unless it holds a solution,
+    /// breeds the next one. Once a solution has been found, the simulation
+    /// stops: stepping it again returns the same outcome without evaluating
+    /// anything.
+    #[inline]
+    pub fn step(&mut self) -> GenerationOutcome<N> {
+        if let Some(solution) = self.solution {
+            return GenerationOutcome {
+                generation: self.generation,
+                solution: Some(solution),
+                stats: PopulationStats::of(self.generation, &self.population, &self.scores),
+            };
+        }
+
+        let result = run_generation::<N, MAX_POPULATION, R>(
+            &self.params,
+            self.generation,
+            &self.base,
+            &mut self.population,
+            &mut self.scores,
+        );
+        let outcome = GenerationOutcome {
+            generation: self.generation,
+            solution: result.solution,
+            stats: result.stats,
+        };
+        match result.solution {
+            Some(solution) => self.solution = Some(solution),
+            None => self.generation += 1,
+        }
+        outcome
+    }
+
+    #[inline]
+    #[must_use]
+    pub const fn params(&self) -> &GAParams {
+        &self.params
+    }
+
+    /// Returns the generation the next step evaluates, or the one that held
+    /// the solution once one has been found.
+    #[inline]
+    #[must_use]
+    pub const fn generation(&self) -> u64 {
+        self.generation
+    }
+
+    /// Returns the current population: the generation the next step
+    /// evaluates, or the one that held the solution once one has been found.
+    #[inline]
+    #[must_use]
+    pub fn population(&self) -> &[Board<N>] {
+        &self.population
+    }
+
+    /// Returns the solution, once a step has found one.
+    #[inline]
+    #[must_use]
+    pub const fn solution(&self) -> Option<&Board<N>> {
+        self.solution.as_ref()
+    }
+}
+
+impl<const N: usize, R: Rng + SeedableRng> Iterator for Simulation<N, R> {
+    type Item = GenerationOutcome<N>;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        let cancelled = self
+            .cancellation
+            .as_ref()
+            .is_some_and(CancellationToken::is_cancelled);
+        if self.solution.is_some() || cancelled {
+            return None;
+        }
+        Some(self.step())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::SOLVED_9;
+
+    #[test]
+    fn test_simulation() {
+        let mut base = SOLVED_9;
+        base.0[0].0.fill(0);
+        base.0[1].0.fill(0);
+        let params = GAParams::new(20, 0.5, 0.05, None).unwrap().with_seed(3);
+
+        // It steps the same generations as driving `run_generation` by hand.
+        let mut population =
+            generate_initial_population::<9, MAX_POPULATION, DefaultRng>(&params, &base);
+        let mut scores = Vec::new();
+        let mut simulation = Simulation::<9>::new(params.clone(), base);
+        for (generation, outcome) in (0..).zip(simulation.by_ref().take(3)) {
+            let result = run_generation::<9, MAX_POPULATION, DefaultRng>(
+                &params,
+                generation,
+                &base,
+                &mut population,
+                &mut scores,
+            );
+            assert_eq!(generation, outcome.generation);
+            assert_eq!(result.solution, outcome.solution);
+            assert_eq!(result.stats, outcome.stats);
+        }
+        assert_eq!(3, simulation.generation());
+        assert_eq!(population, simulation.population());
+
+        // It ends with the first generation holding a solution.
+        let mut base = SOLVED_9;
+        base.0[0].0[0] = 0;
+        let mut simulation = Simulation::<9>::new(params.clone(), base);
+        let outcomes: Vec<_> = simulation.by_ref().collect();
+        assert_eq!(1, outcomes.len());
+        assert_eq!(Some(SOLVED_9), outcomes[0].solution);
+        assert_eq!(Some(&SOLVED_9), simulation.solution());
+        assert_eq!(outcomes[0], simulation.step());
+        assert_eq!(None, simulation.next());
+
+        let token = CancellationToken::new();
+        let mut simulation = Simulation::<9>::new(params, base).with_cancellation(token.clone());
+        token.cancel();
+        assert_eq!(None, simulation.next());
+        assert_eq!(0, simulation.generation());
+    }
+}
@@ -0,0 +1,532 @@
+#![warn(
+    clippy::all,
+    // clippy::restriction,
+    clippy::pedantic,
+    clippy::nursery,
+    clippy::cargo
+)]
+
+use super::solver::Observer;
+use super::stats::GenerationStats;
+use super::sudoku::{Board, Fitness};
+use serde_json::json;
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::PathBuf;
+use std::str::FromStr;
+use std::time::{Duration, Instant};
+
+/// The telemetry of one generation.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct Sample {
+    /// The run the generation belongs to, counting from 0, for modes that
+    /// solve more than once.
+    pub run: u32,
+    pub stats: GenerationStats,
+    /// The time since the run's first generation.
+    pub elapsed: Duration,
+}
+
+impl Sample {
+    fn to_json(self) -> serde_json::Value {
+        json!({
+            "run": self.run,
+            "generation": self.stats.generation,
+            "best": self.stats.best,
+            "median": self.stats.median,
+            "worst": self.stats.worst,
+            "elapsed_secs": self.elapsed.as_secs_f64(),
+        })
+    }
+}
+
+/// A destination for generation telemetry.
+pub trait OutputSink {
+    /// Writes the telemetry of one generation.
+    ///
+    /// # Errors
+    ///
+    /// Fails if the sample cannot be written.
+    fn write(&mut self, sample: &Sample) -> std::io::Result<()>;
+
+    /// Writes out any buffered samples. Called at the end of each run.
+    ///
+    /// # Errors
+    ///
+    /// Fails if the samples cannot be written.
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+/// Writes samples as lines of text, in the style of `--stats`.
+#[derive(Debug)]
+pub struct TextSink<W: Write>(pub W);
+
+impl<W: Write> OutputSink for TextSink<W> {
+    #[inline]
+    fn write(&mut self, sample: &Sample) -> std::io::Result<()> {
+        writeln!(
+            self.0,
+            "Telemetry: Run: {} | Generation: {} | Best: {} | Median: {} | Worst: {} | Elapsed: {:?}",
+            sample.run,
+            sample.stats.generation,
+            sample.stats.best,
+            sample.stats.median,
+            sample.stats.worst,
+            sample.elapsed,
+        )
+    }
+
+    #[inline]
+    fn flush(&mut self) -> std::io::Result<()> {
+        self.0.flush()
+    }
+}
+
+/// Writes samples as comma separated values, after a header line.
+#[derive(Debug)]
+pub struct CsvSink<W: Write> {
+    writer: W,
+    header: bool,
+}
+
+impl<W: Write> CsvSink<W> {
+    #[inline]
+    pub const fn new(writer: W) -> Self {
+        Self {
+            writer,
+            header: false,
+        }
+    }
+}
+
+impl<W: Write> OutputSink for CsvSink<W> {
+    #[inline]
+    fn write(&mut self, sample: &Sample) -> std::io::Result<()> {
+        if !self.header {
+            writeln!(self.writer, "run,generation,best,median,worst,elapsed_secs")?;
+            self.header = true;
+        }
+
+        writeln!(
+            self.writer,
+            "{},{},{},{},{},{}",
+            sample.run,
+            sample.stats.generation,
+            sample.stats.best,
+            sample.stats.median,
+            sample.stats.worst,
+            sample.elapsed.as_secs_f64(),
+        )
+    }
+
+    #[inline]
+    fn flush(&mut self) -> std::io::Result<()> {
+        self.writer.flush()
+    }
+}
+
+/// Writes samples as JSON objects, one per line.
+#[derive(Debug)]
+pub struct JsonlSink<W: Write>(pub W);
+
+impl<W: Write> OutputSink for JsonlSink<W> {
+    #[inline]
+    fn write(&mut self, sample: &Sample) -> std::io::Result<()> {
+        writeln!(self.0, "{}", sample.to_json())
+    }
+
+    #[inline]
+    fn flush(&mut self) -> std::io::Result<()> {
+        self.0.flush()
+    }
+}
+
+/// Writes samples to a `telemetry` table in a SQLite-backed database, which
+/// may be a results database. Samples are inserted in one transaction per
+/// flush.
+#[cfg(feature = "db")]
+#[derive(Debug)]
+pub struct SqliteSink {
+    conn: rusqlite::Connection,
+    pending: Vec<Sample>,
+}
+
+#[cfg(feature = "db")]
+impl SqliteSink {
+    /// Opens the database at `path`, creating it and the table if needed.
+    ///
+    /// # Errors
+    ///
+    /// Fails if the database cannot be opened or the table cannot be
+    /// created.
+    #[inline]
+    pub fn open<P: AsRef<std::path::Path>>(path: P) -> rusqlite::Result<Self> {
+        let conn = rusqlite::Connection::open(path)?;
+        conn.execute_batch(
+            "CREATE TABLE IF NOT EXISTS telemetry (
+                run INTEGER NOT NULL,
+                generation INTEGER NOT NULL,
+                best INTEGER NOT NULL,
+                median INTEGER NOT NULL,
+                worst INTEGER NOT NULL,
+                elapsed_secs REAL NOT NULL
+            );",
+        )?;
+
+        Ok(Self {
+            conn,
+            pending: Vec::new(),
+        })
+    }
+}
+
+#[cfg(feature = "db")]
+impl OutputSink for SqliteSink {
+    #[inline]
+    fn write(&mut self, sample: &Sample) -> std::io::Result<()> {
+        self.pending.push(*sample);
+        Ok(())
+    }
+
+    #[inline]
+    fn flush(&mut self) -> std::io::Result<()> {
+        let insert = |conn: &mut rusqlite::Connection, samples: &[Sample]| {
+            let transaction = conn.transaction()?;
+            for sample in samples {
+                transaction.execute(
+                    "INSERT INTO telemetry (run, generation, best, median, worst, elapsed_secs) \
+                     VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+                    rusqlite::params![
+                        sample.run,
+                        sample.stats.generation,
+                        sample.stats.best.get(),
+                        sample.stats.median.get(),
+                        sample.stats.worst.get(),
+                        sample.elapsed.as_secs_f64(),
+                    ],
+                )?;
+            }
+            transaction.commit()
+        };
+
+        insert(&mut self.conn, &self.pending).map_err(std::io::Error::other)?;
+        self.pending.clear();
+        Ok(())
+    }
+}
+
+/// POSTs samples to a URL as a JSON array, in batches of up to
+/// `HttpSink::BATCH` and at the end of each run.
+#[cfg(feature = "http")]
+#[derive(Debug)]
+pub struct HttpSink {
+    url: String,
+    pending: Vec<serde_json::Value>,
+}
+
+#[cfg(feature = "http")]
+impl HttpSink {
+    /// The most samples sent in one request.
+    pub const BATCH: usize = 100;
+
+    #[inline]
+    #[must_use]
+    pub fn new(url: &str) -> Self {
+        Self {
+            url: url.to_owned(),
+            pending: Vec::with_capacity(Self::BATCH),
+        }
+    }
+}
+
+#[cfg(feature = "http")]
+impl OutputSink for HttpSink {
+    #[inline]
+    fn write(&mut self, sample: &Sample) -> std::io::Result<()> {
+        self.pending.push(sample.to_json());
+        if self.pending.len() == Self::BATCH {
+            self.flush()?;
+        }
+        Ok(())
+    }
+
+    #[inline]
+    fn flush(&mut self) -> std::io::Result<()> {
+        if self.pending.is_empty() {
+            return Ok(());
+        }
+
+        ureq::post(&self.url)
+            .set("Content-Type", "application/json")
+            .send_string(&serde_json::Value::from(std::mem::take(&mut self.pending)).to_string())
+            .map_err(std::io::Error::other)?;
+        Ok(())
+    }
+}
+
+/// Where to send telemetry, as given on the command line: `stdout`,
+/// `csv:PATH`, `jsonl:PATH`, and with the matching features `sqlite:PATH`
+/// and an `http://` or `https://` URL.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum SinkSpec {
+    Stdout,
+    Csv(PathBuf),
+    Jsonl(PathBuf),
+    #[cfg(feature = "db")]
+    Sqlite(PathBuf),
+    #[cfg(feature = "http")]
+    Http(String),
+}
+
+impl SinkSpec {
+    /// Opens the sink, creating or truncating its file if it has one.
+    ///
+    /// # Errors
+    ///
+    /// Fails if the file or database cannot be opened.
+    #[inline]
+    pub fn open(&self) -> std::io::Result<Box<dyn OutputSink>> {
+        Ok(match self {
+            Self::Stdout => Box::new(TextSink(std::io::stdout())),
+            Self::Csv(path) => Box::new(CsvSink::new(BufWriter::new(File::create(path)?))),
+            Self::Jsonl(path) => Box::new(JsonlSink(BufWriter::new(File::create(path)?))),
+            #[cfg(feature = "db")]
+            Self::Sqlite(path) => Box::new(SqliteSink::open(path).map_err(std::io::Error::other)?),
+            #[cfg(feature = "http")]
+            Self::Http(url) => Box::new(HttpSink::new(url)),
+        })
+    }
+}
+
+impl FromStr for SinkSpec {
+    type Err = String;
+
+    #[inline]
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s == "stdout" {
+            return Ok(Self::Stdout);
+        }
+
+        match s.split_once(':') {
+            Some(("csv", path)) if !path.is_empty() => Ok(Self::Csv(path.into())),
+            Some(("jsonl", path)) if !path.is_empty() => Ok(Self::Jsonl(path.into())),
+            #[cfg(feature = "db")]
+            Some(("sqlite", path)) if !path.is_empty() => Ok(Self::Sqlite(path.into())),
+            #[cfg(feature = "http")]
+            Some(("http", url)) if url.starts_with("//") => Ok(Self::Http(s.to_owned())),
+            #[cfg(feature = "http")]
+            Some(("https", url)) if url.starts_with("//") => Ok(Self::Http(s.to_owned())),
+            _ => {
+                let mut kinds = vec!["stdout", "csv:PATH", "jsonl:PATH"];
+                if cfg!(feature = "db") {
+                    kinds.push("sqlite:PATH");
+                }
+                if cfg!(feature = "http") {
+                    kinds.push("URL");
+                }
+                Err(format!(
+                    "expected one of {} but got {s:?}",
+                    kinds.join(", ")
+                ))
+            }
+        }
+    }
+}
+
+/// An observer that sends the telemetry of every generation to each of a
+/// set of sinks.
+///
+/// Observers cannot fail, so the first error a sink returns is kept and
+/// reported by `finish_run`; no more samples are written after it.
+/// Solvers with several populations write one sample per population.
+pub struct Telemetry {
+    sinks: Vec<Box<dyn OutputSink>>,
+    run: u32,
+    start: Option<Instant>,
+    error: Option<std::io::Error>,
+}
+
+impl Telemetry {
+    #[inline]
+    #[must_use]
+    pub fn new(sinks: Vec<Box<dyn OutputSink>>) -> Self {
+        Self {
+            sinks,
+            run: 0,
+            start: None,
+            error: None,
+        }
+    }
+
+    /// Flushes every sink at the end of a run, and moves on to the next.
+    ///
+    /// # Errors
+    ///
+    /// Fails with the first error any sink has returned since the last
+    /// call.
+    #[inline]
+    pub fn finish_run(&mut self) -> std::io::Result<()> {
+        self.run += 1;
+        self.start = None;
+
+        if let Some(err) = self.error.take() {
+            return Err(err);
+        }
+        self.sinks.iter_mut().try_for_each(|sink| sink.flush())
+    }
+}
+
+impl<const N: usize> Observer<N> for Telemetry {
+    #[inline]
+    fn on_generation(&mut self, generation: u64, _: &[Board<N>], scores: &[Fitness]) {
+        if self.error.is_some() {
+            return;
+        }
+        let Some(stats) = GenerationStats::of(generation, scores) else {
+            return;
+        };
+
+        let sample = Sample {
+            run: self.run,
+            stats,
+            elapsed: self.start.get_or_insert_with(Instant::now).elapsed(),
+        };
+        if let Err(err) = self
+            .sinks
+            .iter_mut()
+            .try_for_each(|sink| sink.write(&sample))
+        {
+            self.error = Some(err);
+        }
+    }
+
+    #[inline]
+    fn needs_population(&self) -> bool {
+        false
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    /// A writer whose contents outlive the sink that owns it.
+    #[derive(Clone, Default)]
+    struct Shared(Rc<RefCell<Vec<u8>>>);
+
+    impl Write for Shared {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.0.borrow_mut().write(buf)
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    impl Shared {
+        fn lines(&self) -> Vec<String> {
+            String::from_utf8(self.0.borrow().clone())
+                .unwrap()
+                .lines()
+                .map(str::to_owned)
+                .collect()
+        }
+    }
+
+    struct Failing;
+
+    impl OutputSink for Failing {
+        fn write(&mut self, _: &Sample) -> std::io::Result<()> {
+            Err(std::io::Error::other("full"))
+        }
+    }
+
+    #[test]
+    fn test_telemetry() {
+        let (csv, jsonl) = (Shared::default(), Shared::default());
+        let mut telemetry = Telemetry::new(vec![
+            Box::new(CsvSink::new(csv.clone())),
+            Box::new(JsonlSink(jsonl.clone())),
+        ]);
+
+        Observer::<4>::on_generation(&mut telemetry, 0, &[], &[3, 1, 2].map(Fitness::new));
+        Observer::<4>::on_generation(&mut telemetry, 1, &[], &[]);
+        telemetry.finish_run().unwrap();
+        Observer::<4>::on_generation(&mut telemetry, 0, &[], &[Fitness::new(0)]);
+        telemetry.finish_run().unwrap();
+
+        let csv = csv.lines();
+        assert_eq!(3, csv.len());
+        assert_eq!("run,generation,best,median,worst,elapsed_secs", csv[0]);
+        assert!(csv[1].starts_with("0,0,1,2,3,"));
+        assert!(csv[2].starts_with("1,0,0,0,0,"));
+
+        let jsonl = jsonl.lines();
+        assert_eq!(2, jsonl.len());
+        let sample: serde_json::Value = serde_json::from_str(&jsonl[1]).unwrap();
+        assert_eq!(Some(1), sample["run"].as_u64());
+        assert_eq!(Some(0), sample["best"].as_u64());
+    }
+
+    #[test]
+    fn test_telemetry_error() {
+        let text = Shared::default();
+        let mut telemetry =
+            Telemetry::new(vec![Box::new(Failing), Box::new(TextSink(text.clone()))]);
+
+        Observer::<4>::on_generation(&mut telemetry, 0, &[], &[Fitness::new(1)]);
+        Observer::<4>::on_generation(&mut telemetry, 1, &[], &[Fitness::new(1)]);
+        assert_eq!("full", telemetry.finish_run().unwrap_err().to_string());
+        assert!(text.lines().is_empty());
+
+        let mut telemetry = Telemetry::new(vec![Box::new(TextSink(text.clone()))]);
+        Observer::<4>::on_generation(&mut telemetry, 7, &[], &[4, 2].map(Fitness::new));
+        assert!(telemetry.finish_run().is_ok());
+        assert!(text.lines()[0].starts_with("Telemetry: Run: 0 | Generation: 7 | Best: 2"));
+    }
+
+    #[test]
+    fn test_sink_spec() {
+        assert_eq!(Ok(SinkSpec::Stdout), "stdout".parse());
+        assert_eq!(
+            Ok(SinkSpec::Csv(PathBuf::from("run.csv"))),
+            "csv:run.csv".parse()
+        );
+        assert_eq!(
+            Ok(SinkSpec::Jsonl(PathBuf::from("a:b.jsonl"))),
+            "jsonl:a:b.jsonl".parse()
+        );
+        assert!("csv:".parse::<SinkSpec>().is_err());
+        assert!("xml:run.xml".parse::<SinkSpec>().is_err());
+    }
+
+    #[cfg(feature = "db")]
+    #[test]
+    fn test_sqlite_sink() {
+        let mut sink = SqliteSink::open(":memory:").unwrap();
+        let stats = GenerationStats::of(3, &[1, 2].map(Fitness::new)).unwrap();
+        sink.write(&Sample {
+            run: 0,
+            stats,
+            elapsed: Duration::from_millis(5),
+        })
+        .unwrap();
+        sink.flush().unwrap();
+
+        let count: i64 = sink
+            .conn
+            .query_row(
+                "SELECT COUNT(*) FROM telemetry WHERE generation = 3",
+                [],
+                |row| row.get(0),
+            )
+            .unwrap();
+        assert_eq!(1, count);
+        assert!(sink.pending.is_empty());
+    }
+}
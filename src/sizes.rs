@@ -0,0 +1,144 @@
+#![warn(
+    clippy::all,
+    // clippy::restriction,
+    clippy::pedantic,
+    clippy::nursery,
+    clippy::cargo
+)]
+
+use super::formats;
+
+/// A computation generic over the board size N, so that it can be run for a
+/// size only known at runtime, such as that of a board read from a file.
+pub trait SizedDriver {
+    type Output;
+
+    /// Runs the computation for boards of size N.
+    fn run<const N: usize>(self) -> Self::Output;
+}
+
+/// A list of board sizes, with every [`SizedDriver`] instantiated for each.
+///
+/// Implement this with [`board_sizes!`] rather than by hand, so that the
+/// sizes are listed once.
+pub trait BoardSizes {
+    /// The sizes, in the order they are tried by [`BoardSizes::detect`].
+    const SIZES: &'static [usize];
+
+    /// Runs `driver` for boards of size `size`, or returns `None` if `size`
+    /// is not one of the sizes.
+    fn dispatch<D: SizedDriver>(size: usize, driver: D) -> Option<D::Output>;
+
+    /// Returns the first of the sizes that `text` parses as boards of, in
+    /// any of the [`formats`], or `None` if it parses as none of them.
+    #[inline]
+    #[must_use]
+    fn detect(text: &str) -> Option<usize> {
+        Self::SIZES
+            .iter()
+            .copied()
+            .find(|&size| Self::dispatch(size, Parses(text)) == Some(true))
+    }
+}
+
+/// Whether text parses as boards of the size it is run for.
+struct Parses<'a>(&'a str);
+
+impl SizedDriver for Parses<'_> {
+    type Output = bool;
+
+    #[inline]
+    fn run<const N: usize>(self) -> bool {
+        formats::parse::<N>(self.0).is_ok()
+    }
+}
+
+/// Declares a unit struct implementing [`BoardSizes`] for the given sizes,
+/// each of which must have box dimensions (see
+/// [`box_dims`](crate::sudoku::box_dims)).
+///
+/// ```
+/// use genetic_sudoku::sizes::{BoardSizes, SizedDriver};
+///
+/// genetic_sudoku::board_sizes! {
+///     /// Every size up to 36 by 36, including 6 by 6 with 2 by 3 boxes.
+///     struct Sizes = [4, 6, 9, 16, 25, 36];
+/// }
+///
+/// struct Cells;
+///
+/// impl SizedDriver for Cells {
+///     type Output = usize;
+///
+///     fn run<const N: usize>(self) -> usize {
+///         N * N
+///     }
+/// }
+///
+/// assert_eq!(Some(1296), Sizes::dispatch(36, Cells));
+/// assert_eq!(None, Sizes::dispatch(49, Cells));
+/// assert_eq!(Some(4), Sizes::detect("1234\n3412\n2143\n4321"));
+/// assert_eq!(Some(6), Sizes::detect("123456\n456123\n......\n......\n......\n......"));
+/// ```
+#[macro_export]
+macro_rules! board_sizes {
+    ($(#[$attr:meta])* $vis:vis struct $name:ident = [$($size:literal),+ $(,)?];) => {
+        $(#[$attr])*
+        #[derive(Debug, Copy, Clone)]
+        $vis struct $name;
+
+        impl $crate::sizes::BoardSizes for $name {
+            const SIZES: &'static [usize] = &[$($size),+];
+
+            #[inline]
+            fn dispatch<D: $crate::sizes::SizedDriver>(
+                size: usize,
+                driver: D,
+            ) -> ::core::option::Option<D::Output> {
+                match size {
+                    $($size => ::core::option::Option::Some(driver.run::<$size>()),)+
+                    _ => ::core::option::Option::None,
+                }
+            }
+        }
+    };
+}
+
+board_sizes! {
+    /// The board sizes the library was written for, from 4 by 4 to 25 by 25.
+    pub struct Classic = [4, 9, 16, 25];
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sudoku::Board;
+    use crate::testing::SOLVED_9;
+
+    /// The number of cells of a board of the size it is run for.
+    struct Cells;
+
+    impl SizedDriver for Cells {
+        type Output = usize;
+
+        fn run<const N: usize>(self) -> usize {
+            Board::<N>::layout().size * N
+        }
+    }
+
+    #[test]
+    fn test_board_sizes() {
+        assert_eq!(&[4, 9, 16, 25], Classic::SIZES);
+        assert_eq!(Some(16), Classic::dispatch(4, Cells));
+        assert_eq!(Some(625), Classic::dispatch(25, Cells));
+        assert_eq!(None, Classic::dispatch(36, Cells));
+
+        assert_eq!(Some(9), Classic::detect(&SOLVED_9.to_string()));
+        assert_eq!(Some(4), Classic::detect("1..4\n.41.\n2..3\n43..\n"));
+        let line_16 = "0".repeat(256);
+        assert_eq!(Some(16), Classic::detect(&line_16));
+        assert_eq!(Some(16), Classic::detect(&format!("{line_16}\n{line_16}")));
+        assert_eq!(None, Classic::detect(&"0".repeat(36 * 36)));
+        assert_eq!(None, Classic::detect("123\n231\n312"));
+    }
+}
@@ -0,0 +1,2271 @@
+#![warn(
+    clippy::all,
+    // clippy::restriction,
+    clippy::pedantic,
+    clippy::nursery,
+    clippy::cargo
+)]
+
+pub mod backtrack;
+
+use super::arena::PopulationArena;
+use super::audit::AuditSample;
+use super::errors::{ParamError, StoppedEarly};
+use super::genetics::{
+    audit_children, dedup_population, fill_initial_population, generate_initial_population,
+    initial_population, next_generation, reject_tabu, repair_arena, repair_population,
+    restart_keeping, score_arena_with, score_population_chunked, score_population_with, Bursts,
+    DefaultRng, GAParams, Lineage, OperatorStats, MAX_POPULATION,
+};
+use super::islands::migrate;
+use super::portfolio::allocate_threads;
+use super::profile::{Phase, PhaseTimer, PhaseTimes};
+use super::restarts::{RestartMetrics, RestartTuner, StallCounter};
+use super::stats::PopulationStats;
+use super::sudoku::{Board, Fitness};
+use super::tabu::TabuArchive;
+use super::tempering::evolve_replicas;
+use super::termination::Termination;
+use backtrack::Handoff;
+use rayon::prelude::*;
+use rayon::{ThreadPool, ThreadPoolBuilder};
+use std::ops::ControlFlow;
+use std::time::{Duration, Instant};
+
+/// Limits on how long a solver may run.
+#[derive(Debug, Copy, Clone, Default, Eq, PartialEq)]
+pub struct Budget {
+    /// The maximum number of generations to evaluate.
+    pub generations: Option<u64>,
+    /// The maximum wall-clock time to run for.
+    pub duration: Option<Duration>,
+    /// A fitness score good enough to stop at before finding a solution.
+    pub target: Option<Fitness>,
+}
+
+impl Budget {
+    /// Returns a budget with no limits.
+    #[inline]
+    #[must_use]
+    pub const fn unlimited() -> Self {
+        Self {
+            generations: None,
+            duration: None,
+            target: None,
+        }
+    }
+
+    /// Returns true if a run that has evaluated `generations` generations
+    /// over `elapsed` time has used up this budget.
+    #[inline]
+    #[must_use]
+    pub fn is_exhausted(&self, generations: u64, elapsed: Duration) -> bool {
+        self.generations.is_some_and(|limit| generations >= limit)
+            || self.duration.is_some_and(|limit| elapsed >= limit)
+    }
+
+    /// Returns true if `best`, the best fitness score of a run so far, is
+    /// as good as this budget's target.
+    #[inline]
+    #[must_use]
+    pub fn is_reached(&self, best: Fitness) -> bool {
+        self.target.is_some_and(|target| best <= target)
+    }
+
+    /// Returns the fraction of this budget that a run which has evaluated
+    /// `generations` generations over `elapsed` time has used, from 0.0 to
+    /// 1.0, or `None` if the budget has no limits. With both limits, the
+    /// larger fraction counts.
+    #[inline]
+    #[must_use]
+    pub fn used(&self, generations: u64, elapsed: Duration) -> Option<f64> {
+        #[allow(clippy::cast_precision_loss)]
+        let by_generations = self
+            .generations
+            .map(|limit| generations as f64 / limit.max(1) as f64);
+        let by_duration = self.duration.map(|limit| {
+            if limit.is_zero() {
+                1.0
+            } else {
+                elapsed.as_secs_f64() / limit.as_secs_f64()
+            }
+        });
+
+        match (by_generations, by_duration) {
+            (Some(a), Some(b)) => Some(a.max(b).min(1.0)),
+            (used, None) | (None, used) => used.map(|used| used.min(1.0)),
+        }
+    }
+}
+
+/// How far a run has got, for progress bars.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Progress {
+    /// The last generation evaluated.
+    pub generation: u64,
+    /// How long the run has taken so far.
+    pub elapsed: Duration,
+    /// The fraction of the budget used, or `None` if it has no limits.
+    pub budget_used: Option<f64>,
+    /// The best fitness score so far, if any generation was evaluated.
+    pub best: Option<Fitness>,
+    /// The best fitness score so far, normalized from 0.0 for the best
+    /// score of the first generation to 1.0 for a solution.
+    pub fitness: f64,
+}
+
+/// Backend-specific progress, reported alongside the fitness scores of each
+/// generation.
+#[derive(Debug, Clone, PartialEq)]
+#[non_exhaustive]
+pub enum Metrics {
+    /// Parallel tempering, one entry per replica, by increasing temperature.
+    Tempering(Vec<ReplicaMetrics>),
+    /// The genetic algorithm's operator statistics over the run so far.
+    Operators(OperatorStats),
+    /// The genetic algorithm's automatic restarts so far, and why.
+    Restarts(RestartMetrics),
+    /// The time the genetic algorithm has spent in each phase so far, if
+    /// it is profiled.
+    Phases(PhaseTimes),
+    /// A portfolio, one entry per member, in the order they were given.
+    Portfolio(Vec<MemberMetrics>),
+}
+
+/// The state of one parallel tempering replica.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct ReplicaMetrics {
+    /// The replica's mutation rate.
+    pub temperature: f32,
+    /// The best fitness score of the replica's current population.
+    pub energy: Fitness,
+    /// The fraction of elites migrated into the replica so far that were
+    /// fitter than the candidate they replaced, or `None` before the first
+    /// migration.
+    pub acceptance: Option<f64>,
+}
+
+/// The state of one portfolio member.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct MemberMetrics {
+    /// The worker threads the member runs on until the next reallocation.
+    pub threads: usize,
+    /// The best fitness score the member has found so far.
+    pub best: Fitness,
+}
+
+/// Receives progress from a running solver.
+pub trait Observer<const N: usize> {
+    /// Called after each generation has been scored, before the next one is
+    /// bred. `scores` runs parallel to `population`.
+    ///
+    /// Solvers with several populations call this once per population.
+    fn on_generation(&mut self, generation: u64, population: &[Board<N>], scores: &[Fitness]) {
+        let _ = (generation, population, scores);
+    }
+
+    /// Returns false if `on_generation` never looks at the population, in
+    /// which case solvers that do not keep a `Vec<Board<N>>` may pass an
+    /// empty one instead of copying their population out.
+    fn needs_population(&self) -> bool {
+        true
+    }
+
+    /// Called once per generation, after `on_generation`, by solvers that
+    /// have metrics beyond fitness scores.
+    fn on_metrics(&mut self, generation: u64, metrics: &Metrics) {
+        let _ = (generation, metrics);
+    }
+
+    /// Called after `on_generation` with the run's progress. Solvers with
+    /// several populations call this once per population.
+    fn on_progress(&mut self, progress: &Progress) {
+        let _ = progress;
+    }
+
+    /// Called once per generation, before the next one is bred, with the
+    /// children sampled for an audit, by solvers that audit their operators.
+    fn on_audit(&mut self, generation: u64, samples: &[AuditSample<N>]) {
+        let _ = (generation, samples);
+    }
+
+    /// Called once per generation, after `on_generation`, with how each
+    /// child in the population was bred, by solvers that track lineage. The
+    /// children come first in the population, followed by the candidates
+    /// carried over, which have no lineage. A generation that was not bred,
+    /// such as the first or one after a restart, has no lineage at all.
+    fn on_lineage(&mut self, generation: u64, lineage: &[Lineage]) {
+        let _ = (generation, lineage);
+    }
+
+    /// Called at each checkpoint between the chunks of a generation scored
+    /// in chunks, with the number of its `population` candidates scored so
+    /// far, by solvers that score in chunks.
+    fn on_chunk(&mut self, generation: u64, scored: usize, population: usize) {
+        let _ = (generation, scored, population);
+    }
+
+    /// Returns true to stop the run before its next generation, which then
+    /// ends as `Termination::Cancelled`. Checked once per generation, and at
+    /// every checkpoint of a generation scored in chunks.
+    fn is_cancelled(&self) -> bool {
+        false
+    }
+}
+
+/// An observer that ignores all progress.
+impl<const N: usize> Observer<N> for () {
+    #[inline]
+    fn needs_population(&self) -> bool {
+        false
+    }
+}
+
+impl<const N: usize, O: Observer<N> + ?Sized> Observer<N> for &mut O {
+    #[inline]
+    fn on_generation(&mut self, generation: u64, population: &[Board<N>], scores: &[Fitness]) {
+        (**self).on_generation(generation, population, scores);
+    }
+
+    #[inline]
+    fn needs_population(&self) -> bool {
+        (**self).needs_population()
+    }
+
+    #[inline]
+    fn on_metrics(&mut self, generation: u64, metrics: &Metrics) {
+        (**self).on_metrics(generation, metrics);
+    }
+
+    #[inline]
+    fn on_progress(&mut self, progress: &Progress) {
+        (**self).on_progress(progress);
+    }
+
+    #[inline]
+    fn on_audit(&mut self, generation: u64, samples: &[AuditSample<N>]) {
+        (**self).on_audit(generation, samples);
+    }
+
+    #[inline]
+    fn on_lineage(&mut self, generation: u64, lineage: &[Lineage]) {
+        (**self).on_lineage(generation, lineage);
+    }
+
+    #[inline]
+    fn on_chunk(&mut self, generation: u64, scored: usize, population: usize) {
+        (**self).on_chunk(generation, scored, population);
+    }
+
+    #[inline]
+    fn is_cancelled(&self) -> bool {
+        (**self).is_cancelled()
+    }
+}
+
+/// An observer that may be switched off.
+impl<const N: usize, O: Observer<N>> Observer<N> for Option<O> {
+    #[inline]
+    fn on_generation(&mut self, generation: u64, population: &[Board<N>], scores: &[Fitness]) {
+        if let Some(observer) = self {
+            observer.on_generation(generation, population, scores);
+        }
+    }
+
+    #[inline]
+    fn needs_population(&self) -> bool {
+        self.as_ref().is_some_and(Observer::needs_population)
+    }
+
+    #[inline]
+    fn on_metrics(&mut self, generation: u64, metrics: &Metrics) {
+        if let Some(observer) = self {
+            observer.on_metrics(generation, metrics);
+        }
+    }
+
+    #[inline]
+    fn on_progress(&mut self, progress: &Progress) {
+        if let Some(observer) = self {
+            observer.on_progress(progress);
+        }
+    }
+
+    #[inline]
+    fn on_audit(&mut self, generation: u64, samples: &[AuditSample<N>]) {
+        if let Some(observer) = self {
+            observer.on_audit(generation, samples);
+        }
+    }
+
+    #[inline]
+    fn on_lineage(&mut self, generation: u64, lineage: &[Lineage]) {
+        if let Some(observer) = self {
+            observer.on_lineage(generation, lineage);
+        }
+    }
+
+    #[inline]
+    fn on_chunk(&mut self, generation: u64, scored: usize, population: usize) {
+        if let Some(observer) = self {
+            observer.on_chunk(generation, scored, population);
+        }
+    }
+
+    #[inline]
+    fn is_cancelled(&self) -> bool {
+        self.as_ref().is_some_and(Observer::is_cancelled)
+    }
+}
+
+/// Two observers that both receive all progress, in order.
+impl<const N: usize, A: Observer<N>, B: Observer<N>> Observer<N> for (A, B) {
+    #[inline]
+    fn on_generation(&mut self, generation: u64, population: &[Board<N>], scores: &[Fitness]) {
+        self.0.on_generation(generation, population, scores);
+        self.1.on_generation(generation, population, scores);
+    }
+
+    #[inline]
+    fn needs_population(&self) -> bool {
+        self.0.needs_population() || self.1.needs_population()
+    }
+
+    #[inline]
+    fn on_metrics(&mut self, generation: u64, metrics: &Metrics) {
+        self.0.on_metrics(generation, metrics);
+        self.1.on_metrics(generation, metrics);
+    }
+
+    #[inline]
+    fn on_progress(&mut self, progress: &Progress) {
+        self.0.on_progress(progress);
+        self.1.on_progress(progress);
+    }
+
+    #[inline]
+    fn on_audit(&mut self, generation: u64, samples: &[AuditSample<N>]) {
+        self.0.on_audit(generation, samples);
+        self.1.on_audit(generation, samples);
+    }
+
+    #[inline]
+    fn on_lineage(&mut self, generation: u64, lineage: &[Lineage]) {
+        self.0.on_lineage(generation, lineage);
+        self.1.on_lineage(generation, lineage);
+    }
+
+    #[inline]
+    fn on_chunk(&mut self, generation: u64, scored: usize, population: usize) {
+        self.0.on_chunk(generation, scored, population);
+        self.1.on_chunk(generation, scored, population);
+    }
+
+    #[inline]
+    fn is_cancelled(&self) -> bool {
+        self.0.is_cancelled() || self.1.is_cancelled()
+    }
+}
+
+/// An observer that calls a closure with the statistics and the fittest
+/// candidate of each generation, for callers that need no more than that.
+///
+/// Solvers with several populations call it once per population.
+#[derive(Debug, Copy, Clone)]
+pub struct OnGeneration<F>(pub F);
+
+impl<const N: usize, F: FnMut(&PopulationStats, &Board<N>)> Observer<N> for OnGeneration<F> {
+    #[inline]
+    fn on_generation(&mut self, generation: u64, population: &[Board<N>], scores: &[Fitness]) {
+        let Some(stats) = PopulationStats::of(generation, population, scores) else {
+            return;
+        };
+        let fittest = scores.iter().enumerate().min_by_key(|(_, score)| **score);
+        if let Some(best) = fittest.and_then(|(i, _)| population.get(i)) {
+            (self.0)(&stats, best);
+        }
+    }
+}
+
+/// The result of a solver run.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Outcome<const N: usize> {
+    /// Why the run ended.
+    pub termination: Termination,
+    /// The best board found and its fitness score, if any generation was
+    /// evaluated. This is the solution when the run was solved.
+    pub best: Option<(Board<N>, Fitness)>,
+    /// The last generation evaluated.
+    pub generation: u64,
+    /// How long the run took.
+    pub duration: Duration,
+}
+
+impl<const N: usize> Outcome<N> {
+    /// Returns the solution, if one was found.
+    #[inline]
+    #[must_use]
+    pub const fn solution(&self) -> Option<&Board<N>> {
+        match (self.termination, &self.best) {
+            (Termination::Solved, Some((board, _))) => Some(board),
+            _ => None,
+        }
+    }
+
+    /// Returns the solution, or a `StoppedEarly` error carrying the best
+    /// board found if the run ended without one.
+    ///
+    /// # Errors
+    ///
+    /// Returns `StoppedEarly` if the run was not solved.
+    #[inline]
+    pub const fn into_result(self) -> Result<Board<N>, StoppedEarly<N>> {
+        match (self.termination, self.best) {
+            (Termination::Solved, Some((board, _))) => Ok(board),
+            (termination, best) => Err(StoppedEarly {
+                termination,
+                best,
+                generation: self.generation,
+            }),
+        }
+    }
+}
+
+/// A Sudoku solving backend.
+pub trait Solver<const N: usize> {
+    /// Solves `base` within `budget`, reporting progress to `observer`.
+    fn solve(&self, base: &Board<N>, budget: Budget, observer: &mut dyn Observer<N>) -> Outcome<N>;
+}
+
+/// Tracks the best candidate seen over a run, and the budget it has used.
+/// Every backend accounts for its budget through this.
+struct Tracker<const N: usize> {
+    start: Instant,
+    budget: Budget,
+    best: Option<(Board<N>, Fitness)>,
+    /// The best score of the first generation, which progress is
+    /// normalized against.
+    initial: Option<Fitness>,
+}
+
+impl<const N: usize> Tracker<N> {
+    fn new(budget: Budget) -> Self {
+        Self {
+            start: Instant::now(),
+            budget,
+            best: None,
+            initial: None,
+        }
+    }
+
+    /// Returns why the run should stop before evaluating `generation`, if
+    /// its best candidate has reached the budget's target or it has used up
+    /// the budget.
+    fn stopped(&self, generation: u64) -> Option<Termination> {
+        if self
+            .best
+            .is_some_and(|(_, best)| self.budget.is_reached(best))
+        {
+            Some(Termination::TargetReached)
+        } else if self.budget.is_exhausted(generation, self.start.elapsed()) {
+            Some(Termination::BudgetExhausted)
+        } else {
+            None
+        }
+    }
+
+    /// Records the generation just scored, and reports the run's progress.
+    /// `solved` is the index of a solution, which is taken as the best
+    /// candidate even if other scores tie it.
+    fn update(
+        &mut self,
+        observer: &mut dyn Observer<N>,
+        generation: u64,
+        population: &[Board<N>],
+        scores: &[Fitness],
+        solved: Option<usize>,
+    ) {
+        self.update_with(observer, generation, |i| population[i], scores, solved);
+    }
+
+    fn update_with(
+        &mut self,
+        observer: &mut dyn Observer<N>,
+        generation: u64,
+        board: impl Fn(usize) -> Board<N>,
+        scores: &[Fitness],
+        solved: Option<usize>,
+    ) {
+        let fittest = solved.map_or_else(
+            || scores.iter().enumerate().min_by_key(|(_, score)| **score),
+            |i| Some((i, &scores[i])),
+        );
+
+        if let Some((i, &score)) = fittest {
+            if solved.is_some() || self.best.is_none_or(|(_, best)| score < best) {
+                self.best = Some((board(i), score));
+            }
+            self.initial.get_or_insert(score);
+        }
+        observer.on_progress(&self.progress(generation));
+    }
+
+    fn progress(&self, generation: u64) -> Progress {
+        let elapsed = self.start.elapsed();
+        let best = self.best.map(|(_, score)| score);
+        let fitness = match (best, self.initial) {
+            (Some(best), _) if best.is_solution() => 1.0,
+            (Some(best), Some(initial)) => {
+                1.0 - f64::from(best.get()) / f64::from(initial.get().max(1))
+            }
+            _ => 0.0,
+        };
+
+        Progress {
+            generation,
+            elapsed,
+            budget_used: self.budget.used(generation, elapsed),
+            best,
+            fitness,
+        }
+    }
+
+    fn finish(self, termination: Termination, generation: u64) -> Outcome<N> {
+        Outcome {
+            termination,
+            best: self.best,
+            generation,
+            duration: self.start.elapsed(),
+        }
+    }
+}
+
+/// The genetic algorithm.
+pub struct GeneticSolver {
+    params: GAParams,
+}
+
+impl GeneticSolver {
+    #[inline]
+    #[must_use]
+    pub const fn new(params: GAParams) -> Self {
+        Self { params }
+    }
+
+    /// Returns the GA parameters.
+    #[inline]
+    #[must_use]
+    pub const fn params(&self) -> &GAParams {
+        &self.params
+    }
+
+    fn solve_compact<const N: usize>(
+        &self,
+        base: &Board<N>,
+        budget: Budget,
+        observer: &mut dyn Observer<N>,
+    ) -> Outcome<N> {
+        let mut tracker = Tracker::new(budget);
+        let initial =
+            generate_initial_population::<N, MAX_POPULATION, DefaultRng>(&self.params, base);
+        let mut population = PopulationArena::from_boards(&initial);
+        let mut scores = Vec::with_capacity(MAX_POPULATION);
+        let mut lineage = Vec::new();
+        let mut operators = OperatorStats::default();
+        let mut restarts = self.params.auto_restart().map(RestartTuner::new);
+        let mut stalls = self.params.stall_generations().map(StallCounter::new);
+        let mut bursts = self.params.hypermutation().map(Bursts::new);
+        let mut handoff = self.params.hybrid().map(Handoff::new);
+        let mut tabu = TabuArchive::new(self.params.tabu());
+        let mut cutoff = None;
+        let mut timer = PhaseTimer::new(self.params.profiling());
+        let mut generation = 0;
+
+        loop {
+            if let Some(termination) = tracker.stopped(generation) {
+                return tracker.finish(termination, generation);
+            }
+            if observer.is_cancelled() {
+                return tracker.finish(Termination::Cancelled, generation);
+            }
+
+            if self.params.column_repair() > 0 {
+                repair_arena(base, &mut population, self.params.column_repair());
+            }
+            dedup_population(&self.params, base, generation, &mut population);
+            timer.lap(Phase::Bookkeeping);
+            let mut solved = score_arena_with(&self.params, base, &mut population, &mut scores);
+            if let (None, Some(handoff)) = (solved, &mut handoff) {
+                solved = hand_off_arena(handoff, &self.params, base, &mut population, &mut scores);
+            }
+            timer.lap(Phase::Scoring);
+            if observer.needs_population() {
+                observer.on_generation(generation, &population.to_boards(), &scores);
+            } else {
+                observer.on_generation(generation, &[], &scores);
+            }
+            tracker.update_with(
+                observer,
+                generation,
+                |i| population.board(i),
+                &scores,
+                solved,
+            );
+            report_operators(observer, generation, &mut operators, &lineage, &scores);
+            report_phases(observer, generation, &timer);
+
+            if solved.is_some() {
+                return tracker.finish(Termination::Solved, generation);
+            }
+            tabu.record(base, |i| population.board(i), &scores);
+            if restart_arena(
+                &self.params,
+                base,
+                generation,
+                observer,
+                (&mut restarts, &mut stalls),
+                &mut population,
+                &scores,
+            ) {
+                lineage.clear();
+                cutoff = None;
+                generation += 1;
+                continue;
+            }
+
+            let burst = bursts
+                .as_mut()
+                .and_then(|bursts| bursts.record(&self.params, &scores));
+            let breeding = burst.as_ref().unwrap_or(&self.params);
+            report_audit(
+                observer,
+                breeding,
+                base,
+                generation,
+                population.len(),
+                |i| population.board(i),
+            );
+            cutoff = breed_arena(
+                breeding,
+                base,
+                generation,
+                &mut population,
+                (&scores, cutoff),
+                (&mut lineage, &tabu),
+                &mut timer,
+            );
+            generation += 1;
+        }
+    }
+}
+
+impl<const N: usize> Solver<N> for GeneticSolver {
+    #[inline]
+    fn solve(&self, base: &Board<N>, budget: Budget, observer: &mut dyn Observer<N>) -> Outcome<N> {
+        if self.params.compact() {
+            return self.solve_compact(base, budget, observer);
+        }
+
+        let mut population =
+            generate_initial_population::<N, MAX_POPULATION, DefaultRng>(&self.params, base);
+        let mut scores = Vec::with_capacity(MAX_POPULATION);
+
+        evolve(
+            &self.params,
+            base,
+            budget,
+            observer,
+            &mut population,
+            &mut scores,
+        )
+    }
+}
+
+/// Runs the genetic algorithm on `base` from the given initial population.
+///
+/// `population` and `scores` are left holding the last generation, so that
+/// their allocations can be reused by the next run.
+pub(crate) fn evolve<const N: usize>(
+    params: &GAParams,
+    base: &Board<N>,
+    budget: Budget,
+    observer: &mut dyn Observer<N>,
+    population: &mut Vec<Board<N>>,
+    scores: &mut Vec<Fitness>,
+) -> Outcome<N> {
+    let mut tracker = Tracker::new(budget);
+    let mut lineage = Vec::new();
+    let mut operators = OperatorStats::default();
+    let mut restarts = params.auto_restart().map(RestartTuner::new);
+    let mut stalls = params.stall_generations().map(StallCounter::new);
+    let mut bursts = params.hypermutation().map(Bursts::new);
+    let mut handoff = params.hybrid().map(Handoff::new);
+    let mut tabu = TabuArchive::new(params.tabu());
+    let mut cutoff = None;
+    let mut timer = PhaseTimer::new(params.profiling());
+    let mut generation = 0;
+
+    loop {
+        if let Some(termination) = tracker.stopped(generation) {
+            return tracker.finish(termination, generation);
+        }
+        if observer.is_cancelled() {
+            return tracker.finish(Termination::Cancelled, generation);
+        }
+
+        if params.column_repair() > 0 {
+            repair_population(base, population, params.column_repair());
+        }
+        dedup_population(params, base, generation, population);
+        timer.lap(Phase::Bookkeeping);
+        let scoring = score_checked(
+            params,
+            base,
+            generation,
+            population,
+            scores,
+            (&tracker, &mut *observer),
+        );
+        let mut solved = match scoring {
+            ControlFlow::Continue(solved) => solved,
+            ControlFlow::Break(termination) => return tracker.finish(termination, generation),
+        };
+        if let (None, Some(handoff)) = (solved, &mut handoff) {
+            solved = hand_off(handoff, params, base, population, scores);
+        }
+        timer.lap(Phase::Scoring);
+        observer.on_generation(generation, population, scores);
+        tracker.update(observer, generation, population, scores, solved);
+        report_operators(observer, generation, &mut operators, &lineage, scores);
+        report_phases(observer, generation, &timer);
+
+        if solved.is_some() {
+            return tracker.finish(Termination::Solved, generation);
+        }
+        tabu.record(base, |i| population[i], scores);
+        if let Some(tuner) = &mut restarts {
+            if decide_restart(tuner, observer, generation, population, scores) {
+                fill_initial_population::<N, DefaultRng>(params, base, generation, population);
+                lineage.clear();
+                cutoff = None;
+                generation += 1;
+                continue;
+            }
+        }
+        if let Some(counter) = &mut stalls {
+            if counter.stalled(scores) {
+                restart_keeping(params, base, generation, population, scores);
+                lineage.clear();
+                cutoff = None;
+                generation += 1;
+                continue;
+            }
+        }
+
+        let burst = bursts
+            .as_mut()
+            .and_then(|bursts| bursts.record(params, scores));
+        let breeding = burst.as_ref().unwrap_or(params);
+        report_audit(
+            observer,
+            breeding,
+            base,
+            generation,
+            population.len(),
+            |i| population[i],
+        );
+        cutoff = next_generation::<N, MAX_POPULATION, DefaultRng>(
+            breeding,
+            base,
+            generation,
+            population,
+            scores,
+            cutoff,
+            Some(&mut lineage),
+            &mut timer,
+        );
+        reject_tabu(breeding, base, generation, &tabu, population, &mut lineage);
+        generation += 1;
+    }
+}
+
+/// Scores a generation, in chunks if `params` sets a chunk size, checking
+/// at each checkpoint whether the run has been cancelled or has run out of
+/// time, in which case it breaks with why the run ended.
+fn score_checked<const N: usize>(
+    params: &GAParams,
+    base: &Board<N>,
+    generation: u64,
+    population: &mut [Board<N>],
+    scores: &mut Vec<Fitness>,
+    (tracker, observer): (&Tracker<N>, &mut dyn Observer<N>),
+) -> ControlFlow<Termination, Option<usize>> {
+    let Some(size) = params.chunk_size() else {
+        return ControlFlow::Continue(score_population_with(params, base, population, scores));
+    };
+
+    let total = population.len();
+    score_population_chunked(params, base, population, scores, size, |scored| {
+        observer.on_chunk(generation, scored, total);
+        if observer.is_cancelled() {
+            ControlFlow::Break(Termination::Cancelled)
+        } else {
+            tracker
+                .stopped(generation)
+                .map_or(ControlFlow::Continue(()), ControlFlow::Break)
+        }
+    })
+}
+
+/// Restarts a compact population, if its automatic or stall restarts call
+/// for it, and returns whether it did.
+fn restart_arena<const N: usize>(
+    params: &GAParams,
+    base: &Board<N>,
+    generation: u64,
+    observer: &mut dyn Observer<N>,
+    (restarts, stalls): (&mut Option<RestartTuner>, &mut Option<StallCounter>),
+    population: &mut PopulationArena<N>,
+    scores: &[Fitness],
+) -> bool {
+    if let Some(tuner) = restarts {
+        let boards = population.to_boards();
+        if decide_restart(tuner, observer, generation, &boards, scores) {
+            let fresh =
+                initial_population::<N, MAX_POPULATION, DefaultRng>(params, base, generation);
+            *population = PopulationArena::from_boards(&fresh);
+            return true;
+        }
+    }
+    if let Some(counter) = stalls {
+        if counter.stalled(scores) {
+            let mut boards = population.to_boards();
+            restart_keeping(params, base, generation, &mut boards, scores);
+            *population = PopulationArena::from_boards(&boards);
+            return true;
+        }
+    }
+    false
+}
+
+/// Breeds the next generation of a compact population with `params`, as
+/// `next_generation` does, then keeps its children off the boards
+/// in `tabu`. Returns the cutoff for breeding the generation after.
+fn breed_arena<const N: usize>(
+    params: &GAParams,
+    base: &Board<N>,
+    generation: u64,
+    population: &mut PopulationArena<N>,
+    (scores, cutoff): (&[Fitness], Option<Fitness>),
+    (lineage, tabu): (&mut Vec<Lineage>, &TabuArchive<N>),
+    timer: &mut PhaseTimer,
+) -> Option<Fitness> {
+    let cutoff = next_generation::<N, MAX_POPULATION, DefaultRng>(
+        params,
+        base,
+        generation,
+        population,
+        scores,
+        cutoff,
+        Some(lineage),
+        timer,
+    );
+    reject_tabu(params, base, generation, tabu, population, lineage);
+    cutoff
+}
+
+/// Records a scored generation that did not solve the puzzle with
+/// `handoff`, and if it finishes the fittest candidate, puts the solution in
+/// its place and scores the population again. Returns the index of a
+/// solution, as scoring does.
+fn hand_off<const N: usize>(
+    handoff: &mut Handoff,
+    params: &GAParams,
+    base: &Board<N>,
+    population: &mut [Board<N>],
+    scores: &mut Vec<Fitness>,
+) -> Option<usize> {
+    let (i, solution) = handoff.record(base, |i| population[i], scores)?;
+    population[i] = solution;
+    score_population_with(params, base, population, scores)
+}
+
+/// Behaves like `hand_off`, but for a `PopulationArena`.
+fn hand_off_arena<const N: usize>(
+    handoff: &mut Handoff,
+    params: &GAParams,
+    base: &Board<N>,
+    population: &mut PopulationArena<N>,
+    scores: &mut Vec<Fitness>,
+) -> Option<usize> {
+    let (i, solution) = handoff.record(base, |i| population.board(i), scores)?;
+    population.set(i, &solution);
+    score_arena_with(params, base, population, scores)
+}
+
+/// Breeds the audit children of the generation about to be bred with
+/// `params`, if it is audited, from the `size` candidates of the population,
+/// looked up with `board`, and reports them.
+fn report_audit<const N: usize>(
+    observer: &mut dyn Observer<N>,
+    params: &GAParams,
+    base: &Board<N>,
+    generation: u64,
+    size: usize,
+    board: impl Fn(usize) -> Board<N>,
+) {
+    if params.audit() > 0 {
+        let samples = audit_children(params, base, generation, size, board);
+        observer.on_audit(generation, &samples);
+    }
+}
+
+/// Scores the operators that bred the generation just scored, then reports
+/// the totals and the lineage itself. The first generation, and one after a
+/// restart, were not bred and have no lineage.
+fn report_operators<const N: usize>(
+    observer: &mut dyn Observer<N>,
+    generation: u64,
+    operators: &mut OperatorStats,
+    lineage: &[Lineage],
+    scores: &[Fitness],
+) {
+    for (lineage, &score) in lineage.iter().zip(scores) {
+        operators.record(lineage, score);
+    }
+    observer.on_metrics(generation, &Metrics::Operators(*operators));
+    observer.on_lineage(generation, lineage);
+}
+
+/// Reports the time spent in each phase so far, if the run is profiled.
+fn report_phases<const N: usize>(
+    observer: &mut dyn Observer<N>,
+    generation: u64,
+    timer: &PhaseTimer,
+) {
+    if let Some(times) = timer.times() {
+        observer.on_metrics(generation, &Metrics::Phases(*times));
+    }
+}
+
+/// Asks `tuner` whether to restart after an unsolved generation, and reports
+/// its decision.
+fn decide_restart<const N: usize>(
+    tuner: &mut RestartTuner,
+    observer: &mut dyn Observer<N>,
+    generation: u64,
+    population: &[Board<N>],
+    scores: &[Fitness],
+) -> bool {
+    let decision = tuner.decide(generation, population, scores);
+    observer.on_metrics(generation, &Metrics::Restarts(tuner.metrics(decision)));
+    decision.restarted
+}
+
+/// Parallel tempering over several mutation rates.
+pub struct TemperingSolver {
+    replicas: Vec<GAParams>,
+    swap_interval: u64,
+}
+
+impl TemperingSolver {
+    /// Returns a new parallel tempering solver.
+    ///
+    /// # Arguments
+    ///
+    /// * `replicas` - GA parameters of each replica, by increasing temperature
+    /// * `swap_interval` - the number of generations between elite migrations
+    #[inline]
+    #[must_use]
+    pub const fn new(replicas: Vec<GAParams>, swap_interval: u64) -> Self {
+        Self {
+            replicas,
+            swap_interval,
+        }
+    }
+}
+
+impl<const N: usize> Solver<N> for TemperingSolver {
+    #[inline]
+    fn solve(&self, base: &Board<N>, budget: Budget, observer: &mut dyn Observer<N>) -> Outcome<N> {
+        let mut tracker = Tracker::new(budget);
+        let mut populations: Vec<Vec<Board<N>>> = self
+            .replicas
+            .iter()
+            .map(|params| {
+                generate_initial_population::<N, MAX_POPULATION, DefaultRng>(params, base)
+            })
+            .collect();
+        let mut scores = vec![Vec::new(); self.replicas.len()];
+        // Elites migrated into each replica that improved on the candidate
+        // they replaced, and elites migrated in at all.
+        let mut swaps = vec![(0_u64, 0_u64); self.replicas.len()];
+        let mut generation = 0;
+
+        loop {
+            if let Some(termination) = tracker.stopped(generation) {
+                return tracker.finish(termination, generation);
+            }
+            if observer.is_cancelled() {
+                return tracker.finish(Termination::Cancelled, generation);
+            }
+
+            let replicas = self.replicas.iter().zip(populations.iter_mut());
+            for ((params, population), scores) in replicas.zip(scores.iter_mut()) {
+                let solved = score_population_with(params, base, population, scores);
+                observer.on_generation(generation, population, scores);
+                tracker.update(observer, generation, population, scores, solved);
+
+                if solved.is_some() {
+                    return tracker.finish(Termination::Solved, generation);
+                }
+            }
+
+            let metrics = self
+                .replicas
+                .iter()
+                .zip(&scores)
+                .zip(&swaps)
+                .map(|((params, scores), &(improved, swapped))| {
+                    #[allow(clippy::cast_precision_loss)]
+                    let acceptance = (swapped > 0).then(|| improved as f64 / swapped as f64);
+
+                    ReplicaMetrics {
+                        temperature: params.scheduled_mutation_rate(generation),
+                        energy: scores.iter().copied().min().unwrap_or(Fitness::MAX),
+                        acceptance,
+                    }
+                })
+                .collect();
+            observer.on_metrics(generation, &Metrics::Tempering(metrics));
+
+            let swapped = evolve_replicas::<N, MAX_POPULATION>(
+                &self.replicas,
+                self.swap_interval,
+                generation,
+                base,
+                &mut populations,
+                &mut scores,
+            );
+            for (total, (improved, swapped)) in swaps.iter_mut().zip(swapped.into_iter().flatten())
+            {
+                total.0 += improved;
+                total.1 += swapped;
+            }
+            generation += 1;
+        }
+    }
+}
+
+/// The island model: a population split between islands that evolve
+/// independently, in parallel, and exchange their fittest candidates every
+/// so often.
+///
+/// Islands converge on different parts of the search space, and migration
+/// spreads good building blocks between them without letting one candidate
+/// take over every island. Like `TemperingSolver`, it ignores compact
+/// populations, column repair, automatic restarts and profiling.
+pub struct IslandSolver {
+    islands: Vec<GAParams>,
+    migration_interval: u64,
+    migrants: usize,
+}
+
+impl IslandSolver {
+    /// Returns a new island model solver, migrating one candidate from each
+    /// island at a time.
+    ///
+    /// # Arguments
+    ///
+    /// * `params` - GA parameters of the whole population; seeded runs give
+    ///   each island its own seed derived from this one
+    /// * `islands` - the number of islands to split the population between
+    /// * `migration_interval` - the number of generations between
+    ///   migrations, or 0 to never migrate
+    ///
+    /// # Errors
+    ///
+    /// Returns `ParamError::NoIslands` if `islands` is 0, or another
+    /// `ParamError` if an island's share of the population is smaller than
+    /// the population schedule's minimum population, or keeps fewer than the
+    /// two survivors breeding needs.
+    #[inline]
+    pub fn new(
+        params: &GAParams,
+        islands: usize,
+        migration_interval: u64,
+    ) -> Result<Self, ParamError> {
+        if islands == 0 {
+            return Err(ParamError::NoIslands);
+        }
+        let population = params.population() / islands;
+
+        Ok(Self {
+            islands: (0..islands as u64)
+                .map(|island| {
+                    let params = params.clone().with_population(population)?;
+                    Ok(match params.seed() {
+                        Some(seed) => params.with_seed(seed.wrapping_add(island)),
+                        None => params,
+                    })
+                })
+                .collect::<Result<_, _>>()?,
+            migration_interval,
+            migrants: 1,
+        })
+    }
+
+    /// Migrates the `migrants` fittest candidates of each island at a time.
+    #[inline]
+    #[must_use]
+    pub const fn with_migrants(mut self, migrants: usize) -> Self {
+        self.migrants = migrants;
+        self
+    }
+}
+
+impl<const N: usize> Solver<N> for IslandSolver {
+    #[inline]
+    fn solve(&self, base: &Board<N>, budget: Budget, observer: &mut dyn Observer<N>) -> Outcome<N> {
+        let mut tracker = Tracker::new(budget);
+        let mut populations: Vec<Vec<Board<N>>> = self
+            .islands
+            .par_iter()
+            .map(|params| {
+                generate_initial_population::<N, MAX_POPULATION, DefaultRng>(params, base)
+            })
+            .collect();
+        let mut scores = vec![Vec::new(); self.islands.len()];
+        let mut generation = 0;
+
+        loop {
+            if let Some(termination) = tracker.stopped(generation) {
+                return tracker.finish(termination, generation);
+            }
+            if observer.is_cancelled() {
+                return tracker.finish(Termination::Cancelled, generation);
+            }
+
+            let solved: Vec<Option<usize>> = self
+                .islands
+                .par_iter()
+                .zip(populations.par_iter_mut())
+                .zip(scores.par_iter_mut())
+                .map(|((params, population), scores)| {
+                    score_population_with(params, base, population, scores)
+                })
+                .collect();
+            for ((population, scores), solved) in populations.iter().zip(&scores).zip(solved) {
+                observer.on_generation(generation, population, scores);
+                tracker.update(observer, generation, population, scores, solved);
+
+                if solved.is_some() {
+                    return tracker.finish(Termination::Solved, generation);
+                }
+            }
+
+            if self.migration_interval != 0
+                && generation != 0
+                && generation % self.migration_interval == 0
+            {
+                migrate(&mut populations, &mut scores, self.migrants);
+            }
+            self.islands
+                .par_iter()
+                .zip(populations.par_iter_mut())
+                .zip(scores.par_iter())
+                .for_each(|((params, population), scores)| {
+                    next_generation::<N, MAX_POPULATION, DefaultRng>(
+                        params,
+                        base,
+                        generation,
+                        population,
+                        scores,
+                        None,
+                        None,
+                        &mut PhaseTimer::new(false),
+                    );
+                });
+            generation += 1;
+        }
+    }
+}
+
+/// A portfolio of differently parameterized genetic algorithms racing on
+/// one puzzle at once, for when the right parameters are not known.
+///
+/// The members share a budget of worker threads, each running on a thread
+/// pool of its own. Every so often the threads are reallocated by
+/// [`allocate_threads`], so that the members that have come closest to a
+/// solution get more of them, while every member keeps at least one. Unlike
+/// `IslandSolver`, the members never exchange candidates. Like it, they
+/// ignore compact populations, column repair, automatic restarts and
+/// profiling.
+pub struct PortfolioSolver {
+    members: Vec<GAParams>,
+    threads: usize,
+    reallocation_interval: u64,
+}
+
+impl PortfolioSolver {
+    /// Returns a new portfolio solver.
+    ///
+    /// # Arguments
+    ///
+    /// * `members` - GA parameters of each member
+    /// * `threads` - the number of worker threads the members share
+    /// * `reallocation_interval` - the number of generations between
+    ///   reallocations of the threads, or 0 to keep an even split
+    ///
+    /// # Errors
+    ///
+    /// Returns `ParamError::EmptyPortfolio` if `members` is empty, or
+    /// `ParamError::PortfolioThreads` if it has more members than
+    /// `threads`.
+    #[inline]
+    pub fn new(
+        members: Vec<GAParams>,
+        threads: usize,
+        reallocation_interval: u64,
+    ) -> Result<Self, ParamError> {
+        if members.is_empty() {
+            return Err(ParamError::EmptyPortfolio);
+        }
+        if threads < members.len() {
+            return Err(ParamError::PortfolioThreads {
+                members: members.len(),
+                threads,
+            });
+        }
+
+        Ok(Self {
+            members,
+            threads,
+            reallocation_interval,
+        })
+    }
+
+    /// Returns the GA parameters of each member.
+    #[inline]
+    #[must_use]
+    pub fn members(&self) -> &[GAParams] {
+        &self.members
+    }
+
+    /// Runs `step` for every member at once, each within its own thread
+    /// pool, and returns what each returned.
+    fn run_members<const N: usize, T: Send>(
+        &self,
+        pools: &[ThreadPool],
+        populations: &mut [Vec<Board<N>>],
+        scores: &mut [Vec<Fitness>],
+        step: impl Fn(&GAParams, &mut Vec<Board<N>>, &mut Vec<Fitness>) -> T + Sync,
+    ) -> Vec<T> {
+        std::thread::scope(|scope| {
+            let step = &step;
+            let handles: Vec<_> = self
+                .members
+                .iter()
+                .zip(pools)
+                .zip(populations.iter_mut().zip(scores.iter_mut()))
+                .map(|((params, pool), (population, scores))| {
+                    scope.spawn(move || pool.install(|| step(params, population, scores)))
+                })
+                .collect();
+
+            handles
+                .into_iter()
+                .map(|handle| {
+                    handle
+                        .join()
+                        .unwrap_or_else(|panic| std::panic::resume_unwind(panic))
+                })
+                .collect()
+        })
+    }
+}
+
+/// Returns a thread pool of `threads` worker threads.
+fn thread_pool(threads: usize) -> ThreadPool {
+    ThreadPoolBuilder::new()
+        .num_threads(threads)
+        .build()
+        .expect("thread pool can be built")
+}
+
+impl<const N: usize> Solver<N> for PortfolioSolver {
+    #[inline]
+    fn solve(&self, base: &Board<N>, budget: Budget, observer: &mut dyn Observer<N>) -> Outcome<N> {
+        let mut tracker = Tracker::new(budget);
+        // The threads are split evenly until the first reallocation.
+        let members = self.members.len();
+        let mut shares: Vec<usize> = (0..members)
+            .map(|i| self.threads / members + usize::from(i < self.threads % members))
+            .collect();
+        let mut pools: Vec<ThreadPool> = shares.iter().map(|&share| thread_pool(share)).collect();
+        let mut populations: Vec<Vec<Board<N>>> = self
+            .members
+            .iter()
+            .map(|params| {
+                generate_initial_population::<N, MAX_POPULATION, DefaultRng>(params, base)
+            })
+            .collect();
+        let mut scores = vec![Vec::new(); self.members.len()];
+        let mut bests = vec![Fitness::MAX; self.members.len()];
+        let mut generation = 0;
+
+        loop {
+            if let Some(termination) = tracker.stopped(generation) {
+                return tracker.finish(termination, generation);
+            }
+            if observer.is_cancelled() {
+                return tracker.finish(Termination::Cancelled, generation);
+            }
+
+            let solved = self.run_members(
+                &pools,
+                &mut populations,
+                &mut scores,
+                |params, population, scores| {
+                    score_population_with(params, base, population, scores)
+                },
+            );
+            for (((population, scores), solved), best) in
+                populations.iter().zip(&scores).zip(solved).zip(&mut bests)
+            {
+                observer.on_generation(generation, population, scores);
+                tracker.update(observer, generation, population, scores, solved);
+                *best = scores.iter().copied().fold(*best, Fitness::min);
+
+                if solved.is_some() {
+                    return tracker.finish(Termination::Solved, generation);
+                }
+            }
+
+            if self.reallocation_interval != 0
+                && generation != 0
+                && generation % self.reallocation_interval == 0
+            {
+                let reallocated = allocate_threads(self.threads, &bests);
+                for ((pool, share), &new) in pools.iter_mut().zip(&mut shares).zip(&reallocated) {
+                    if *share != new {
+                        *pool = thread_pool(new);
+                        *share = new;
+                    }
+                }
+            }
+            let metrics = shares
+                .iter()
+                .zip(&bests)
+                .map(|(&threads, &best)| MemberMetrics { threads, best })
+                .collect();
+            observer.on_metrics(generation, &Metrics::Portfolio(metrics));
+
+            self.run_members(
+                &pools,
+                &mut populations,
+                &mut scores,
+                |params, population, scores| {
+                    next_generation::<N, MAX_POPULATION, DefaultRng>(
+                        params,
+                        base,
+                        generation,
+                        population,
+                        scores,
+                        None,
+                        None,
+                        &mut PhaseTimer::new(false),
+                    );
+                },
+            );
+            generation += 1;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::backtrack::Hybrid;
+    use super::*;
+    use crate::genetics::{Crossover, Curriculum, Encoding, Hypermutation, Mutation, Operator};
+    use crate::overrides::CandidateOverrides;
+    use crate::restarts::{AutoRestart, StallRestart};
+    use crate::stats::StatsAggregator;
+    use crate::sudoku::Row;
+    use crate::testing::SOLVED_9;
+    use std::sync::Arc;
+
+    #[test]
+    fn test_solvers() {
+        let mut base = SOLVED_9;
+        base.0[0].0[0] = 0;
+        base.0[4].0[4] = 0;
+
+        let solvers: [Box<dyn Solver<9>>; 3] = [
+            Box::new(GeneticSolver::new(
+                GAParams::new(100, 0.5, 0.05, None).unwrap(),
+            )),
+            Box::new(TemperingSolver::new(
+                vec![
+                    GAParams::new(100, 0.5, 0.01, None).unwrap(),
+                    GAParams::new(100, 0.5, 0.1, None).unwrap(),
+                ],
+                2,
+            )),
+            Box::new(
+                IslandSolver::new(&GAParams::new(200, 0.5, 0.05, None).unwrap(), 2, 2).unwrap(),
+            ),
+        ];
+
+        for solver in &solvers {
+            let outcome = solver.solve(&base, Budget::unlimited(), &mut ());
+            assert_eq!(Termination::Solved, outcome.termination);
+            assert!(outcome
+                .solution()
+                .is_some_and(|board| board.fitness().is_solution()));
+        }
+    }
+
+    #[test]
+    fn test_compact_matches_boards() {
+        let mut base = SOLVED_9;
+        for i in 0..9 {
+            base.0[i].0[(i * 4) % 9] = 0;
+            base.0[i].0[(i * 7 + 2) % 9] = 0;
+        }
+        let params = GAParams::new(50, 0.5, 0.05, Some(3)).unwrap().with_seed(5);
+        let budget = Budget {
+            generations: Some(8),
+            duration: None,
+            target: None,
+        };
+        let mut boards = StatsAggregator::new(8, 8);
+        let mut compact = StatsAggregator::new(8, 8);
+
+        let outcome = GeneticSolver::new(params.clone()).solve(&base, budget, &mut boards);
+        let compact_outcome = GeneticSolver::new(params.with_compact_population(true)).solve(
+            &base,
+            budget,
+            &mut compact,
+        );
+
+        assert_eq!(outcome.best, compact_outcome.best);
+        assert_eq!(outcome.generation, compact_outcome.generation);
+        assert_eq!(boards.history(), compact.history());
+    }
+
+    #[test]
+    fn test_column_repair() {
+        let mut base = SOLVED_9;
+        for row in &mut base.0 {
+            row.0[..6].fill(0);
+        }
+        let params = GAParams::new(30, 0.5, 0.05, None).unwrap().with_seed(3);
+        let budget = Budget {
+            generations: Some(5),
+            duration: None,
+            target: None,
+        };
+        let mut plain = StatsAggregator::new(8, 8);
+        let mut boards = StatsAggregator::new(8, 8);
+        let mut compact = StatsAggregator::new(8, 8);
+
+        GeneticSolver::new(params.clone()).solve(&base, budget, &mut plain);
+        let repaired = params.with_column_repair(3);
+        let outcome = GeneticSolver::new(repaired.clone()).solve(&base, budget, &mut boards);
+        let compact_outcome = GeneticSolver::new(repaired.with_compact_population(true)).solve(
+            &base,
+            budget,
+            &mut compact,
+        );
+
+        assert_eq!(outcome.best, compact_outcome.best);
+        assert_eq!(boards.history(), compact.history());
+        // The initial populations are the same, and repair only ever lowers
+        // a candidate's score.
+        assert!(boards.history()[0].best <= plain.history()[0].best);
+        assert!(boards.history()[0].median <= plain.history()[0].median);
+    }
+
+    #[test]
+    fn test_crossover_compact() {
+        let mut base = SOLVED_9;
+        for row in &mut base.0 {
+            row.0[..6].fill(0);
+        }
+        let budget = Budget {
+            generations: Some(5),
+            duration: None,
+            target: None,
+        };
+
+        for crossover in [
+            Crossover::SinglePoint,
+            Crossover::TwoPoint,
+            Crossover::Row,
+            Crossover::Box,
+        ] {
+            let params = GAParams::new(30, 0.5, 0.05, None)
+                .unwrap()
+                .with_seed(4)
+                .with_crossover(crossover);
+            let mut boards = StatsAggregator::new(8, 8);
+            let mut compact = StatsAggregator::new(8, 8);
+
+            GeneticSolver::new(params.clone()).solve(&base, budget, &mut boards);
+            GeneticSolver::new(params.with_compact_population(true)).solve(
+                &base,
+                budget,
+                &mut compact,
+            );
+            assert_eq!(boards.history(), compact.history());
+        }
+    }
+
+    #[test]
+    fn test_mutation_compact() {
+        let mut base = SOLVED_9;
+        for row in &mut base.0 {
+            row.0[..6].fill(0);
+        }
+        let budget = Budget {
+            generations: Some(5),
+            duration: None,
+            target: None,
+        };
+
+        for mutation in [Mutation::SwapWithinRow, Mutation::ScrambleRow] {
+            let params = GAParams::new(30, 0.5, 0.2, None)
+                .unwrap()
+                .with_seed(4)
+                .with_mutation_operator(mutation);
+            let mut boards = StatsAggregator::new(8, 8);
+            let mut compact = StatsAggregator::new(8, 8);
+
+            GeneticSolver::new(params.clone()).solve(&base, budget, &mut boards);
+            GeneticSolver::new(params.with_compact_population(true)).solve(
+                &base,
+                budget,
+                &mut compact,
+            );
+            assert_eq!(boards.history(), compact.history());
+        }
+    }
+
+    #[test]
+    fn test_permutation_encoding() {
+        let mut base = SOLVED_9;
+        for row in &mut base.0 {
+            row.0[..6].fill(0);
+        }
+        let budget = Budget {
+            generations: Some(5),
+            duration: None,
+            target: None,
+        };
+        let params = GAParams::new(30, 0.5, 0.2, None)
+            .unwrap()
+            .with_seed(4)
+            .with_encoding(Encoding::Permutation);
+        let mut boards = StatsAggregator::new(8, 8);
+        let mut compact = StatsAggregator::new(8, 8);
+
+        let outcome = GeneticSolver::new(params.clone()).solve(&base, budget, &mut boards);
+        GeneticSolver::new(params.with_compact_population(true)).solve(&base, budget, &mut compact);
+        assert_eq!(boards.history(), compact.history());
+
+        // Rows never repeat a digit, so only columns and boxes can.
+        let (best, _) = outcome.best.unwrap();
+        for row in &best.0 {
+            let mut digits = row.0;
+            digits.sort_unstable();
+            assert_eq!([1, 2, 3, 4, 5, 6, 7, 8, 9], digits);
+        }
+    }
+
+    #[test]
+    fn test_curriculum() {
+        let mut base = SOLVED_9;
+        for row in &mut base.0 {
+            row.0[..3].fill(0);
+        }
+        let params = GAParams::new(100, 0.5, 0.05, None)
+            .unwrap()
+            .with_seed(1)
+            .with_curriculum(Some(Curriculum { generations: 1000 }));
+
+        for params in [params.clone(), params.with_compact_population(true)] {
+            let outcome = GeneticSolver::new(params).solve(&base, Budget::unlimited(), &mut ());
+            assert_eq!(Termination::Solved, outcome.termination);
+            // Weighted selection must not keep the solution from winning.
+            let solution = outcome.solution().unwrap();
+            assert!(solution.fitness().is_solution());
+            assert_eq!(solution, &base.overlay(solution));
+        }
+    }
+
+    #[test]
+    fn test_curriculum_keeps_fitness() {
+        // A Latin square: rows and columns are whole, but boxes are not,
+        // and boxes weigh nothing when the curriculum starts.
+        let base = Board::<9>::new(std::array::from_fn(|row| {
+            Row(std::array::from_fn(|col| {
+                u8::try_from((row + col) % 9 + 1).unwrap()
+            }))
+        }));
+        let params = GAParams::new(10, 0.5, 0.05, None)
+            .unwrap()
+            .with_seed(1)
+            .with_curriculum(Some(Curriculum { generations: 1000 }));
+        let budget = Budget {
+            generations: Some(3),
+            duration: None,
+            target: Some(Fitness::ZERO),
+        };
+
+        for params in [params.clone(), params.with_compact_population(true)] {
+            let outcome = GeneticSolver::new(params).solve(&base, budget, &mut ());
+            assert_eq!(Termination::BudgetExhausted, outcome.termination);
+            let (board, score) = outcome.best.unwrap();
+            assert_eq!(board.fitness(), score);
+            assert!(!score.is_solution());
+        }
+    }
+
+    #[test]
+    fn test_islands() {
+        /// Records the size of every population reported.
+        struct Sizes(Vec<usize>);
+
+        impl Observer<9> for Sizes {
+            fn on_generation(&mut self, _: u64, population: &[Board<9>], _: &[Fitness]) {
+                self.0.push(population.len());
+            }
+        }
+
+        let mut base = SOLVED_9;
+        for row in &mut base.0 {
+            row.0[..5].fill(0);
+        }
+        let budget = Budget {
+            generations: Some(6),
+            duration: None,
+            target: None,
+        };
+        let params = GAParams::new(120, 0.5, 0.05, None).unwrap().with_seed(3);
+        let solver = IslandSolver::new(&params, 3, 2).unwrap().with_migrants(2);
+
+        let mut sizes = Sizes(Vec::new());
+        let outcome = solver.solve(&base, budget, &mut sizes);
+        if outcome.termination == Termination::BudgetExhausted {
+            assert_eq!(vec![40; 18], sizes.0);
+        }
+        // Each island has its own seed, but the run as a whole is seeded.
+        assert_eq!(outcome.best, solver.solve(&base, budget, &mut ()).best);
+        assert_eq!(
+            Some(ParamError::NoIslands),
+            IslandSolver::new(&params, 0, 2).err()
+        );
+        assert_ne!(
+            outcome.best,
+            IslandSolver::new(&params.with_seed(4), 3, 2)
+                .unwrap()
+                .with_migrants(2)
+                .solve(&base, budget, &mut ())
+                .best
+        );
+    }
+
+    #[test]
+    fn test_portfolio() {
+        let mut base = SOLVED_9;
+        for row in &mut base.0 {
+            row.0[..5].fill(0);
+        }
+        let budget = Budget {
+            generations: Some(6),
+            duration: None,
+            target: None,
+        };
+        let members = vec![
+            GAParams::new(40, 0.5, 0.001, None).unwrap().with_seed(1),
+            GAParams::new(60, 0.25, 0.05, None).unwrap().with_seed(2),
+            GAParams::new(20, 0.5, 0.2, None).unwrap().with_seed(3),
+        ];
+        let solver = PortfolioSolver::new(members, 5, 1).unwrap();
+        let mut stats = StatsAggregator::new(8, 8);
+
+        let outcome = solver.solve(&base, budget, &mut stats);
+        if outcome.termination == Termination::BudgetExhausted {
+            let [Metrics::Portfolio(members)] = stats.metrics() else {
+                panic!("no portfolio metrics");
+            };
+            assert_eq!(3, members.len());
+            assert_eq!(5, members.iter().map(|m| m.threads).sum::<usize>());
+            assert!(members.iter().all(|m| m.threads >= 1));
+            // Reallocated after the last generation, the fittest member was
+            // given the most threads.
+            let fittest = members.iter().min_by_key(|m| m.best).unwrap();
+            assert!(members.iter().all(|m| m.threads <= fittest.threads));
+            assert!(fittest.threads > 1);
+            assert_eq!(outcome.best.map(|(_, score)| score), Some(fittest.best));
+        }
+
+        let members = vec![GAParams::new(50, 0.5, 0.05, None).unwrap(); 2];
+        let solution =
+            PortfolioSolver::new(members, 2, 1)
+                .unwrap()
+                .solve(&SOLVED_9, budget, &mut ());
+        assert_eq!(Termination::Solved, solution.termination);
+    }
+
+    #[test]
+    fn test_portfolio_errors() {
+        let members = vec![GAParams::new(50, 0.5, 0.05, None).unwrap(); 2];
+
+        assert_eq!(
+            Some(ParamError::PortfolioThreads {
+                members: 2,
+                threads: 1
+            }),
+            PortfolioSolver::new(members.clone(), 1, 1).err()
+        );
+        assert_eq!(
+            Some(ParamError::PortfolioThreads {
+                members: 2,
+                threads: 0
+            }),
+            PortfolioSolver::new(members, 0, 1).err()
+        );
+        assert_eq!(
+            Some(ParamError::EmptyPortfolio),
+            PortfolioSolver::new(Vec::new(), 4, 1).err()
+        );
+    }
+
+    #[test]
+    fn test_tempering_metrics() {
+        let mut base = SOLVED_9;
+        for row in &mut base.0 {
+            row.0[..5].fill(0);
+        }
+        let budget = Budget {
+            generations: Some(3),
+            duration: None,
+            target: None,
+        };
+        let mut stats = StatsAggregator::new(8, 8);
+
+        let outcome = TemperingSolver::new(
+            vec![
+                GAParams::new(20, 0.5, 0.01, None).unwrap(),
+                GAParams::new(20, 0.5, 0.2, None).unwrap(),
+            ],
+            1,
+        )
+        .solve(&base, budget, &mut stats);
+
+        if outcome.termination == Termination::BudgetExhausted {
+            let [Metrics::Tempering(replicas)] = stats.metrics() else {
+                panic!("no tempering metrics");
+            };
+            assert_eq!(
+                vec![0.01, 0.2],
+                replicas.iter().map(|r| r.temperature).collect::<Vec<_>>()
+            );
+            let best = outcome.best.map(|(_, score)| score);
+            assert!(replicas.iter().all(|r| Some(r.energy) >= best));
+            assert!(replicas
+                .iter()
+                .all(|r| r.acceptance.is_some_and(|rate| (0.0..=1.0).contains(&rate))));
+        }
+    }
+
+    #[test]
+    fn test_operator_metrics() {
+        let mut base = SOLVED_9;
+        for row in &mut base.0 {
+            row.0[..5].fill(0);
+        }
+        let budget = Budget {
+            generations: Some(4),
+            duration: None,
+            target: None,
+        };
+
+        for params in [
+            GAParams::new(40, 0.5, 0.05, None).unwrap(),
+            GAParams::new(40, 0.5, 0.05, Some(2))
+                .unwrap()
+                .with_compact_population(true),
+        ] {
+            let mut stats = StatsAggregator::new(8, 8);
+            let outcome = GeneticSolver::new(params).solve(&base, budget, &mut stats);
+            if outcome.termination != Termination::BudgetExhausted {
+                continue;
+            }
+
+            let [Metrics::Operators(operators)] = stats.metrics() else {
+                panic!("no operator metrics");
+            };
+            // Generations 1 to 3 were bred, except that the compact run
+            // restarted its population for generation 3.
+            assert!(operators.children(Operator::Crossover) >= 40);
+            assert!(
+                operators.children(Operator::Mutation) <= operators.children(Operator::Crossover)
+            );
+            for operator in Operator::ALL {
+                assert!(operators.improved(operator) <= operators.children(operator));
+            }
+        }
+    }
+
+    #[test]
+    fn test_phase_metrics() {
+        let mut base = SOLVED_9;
+        for row in &mut base.0 {
+            row.0[..5].fill(0);
+        }
+        let budget = Budget {
+            generations: Some(4),
+            duration: None,
+            target: None,
+        };
+
+        for compact in [false, true] {
+            let params = GAParams::new(40, 0.5, 0.05, None)
+                .unwrap()
+                .with_compact_population(compact);
+            let mut stats = StatsAggregator::new(8, 8);
+            GeneticSolver::new(params.clone()).solve(&base, budget, &mut stats);
+            assert!(!stats
+                .metrics()
+                .iter()
+                .any(|metrics| matches!(metrics, Metrics::Phases(_))));
+
+            let mut stats = StatsAggregator::new(8, 8);
+            let outcome =
+                GeneticSolver::new(params.with_profiling(true)).solve(&base, budget, &mut stats);
+            let phases = stats
+                .metrics()
+                .iter()
+                .find_map(|metrics| match metrics {
+                    Metrics::Phases(phases) => Some(*phases),
+                    _ => None,
+                })
+                .unwrap();
+            let scored = outcome.generation + u64::from(outcome.termination == Termination::Solved);
+            assert_eq!(scored, phases.generations());
+            for phase in [Phase::Scoring, Phase::Crossover, Phase::Mutation] {
+                assert!(phases.total(phase) > Duration::ZERO);
+            }
+        }
+    }
+
+    #[test]
+    fn test_auto_restart() {
+        let mut base = SOLVED_9;
+        for row in &mut base.0 {
+            row.0.fill(0);
+        }
+        let budget = Budget {
+            generations: Some(100),
+            duration: None,
+            target: None,
+        };
+        let settings = AutoRestart {
+            window: 5,
+            min_entropy: 1.0,
+        };
+
+        for compact in [false, true] {
+            let params = GAParams::new(10, 0.5, 0.0, None)
+                .unwrap()
+                .with_seed(5)
+                .with_compact_population(compact)
+                .with_auto_restart(Some(settings));
+            let mut stats = StatsAggregator::new(8, 8);
+            let outcome = GeneticSolver::new(params).solve(&base, budget, &mut stats);
+            assert_eq!(Termination::BudgetExhausted, outcome.termination);
+
+            let Some(Metrics::Restarts(restarts)) = stats
+                .metrics()
+                .iter()
+                .find(|metrics| matches!(metrics, Metrics::Restarts(_)))
+            else {
+                panic!("no restart metrics");
+            };
+            // Without mutation the population soon stalls for good.
+            assert!(!restarts.restarts.is_empty());
+            assert!(restarts.restarts.iter().all(|decision| decision.restarted));
+            assert_eq!(99, restarts.latest.generation);
+        }
+    }
+
+    #[test]
+    fn test_stall_restart() {
+        let mut base = SOLVED_9;
+        for row in &mut base.0 {
+            row.0.fill(0);
+        }
+        let budget = Budget {
+            generations: Some(60),
+            duration: None,
+            target: None,
+        };
+        let settings = StallRestart {
+            generations: 3,
+            keep: 2,
+        };
+
+        // Without mutation the population soon stalls, and restarts. Both
+        // representations restart alike.
+        let runs: Vec<Vec<Vec<Fitness>>> = [false, true]
+            .into_iter()
+            .map(|compact| {
+                let params = GAParams::new(10, 0.5, 0.0, None)
+                    .unwrap()
+                    .with_seed(5)
+                    .with_compact_population(compact)
+                    .with_stall_restart(Some(settings));
+                let mut scores = AllScores(Vec::new());
+                GeneticSolver::new(params).solve(&base, budget, &mut scores);
+                scores.0
+            })
+            .collect();
+        assert_eq!(runs[0], runs[1]);
+
+        let mut unrestarted = AllScores(Vec::new());
+        let params = GAParams::new(10, 0.5, 0.0, None).unwrap().with_seed(5);
+        GeneticSolver::new(params).solve(&base, budget, &mut unrestarted);
+        assert_ne!(unrestarted.0, runs[0]);
+
+        // The fittest survive each restart, so the best score, once stalled,
+        // is never lost.
+        let best = |scores: &Vec<Fitness>| scores.iter().copied().min().unwrap();
+        let stalled = best(&runs[0][10]);
+        assert!(runs[0][10..].iter().all(|scores| best(scores) == stalled));
+    }
+
+    #[test]
+    fn test_hypermutation() {
+        let mut base = SOLVED_9;
+        for row in &mut base.0 {
+            row.0.fill(0);
+        }
+        let budget = Budget {
+            generations: Some(60),
+            duration: None,
+            target: None,
+        };
+        let settings = Hypermutation {
+            stall: 3,
+            factor: 20.0,
+            generations: 2,
+        };
+
+        // Without much mutation the population soon stalls, and bursts.
+        // Both representations burst alike.
+        let runs: Vec<Vec<Vec<Fitness>>> = [false, true]
+            .into_iter()
+            .map(|compact| {
+                let params = GAParams::new(10, 0.5, 0.01, None)
+                    .unwrap()
+                    .with_seed(5)
+                    .with_compact_population(compact)
+                    .with_hypermutation(Some(settings))
+                    .unwrap();
+                let mut scores = AllScores(Vec::new());
+                GeneticSolver::new(params).solve(&base, budget, &mut scores);
+                scores.0
+            })
+            .collect();
+        assert_eq!(runs[0], runs[1]);
+
+        let mut steady = AllScores(Vec::new());
+        let params = GAParams::new(10, 0.5, 0.01, None).unwrap().with_seed(5);
+        GeneticSolver::new(params).solve(&base, budget, &mut steady);
+        assert_ne!(steady.0, runs[0]);
+    }
+
+    #[test]
+    fn test_hybrid() {
+        let mut base = SOLVED_9;
+        for row in &mut base.0 {
+            row.0[6..].fill(0);
+        }
+        let budget = Budget {
+            generations: Some(100),
+            duration: None,
+            target: None,
+        };
+        let settings = Hybrid {
+            threshold: 100,
+            stall: 3,
+        };
+
+        // Without mutation the population soon stalls short of a solution,
+        // and the finisher solves it. Both representations hand off alike.
+        for compact in [false, true] {
+            let params = GAParams::new(10, 0.5, 0.0, None)
+                .unwrap()
+                .with_seed(5)
+                .with_compact_population(compact);
+            let outcome = GeneticSolver::new(params.clone()).solve(&base, budget, &mut ());
+            assert_eq!(Termination::BudgetExhausted, outcome.termination);
+
+            let outcome = GeneticSolver::new(params.with_hybrid(Some(settings))).solve(
+                &base,
+                budget,
+                &mut (),
+            );
+            assert_eq!(Termination::Solved, outcome.termination);
+            assert!(outcome
+                .solution()
+                .is_some_and(|board| board.fitness().is_solution()));
+        }
+    }
+
+    #[test]
+    fn test_candidate_overrides() {
+        let mut base = SOLVED_9;
+        for row in &mut base.0 {
+            row.0[3..].fill(0);
+        }
+        let budget = Budget {
+            generations: Some(10),
+            duration: None,
+            target: None,
+        };
+        let overrides = Arc::new(CandidateOverrides::parse("1,4,5/6\n2,9,1\n9,9,1/2/3").unwrap());
+
+        // Every candidate of every generation keeps within the overrides,
+        // whatever the encoding or mutation operator.
+        for compact in [false, true] {
+            for (encoding, mutation) in [
+                (Encoding::Cells, Mutation::RandomResetting),
+                (Encoding::Cells, Mutation::ScrambleRow),
+                (Encoding::Permutation, Mutation::RandomResetting),
+            ] {
+                let params = GAParams::new(20, 0.5, 0.2, None)
+                    .unwrap()
+                    .with_compact_population(compact)
+                    .with_encoding(encoding)
+                    .with_mutation_operator(mutation)
+                    .with_candidate_overrides(Some(overrides.clone()));
+                let mut populations = Populations(Vec::new());
+                GeneticSolver::new(params).solve(&base, budget, &mut populations);
+
+                assert!(!populations.0.is_empty());
+                assert!(populations
+                    .0
+                    .iter()
+                    .all(|board| overrides.penalty(board) == Fitness::ZERO));
+            }
+        }
+    }
+
+    #[test]
+    fn test_permutation_overrides() {
+        let mut base = SOLVED_9;
+        for row in &mut base.0 {
+            row.0[3..].fill(0);
+        }
+        let budget = Budget {
+            generations: Some(5),
+            duration: None,
+            target: None,
+        };
+        // The first row gives its 1, so no swap can satisfy this override.
+        let overrides = Arc::new(CandidateOverrides::parse("1,5,1").unwrap());
+
+        for compact in [false, true] {
+            let params = GAParams::new(20, 0.5, 0.2, None)
+                .unwrap()
+                .with_seed(2)
+                .with_compact_population(compact)
+                .with_encoding(Encoding::Permutation)
+                .with_candidate_overrides(Some(overrides.clone()));
+            let mut populations = Populations(Vec::new());
+            let outcome = GeneticSolver::new(params).solve(&base, budget, &mut populations);
+
+            // Rows stay permutations, and the broken override is scored.
+            assert!(!populations.0.is_empty());
+            for board in &populations.0 {
+                for row in &board.0 {
+                    let mut digits = row.0;
+                    digits.sort_unstable();
+                    assert_eq!([1, 2, 3, 4, 5, 6, 7, 8, 9], digits);
+                }
+            }
+            let (best, score) = outcome.best.unwrap();
+            assert_eq!(best.fitness() + overrides.penalty(&best), score);
+            assert!(score >= Fitness::new(1));
+        }
+    }
+
+    /// Records the scores of each generation.
+    struct AllScores(Vec<Vec<Fitness>>);
+
+    impl<const N: usize> Observer<N> for AllScores {
+        fn on_generation(&mut self, _: u64, _: &[Board<N>], scores: &[Fitness]) {
+            self.0.push(scores.to_vec());
+        }
+    }
+
+    /// Every candidate of every generation, one after another.
+    struct Populations(Vec<Board<9>>);
+
+    impl Observer<9> for Populations {
+        fn on_generation(&mut self, _: u64, population: &[Board<9>], _: &[Fitness]) {
+            self.0.extend_from_slice(population);
+        }
+    }
+
+    #[test]
+    fn test_on_generation() {
+        let mut base = SOLVED_9;
+        base.0[0].0.fill(0);
+        base.0[1].0.fill(0);
+        let params = GAParams::new(20, 0.5, 0.05, None).unwrap().with_seed(3);
+        let budget = Budget {
+            generations: Some(4),
+            duration: None,
+            target: None,
+        };
+
+        let mut seen = Vec::new();
+        let callback = OnGeneration(|stats: &PopulationStats, best: &Board<9>| {
+            seen.push((stats.generation, stats.best, best.fitness()));
+        });
+        let mut observer = (callback, AllScores(Vec::new()));
+        let outcome = GeneticSolver::new(params).solve(&base, budget, &mut observer);
+        let all_scores = observer.1 .0;
+
+        assert_eq!(Termination::BudgetExhausted, outcome.termination);
+        assert_eq!(4, seen.len());
+        for ((generation, (i, best, fitness)), scores) in (0..).zip(seen).zip(all_scores) {
+            assert_eq!(generation, i);
+            assert_eq!(scores.iter().min(), Some(&best));
+            assert_eq!(best, fitness);
+        }
+    }
+
+    #[test]
+    fn test_budget_exhausted() {
+        let solver = GeneticSolver::new(GAParams::new(10, 0.5, 0.05, None).unwrap());
+        let budget = Budget {
+            generations: Some(0),
+            duration: None,
+            target: None,
+        };
+        let outcome = solver.solve(&SOLVED_9, budget, &mut ());
+
+        assert_eq!(Termination::BudgetExhausted, outcome.termination);
+        assert_eq!(None, outcome.solution());
+    }
+
+    #[test]
+    fn test_target_reached() {
+        let mut base = SOLVED_9;
+        for row in &mut base.0[..3] {
+            row.0.fill(0);
+        }
+        let params = GAParams::new(20, 0.5, 0.05, None).unwrap().with_seed(2);
+        let budget = Budget {
+            generations: Some(1000),
+            duration: None,
+            target: Some(Fitness::new(4)),
+        };
+
+        for compact in [false, true] {
+            let solver = GeneticSolver::new(params.clone().with_compact_population(compact));
+            let outcome = solver.solve(&base, budget, &mut ());
+            assert_eq!(Termination::TargetReached, outcome.termination);
+            let (board, score) = outcome.best.unwrap();
+            assert!(budget.is_reached(score));
+            let stopped = outcome.clone().into_result().unwrap_err();
+            assert_eq!(Some((board, score)), stopped.best);
+            assert_eq!(outcome.generation, stopped.generation);
+        }
+    }
+
+    /// Cancels the run once it has seen `limit` generations.
+    struct CancelAfter {
+        limit: u64,
+        seen: u64,
+    }
+
+    impl<const N: usize> Observer<N> for CancelAfter {
+        fn on_generation(&mut self, _: u64, _: &[Board<N>], _: &[Fitness]) {
+            self.seen += 1;
+        }
+
+        fn is_cancelled(&self) -> bool {
+            self.seen >= self.limit
+        }
+    }
+
+    #[test]
+    fn test_cancelled() {
+        let mut base = SOLVED_9;
+        for row in &mut base.0 {
+            row.0.fill(0);
+        }
+        let params = GAParams::new(10, 0.5, 0.05, None).unwrap();
+        let solvers: [Box<dyn Solver<9>>; 2] = [
+            Box::new(GeneticSolver::new(params.clone())),
+            Box::new(TemperingSolver::new(vec![params.clone(), params], 5)),
+        ];
+
+        for (solver, generations) in solvers.iter().zip([3, 2]) {
+            let mut observer = CancelAfter { limit: 3, seen: 0 };
+            let outcome = solver.solve(&base, Budget::unlimited(), &mut observer);
+            if outcome.termination == Termination::Solved {
+                continue;
+            }
+
+            assert_eq!(Termination::Cancelled, outcome.termination);
+            assert_eq!(generations, outcome.generation);
+        }
+    }
+
+    /// Every checkpoint reported, cancelling the run after `limit`.
+    struct Checkpoints {
+        limit: usize,
+        seen: Vec<(u64, usize, usize)>,
+    }
+
+    impl Observer<9> for Checkpoints {
+        fn on_chunk(&mut self, generation: u64, scored: usize, population: usize) {
+            self.seen.push((generation, scored, population));
+        }
+
+        fn is_cancelled(&self) -> bool {
+            self.seen.len() >= self.limit
+        }
+    }
+
+    #[test]
+    fn test_chunked_scoring() {
+        let mut base = SOLVED_9;
+        for row in &mut base.0 {
+            row.0.fill(0);
+        }
+        let params = GAParams::new(10, 0.5, 0.05, None)
+            .unwrap()
+            .with_chunk_size(Some(4))
+            .unwrap();
+        let mut observer = Checkpoints {
+            limit: 3,
+            seen: Vec::new(),
+        };
+        let outcome = GeneticSolver::new(params).solve(&base, Budget::unlimited(), &mut observer);
+
+        // The run is cancelled between the chunks of its second generation,
+        // before it is scored in full.
+        assert_eq!(Termination::Cancelled, outcome.termination);
+        assert_eq!(1, outcome.generation);
+        assert_eq!(vec![(0, 4, 10), (0, 8, 10), (1, 4, 10)], observer.seen);
+    }
+}
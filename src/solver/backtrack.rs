@@ -0,0 +1,185 @@
+#![warn(
+    clippy::all,
+    // clippy::restriction,
+    clippy::pedantic,
+    clippy::nursery,
+    clippy::cargo
+)]
+
+use crate::exact;
+use crate::restarts::StallCounter;
+use crate::sudoku::unit::Units;
+use crate::sudoku::{Board, Fitness};
+use serde::{Deserialize, Serialize};
+
+/// Settings for handing a nearly solved run that has stalled to an exact
+/// backtracking finisher.
+///
+/// Once the best score has not improved for `stall` generations and is at
+/// most `threshold`, the fittest candidate is passed to [`finish`]. If it
+/// finds a solution, the solution takes the candidate's place and the run is
+/// solved; otherwise the GA carries on, and tries again after another
+/// `stall` generations. With a `stall` of 0 the candidate is polished as
+/// soon as the best score reaches `threshold`, and every generation after
+/// that until the run is solved.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Serialize, Deserialize)]
+pub struct Hybrid {
+    /// The highest best score the finisher is tried on.
+    pub threshold: u32,
+    /// The number of generations without a better best score before a
+    /// handoff, or 0 to hand off every generation.
+    pub stall: u64,
+}
+
+impl Default for Hybrid {
+    #[inline]
+    fn default() -> Self {
+        Self {
+            threshold: 4,
+            stall: 20,
+        }
+    }
+}
+
+/// Finishes `board`, a candidate for the puzzle `base`, by backtracking
+/// over the cells in conflict.
+///
+/// The free cells holding a duplicated digit are emptied and the puzzle
+/// that leaves is solved exactly. If it has no solution, because some of
+/// the cells kept are wrong as well, every free cell of the units with a
+/// duplicate is emptied and the search tried once more. Givens are never
+/// changed.
+///
+/// Returns the solution, or `None` if neither search finds one. A board
+/// without conflicts is returned as it is.
+///
+/// # Panics
+///
+/// Panics if N is not a supported board size.
+#[inline]
+#[must_use]
+pub fn finish<const N: usize>(base: &Board<N>, board: &Board<N>) -> Option<Board<N>> {
+    let violations: Vec<_> = board
+        .violations()
+        .into_iter()
+        .filter(|violation| violation.digit != 0)
+        .collect();
+    if violations.is_empty() {
+        return Some(*board);
+    }
+
+    let conflicts = violations
+        .iter()
+        .flat_map(|violation| violation.cells.iter().copied());
+    let units = violations.iter().flat_map(|violation| {
+        Units::<N>::new()
+            .find(|&(unit, index, _)| (unit, index) == (violation.unit, violation.index))
+            .map_or_else(Vec::new, |(_, _, cells)| cells.to_vec())
+    });
+
+    [conflicts.collect::<Vec<_>>(), units.collect()]
+        .into_iter()
+        .find_map(|cells| {
+            let mut puzzle = *board;
+            for (row, col) in cells {
+                if base.0[row].0[col] == 0 {
+                    puzzle.0[row].0[col] = 0;
+                }
+            }
+            exact::solve(&puzzle, 1).pop()
+        })
+}
+
+/// Decides, generation by generation, when a run is handed to the
+/// finisher.
+#[derive(Debug, Clone)]
+pub(crate) struct Handoff {
+    settings: Hybrid,
+    stalls: StallCounter,
+}
+
+impl Handoff {
+    pub(crate) const fn new(settings: Hybrid) -> Self {
+        Self {
+            settings,
+            stalls: StallCounter::new(settings.stall),
+        }
+    }
+
+    /// Records a scored generation that did not solve the puzzle, and hands
+    /// its fittest candidate, looked up with `board`, to the finisher if the
+    /// run has stalled close enough to a solution. Returns the candidate's
+    /// index and the solution, if one was found.
+    pub(crate) fn record<const N: usize>(
+        &mut self,
+        base: &Board<N>,
+        board: impl Fn(usize) -> Board<N>,
+        scores: &[Fitness],
+    ) -> Option<(usize, Board<N>)> {
+        if self.settings.stall > 0 && !self.stalls.stalled(scores) {
+            return None;
+        }
+
+        let (best, score) = scores.iter().enumerate().min_by_key(|&(_, score)| score)?;
+        if score.get() > self.settings.threshold {
+            return None;
+        }
+        finish(base, &board(best)).map(|solution| (best, solution))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::SOLVED_9;
+
+    #[test]
+    fn test_finish() {
+        let mut base = SOLVED_9;
+        for row in &mut base.0 {
+            row.0[4..].fill(0);
+        }
+        assert_eq!(Some(SOLVED_9), finish(&base, &SOLVED_9));
+
+        // Swapping two free cells of a row puts them in conflict.
+        let mut board = SOLVED_9;
+        board.0[0].0.swap(5, 7);
+        assert_eq!(Some(SOLVED_9), finish(&base, &board));
+
+        // Conflicts between givens cannot be finished.
+        let mut base = SOLVED_9;
+        base.0[0].0[0] = base.0[0].0[1];
+        assert_eq!(None, finish(&base, &base));
+    }
+
+    #[test]
+    fn test_handoff() {
+        let mut base = SOLVED_9;
+        base.0[0].0[5..].fill(0);
+        let mut board = SOLVED_9;
+        board.0[0].0.swap(5, 7);
+        let mut handoff = Handoff::new(Hybrid {
+            threshold: 4,
+            stall: 2,
+        });
+
+        let high = [Fitness::new(10), Fitness::new(8)];
+        let low = [Fitness::new(10), Fitness::new(4)];
+        assert_eq!(None, handoff.record(&base, |_| board, &high));
+        assert_eq!(None, handoff.record(&base, |_| board, &high));
+        assert_eq!(None, handoff.record(&base, |_| board, &high));
+        assert_eq!(None, handoff.record(&base, |_| board, &low));
+        assert_eq!(None, handoff.record(&base, |_| board, &low));
+        assert_eq!(Some((1, SOLVED_9)), handoff.record(&base, |_| board, &low));
+
+        // Without a stall, the finisher is tried on every generation close
+        // enough to a solution.
+        let mut eager = Handoff::new(Hybrid {
+            threshold: 4,
+            stall: 0,
+        });
+        assert_eq!(None, eager.record(&base, |_| board, &high));
+        assert_eq!(Some((1, SOLVED_9)), eager.record(&base, |_| board, &low));
+        assert_eq!(Some((1, SOLVED_9)), eager.record(&base, |_| board, &low));
+    }
+}
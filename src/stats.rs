@@ -0,0 +1,364 @@
+#![warn(
+    clippy::all,
+    // clippy::restriction,
+    clippy::pedantic,
+    clippy::nursery,
+    clippy::cargo
+)]
+
+use super::diversity::mean_distance;
+use super::solver::{Metrics, Observer};
+use super::sudoku::{Board, Fitness};
+use rand::rngs::OsRng;
+use rand::{Rng, SeedableRng};
+use rand_pcg::Pcg64Mcg;
+use std::collections::VecDeque;
+use std::time::{Duration, Instant};
+
+/// Fitness statistics of a single generation.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct GenerationStats {
+    pub generation: u64,
+    /// The lowest, and so fittest, score.
+    pub best: Fitness,
+    /// The lower median score.
+    pub median: Fitness,
+    /// The highest score.
+    pub worst: Fitness,
+}
+
+impl GenerationStats {
+    /// Summarizes the scores of one generation, or returns `None` if there
+    /// are none.
+    #[inline]
+    #[must_use]
+    pub fn of(generation: u64, scores: &[Fitness]) -> Option<Self> {
+        let mut scores = scores.to_vec();
+        let middle = scores.len().checked_sub(1)? / 2;
+        let (_, &mut median, _) = scores.select_nth_unstable(middle);
+
+        Some(Self {
+            generation,
+            best: scores.iter().copied().min()?,
+            median,
+            worst: scores.iter().copied().max()?,
+        })
+    }
+}
+
+/// Fitness and diversity statistics of a single evaluated generation, as
+/// returned by `genetics::run_generation`.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct PopulationStats {
+    pub generation: u64,
+    /// The lowest, and so fittest, score.
+    pub best: Fitness,
+    /// The mean score.
+    pub mean: f64,
+    /// The lower median score.
+    pub median: Fitness,
+    /// The highest score.
+    pub worst: Fitness,
+    /// The mean number of cells in which a candidate differs from the
+    /// fittest candidate.
+    pub diversity: f64,
+}
+
+impl PopulationStats {
+    /// Summarizes one scored generation, or returns `None` if it is empty.
+    /// `scores` runs parallel to `population`.
+    #[inline]
+    #[must_use]
+    pub fn of<const N: usize>(
+        generation: u64,
+        population: &[Board<N>],
+        scores: &[Fitness],
+    ) -> Option<Self> {
+        let GenerationStats {
+            best,
+            median,
+            worst,
+            ..
+        } = GenerationStats::of(generation, scores)?;
+        let (fittest, _) = scores.iter().enumerate().min_by_key(|(_, score)| **score)?;
+        let total: u64 = scores.iter().map(|score| u64::from(score.get())).sum();
+
+        #[allow(clippy::cast_precision_loss)]
+        let mean = total as f64 / scores.len() as f64;
+
+        Some(Self {
+            generation,
+            best,
+            mean,
+            median,
+            worst,
+            diversity: mean_distance(population, population.get(fittest)?),
+        })
+    }
+}
+
+/// Aggregates per-generation statistics in constant memory.
+///
+/// Keeps a uniform random sample of at most `capacity` generations, using
+/// reservoir sampling, for drawing fitness curves of arbitrarily long runs,
+/// and the last `window` generations for rolling percentiles. Each sample
+/// is stamped with the time since the first generation was recorded.
+/// Solvers with several populations record one entry per population. The
+/// latest backend-specific metrics of each kind, such as the temperature of
+/// each tempering replica, are kept too.
+#[derive(Debug, Clone)]
+pub struct StatsAggregator {
+    capacity: usize,
+    window: usize,
+    seen: u64,
+    best: Option<Fitness>,
+    start: Option<Instant>,
+    reservoir: Vec<(GenerationStats, Duration)>,
+    recent: VecDeque<GenerationStats>,
+    last_elapsed: Duration,
+    metrics: Vec<Metrics>,
+    rng: Pcg64Mcg,
+}
+
+impl StatsAggregator {
+    /// Returns an empty aggregator.
+    ///
+    /// # Arguments
+    ///
+    /// * `capacity` - the number of generations to sample for the history
+    /// * `window` - the number of recent generations to compute percentiles
+    ///   over
+    ///
+    /// # Panics
+    ///
+    /// Panics if `capacity` or `window` is 0.
+    #[inline]
+    #[must_use]
+    pub fn new(capacity: usize, window: usize) -> Self {
+        assert!(capacity > 0 && window > 0);
+
+        Self {
+            capacity,
+            window,
+            seen: 0,
+            best: None,
+            start: None,
+            reservoir: Vec::with_capacity(capacity),
+            recent: VecDeque::with_capacity(window),
+            last_elapsed: Duration::ZERO,
+            metrics: Vec::new(),
+            rng: Pcg64Mcg::from_rng(OsRng).unwrap(),
+        }
+    }
+
+    /// Records the statistics of one generation.
+    #[inline]
+    pub fn record(&mut self, stats: GenerationStats) {
+        let elapsed = self.start.get_or_insert_with(Instant::now).elapsed();
+        self.seen += 1;
+        self.best = Some(self.best.map_or(stats.best, |best| best.min(stats.best)));
+
+        if self.reservoir.len() < self.capacity {
+            self.reservoir.push((stats, elapsed));
+        } else {
+            let slot = self.rng.gen_range(0..self.seen);
+            if let Ok(slot) = usize::try_from(slot) {
+                if slot < self.capacity {
+                    self.reservoir[slot] = (stats, elapsed);
+                }
+            }
+        }
+        self.last_elapsed = elapsed;
+
+        if self.recent.len() == self.window {
+            self.recent.pop_front();
+        }
+        self.recent.push_back(stats);
+    }
+
+    /// Forgets everything recorded so far.
+    #[inline]
+    pub fn clear(&mut self) {
+        self.seen = 0;
+        self.best = None;
+        self.start = None;
+        self.reservoir.clear();
+        self.recent.clear();
+        self.metrics.clear();
+    }
+
+    /// Returns the number of generations recorded.
+    #[inline]
+    #[must_use]
+    pub const fn generations(&self) -> u64 {
+        self.seen
+    }
+
+    /// Returns the best score recorded over the whole run.
+    #[inline]
+    #[must_use]
+    pub const fn best(&self) -> Option<Fitness> {
+        self.best
+    }
+
+    /// Returns the latest backend-specific metrics of each kind the solver
+    /// reported.
+    #[inline]
+    #[must_use]
+    pub fn metrics(&self) -> &[Metrics] {
+        &self.metrics
+    }
+
+    /// Returns the sampled history in generation order.
+    ///
+    /// The most recent generation is always included, so the curve ends
+    /// where the run did.
+    #[inline]
+    #[must_use]
+    pub fn history(&self) -> Vec<GenerationStats> {
+        self.timed_history()
+            .into_iter()
+            .map(|(stats, _)| stats)
+            .collect()
+    }
+
+    /// Returns the sampled history in generation order, with the time since
+    /// the first generation at which each sample was recorded.
+    #[inline]
+    #[must_use]
+    pub fn timed_history(&self) -> Vec<(GenerationStats, Duration)> {
+        let mut history = self.reservoir.clone();
+
+        if let Some(last) = self.recent.back() {
+            if !history.iter().any(|(stats, _)| stats == last) {
+                history.push((*last, self.last_elapsed));
+            }
+        }
+        history.sort_by_key(|(stats, _)| stats.generation);
+
+        history
+    }
+
+    /// Returns the `percentile`th percentile of the best scores over the
+    /// recent window, using the nearest-rank method.
+    ///
+    /// # Arguments
+    ///
+    /// * `percentile` - a percentile between 0 and 100
+    #[inline]
+    #[must_use]
+    pub fn rolling_percentile(&self, percentile: u8) -> Option<Fitness> {
+        let mut scores: Vec<Fitness> = self.recent.iter().map(|stats| stats.best).collect();
+        scores.sort_unstable();
+
+        let rank = (usize::from(percentile.min(100)) * scores.len()).div_ceil(100);
+        scores.get(rank.saturating_sub(1)).copied()
+    }
+}
+
+impl<const N: usize> Observer<N> for StatsAggregator {
+    #[inline]
+    fn on_generation(&mut self, generation: u64, _: &[Board<N>], scores: &[Fitness]) {
+        if let Some(stats) = GenerationStats::of(generation, scores) {
+            self.record(stats);
+        }
+    }
+
+    #[inline]
+    fn needs_population(&self) -> bool {
+        false
+    }
+
+    #[inline]
+    fn on_metrics(&mut self, _: u64, metrics: &Metrics) {
+        let kind = std::mem::discriminant(metrics);
+
+        match self
+            .metrics
+            .iter_mut()
+            .find(|latest| std::mem::discriminant(*latest) == kind)
+        {
+            Some(latest) => latest.clone_from(metrics),
+            None => self.metrics.push(metrics.clone()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::SOLVED_4;
+
+    fn stats(generation: u64, best: u32) -> GenerationStats {
+        let best = Fitness::new(best);
+        GenerationStats {
+            generation,
+            best,
+            median: best,
+            worst: best,
+        }
+    }
+
+    #[test]
+    fn test_generation_stats() {
+        assert_eq!(
+            Some(GenerationStats {
+                generation: 3,
+                best: Fitness::new(1),
+                median: Fitness::new(4),
+                worst: Fitness::new(9),
+            }),
+            GenerationStats::of(3, &[9, 4, 1, 7, 2].map(Fitness::new))
+        );
+        assert_eq!(None, GenerationStats::of(0, &[]));
+    }
+
+    #[test]
+    fn test_population_stats() {
+        let mut other = SOLVED_4;
+        other.0[0].0.swap(0, 1);
+        let scores = [3, 0, 1, 2].map(Fitness::new);
+
+        let stats = PopulationStats::of(5, &[other, SOLVED_4, SOLVED_4, other], &scores).unwrap();
+        assert_eq!(5, stats.generation);
+        assert_eq!(Fitness::new(0), stats.best);
+        assert!((stats.mean - 1.5).abs() < 1e-9);
+        assert_eq!(Fitness::new(1), stats.median);
+        assert_eq!(Fitness::new(3), stats.worst);
+        assert!((stats.diversity - 1.0).abs() < 1e-9);
+        assert_eq!(None, PopulationStats::of::<4>(0, &[], &[]));
+    }
+
+    #[test]
+    fn test_aggregator() {
+        let mut aggregator = StatsAggregator::new(10, 4);
+
+        for generation in 0..1000 {
+            let best = u32::try_from(100 - generation / 10).unwrap();
+            aggregator.record(stats(generation, best));
+        }
+
+        let history = aggregator.history();
+        assert_eq!(1000, aggregator.generations());
+        assert_eq!(Some(Fitness::new(1)), aggregator.best());
+        assert!(history.len() <= 11);
+        assert!(history
+            .windows(2)
+            .all(|w| w[0].generation < w[1].generation));
+        assert_eq!(Some(&stats(999, 1)), history.last());
+        let timed = aggregator.timed_history();
+        assert_eq!(history.len(), timed.len());
+        assert!(timed.windows(2).all(|w| w[0].1 <= w[1].1));
+        assert_eq!(Some(Fitness::new(1)), aggregator.rolling_percentile(50));
+        assert_eq!(Some(Fitness::new(1)), aggregator.rolling_percentile(100));
+
+        aggregator.record(stats(1000, 0));
+        assert_eq!(Some(Fitness::new(0)), aggregator.rolling_percentile(0));
+        assert_eq!(Some(Fitness::new(0)), aggregator.rolling_percentile(25));
+        assert_eq!(Some(Fitness::new(1)), aggregator.rolling_percentile(50));
+
+        aggregator.clear();
+        assert_eq!(None, aggregator.rolling_percentile(50));
+        assert!(aggregator.history().is_empty());
+    }
+}
@@ -0,0 +1,199 @@
+#![warn(
+    clippy::all,
+    // clippy::restriction,
+    clippy::pedantic,
+    clippy::nursery,
+    clippy::cargo
+)]
+
+use super::genetics::{fill_initial_population, is_rate, DefaultRng, GAParams, MAX_POPULATION};
+use super::solver::{evolve, Budget, Observer, Outcome};
+use super::sudoku::{Board, Fitness};
+use rayon::{ThreadPool, ThreadPoolBuildError, ThreadPoolBuilder};
+
+/// Solves a stream of related puzzles, such as the candidates of a puzzle
+/// generator, one after another.
+///
+/// Unlike calling `GeneticSolver::solve` per puzzle, the thread pool and the
+/// population buffers are kept between runs. With a warm start, part of each
+/// initial population is also seeded from the previous solution, with the
+/// new puzzle's givens laid over it, so that puzzles sharing most of their
+/// solution are solved from it rather than from scratch.
+///
+/// Populations are always bred as boards, whatever `GAParams::compact` says.
+pub struct StreamSolver<const N: usize> {
+    params: GAParams,
+    pool: ThreadPool,
+    warm_start: f32,
+    previous: Option<Board<N>>,
+    population: Vec<Board<N>>,
+    scores: Vec<Fitness>,
+}
+
+impl<const N: usize> StreamSolver<N> {
+    /// Returns a new stream solver with its own thread pool.
+    ///
+    /// # Arguments
+    ///
+    /// * `params` - GA parameters for every run
+    /// * `threads` - the number of threads in the pool, or 0 for rayon's
+    ///   default
+    ///
+    /// # Errors
+    ///
+    /// Fails if the thread pool cannot be built.
+    #[inline]
+    pub fn new(params: GAParams, threads: usize) -> Result<Self, ThreadPoolBuildError> {
+        Ok(Self {
+            params,
+            pool: ThreadPoolBuilder::new().num_threads(threads).build()?,
+            warm_start: 0.0,
+            previous: None,
+            population: Vec::with_capacity(MAX_POPULATION),
+            scores: Vec::with_capacity(MAX_POPULATION),
+        })
+    }
+
+    /// Seeds `fraction` of each initial population from the previous
+    /// solution, once there is one.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `fraction` is not between 0.0 and 1.0 inclusive.
+    #[inline]
+    #[must_use]
+    pub fn with_warm_start(mut self, fraction: f32) -> Self {
+        assert!(
+            is_rate(fraction),
+            "warm start {fraction} is not between 0.0 and 1.0"
+        );
+        self.warm_start = fraction;
+        self
+    }
+
+    /// Returns the GA parameters.
+    #[inline]
+    #[must_use]
+    pub const fn params(&self) -> &GAParams {
+        &self.params
+    }
+
+    /// Returns the last solution found, which seeds the next run.
+    #[inline]
+    #[must_use]
+    pub const fn previous(&self) -> Option<&Board<N>> {
+        self.previous.as_ref()
+    }
+
+    /// Seeds the next run from `solution` as if it had just been found, such
+    /// as a solution saved by an earlier process. Only seeds anything with a
+    /// warm start.
+    #[inline]
+    #[must_use]
+    pub const fn with_previous(mut self, solution: Board<N>) -> Self {
+        self.previous = Some(solution);
+        self
+    }
+
+    /// Forgets the last solution, so that the next run starts cold.
+    #[inline]
+    pub const fn reset(&mut self) {
+        self.previous = None;
+    }
+
+    /// Solves `base` within `budget`, reporting progress to `observer`.
+    ///
+    /// The observer is called from within the solver's thread pool, so it
+    /// must be `Send`.
+    #[inline]
+    pub fn solve(
+        &mut self,
+        base: &Board<N>,
+        budget: Budget,
+        observer: &mut (dyn Observer<N> + Send),
+    ) -> Outcome<N> {
+        let Self {
+            ref params,
+            ref pool,
+            warm_start,
+            previous,
+            population,
+            scores,
+        } = self;
+
+        let outcome = pool.install(|| {
+            fill_initial_population::<N, DefaultRng>(params, base, 0, population);
+            if let Some(previous) = previous {
+                #[allow(
+                    clippy::cast_sign_loss,
+                    clippy::cast_possible_truncation,
+                    clippy::cast_precision_loss
+                )]
+                let warm = (population.len() as f32 * *warm_start).floor() as usize;
+                population[..warm].fill(base.overlay(previous));
+            }
+
+            evolve(params, base, budget, observer, population, scores)
+        });
+
+        if let Some(solution) = outcome.solution() {
+            *previous = Some(*solution);
+        }
+        outcome
+    }
+
+    /// Solves each of `boards` in turn within `budget`, returning their
+    /// outcomes in order.
+    #[inline]
+    pub fn solve_all<'a>(
+        &mut self,
+        boards: impl IntoIterator<Item = &'a Board<N>>,
+        budget: Budget,
+    ) -> Vec<Outcome<N>> {
+        boards
+            .into_iter()
+            .map(|base| self.solve(base, budget, &mut ()))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::termination::Termination;
+    use crate::testing::SOLVED_4;
+
+    #[test]
+    fn test_stream_solver() {
+        let params = GAParams::new(100, 0.5, 0.05, None).unwrap().with_seed(1);
+        let mut solver = StreamSolver::<4>::new(params, 2).unwrap();
+
+        let mut first = SOLVED_4;
+        first.0[0].0[..2].fill(0);
+        let mut second = SOLVED_4;
+        second.0[3].0[2..].fill(0);
+        let budget = Budget {
+            generations: Some(10_000),
+            duration: None,
+            target: None,
+        };
+
+        let outcomes = solver.solve_all([&first, &second], budget);
+        assert!(outcomes
+            .iter()
+            .all(|outcome| outcome.termination == Termination::Solved));
+        assert_eq!(Some(&SOLVED_4), solver.previous());
+
+        // Fully seeded from a solution that also solves the next puzzle, the
+        // first generation is already solved.
+        let mut solver = solver.with_warm_start(1.0);
+        let outcome = solver.solve(&first, budget, &mut ());
+        assert_eq!(Some(&SOLVED_4), outcome.solution());
+        assert_eq!(0, outcome.generation);
+
+        solver.reset();
+        assert_eq!(None, solver.previous());
+        let mut solver = solver.with_previous(SOLVED_4);
+        assert_eq!(0, solver.solve(&second, budget, &mut ()).generation);
+    }
+}
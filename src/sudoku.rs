@@ -6,31 +6,104 @@
     clippy::cargo
 )]
 
+pub mod fitness;
+pub mod propagate;
+pub mod symmetry;
+pub mod unit;
+
+use super::errors::{GivenConflict, SudokuError};
 use arrayvec::ArrayVec;
+use static_assertions::{assert_eq_size, const_assert_eq};
 use std::fmt;
 use std::fmt::{Debug, Display, Formatter};
+use std::mem::{align_of, size_of};
+use unit::{UnitScorer, UnitSet, Units};
+
+pub use fitness::{Fitness, Objectives, UnitWeights};
 
-#[derive(Debug, Default)]
-struct Scorer {
-    seen: u64,
-    score: u8,
+/// A kind of unit in which every digit must appear exactly once.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum Unit {
+    Row,
+    Column,
+    Box,
+    /// A region of a variant puzzle, such as a diagonal or a killer cage.
+    Region,
 }
 
-impl Scorer {
-    fn check(&mut self, digit: u8) {
-        let bit = 1 << digit;
-        if self.seen & bit != 0 {
-            self.score += 1;
-        }
-        self.seen |= bit;
+/// A digit that appears more than once within a single unit.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Violation {
+    /// The kind of unit the violation occurs in.
+    pub unit: Unit,
+    /// The index of the unit; boxes are numbered in row-major order, and
+    /// regions in the order their `UnitSet` lists them.
+    pub index: usize,
+    /// The duplicated digit.
+    pub digit: u8,
+    /// The `(row, column)` coordinates of every cell holding `digit`.
+    pub cells: Vec<(usize, usize)>,
+}
+
+/// A set of cells on a board of size N, such as the cells holding givens.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct GivensMask<const N: usize>(pub [[bool; N]; N]);
+
+impl<const N: usize> GivensMask<N> {
+    /// Returns the mask of every filled in cell of `board`.
+    #[inline]
+    #[must_use]
+    pub fn of(board: &Board<N>) -> Self {
+        Self(board.0.map(|row| row.0.map(|digit| digit != 0)))
     }
 
-    const fn score(self) -> u8 {
-        self.score
+    #[inline]
+    #[must_use]
+    pub const fn contains(&self, row: usize, col: usize) -> bool {
+        self.0[row][col]
+    }
+
+    #[inline]
+    pub const fn insert(&mut self, row: usize, col: usize) {
+        self.0[row][col] = true;
+    }
+
+    #[inline]
+    pub const fn remove(&mut self, row: usize, col: usize) {
+        self.0[row][col] = false;
+    }
+
+    /// Returns the number of cells in the mask.
+    #[inline]
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.0.iter().flatten().filter(|&&cell| cell).count()
+    }
+
+    #[inline]
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
     }
 }
 
+impl<const N: usize> Default for GivensMask<N> {
+    #[inline]
+    fn default() -> Self {
+        Self([[false; N]; N])
+    }
+}
+
+/// A change of one cell of a board to `digit`, or to empty if it is 0.
 #[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct CellChange {
+    pub row: usize,
+    pub col: usize,
+    pub digit: u8,
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+#[repr(C)]
 pub struct Row<const N: usize>(pub [u8; N]);
 
 impl<const N: usize> Default for Row<N> {
@@ -47,8 +120,8 @@ impl<const N: usize> Display for Row<N> {
 
         for (i, value) in values.iter().enumerate() {
             match values.len() - i {
-                1 => write!(f, "{}", value)?,
-                _ => write!(f, "{} ", value)?,
+                1 => write!(f, "{value}")?,
+                _ => write!(f, "{value} ")?,
             }
         }
 
@@ -56,10 +129,59 @@ impl<const N: usize> Display for Row<N> {
     }
 }
 
-#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+/// A board of N by N cells, each holding a digit from 1 to N or 0 if empty.
+///
+/// Boards are laid out as C would lay them out: N rows of N one-byte cells,
+/// row after row with no padding, so that code outside Rust can read and
+/// write them in place. `Board::layout` reports the layout.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+#[repr(C)]
 pub struct Board<const N: usize>(pub [Row<N>; N]);
 
+assert_eq_size!(Board<9>, [u8; 81]);
+const_assert_eq!(align_of::<Board<9>>(), 1);
+
+/// How a board is laid out in memory.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+#[repr(C)]
+pub struct BoardLayout {
+    /// The number of rows, columns and digits.
+    pub size: usize,
+    /// The size of a board in bytes.
+    pub bytes: usize,
+    /// The distance in bytes from the start of one row to the next.
+    pub row_stride: usize,
+    /// The alignment of a board in bytes.
+    pub align: usize,
+}
+
+impl Display for BoardLayout {
+    #[inline]
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{size}x{size} cells of 1 byte | {} bytes | row stride {} | align {}",
+            self.bytes,
+            self.row_stride,
+            self.align,
+            size = self.size,
+        )
+    }
+}
+
 impl<const N: usize> Board<N> {
+    /// Returns the memory layout of boards of this size.
+    #[inline]
+    #[must_use]
+    pub const fn layout() -> BoardLayout {
+        BoardLayout {
+            size: N,
+            bytes: size_of::<Self>(),
+            row_stride: size_of::<Row<N>>(),
+            align: align_of::<Self>(),
+        }
+    }
+
     #[inline]
     #[must_use]
     pub const fn new(rows: [Row<N>; N]) -> Self {
@@ -117,27 +239,412 @@ impl<const N: usize> Board<N> {
         Self(board)
     }
 
+    /// Returns a copy of `self` with every cell in `cells` blanked out.
+    ///
+    /// # Arguments
+    ///
+    /// * `cells` - The cells to clear
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_sudoku::sudoku::{Board, GivensMask, Row};
+    ///
+    /// let board = Board::new([Row([1, 2]), Row([2, 1])]);
+    /// let mut cells = GivensMask::default();
+    /// cells.insert(0, 1);
+    ///
+    /// assert_eq!(Board::new([Row([1, 0]), Row([2, 1])]), board.mask(&cells));
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn mask(&self, cells: &GivensMask<N>) -> Self {
+        let mut board = *self;
+
+        for (row, mask_row) in board.0.iter_mut().zip(cells.0) {
+            for (digit, masked) in row.0.iter_mut().zip(mask_row) {
+                if masked {
+                    *digit = 0;
+                }
+            }
+        }
+
+        board
+    }
+
+    /// Makes `changes` in order. Every change is checked before any is
+    /// made, so the board is either changed fully or left as it was.
+    ///
+    /// # Errors
+    ///
+    /// Fails if a change's cell lies outside the board or its digit is
+    /// greater than N.
+    #[inline]
+    pub fn apply(&mut self, changes: &[CellChange]) -> Result<(), SudokuError> {
+        for &CellChange { row, col, digit } in changes {
+            if row >= N || col >= N || usize::from(digit) > N {
+                return Err(SudokuError::OutOfRange { row, col, digit });
+            }
+        }
+
+        for &CellChange { row, col, digit } in changes {
+            self.0[row].0[col] = digit;
+        }
+
+        Ok(())
+    }
+
+    /// Makes `changes` like `apply`, to a board whose `givens` must stay as
+    /// they are.
+    ///
+    /// Debug builds also check that no change touches a given, to catch
+    /// operators and editors that forget to skip them; release builds leave
+    /// that to the caller.
+    ///
+    /// # Errors
+    ///
+    /// Fails like `apply`, or in debug builds if a change's cell is in
+    /// `givens`.
+    #[inline]
+    pub fn apply_around(
+        &mut self,
+        givens: &GivensMask<N>,
+        changes: &[CellChange],
+    ) -> Result<(), SudokuError> {
+        if cfg!(debug_assertions) {
+            let given = changes.iter().find(|change| {
+                change.row < N && change.col < N && givens.contains(change.row, change.col)
+            });
+            if let Some(&CellChange { row, col, .. }) = given {
+                return Err(SudokuError::Given { row, col });
+            }
+        }
+
+        self.apply(changes)
+    }
+
+    /// Returns a copy of `self` with the given `(row, column, digit)` hints
+    /// filled in.
+    ///
+    /// Re-stating a digit that is already in place is allowed. The hints are
+    /// applied in order, so later hints are checked against earlier ones.
+    ///
+    /// # Arguments
+    ///
+    /// * `givens` - The hints to add
+    ///
+    /// # Errors
+    ///
+    /// Fails if a hint is out of range, would overwrite a different digit, or
+    /// would repeat a digit already present in its row, column or box.
+    ///
+    /// # Panics
+    ///
+    /// Panics if N is not a supported board size.
+    #[inline]
+    pub fn with_extra_givens(&self, givens: &[(usize, usize, u8)]) -> Result<Self, GivenConflict> {
+        let mut board = *self;
+
+        for &(row, col, digit) in givens {
+            if row >= N || col >= N || digit == 0 || usize::from(digit) > N {
+                return Err(GivenConflict::OutOfRange { row, col, digit });
+            }
+
+            match board.0[row].0[col] {
+                0 => {}
+                existing if existing == digit => continue,
+                existing => return Err(GivenConflict::Occupied { row, col, existing }),
+            }
+
+            let units = [
+                (Unit::Row, row),
+                (Unit::Column, col),
+                (Unit::Box, box_index::<N>(row, col)),
+            ];
+
+            for (unit, index) in units {
+                if unit::cells::<N>(unit, index)
+                    .iter()
+                    .any(|&(r, c)| board.0[r].0[c] == digit)
+                {
+                    return Err(GivenConflict::Duplicate {
+                        row,
+                        col,
+                        digit,
+                        unit,
+                    });
+                }
+            }
+
+            board.0[row].0[col] = digit;
+        }
+
+        Ok(board)
+    }
+
     #[inline]
     #[must_use]
-    pub fn fitness(&self) -> u8 {
+    pub fn fitness(&self) -> Fitness {
         self.count_row_duplicates()
             + self.transpose().count_row_duplicates()
             + self.count_box_duplicates()
     }
 
+    /// Scores the board like `fitness`, counting the duplicates within the
+    /// units of `units` instead of the classic ones.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the size of the Board, N, has no box dimensions (see
+    /// [`box_dims`]) and `units` has no region map.
+    #[inline]
+    #[must_use]
+    pub fn fitness_in(&self, units: &UnitSet<N>) -> Fitness {
+        units
+            .units()
+            .map(|(_, _, cells)| self.unit_duplicates(&cells))
+            .sum()
+    }
+
+    /// Returns the number of cells in which the board differs from `other`,
+    /// its Hamming distance.
+    #[inline]
+    #[must_use]
+    pub fn distance(&self, other: &Self) -> usize {
+        self.0
+            .iter()
+            .zip(&other.0)
+            .map(|(row, other)| row.0.iter().zip(&other.0).filter(|(a, b)| a != b).count())
+            .sum()
+    }
+
+    /// Scores the board like `fitness`, but keeps the duplicates of rows,
+    /// columns and boxes apart, as the objectives of Pareto selection.
+    #[inline]
+    #[must_use]
+    pub fn objectives(&self) -> Objectives {
+        Objectives {
+            rows: self.count_row_duplicates(),
+            columns: self.transpose().count_row_duplicates(),
+            boxes: self.count_box_duplicates(),
+        }
+    }
+
+    /// Scores the board like `fitness`, but with the duplicates of each
+    /// kind of unit multiplied by its weight, rounded to the nearest whole
+    /// number. A weighted score of zero does not make a solution; only
+    /// `fitness` tells.
+    #[inline]
+    #[must_use]
+    pub fn weighted_fitness(&self, weights: UnitWeights) -> Fitness {
+        let weigh = |duplicates: Fitness, weight: f32| f64::from(duplicates) * f64::from(weight);
+        let objectives = self.objectives();
+        let total = weigh(objectives.rows, weights.rows)
+            + weigh(objectives.columns, weights.columns)
+            + weigh(objectives.boxes, weights.boxes);
+
+        #[allow(clippy::cast_sign_loss, clippy::cast_possible_truncation)]
+        Fitness::new(total.round() as u32)
+    }
+
+    /// Swaps duplicated digits out of columns, within their rows.
+    ///
+    /// For each column that repeats a digit, tries swapping each repeated
+    /// cell with every other cell of its row, leaving the givens of `base`
+    /// in place, and makes the swap that lowers `fitness` the most, if any
+    /// does. Rows keep the same digits, so this is a cheap directed repair
+    /// rather than a full hill climb.
+    ///
+    /// # Arguments
+    ///
+    /// * `base` - The puzzle, whose givens must not move
+    /// * `max_swaps` - The most swaps to make
+    ///
+    /// Returns the number of swaps made.
+    #[inline]
+    pub fn repair_columns(&mut self, base: &Self, max_swaps: usize) -> usize {
+        let givens = GivensMask::of(base);
+        let swap = |board: &Self, row: usize, a: usize, b: usize| {
+            [
+                CellChange {
+                    row,
+                    col: a,
+                    digit: board.0[row].0[b],
+                },
+                CellChange {
+                    row,
+                    col: b,
+                    digit: board.0[row].0[a],
+                },
+            ]
+        };
+        let mut fitness = self.fitness();
+        let mut swaps = 0;
+
+        for col in 0..N {
+            if swaps == max_swaps || fitness.is_solution() {
+                break;
+            }
+
+            let mut counts = [0_u8; 256];
+            for row in &self.0 {
+                counts[usize::from(row.0[col])] += 1;
+            }
+
+            let free = |row: usize, col: usize| !givens.contains(row, col);
+            let repeated =
+                (0..N).filter(|&row| free(row, col) && counts[usize::from(self.0[row].0[col])] > 1);
+            let best = repeated
+                .flat_map(|row| {
+                    (0..N)
+                        .filter(move |&other| other != col && free(row, other))
+                        .map(move |other| (row, other))
+                })
+                .filter_map(|(row, other)| {
+                    let mut swapped = *self;
+                    swapped
+                        .apply_around(&givens, &swap(self, row, col, other))
+                        .ok()?;
+                    Some((swapped.fitness(), swapped))
+                })
+                .min_by_key(|&(score, _)| score);
+
+            if let Some((score, swapped)) = best {
+                if score < fitness {
+                    *self = swapped;
+                    fitness = score;
+                    swaps += 1;
+                }
+            }
+        }
+
+        swaps
+    }
+
+    /// Computes fitness for a board whose rows are known to be valid.
+    ///
+    /// Encodings that keep every row a permutation of the digits guarantee
+    /// that rows never contain duplicates, so row scoring is skipped entirely.
+    /// Columns and boxes are checked with one `UnitScorer` per unit, filled
+    /// in a single pass over the board, so N must be less than 64.
+    ///
+    /// Returns the same score as `fitness` for such boards.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the size of the Board, N, has no box dimensions (see
+    /// [`box_dims`]).
+    #[inline]
+    #[must_use]
+    pub fn fitness_with_valid_rows(&self) -> Fitness {
+        debug_assert_eq!(
+            Fitness::ZERO,
+            self.count_row_duplicates(),
+            "rows are not valid"
+        );
+
+        let mut columns = [UnitScorer::<N>::new(); N];
+        let mut boxes = [UnitScorer::<N>::new(); N];
+
+        for (i, row) in self.0.iter().enumerate() {
+            for (j, &value) in row.0.iter().enumerate() {
+                columns[j].check(value);
+                boxes[box_index::<N>(i, j)].check(value);
+            }
+        }
+
+        columns
+            .iter()
+            .chain(&boxes)
+            .map(|scorer| Fitness::from(scorer.score()))
+            .sum()
+    }
+
+    /// Lists all constraint violations.
+    ///
+    /// Returns one `Violation` per digit that appears more than once in a
+    /// row, column or box, ordered by unit kind, then unit index, then digit.
+    /// Each violation accounts for `cells.len() - 1` points of `fitness`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the size of the Board, N, has no box dimensions (see
+    /// [`box_dims`]).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use genetic_sudoku::sudoku::{Board, Row, Unit};
+    ///
+    /// let board = Board::new([
+    ///     Row([1, 2, 3, 4]),
+    ///     Row([3, 4, 1, 2]),
+    ///     Row([4, 3, 2, 1]),
+    ///     Row([2, 1, 4, 4]),
+    /// ]);
+    /// let violations = board.violations();
+    ///
+    /// assert_eq!(3, violations.len());
+    /// assert_eq!(Unit::Row, violations[0].unit);
+    /// assert_eq!(vec![(3, 2), (3, 3)], violations[0].cells);
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn violations(&self) -> Vec<Violation> {
+        self.violations_of(Units::new())
+    }
+
+    /// Lists the constraint violations of the units of `units`, like
+    /// `violations` does for the classic ones.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the size of the Board, N, has no box dimensions (see
+    /// [`box_dims`]) and `units` has no region map.
     #[inline]
     #[must_use]
-    pub fn count_row_duplicates(&self) -> u8 {
-        let mut total_duplicates: u8 = 0;
+    pub fn violations_in(&self, units: &UnitSet<N>) -> Vec<Violation> {
+        self.violations_of(units.units())
+    }
+
+    fn violations_of(&self, units: Units<'_, N>) -> Vec<Violation> {
+        let mut violations = Vec::new();
+
+        for (unit, index, cells) in units {
+            let mut cells_by_digit: Vec<Vec<(usize, usize)>> = vec![Vec::new(); N + 1];
+
+            for (row, col) in cells {
+                cells_by_digit[usize::from(self.0[row].0[col])].push((row, col));
+            }
+
+            for (digit, cells) in cells_by_digit.into_iter().enumerate() {
+                if cells.len() > 1 {
+                    violations.push(Violation {
+                        unit,
+                        index,
+                        digit: u8::try_from(digit).expect("digit size exceeds 255"),
+                        cells,
+                    });
+                }
+            }
+        }
+
+        violations
+    }
+
+    #[inline]
+    #[must_use]
+    pub fn count_row_duplicates(&self) -> Fitness {
+        let mut total_duplicates = Fitness::ZERO;
 
         for row in self.0 {
-            let mut scorer = Scorer::default();
+            let mut scorer = UnitScorer::<N>::new();
 
             for value in &row.0 {
                 scorer.check(*value);
             }
 
-            total_duplicates += scorer.score();
+            total_duplicates += Fitness::from(scorer.score());
         }
 
         total_duplicates
@@ -149,36 +656,25 @@ impl<const N: usize> Board<N> {
     ///
     /// # Panics
     ///
-    /// Panics if the size of the Board, N, is not a perfect square >= 4 or <=
-    /// 25.
+    /// Panics if the size of the Board, N, has no box dimensions (see
+    /// [`box_dims`]).
     #[inline]
     #[must_use]
-    pub fn count_box_duplicates(&self) -> u8 {
-        let mut total_duplicates: u8 = 0;
-
-        // XXX This could be a proper integer square root.
-        // Realistically these are the only sizes that
-        // matter anyhow, and theres no built-in integer
-        // sqrt() in Rust.
-        let box_size = match N {
-            4 => 2,
-            9 => 3,
-            16 => 4,
-            25 => 5,
-            _ => panic!("puzzle size N must be one of (2..5)^2"),
-        };
+    pub fn count_box_duplicates(&self) -> Fitness {
+        let mut total_duplicates = Fitness::ZERO;
+        let (rows, cols) = box_dims::<N>();
 
-        for row in (0..N).step_by(box_size) {
-            for col in (0..N).step_by(box_size) {
-                let mut scorer = Scorer::default();
+        for row in (0..N).step_by(rows) {
+            for col in (0..N).step_by(cols) {
+                let mut scorer = UnitScorer::<N>::new();
 
-                for r in &self.0[row..row + box_size] {
-                    for value in &r.0[col..col + box_size] {
+                for r in &self.0[row..row + rows] {
+                    for value in &r.0[col..col + cols] {
                         scorer.check(*value);
                     }
                 }
 
-                total_duplicates += scorer.score();
+                total_duplicates += Fitness::from(scorer.score());
             }
         }
 
@@ -198,6 +694,26 @@ impl<const N: usize> Board<N> {
         Self(transposed)
     }
 
+    fn unit_duplicates(&self, cells: &[(usize, usize)]) -> Fitness {
+        let mut scorer = UnitScorer::<N>::new();
+
+        for &(row, col) in cells {
+            scorer.check(self.0[row].0[col]);
+        }
+
+        Fitness::from(scorer.score())
+    }
+
+    /// Returns a stable 64-bit hash of the board's digits.
+    ///
+    /// Uses FNV-1a so the value is the same across runs, platforms and Rust
+    /// versions, and can be stored to identify a puzzle later.
+    #[inline]
+    #[must_use]
+    pub fn puzzle_hash(&self) -> u64 {
+        fnv1a(self.0.iter().flat_map(|row| row.0))
+    }
+
     /// Read a board from a file.
     ///
     /// # Errors
@@ -205,7 +721,30 @@ impl<const N: usize> Board<N> {
     /// Fails if file is nonexistent, unreadable, or of the wrong size.
     #[inline]
     pub fn read<P: AsRef<std::path::Path>>(path: P) -> Result<Self, std::io::Error> {
-        let board = std::fs::read_to_string(path)?;
+        Self::parse(&std::fs::read_to_string(path)?)
+    }
+
+    /// Download a board from an `http://` or `https://` URL.
+    ///
+    /// # Errors
+    ///
+    /// Fails if the request fails, or if the response is not a board of the
+    /// right size.
+    #[cfg(feature = "http")]
+    #[inline]
+    pub fn fetch(url: &str) -> Result<Self, std::io::Error> {
+        let response = ureq::get(url).call().map_err(std::io::Error::other)?;
+
+        Self::parse(&response.into_string()?)
+    }
+
+    /// Parse a board from its textual form.
+    ///
+    /// # Errors
+    ///
+    /// Fails if the text is malformed or of the wrong size.
+    #[inline]
+    pub fn parse(board: &str) -> Result<Self, std::io::Error> {
         let format_error =
             || std::io::Error::new(std::io::ErrorKind::InvalidData, "malformed sudoku board");
         let dim = board.lines().next().ok_or_else(format_error)?.len();
@@ -238,11 +777,11 @@ impl<const N: usize> Board<N> {
 impl<const N: usize> Display for Board<N> {
     #[inline]
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
-        for (i, row) in (&self.0).iter().enumerate() {
-            if i < (&self.0).len() - 1 {
-                writeln!(f, "{}", row)?;
+        for (i, row) in self.0.iter().enumerate() {
+            if i < self.0.len() - 1 {
+                writeln!(f, "{row}")?;
             } else {
-                write!(f, "{}", row)?;
+                write!(f, "{row}")?;
             }
         }
 
@@ -250,6 +789,64 @@ impl<const N: usize> Display for Board<N> {
     }
 }
 
+/// Returns the 64-bit FNV-1a hash of `bytes`.
+pub(crate) fn fnv1a<I: IntoIterator<Item = u8>>(bytes: I) -> u64 {
+    const FNV_OFFSET_BASIS: u64 = 0xcbf2_9ce4_8422_2325;
+    const FNV_PRIME: u64 = 0x0000_0100_0000_01b3;
+
+    bytes.into_iter().fold(FNV_OFFSET_BASIS, |hash, byte| {
+        (hash ^ u64::from(byte)).wrapping_mul(FNV_PRIME)
+    })
+}
+
+/// Returns the `(rows, columns)` of a sub-box for a board of size N.
+///
+/// Boxes are as square as N allows: a perfect square N has square boxes,
+/// and any other N has boxes wider than they are tall, such as 2 by 3 for
+/// a 6 by 6 board or 3 by 4 for a 12 by 12 board.
+///
+/// # Panics
+///
+/// Panics if N has no factor pair with both sides at least 2, such as a
+/// prime N.
+#[inline]
+#[must_use]
+pub const fn box_dims<const N: usize>() -> (usize, usize) {
+    let mut rows = N.isqrt();
+    while rows > 1 && N % rows != 0 {
+        rows -= 1;
+    }
+    assert!(
+        rows >= 2,
+        "puzzle size N must be a product of two box sides >= 2"
+    );
+    (rows, N / rows)
+}
+
+/// Returns the index of the box holding the cell at `row` and `col`, with
+/// boxes numbered across each band of rows, then down the bands.
+pub(crate) const fn box_index<const N: usize>(row: usize, col: usize) -> usize {
+    let (rows, cols) = box_dims::<N>();
+    // A band is `rows` tall and holds `N / cols == rows` boxes.
+    (row / rows) * rows + col / cols
+}
+
+/// Returns the `(row, column)` coordinates of the `k`th cell of a unit.
+fn unit_cell<const N: usize>(unit: Unit, index: usize, k: usize) -> (usize, usize) {
+    match unit {
+        Unit::Row => (index, k),
+        Unit::Column => (k, index),
+        Unit::Box => {
+            let (rows, cols) = box_dims::<N>();
+            (
+                (index / rows) * rows + k / cols,
+                (index % rows) * cols + k % cols,
+            )
+        }
+        Unit::Region => panic!("region cells are only known to their UnitSet"),
+    }
+}
+
 fn apply_overlay<T, F, const N: usize>(base: &[T; N], overlay: &[T; N], f: F) -> [T; N]
 where
     T: Debug,
@@ -266,6 +863,7 @@ where
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::testing::SOLVED_6;
 
     const GOOD_BOARD: Board<4> = Board([
         Row([1, 2, 3, 4]),
@@ -296,8 +894,176 @@ mod tests {
 
     #[test]
     fn test_board_fitness() {
-        assert_eq!(0, GOOD_BOARD.fitness());
-        assert_eq!(20, BAD_BOARD.fitness());
+        assert!(GOOD_BOARD.fitness().is_solution());
+        assert_eq!(Fitness::new(20), BAD_BOARD.fitness());
+    }
+
+    #[test]
+    fn test_board_objectives() {
+        let objectives = BAD_BOARD.objectives();
+        assert_eq!(BAD_BOARD.fitness(), objectives.total());
+        assert_eq!(
+            [Fitness::ZERO, Fitness::new(12), Fitness::new(8)],
+            objectives.to_array()
+        );
+        assert_eq!(Objectives::default(), GOOD_BOARD.objectives());
+    }
+
+    #[test]
+    fn test_board_distance() {
+        assert_eq!(0, GOOD_BOARD.distance(&GOOD_BOARD));
+        let mut board = GOOD_BOARD;
+        board.0[0].0.swap(0, 1);
+        board.0[3].0[2] = 0;
+        assert_eq!(3, GOOD_BOARD.distance(&board));
+        assert_eq!(3, board.distance(&GOOD_BOARD));
+    }
+
+    #[test]
+    fn test_board_weighted_fitness() {
+        // BAD_BOARD repeats 12 digits down its columns and 8 in its boxes.
+        assert_eq!(
+            BAD_BOARD.fitness(),
+            BAD_BOARD.weighted_fitness(UnitWeights::FULL)
+        );
+        let weights = |columns, boxes| UnitWeights {
+            rows: 1.0,
+            columns,
+            boxes,
+        };
+        assert_eq!(
+            Fitness::new(12),
+            BAD_BOARD.weighted_fitness(weights(1.0, 0.0))
+        );
+        assert_eq!(
+            Fitness::new(16),
+            BAD_BOARD.weighted_fitness(weights(1.0, 0.5))
+        );
+        assert_eq!(
+            Fitness::new(8),
+            BAD_BOARD.weighted_fitness(weights(0.0, 1.0))
+        );
+        assert!(GOOD_BOARD.weighted_fitness(weights(0.3, 0.7)).is_solution());
+    }
+
+    #[test]
+    fn test_board_fitness_with_valid_rows() {
+        assert!(GOOD_BOARD.fitness_with_valid_rows().is_solution());
+        assert_eq!(BAD_BOARD.fitness(), BAD_BOARD.fitness_with_valid_rows());
+        assert_eq!(
+            GOOD_BOARD_TRANSPOSED.fitness(),
+            GOOD_BOARD_TRANSPOSED.fitness_with_valid_rows()
+        );
+    }
+
+    #[test]
+    fn test_board_repair_columns() {
+        let mut board = GOOD_BOARD;
+        board.0[0].0.swap(0, 1);
+
+        let mut repaired = board;
+        assert_eq!(0, repaired.repair_columns(&GOOD_BOARD, 4));
+        assert_eq!(board, repaired);
+        assert_eq!(1, repaired.repair_columns(&Board([Row::default(); 4]), 4));
+        assert_eq!(GOOD_BOARD, repaired);
+
+        // With the first row given, the repair has to move the second copy.
+        let mut base = Board([Row::default(); 4]);
+        base.0[0] = board.0[0];
+        let mut repaired = board;
+        assert_eq!(1, repaired.repair_columns(&base, 4));
+        assert_eq!(Row([1, 2, 4, 3]), repaired.0[3]);
+        assert!(repaired.fitness().is_solution());
+
+        let mut repaired = BAD_BOARD;
+        assert_eq!(2, repaired.repair_columns(&Board([Row::default(); 4]), 2));
+        assert!(repaired.fitness() < BAD_BOARD.fitness());
+    }
+
+    #[test]
+    fn test_board_mask() {
+        let givens = GivensMask::of(&GOOD_BOARD);
+        assert_eq!(16, givens.len());
+        assert_eq!(Board([Row::default(); 4]), GOOD_BOARD.mask(&givens));
+        assert_eq!(GOOD_BOARD, GOOD_BOARD.mask(&GivensMask::default()));
+    }
+
+    #[test]
+    fn test_board_apply() {
+        let change = |row, col, digit| CellChange { row, col, digit };
+        let mut board = GOOD_BOARD;
+        assert_eq!(Ok(()), board.apply(&[change(0, 0, 0), change(3, 3, 1)]));
+        assert_eq!(Row([0, 2, 3, 4]), board.0[0]);
+        assert_eq!(Row([2, 1, 4, 1]), board.0[3]);
+
+        // A bad change leaves every other change unmade.
+        assert_eq!(
+            Err(SudokuError::OutOfRange {
+                row: 1,
+                col: 1,
+                digit: 5
+            }),
+            board.apply(&[change(0, 0, 1), change(1, 1, 5)])
+        );
+        assert_eq!(
+            Err(SudokuError::OutOfRange {
+                row: 4,
+                col: 0,
+                digit: 1
+            }),
+            board.apply(&[change(4, 0, 1)])
+        );
+        assert_eq!(0, board.0[0].0[0]);
+
+        let givens = GivensMask::of(&board);
+        let mut puzzle = board;
+        assert_eq!(Ok(()), puzzle.apply_around(&givens, &[change(0, 0, 1)]));
+        assert_eq!(GOOD_BOARD.0[0], puzzle.0[0]);
+        if cfg!(debug_assertions) {
+            assert_eq!(
+                Err(SudokuError::Given { row: 0, col: 1 }),
+                puzzle.apply_around(&givens, &[change(0, 1, 1)])
+            );
+        }
+    }
+
+    #[test]
+    fn test_board_with_extra_givens() {
+        let mut cells = GivensMask::default();
+        cells.insert(0, 0);
+        cells.insert(3, 3);
+        let puzzle = GOOD_BOARD.mask(&cells);
+
+        assert_eq!(
+            Ok(GOOD_BOARD),
+            puzzle.with_extra_givens(&[(0, 0, 1), (3, 3, 3)])
+        );
+        assert_eq!(Ok(puzzle), puzzle.with_extra_givens(&[(0, 1, 2)]));
+        assert_eq!(
+            Err(GivenConflict::Occupied {
+                row: 0,
+                col: 1,
+                existing: 2
+            }),
+            puzzle.with_extra_givens(&[(0, 1, 3)])
+        );
+        assert_eq!(
+            Err(GivenConflict::Duplicate {
+                row: 0,
+                col: 0,
+                digit: 2,
+                unit: Unit::Row
+            }),
+            puzzle.with_extra_givens(&[(0, 0, 2)])
+        );
+        assert_eq!(
+            Err(GivenConflict::OutOfRange {
+                row: 0,
+                col: 0,
+                digit: 5
+            }),
+            puzzle.with_extra_givens(&[(0, 0, 5)])
+        );
     }
 
     #[test]
@@ -305,10 +1071,61 @@ mod tests {
         assert_eq!(GOOD_BOARD_TRANSPOSED, GOOD_BOARD.transpose());
     }
 
+    #[test]
+    fn test_board_layout() {
+        let layout = Board::<16>::layout();
+        assert_eq!(
+            BoardLayout {
+                size: 16,
+                bytes: 256,
+                row_stride: 16,
+                align: 1,
+            },
+            layout
+        );
+        assert_eq!(
+            "16x16 cells of 1 byte | 256 bytes | row stride 16 | align 1",
+            layout.to_string()
+        );
+    }
+
+    #[test]
+    fn test_box_dims() {
+        assert_eq!((2, 2), box_dims::<4>());
+        assert_eq!((2, 3), box_dims::<6>());
+        assert_eq!((2, 4), box_dims::<8>());
+        assert_eq!((3, 3), box_dims::<9>());
+        assert_eq!((3, 4), box_dims::<12>());
+        assert_eq!((5, 5), box_dims::<25>());
+        assert_eq!(3, box_index::<6>(2, 4));
+        assert_eq!(4, box_index::<6>(5, 2));
+
+        // The transpose has 3 by 2 boxes, so its rows and columns are
+        // complete but its boxes are not.
+        let transposed = SOLVED_6.transpose();
+        assert!(SOLVED_6.fitness().is_solution());
+        assert_eq!(Fitness::ZERO, transposed.count_row_duplicates());
+        assert_eq!(Fitness::new(6), transposed.count_box_duplicates());
+        assert_eq!(transposed.fitness(), transposed.fitness_with_valid_rows());
+
+        let solved_12 = Board::<12>(std::array::from_fn(|row| {
+            Row(std::array::from_fn(|col| {
+                u8::try_from((4 * (row % 3) + row / 3 + col) % 12 + 1).unwrap()
+            }))
+        }));
+        assert!(solved_12.fitness().is_solution());
+        assert!(solved_12.violations().is_empty());
+    }
+
+    #[test]
+    #[should_panic(expected = "box sides")]
+    fn test_box_dims_prime() {
+        let _ = box_dims::<7>();
+    }
+
     fn test_scorer_no_duplicates() {
-        let mut scorer = Scorer::default();
+        let mut scorer = UnitScorer::<49>::new();
 
-        // Since Scorer.seen is u64, it supports up to 49 before overflowing
         for i in 1..=49 {
             scorer.check(i);
         }
@@ -317,7 +1134,7 @@ mod tests {
     }
 
     fn test_scorer_with_duplicates() {
-        let mut scorer = Scorer::default();
+        let mut scorer = UnitScorer::<4>::new();
 
         scorer.check(1);
         scorer.check(1); // One duplicate
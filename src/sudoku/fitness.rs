@@ -0,0 +1,286 @@
+#![warn(
+    clippy::all,
+    // clippy::restriction,
+    clippy::pedantic,
+    clippy::nursery,
+    clippy::cargo
+)]
+
+use serde::{Deserialize, Serialize};
+use std::fmt::{Display, Formatter};
+use std::iter::Sum;
+use std::ops::{Add, AddAssign, Sub};
+use std::str::FromStr;
+
+/// The fitness score of a board: the number of duplicated digits across its
+/// rows, columns and boxes.
+///
+/// Lower is fitter, and a board with no duplicates is a solution, so the
+/// ordering of `Fitness` is the ordering of the underlying count: `a < b`
+/// means that `a` is fitter than `b`. Arithmetic saturates rather than
+/// wrapping or panicking.
+#[derive(
+    Debug, Copy, Clone, Default, Eq, PartialEq, Ord, PartialOrd, Hash, Serialize, Deserialize,
+)]
+#[serde(transparent)]
+pub struct Fitness(u32);
+
+impl Fitness {
+    /// The fitness of a solution.
+    pub const ZERO: Self = Self(0);
+
+    /// The worst fitness there is, for boards yet to be scored.
+    pub const MAX: Self = Self(u32::MAX);
+
+    #[inline]
+    #[must_use]
+    pub const fn new(duplicates: u32) -> Self {
+        Self(duplicates)
+    }
+
+    /// Returns the number of duplicates.
+    #[inline]
+    #[must_use]
+    pub const fn get(self) -> u32 {
+        self.0
+    }
+
+    /// Returns true if the board scored is a solution.
+    #[inline]
+    #[must_use]
+    pub const fn is_solution(self) -> bool {
+        self.0 == 0
+    }
+
+    /// Returns true if `self` is strictly fitter than `other`.
+    #[inline]
+    #[must_use]
+    pub const fn is_fitter_than(self, other: Self) -> bool {
+        self.0 < other.0
+    }
+
+    #[inline]
+    #[must_use]
+    pub const fn saturating_add(self, other: Self) -> Self {
+        Self(self.0.saturating_add(other.0))
+    }
+
+    #[inline]
+    #[must_use]
+    pub const fn saturating_sub(self, other: Self) -> Self {
+        Self(self.0.saturating_sub(other.0))
+    }
+}
+
+/// A fitness score broken down into its three objectives: the duplicated
+/// digits across a board's rows, across its columns and across its boxes.
+///
+/// The objectives add up to the board's `Fitness`. Objectives are not
+/// totally ordered; instead one breakdown dominates another if it is at
+/// least as fit in every objective and fitter in one.
+#[derive(Debug, Copy, Clone, Default, Eq, PartialEq, Ord, PartialOrd, Hash, Serialize)]
+pub struct Objectives {
+    pub rows: Fitness,
+    pub columns: Fitness,
+    pub boxes: Fitness,
+}
+
+impl Objectives {
+    /// Returns the objectives as an array, in the order rows, columns,
+    /// boxes.
+    #[inline]
+    #[must_use]
+    pub const fn to_array(self) -> [Fitness; 3] {
+        [self.rows, self.columns, self.boxes]
+    }
+
+    /// Returns the fitness score the objectives add up to.
+    #[inline]
+    #[must_use]
+    pub fn total(self) -> Fitness {
+        self.to_array().into_iter().sum()
+    }
+
+    /// Returns true if `self` is no less fit than `other` in any objective,
+    /// and fitter in at least one.
+    #[inline]
+    #[must_use]
+    pub fn dominates(self, other: Self) -> bool {
+        let (ours, theirs) = (self.to_array(), other.to_array());
+        ours.iter().zip(&theirs).all(|(a, b)| a <= b) && ours != theirs
+    }
+}
+
+impl Display for Objectives {
+    #[inline]
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "rows {}, columns {}, boxes {}",
+            self.rows, self.columns, self.boxes
+        )
+    }
+}
+
+/// How much the duplicates in each kind of unit count towards a weighted
+/// fitness score: 0.0 for not at all, 1.0 for fully, and more to stress a
+/// kind of unit over the others.
+#[derive(Debug, Copy, Clone, PartialEq, Serialize, Deserialize)]
+pub struct UnitWeights {
+    pub rows: f32,
+    pub columns: f32,
+    pub boxes: f32,
+}
+
+impl UnitWeights {
+    /// Every unit counts fully, as in an unweighted fitness score.
+    pub const FULL: Self = Self {
+        rows: 1.0,
+        columns: 1.0,
+        boxes: 1.0,
+    };
+
+    /// Returns whether every weight is finite and not negative, and at
+    /// least one is positive, so that weighted scores still tell candidates
+    /// apart.
+    #[inline]
+    #[must_use]
+    pub fn is_valid(self) -> bool {
+        let weights = [self.rows, self.columns, self.boxes];
+        weights
+            .iter()
+            .all(|weight| weight.is_finite() && *weight >= 0.0)
+            && weights.iter().any(|&weight| weight > 0.0)
+    }
+}
+
+impl Default for UnitWeights {
+    #[inline]
+    fn default() -> Self {
+        Self::FULL
+    }
+}
+
+impl Add for Fitness {
+    type Output = Self;
+
+    /// Adds two scores, saturating at `Fitness::MAX`.
+    #[inline]
+    fn add(self, other: Self) -> Self {
+        self.saturating_add(other)
+    }
+}
+
+impl AddAssign for Fitness {
+    #[inline]
+    fn add_assign(&mut self, other: Self) {
+        *self = *self + other;
+    }
+}
+
+impl Sub for Fitness {
+    type Output = Self;
+
+    /// Subtracts two scores, saturating at `Fitness::ZERO`.
+    #[inline]
+    fn sub(self, other: Self) -> Self {
+        self.saturating_sub(other)
+    }
+}
+
+impl Sum for Fitness {
+    #[inline]
+    fn sum<I: Iterator<Item = Self>>(iter: I) -> Self {
+        iter.fold(Self::ZERO, Add::add)
+    }
+}
+
+impl From<u8> for Fitness {
+    #[inline]
+    fn from(duplicates: u8) -> Self {
+        Self(u32::from(duplicates))
+    }
+}
+
+impl From<Fitness> for f64 {
+    #[inline]
+    fn from(fitness: Fitness) -> Self {
+        Self::from(fitness.0)
+    }
+}
+
+impl Display for Fitness {
+    #[inline]
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        Display::fmt(&self.0, f)
+    }
+}
+
+impl FromStr for Fitness {
+    type Err = String;
+
+    #[inline]
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        s.parse()
+            .map(Self)
+            .map_err(|_| format!("expected a fitness score but got {s:?}"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fitness() {
+        let (a, b) = (Fitness::new(3), Fitness::new(5));
+
+        assert!(a < b);
+        assert!(a.is_fitter_than(b));
+        assert!(!a.is_fitter_than(a));
+        assert!(Fitness::ZERO.is_solution());
+        assert!(!a.is_solution());
+
+        assert_eq!(Fitness::new(8), a + b);
+        assert_eq!(Fitness::ZERO, a - b);
+        assert_eq!(Fitness::new(2), b - a);
+        assert_eq!(Fitness::MAX, Fitness::MAX + a);
+        assert_eq!(Fitness::new(8), [a, b].into_iter().sum());
+
+        assert_eq!("5", b.to_string());
+        assert_eq!(Ok(b), "5".parse());
+        assert!("-1".parse::<Fitness>().is_err());
+    }
+
+    #[test]
+    fn test_objectives() {
+        let objectives = |rows, columns, boxes| Objectives {
+            rows: Fitness::new(rows),
+            columns: Fitness::new(columns),
+            boxes: Fitness::new(boxes),
+        };
+        let a = objectives(1, 2, 3);
+
+        assert_eq!(Fitness::new(6), a.total());
+        assert_eq!("rows 1, columns 2, boxes 3", a.to_string());
+        assert!(objectives(1, 2, 2).dominates(a));
+        assert!(objectives(0, 0, 0).dominates(a));
+        assert!(!a.dominates(a));
+        assert!(!objectives(0, 3, 0).dominates(a));
+        assert!(!a.dominates(objectives(0, 3, 0)));
+    }
+
+    #[test]
+    fn test_unit_weights() {
+        let weights = |rows, columns, boxes| UnitWeights {
+            rows,
+            columns,
+            boxes,
+        };
+        assert!(UnitWeights::FULL.is_valid());
+        assert!(weights(0.0, 0.5, 3.0).is_valid());
+        assert!(!weights(0.0, 0.0, 0.0).is_valid());
+        assert!(!weights(1.0, -0.5, 1.0).is_valid());
+        assert!(!weights(1.0, 1.0, f32::INFINITY).is_valid());
+    }
+}
@@ -0,0 +1,127 @@
+#![warn(
+    clippy::all,
+    // clippy::restriction,
+    clippy::pedantic,
+    clippy::nursery,
+    clippy::cargo
+)]
+
+use super::Board;
+use crate::errors::Contradiction;
+use crate::exact::{candidates, find_single, Hint};
+
+/// A puzzle with its forced cells filled in by [`propagate`].
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Propagation<const N: usize> {
+    /// The puzzle with every forced cell filled in as a given.
+    pub board: Board<N>,
+    /// The cells filled in, in the order they were deduced.
+    pub placed: Vec<Hint>,
+}
+
+/// Fills in the cells of `board` that naked and hidden singles force, over
+/// and over until none are left, so that a solver only has the genuinely
+/// ambiguous cells left to search.
+///
+/// Every digit placed is forced by the givens, so any solution of the
+/// propagated puzzle is a solution of `board`, and the other way round.
+///
+/// # Errors
+///
+/// Fails with the first cell left without candidates, if `board` has no
+/// solution as far as singles can tell.
+///
+/// # Panics
+///
+/// Panics if N is not a supported board size.
+#[inline]
+pub fn propagate<const N: usize>(board: &Board<N>) -> Result<Propagation<N>, Contradiction> {
+    let mut board = *board;
+    let mut placed = Vec::new();
+
+    loop {
+        if let Some((row, col)) = dead_cell(&board) {
+            return Err(Contradiction { row, col });
+        }
+        let Some(hint) = find_single(&board) else {
+            return Ok(Propagation { board, placed });
+        };
+
+        board.0[hint.row].0[hint.col] = hint.digit;
+        placed.push(hint);
+    }
+}
+
+/// Returns the first empty cell of `board` with no candidates, if any.
+fn dead_cell<const N: usize>(board: &Board<N>) -> Option<(usize, usize)> {
+    let candidates = candidates(board);
+
+    (0..N)
+        .flat_map(|row| (0..N).map(move |col| (row, col)))
+        .find(|&(row, col)| board.0[row].0[col] == 0 && candidates[row][col] == 0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::exact::Technique;
+    use crate::sudoku::Row;
+    use crate::testing::SOLVED_9;
+
+    #[test]
+    fn test_propagate() {
+        // With one cell missing from each row, every cell is a naked single.
+        let mut board = SOLVED_9;
+        for (i, row) in board.0.iter_mut().enumerate() {
+            row.0[i] = 0;
+        }
+        let propagation = propagate(&board).unwrap();
+        assert_eq!(SOLVED_9, propagation.board);
+        assert_eq!(9, propagation.placed.len());
+        assert!(propagation
+            .placed
+            .iter()
+            .all(|hint| hint.technique == Technique::NakedSingle));
+
+        // An empty board forces nothing.
+        let empty = Board([Row::default(); 9]);
+        assert_eq!(Ok(empty), propagate(&empty).map(|p| p.board));
+
+        // A solved board is left alone.
+        assert_eq!(Vec::<Hint>::new(), propagate(&SOLVED_9).unwrap().placed);
+    }
+
+    #[test]
+    fn test_propagate_partial() {
+        let board = Board::<9>::parse(
+            "004050000\n900734600\n003021049\n035090480\n090000030\n\
+             076010920\n310970200\n009182003\n000060100\n",
+        )
+        .unwrap();
+        let propagation = propagate(&board).unwrap();
+
+        assert!(!propagation.placed.is_empty());
+        for hint in &propagation.placed {
+            assert_eq!(0, board.0[hint.row].0[hint.col]);
+            assert_eq!(hint.digit, propagation.board.0[hint.row].0[hint.col]);
+        }
+        assert!(propagation
+            .board
+            .violations()
+            .iter()
+            .all(|violation| violation.digit == 0));
+    }
+
+    #[test]
+    fn test_contradiction() {
+        // The first cell cannot hold 1 to 8, which its row holds, nor 9,
+        // which its column holds.
+        let mut board = Board([Row::default(); 9]);
+        board.0[0].0 = [0, 1, 2, 3, 4, 5, 6, 7, 8];
+        board.0[1].0[0] = 9;
+        assert_eq!(
+            Err(Contradiction { row: 0, col: 0 }),
+            propagate(&board).map(|p| p.board)
+        );
+    }
+}
@@ -0,0 +1,160 @@
+#![warn(
+    clippy::all,
+    // clippy::restriction,
+    clippy::pedantic,
+    clippy::nursery,
+    clippy::cargo
+)]
+
+use super::{box_dims, Board, Row};
+use rand::seq::SliceRandom;
+use rand::Rng;
+use std::fmt::{Display, Formatter};
+
+/// A validity-preserving transformation of a board of size N.
+///
+/// Rows are permuted within and between bands, columns within and between
+/// stacks, the digits are relabeled, and the result is optionally
+/// transposed. Any solution of a puzzle maps to a solution of the
+/// transformed puzzle, so a solver should find both equally hard. Boards
+/// whose boxes are not square are never transposed, since that would turn
+/// their boxes on their side.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct Symmetry<const N: usize> {
+    /// Row `r` of the result is row `rows[r]` of the original.
+    rows: [usize; N],
+    /// Column `c` of the result is column `cols[c]` of the original.
+    cols: [usize; N],
+    /// Digit `d` is relabeled as `digits[d - 1]`.
+    digits: [u8; N],
+    transpose: bool,
+}
+
+impl<const N: usize> Symmetry<N> {
+    /// Returns the symmetry that leaves every board unchanged.
+    ///
+    /// # Panics
+    ///
+    /// Panics if N exceeds 255.
+    #[inline]
+    #[must_use]
+    pub fn identity() -> Self {
+        Self {
+            rows: std::array::from_fn(|i| i),
+            cols: std::array::from_fn(|i| i),
+            digits: std::array::from_fn(|i| u8::try_from(i + 1).expect("digit size exceeds 255")),
+            transpose: false,
+        }
+    }
+
+    /// Returns a uniformly random symmetry.
+    ///
+    /// # Panics
+    ///
+    /// Panics if N is not a supported board size.
+    #[inline]
+    #[must_use]
+    pub fn random<R: Rng + ?Sized>(rng: &mut R) -> Self {
+        let mut symmetry = Self::identity();
+
+        let (rows, cols) = box_dims::<N>();
+        symmetry.rows = band_permutation::<N, R>(rng, rows);
+        symmetry.cols = band_permutation::<N, R>(rng, cols);
+        symmetry.digits.shuffle(rng);
+        symmetry.transpose = rows == cols && rng.gen_bool(0.5);
+
+        symmetry
+    }
+
+    /// Applies the symmetry to `board`. Empty cells stay empty.
+    #[inline]
+    #[must_use]
+    pub fn apply(&self, board: &Board<N>) -> Board<N> {
+        let mut transformed = Board([Row([0; N]); N]);
+
+        for (r, &row) in self.rows.iter().enumerate() {
+            for (c, &col) in self.cols.iter().enumerate() {
+                let digit = match board.0[row].0[col] {
+                    0 => 0,
+                    digit => self.digits[usize::from(digit) - 1],
+                };
+
+                if self.transpose {
+                    transformed.0[c].0[r] = digit;
+                } else {
+                    transformed.0[r].0[c] = digit;
+                }
+            }
+        }
+
+        transformed
+    }
+}
+
+impl<const N: usize> Display for Symmetry<N> {
+    #[inline]
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let join = |values: &mut dyn Iterator<Item = String>| values.collect::<Vec<_>>().join(" ");
+
+        write!(
+            f,
+            "Rows: {} | Columns: {} | Digits: {} | Transposed: {}",
+            join(&mut self.rows.iter().map(ToString::to_string)),
+            join(&mut self.cols.iter().map(ToString::to_string)),
+            join(&mut self.digits.iter().map(ToString::to_string)),
+            self.transpose,
+        )
+    }
+}
+
+/// Shuffles the bands of a board, each `width` lines wide, then the lines
+/// within each band.
+fn band_permutation<const N: usize, R: Rng + ?Sized>(rng: &mut R, width: usize) -> [usize; N] {
+    let mut bands: Vec<usize> = (0..N / width).collect();
+    bands.shuffle(rng);
+
+    let mut lines = [0; N];
+    for (chunk, &band) in lines.chunks_mut(width).zip(&bands) {
+        for (k, line) in chunk.iter_mut().enumerate() {
+            *line = band * width + k;
+        }
+        chunk.shuffle(rng);
+    }
+
+    lines
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{inject_violations, SOLVED_4, SOLVED_6, SOLVED_9};
+
+    #[test]
+    fn test_identity() {
+        assert_eq!(SOLVED_9, Symmetry::identity().apply(&SOLVED_9));
+    }
+
+    #[test]
+    fn test_fitness_invariant() {
+        let mut rng = rand::thread_rng();
+
+        for _ in 0..100 {
+            let symmetry = Symmetry::random(&mut rng);
+            assert!(symmetry.apply(&SOLVED_9).fitness().is_solution());
+
+            for k in 0..=9 {
+                let (board, expected) = inject_violations(&SOLVED_9, k, &mut rng);
+                assert_eq!(expected, symmetry.apply(&board).fitness());
+            }
+
+            let (board, expected) = inject_violations(&SOLVED_4, 2, &mut rng);
+            assert_eq!(expected, Symmetry::random(&mut rng).apply(&board).fitness());
+
+            // Boards with 2 by 3 boxes keep them the right way up.
+            let symmetry = Symmetry::random(&mut rng);
+            assert!(!symmetry.transpose);
+            let (board, expected) = inject_violations(&SOLVED_6, 3, &mut rng);
+            assert_eq!(expected, symmetry.apply(&board).fitness());
+        }
+    }
+}
@@ -0,0 +1,411 @@
+#![warn(
+    clippy::all,
+    // clippy::restriction,
+    clippy::pedantic,
+    clippy::nursery,
+    clippy::cargo
+)]
+
+use super::{unit_cell, Unit};
+use arrayvec::ArrayVec;
+
+/// The cells of a unit, as `(row, column)` coordinates.
+pub type UnitCells<const N: usize> = ArrayVec<(usize, usize), N>;
+
+/// A constraint set: the units of a board of size N that must each hold
+/// distinct digits.
+///
+/// The classic set has the rows, columns and boxes. A region map replaces
+/// the boxes with jigsaw regions, and extra regions, such as diagonals or
+/// the cells of killer cages, add `Unit::Region`s after the boxes, numbered
+/// in the order they were added.
+///
+/// # Examples
+///
+/// ```
+/// use genetic_sudoku::sudoku::unit::UnitSet;
+/// use genetic_sudoku::sudoku::Unit;
+///
+/// let units = UnitSet::<4>::classic().with_diagonal();
+/// let (unit, index, cells) = units.units().last().unwrap();
+///
+/// assert_eq!(13, units.units().len());
+/// assert_eq!((Unit::Region, 0), (unit, index));
+/// assert_eq!([(0, 0), (1, 1), (2, 2), (3, 3)], cells.as_slice());
+/// ```
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct UnitSet<const N: usize> {
+    region_map: Option<[[usize; N]; N]>,
+    regions: Vec<Vec<(usize, usize)>>,
+}
+
+impl<const N: usize> UnitSet<N> {
+    /// Returns the set of rows, columns and boxes.
+    #[inline]
+    #[must_use]
+    pub const fn classic() -> Self {
+        Self {
+            region_map: None,
+            regions: Vec::new(),
+        }
+    }
+
+    /// Replaces the boxes with the regions of `region_map`, which gives
+    /// each cell a region label in `0..N`. Regions keep the index of their
+    /// label, and list their cells in row-major order.
+    ///
+    /// # Panics
+    ///
+    /// Panics if a label is not in `0..N`, or is not given to exactly N
+    /// cells.
+    #[inline]
+    #[must_use]
+    pub fn with_region_map(mut self, region_map: [[usize; N]; N]) -> Self {
+        let mut sizes = [0; N];
+        for &label in region_map.iter().flatten() {
+            sizes[label] += 1;
+        }
+        assert!(sizes.iter().all(|&size| size == N), "uneven region map");
+
+        self.region_map = Some(region_map);
+        self
+    }
+
+    /// Adds a region whose cells must hold distinct digits.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the region has more than N cells, or a cell outside the
+    /// board.
+    #[inline]
+    #[must_use]
+    pub fn with_region(mut self, cells: Vec<(usize, usize)>) -> Self {
+        assert!(cells.len() <= N, "region has more than {N} cells");
+        assert!(
+            cells.iter().all(|&(row, col)| row < N && col < N),
+            "region cell outside the board"
+        );
+
+        self.regions.push(cells);
+        self
+    }
+
+    /// Adds the main diagonal, top left to bottom right, as a region.
+    #[inline]
+    #[must_use]
+    pub fn with_diagonal(self) -> Self {
+        self.with_region((0..N).map(|k| (k, k)).collect())
+    }
+
+    /// Adds the anti-diagonal, top right to bottom left, as a region.
+    #[inline]
+    #[must_use]
+    pub fn with_anti_diagonal(self) -> Self {
+        self.with_region((0..N).map(|k| (k, N - 1 - k)).collect())
+    }
+
+    /// Returns true if the set only has the rows, columns and boxes.
+    #[inline]
+    #[must_use]
+    pub fn is_classic(&self) -> bool {
+        self.region_map.is_none() && self.regions.is_empty()
+    }
+
+    /// Iterates over the units of the set.
+    #[inline]
+    #[must_use]
+    pub fn units(&self) -> Units<'_, N> {
+        Units {
+            next: 0,
+            region_map: self.region_map.as_ref(),
+            regions: &self.regions,
+        }
+    }
+}
+
+impl<const N: usize> Default for UnitSet<N> {
+    #[inline]
+    fn default() -> Self {
+        Self::classic()
+    }
+}
+
+/// Iterates over every constraint unit of a board of size N.
+///
+/// Yields each unit's kind and index together with the `(row, column)`
+/// coordinates of its cells: all rows first, then all columns, then all
+/// boxes, then the extra regions of the `UnitSet` iterated, if any. Boxes
+/// are numbered in row-major order, and their cells are listed in row-major
+/// order within the box.
+///
+/// # Examples
+///
+/// ```
+/// use genetic_sudoku::sudoku::unit::Units;
+/// use genetic_sudoku::sudoku::Unit;
+///
+/// let (unit, index, cells) = Units::<4>::new().nth(9).unwrap();
+///
+/// assert_eq!(Unit::Box, unit);
+/// assert_eq!(1, index);
+/// assert_eq!([(0, 2), (0, 3), (1, 2), (1, 3)], cells.as_slice());
+/// ```
+#[derive(Debug, Clone)]
+pub struct Units<'a, const N: usize> {
+    next: usize,
+    region_map: Option<&'a [[usize; N]; N]>,
+    regions: &'a [Vec<(usize, usize)>],
+}
+
+impl<const N: usize> Units<'_, N> {
+    const KINDS: [Unit; 3] = [Unit::Row, Unit::Column, Unit::Box];
+
+    /// Iterates over the classic units: the rows, columns and boxes.
+    #[inline]
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            next: 0,
+            region_map: None,
+            regions: &[],
+        }
+    }
+}
+
+impl<const N: usize> Default for Units<'_, N> {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<const N: usize> Iterator for Units<'_, N> {
+    type Item = (Unit, usize, UnitCells<N>);
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        let (unit, index) = match Self::KINDS.get(self.next / N) {
+            Some(&unit) => (unit, self.next % N),
+            None => (Unit::Region, self.next - Self::KINDS.len() * N),
+        };
+        let cells = match (unit, self.region_map) {
+            (Unit::Region, _) => self.regions.get(index)?.iter().copied().collect(),
+            (Unit::Box, Some(region_map)) => region_cells(region_map, index),
+            _ => UnitCells::from(cells::<N>(unit, index)),
+        };
+        self.next += 1;
+
+        Some((unit, index, cells))
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = Self::KINDS.len() * N + self.regions.len() - self.next;
+
+        (remaining, Some(remaining))
+    }
+}
+
+impl<const N: usize> ExactSizeIterator for Units<'_, N> {}
+
+fn region_cells<const N: usize>(region_map: &[[usize; N]; N], label: usize) -> UnitCells<N> {
+    (0..N)
+        .flat_map(|row| (0..N).map(move |col| (row, col)))
+        .filter(|&(row, col)| region_map[row][col] == label)
+        .collect()
+}
+
+/// Returns the `(row, column)` coordinates of every cell of a unit.
+///
+/// # Panics
+///
+/// Panics if N is not a supported board size and `unit` is a box, or if
+/// `unit` is a region, whose cells only its `UnitSet` knows.
+#[inline]
+#[must_use]
+pub fn cells<const N: usize>(unit: Unit, index: usize) -> [(usize, usize); N] {
+    std::array::from_fn(|k| unit_cell::<N>(unit, index, k))
+}
+
+/// Tracks the digits seen within a single unit (row, column or box) of a
+/// board of size N.
+///
+/// Seen digits are tracked in a `u64` bitmask indexed by digit, so N must be
+/// less than 64. This is checked at compile time when a `UnitScorer` is
+/// created, and digits greater than N are rejected in debug builds.
+///
+/// # Examples
+///
+/// ```
+/// use genetic_sudoku::sudoku::unit::UnitScorer;
+///
+/// let mut scorer = UnitScorer::<4>::new();
+/// for digit in [1, 2, 2, 4] {
+///     scorer.check(digit);
+/// }
+///
+/// assert_eq!(1, scorer.score());
+/// assert_eq!(vec![3], scorer.missing_digits().collect::<Vec<_>>());
+/// assert_eq!(vec![2], scorer.duplicate_digits().collect::<Vec<_>>());
+/// ```
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct UnitScorer<const N: usize> {
+    seen: u64,
+    duplicated: u64,
+    score: u8,
+}
+
+impl<const N: usize> UnitScorer<N> {
+    const BOUND: () = assert!(N < u64::BITS as usize, "UnitScorer supports N < 64");
+
+    #[inline]
+    #[must_use]
+    pub const fn new() -> Self {
+        #[allow(clippy::let_unit_value)]
+        let () = Self::BOUND;
+
+        Self {
+            seen: 0,
+            duplicated: 0,
+            score: 0,
+        }
+    }
+
+    /// Records a digit, counting it as a duplicate if it was already seen.
+    #[inline]
+    pub fn check(&mut self, digit: u8) {
+        debug_assert!(usize::from(digit) <= N, "digit out of range for board");
+
+        let bit = 1 << digit;
+        if self.seen & bit != 0 {
+            self.score += 1;
+            self.duplicated |= bit;
+        }
+        self.seen |= bit;
+    }
+
+    /// Returns the number of duplicated digits seen.
+    #[inline]
+    #[must_use]
+    pub const fn score(self) -> u8 {
+        self.score
+    }
+
+    /// Returns the digits 1 to N that have not been seen, in ascending order.
+    #[inline]
+    pub fn missing_digits(self) -> impl Iterator<Item = u8> {
+        digits::<N>().filter(move |digit| self.seen & (1 << digit) == 0)
+    }
+
+    /// Returns the digits that were seen more than once, in ascending order.
+    #[inline]
+    pub fn duplicate_digits(self) -> impl Iterator<Item = u8> {
+        (0..=max_digit::<N>()).filter(move |digit| self.duplicated & (1 << digit) != 0)
+    }
+}
+
+impl<const N: usize> Default for UnitScorer<N> {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn max_digit<const N: usize>() -> u8 {
+    u8::try_from(N).expect("digit size exceeds 255")
+}
+
+fn digits<const N: usize>() -> impl Iterator<Item = u8> {
+    1..=max_digit::<N>()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_units() {
+        let units: Vec<_> = Units::<9>::new().collect();
+
+        assert_eq!(27, units.len());
+        assert_eq!(27, Units::<9>::default().len());
+
+        for (unit, index, cells) in units {
+            let mut seen = [[false; 9]; 9];
+
+            for (row, col) in cells {
+                assert!(!seen[row][col], "{unit:?} {index} repeats a cell");
+                seen[row][col] = true;
+
+                match unit {
+                    Unit::Row => assert_eq!(index, row),
+                    Unit::Column => assert_eq!(index, col),
+                    Unit::Box => assert_eq!(index, (row / 3) * 3 + col / 3),
+                    Unit::Region => panic!("classic units have no regions"),
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_unit_set() {
+        let region_map = [[0, 0, 1, 1], [0, 2, 2, 1], [0, 2, 3, 1], [2, 3, 3, 3]];
+        let units = UnitSet::<4>::classic()
+            .with_region_map(region_map)
+            .with_anti_diagonal()
+            .with_region(vec![(0, 0), (3, 3)]);
+        let all: Vec<_> = units.units().collect();
+
+        assert!(!units.is_classic());
+        assert!(UnitSet::<4>::default().is_classic());
+        assert_eq!(14, all.len());
+        assert_eq!(14, units.units().len());
+        assert_eq!(
+            (Unit::Box, 2, [(1, 1), (1, 2), (2, 1), (3, 0)].as_slice()),
+            (all[10].0, all[10].1, all[10].2.as_slice())
+        );
+        assert_eq!(
+            (Unit::Region, 0, [(0, 3), (1, 2), (2, 1), (3, 0)].as_slice()),
+            (all[12].0, all[12].1, all[12].2.as_slice())
+        );
+        assert_eq!(
+            (Unit::Region, 1, [(0, 0), (3, 3)].as_slice()),
+            (all[13].0, all[13].1, all[13].2.as_slice())
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "uneven region map")]
+    fn test_uneven_region_map() {
+        let _ = UnitSet::<4>::classic().with_region_map([[0; 4]; 4]);
+    }
+
+    #[test]
+    fn test_missing_and_duplicate_digits() {
+        let mut scorer = UnitScorer::<9>::new();
+
+        for digit in [5, 3, 5, 9, 1, 1, 1, 7, 3] {
+            scorer.check(digit);
+        }
+
+        assert_eq!(4, scorer.score());
+        assert_eq!(
+            vec![2, 4, 6, 8],
+            scorer.missing_digits().collect::<Vec<_>>()
+        );
+        assert_eq!(vec![1, 3, 5], scorer.duplicate_digits().collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn test_complete_unit() {
+        let mut scorer = UnitScorer::<4>::default();
+
+        for digit in [4, 2, 3, 1] {
+            scorer.check(digit);
+        }
+
+        assert_eq!(0, scorer.score());
+        assert_eq!(None, scorer.missing_digits().next());
+        assert_eq!(None, scorer.duplicate_digits().next());
+    }
+}
@@ -0,0 +1,136 @@
+#![warn(
+    clippy::all,
+    // clippy::restriction,
+    clippy::pedantic,
+    clippy::nursery,
+    clippy::cargo
+)]
+
+use super::sudoku::{Board, Fitness};
+use std::collections::{HashSet, VecDeque};
+
+/// A bounded archive of the best boards of past generations, which children
+/// are kept from revisiting so that the search does not cycle back through
+/// the same local optima.
+///
+/// Boards are archived with the givens of the puzzle overlaid, so that
+/// candidates differing only in given cells count as the same. Once the
+/// archive is full, the oldest board makes way for the next.
+#[derive(Debug, Clone)]
+pub struct TabuArchive<const N: usize> {
+    capacity: usize,
+    boards: HashSet<Board<N>>,
+    order: VecDeque<Board<N>>,
+}
+
+impl<const N: usize> TabuArchive<N> {
+    /// Returns an empty archive holding up to `capacity` boards.
+    #[inline]
+    #[must_use]
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            boards: HashSet::with_capacity(capacity),
+            order: VecDeque::with_capacity(capacity),
+        }
+    }
+
+    /// Returns the number of boards archived.
+    #[inline]
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.order.len()
+    }
+
+    /// Returns true if no board is archived.
+    #[inline]
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.order.is_empty()
+    }
+
+    /// Returns whether `board`, with the givens of `base` overlaid, is
+    /// archived.
+    #[inline]
+    #[must_use]
+    pub fn contains(&self, base: &Board<N>, board: &Board<N>) -> bool {
+        self.boards.contains(&base.overlay(board))
+    }
+
+    /// Archives `board`, with the givens of `base` overlaid, unless it is
+    /// already archived.
+    #[inline]
+    pub fn insert(&mut self, base: &Board<N>, board: &Board<N>) {
+        if self.capacity == 0 {
+            return;
+        }
+
+        let board = base.overlay(board);
+        if !self.boards.insert(board) {
+            return;
+        }
+        if self.order.len() == self.capacity {
+            if let Some(oldest) = self.order.pop_front() {
+                self.boards.remove(&oldest);
+            }
+        }
+        self.order.push_back(board);
+    }
+
+    /// Archives the fittest candidate of a scored generation, looked up with
+    /// `board`.
+    #[inline]
+    pub fn record(
+        &mut self,
+        base: &Board<N>,
+        board: impl Fn(usize) -> Board<N>,
+        scores: &[Fitness],
+    ) {
+        if let Some((best, _)) = scores.iter().enumerate().min_by_key(|(_, score)| **score) {
+            self.insert(base, &board(best));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::SOLVED_9;
+
+    #[test]
+    fn test_tabu_archive() {
+        let mut base = SOLVED_9;
+        base.0[8].0.fill(0);
+        let mut boards = [SOLVED_9; 3];
+        for (i, board) in boards.iter_mut().enumerate() {
+            board.0[8].0.swap(0, i + 1);
+        }
+
+        let mut archive = TabuArchive::new(2);
+        assert!(archive.is_empty());
+        archive.insert(&base, &boards[0]);
+        archive.insert(&base, &boards[0]);
+        assert_eq!(1, archive.len());
+
+        // Givens are overlaid, so a board differing only in them matches.
+        let mut other = boards[0];
+        other.0[0].0[0] = 9;
+        assert!(archive.contains(&base, &other));
+
+        archive.insert(&base, &boards[1]);
+        archive.insert(&base, &boards[2]);
+        assert_eq!(2, archive.len());
+        assert!(!archive.contains(&base, &boards[0]));
+        assert!(archive.contains(&base, &boards[2]));
+
+        let scores = [Fitness::new(4), Fitness::new(2), Fitness::new(3)];
+        let mut archive = TabuArchive::new(2);
+        archive.record(&base, |i| boards[i], &scores);
+        assert!(archive.contains(&base, &boards[1]));
+        assert_eq!(1, archive.len());
+
+        let mut disabled = TabuArchive::new(0);
+        disabled.insert(&base, &boards[0]);
+        assert!(disabled.is_empty());
+    }
+}
@@ -0,0 +1,222 @@
+#![warn(
+    clippy::all,
+    // clippy::restriction,
+    clippy::pedantic,
+    clippy::nursery,
+    clippy::cargo
+)]
+
+use super::errors::NoSolutionFound;
+use super::genetics::{next_generation, score_population_with, DefaultRng, GAParams};
+use super::profile::PhaseTimer;
+use super::sudoku::{Board, Fitness};
+
+/// Runs one generation of parallel tempering.
+///
+/// Each replica is a subpopulation evolving under its own GA parameters,
+/// ordered from the lowest "temperature" (mutation rate) to the highest.
+/// Every `swap_interval` generations, elites migrate between adjacent
+/// replicas: a copy of the best candidate of each replica replaces the
+/// worst candidate of its neighbours. This is elitist migration rather than
+/// a Metropolis replica exchange, so no move is ever rejected. Low
+/// temperatures intensify the search around good candidates while high
+/// temperatures keep diversifying it.
+///
+/// # Arguments
+///
+/// * `replicas` - GA parameters of each replica, by increasing temperature
+/// * `swap_interval` - the number of generations between elite migrations
+/// * `generation` - the current generation counter
+/// * `base` - The base Board to find solutions for
+/// * `populations` - The population of each replica
+/// * `scores` - Receives the fitness scores of each replica
+///
+/// # Errors
+///
+/// Will return `Err(NoSolutionFound)` if no replica found a valid solution.
+/// In that case every population has been replaced in place by its next
+/// generation.
+///
+/// # Panics
+///
+/// Panics if `replicas`, `populations` and `scores` differ in length.
+#[inline]
+pub fn run_tempering<const N: usize, const M: usize>(
+    replicas: &[GAParams],
+    swap_interval: u64,
+    generation: u64,
+    base: &Board<N>,
+    populations: &mut [Vec<Board<N>>],
+    scores: &mut [Vec<Fitness>],
+) -> Result<Board<N>, NoSolutionFound> {
+    assert_eq!(replicas.len(), populations.len());
+    assert_eq!(replicas.len(), scores.len());
+
+    let pairs = replicas.iter().zip(populations.iter_mut());
+    for ((params, population), scores) in pairs.zip(scores.iter_mut()) {
+        if let Some(i) = score_population_with(params, base, population, scores) {
+            return Ok(population[i]);
+        }
+    }
+
+    evolve_replicas::<N, M>(
+        replicas,
+        swap_interval,
+        generation,
+        base,
+        populations,
+        scores,
+    );
+
+    Err(NoSolutionFound)
+}
+
+/// Migrates elites if due, then replaces every scored replica population
+/// with its next generation.
+///
+/// If elites migrated, returns for each replica how many of the elites it
+/// received were fitter than the candidates they replaced, and how many it
+/// received.
+pub(crate) fn evolve_replicas<const N: usize, const M: usize>(
+    replicas: &[GAParams],
+    swap_interval: u64,
+    generation: u64,
+    base: &Board<N>,
+    populations: &mut [Vec<Board<N>>],
+    scores: &mut [Vec<Fitness>],
+) -> Option<Vec<(u64, u64)>> {
+    let swaps = (swap_interval != 0 && generation != 0 && generation % swap_interval == 0)
+        .then(|| migrate_elites(populations, scores));
+
+    for ((params, population), scores) in replicas.iter().zip(populations).zip(scores.iter()) {
+        next_generation::<N, M, DefaultRng>(
+            params,
+            base,
+            generation,
+            population,
+            scores,
+            None,
+            None,
+            &mut PhaseTimer::new(false),
+        );
+    }
+
+    swaps
+}
+
+/// Copies the best candidate of each replica over the worst candidate of
+/// each adjacent replica, updating `scores` to match, and returns for each
+/// replica how many of the elites it received were fitter than the
+/// candidates they replaced, and how many it received.
+fn migrate_elites<const N: usize>(
+    populations: &mut [Vec<Board<N>>],
+    scores: &mut [Vec<Fitness>],
+) -> Vec<(u64, u64)> {
+    let mut swaps = vec![(0, 0); populations.len()];
+    let elites: Vec<(Board<N>, Fitness)> = populations
+        .iter()
+        .zip(scores.iter())
+        .map(|(population, scores)| {
+            let best = scores
+                .iter()
+                .enumerate()
+                .min_by_key(|(_, score)| **score)
+                .map_or(0, |(i, _)| i);
+            (population[best], scores[best])
+        })
+        .collect();
+
+    for i in 0..populations.len() {
+        for neighbour in [i.checked_sub(1), Some(i + 1)].into_iter().flatten() {
+            if let Some(&(elite, elite_score)) = elites.get(neighbour) {
+                let worst = scores[i]
+                    .iter()
+                    .enumerate()
+                    .max_by_key(|(_, score)| **score)
+                    .map_or(0, |(i, _)| i);
+                swaps[i].0 += u64::from(elite_score < scores[i][worst]);
+                swaps[i].1 += 1;
+                populations[i][worst] = elite;
+                scores[i][worst] = elite_score;
+            }
+        }
+    }
+
+    swaps
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::genetics::initial_population;
+    use crate::testing::SOLVED_9;
+
+    #[test]
+    fn test_migrate_elites() {
+        // Boards are told apart by their first cell.
+        let board = |id: u8| {
+            let mut board = SOLVED_9;
+            board.0[0].0[0] = id;
+            board
+        };
+        let mut populations: Vec<Vec<Board<9>>> = (0..3)
+            .map(|replica| (0..3).map(|i| board(replica * 3 + i)).collect())
+            .collect();
+        let fitness = |scores: [u32; 3]| scores.map(Fitness::new).to_vec();
+        let mut scores = vec![fitness([1, 7, 3]), fitness([5, 6, 9]), fitness([2, 4, 3])];
+
+        let swaps = migrate_elites(&mut populations, &mut scores);
+
+        // The middle replica receives both its neighbours' elites, and the
+        // last one an elite less fit than the candidate it replaces.
+        assert_eq!(vec![(1, 1), (2, 2), (0, 1)], swaps);
+        assert_eq!(
+            vec![
+                vec![board(0), board(3), board(2)],
+                vec![board(3), board(6), board(0)],
+                vec![board(6), board(3), board(8)],
+            ],
+            populations
+        );
+        assert_eq!(
+            vec![fitness([1, 5, 3]), fitness([5, 2, 1]), fitness([2, 5, 3])],
+            scores
+        );
+    }
+
+    #[test]
+    fn test_run_tempering() {
+        let mut base = SOLVED_9;
+        for i in 0..9 {
+            base.0[i].0[(i * 4) % 9] = 0;
+        }
+        let replicas = [
+            GAParams::new(40, 0.5, 0.01, None).unwrap().with_seed(1),
+            GAParams::new(40, 0.5, 0.2, None).unwrap().with_seed(2),
+        ];
+        let mut populations: Vec<Vec<Board<9>>> = replicas
+            .iter()
+            .map(|params| initial_population::<9, 40, DefaultRng>(params, &base, 0))
+            .collect();
+        let mut scores = vec![Vec::new(); 2];
+
+        let solution = (0..200)
+            .find_map(|generation| {
+                let result = run_tempering::<9, 40>(
+                    &replicas,
+                    5,
+                    generation,
+                    &base,
+                    &mut populations,
+                    &mut scores,
+                );
+                for population in &populations {
+                    assert_eq!(40, population.len());
+                }
+                result.ok()
+            })
+            .expect("no replica solved the puzzle");
+
+        assert_eq!(SOLVED_9, base.overlay(&solution));
+    }
+}
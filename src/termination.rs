@@ -0,0 +1,91 @@
+#![warn(
+    clippy::all,
+    // clippy::restriction,
+    clippy::pedantic,
+    clippy::nursery,
+    clippy::cargo
+)]
+
+use serde::Serialize;
+use std::fmt;
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+
+/// Why a run ended.
+///
+/// Each reason maps to a distinct process exit code so that scripts can tell
+/// an unsolved puzzle from a failure: an error, such as an unreadable board
+/// or invalid arguments, exits with code 1, and a panic with code 101.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Termination {
+    /// A valid solution was found.
+    Solved,
+    /// The run stopped after using up its generation or time budget.
+    BudgetExhausted,
+    /// The run stopped once its best candidate reached the target fitness
+    /// score of its budget.
+    TargetReached,
+    /// The run was cancelled before finding a solution.
+    Cancelled,
+    /// The puzzle is malformed or its givens conflict with each other.
+    InvalidPuzzle,
+    /// The puzzle does not have exactly one solution.
+    NoUniqueSolution,
+}
+
+impl Termination {
+    /// Returns the process exit code for this termination reason.
+    #[inline]
+    #[must_use]
+    pub const fn exit_code(self) -> u8 {
+        match self {
+            Self::Solved => 0,
+            Self::BudgetExhausted => 2,
+            Self::Cancelled => 3,
+            Self::InvalidPuzzle => 4,
+            Self::NoUniqueSolution => 5,
+            Self::TargetReached => 6,
+        }
+    }
+
+    /// Returns the machine-readable name of this termination reason.
+    #[inline]
+    #[must_use]
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Solved => "solved",
+            Self::BudgetExhausted => "budget-exhausted",
+            Self::Cancelled => "cancelled",
+            Self::InvalidPuzzle => "invalid-puzzle",
+            Self::NoUniqueSolution => "no-unique-solution",
+            Self::TargetReached => "target-reached",
+        }
+    }
+}
+
+impl Display for Termination {
+    #[inline]
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for Termination {
+    type Err = String;
+
+    #[inline]
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        [
+            Self::Solved,
+            Self::BudgetExhausted,
+            Self::Cancelled,
+            Self::InvalidPuzzle,
+            Self::NoUniqueSolution,
+            Self::TargetReached,
+        ]
+        .into_iter()
+        .find(|termination| termination.as_str() == s)
+        .ok_or_else(|| format!("unknown termination reason {s:?}"))
+    }
+}
@@ -0,0 +1,141 @@
+#![warn(
+    clippy::all,
+    // clippy::restriction,
+    clippy::pedantic,
+    clippy::nursery,
+    clippy::cargo
+)]
+
+use super::sudoku::{box_dims, Board, Fitness, Row};
+use rand::seq::index::sample;
+use rand::Rng;
+
+/// A solved 4x4 board.
+pub const SOLVED_4: Board<4> = Board([
+    Row([1, 2, 3, 4]),
+    Row([3, 4, 1, 2]),
+    Row([4, 3, 2, 1]),
+    Row([2, 1, 4, 3]),
+]);
+
+/// A solved 6x6 board, with boxes of 2 rows by 3 columns.
+pub const SOLVED_6: Board<6> = Board([
+    Row([1, 2, 3, 4, 5, 6]),
+    Row([4, 5, 6, 1, 2, 3]),
+    Row([2, 3, 4, 5, 6, 1]),
+    Row([5, 6, 1, 2, 3, 4]),
+    Row([3, 4, 5, 6, 1, 2]),
+    Row([6, 1, 2, 3, 4, 5]),
+]);
+
+/// A solved 9x9 board.
+pub const SOLVED_9: Board<9> = Board([
+    Row([1, 2, 3, 4, 5, 6, 7, 8, 9]),
+    Row([4, 5, 6, 7, 8, 9, 1, 2, 3]),
+    Row([7, 8, 9, 1, 2, 3, 4, 5, 6]),
+    Row([2, 3, 4, 5, 6, 7, 8, 9, 1]),
+    Row([5, 6, 7, 8, 9, 1, 2, 3, 4]),
+    Row([8, 9, 1, 2, 3, 4, 5, 6, 7]),
+    Row([3, 4, 5, 6, 7, 8, 9, 1, 2]),
+    Row([6, 7, 8, 9, 1, 2, 3, 4, 5]),
+    Row([9, 1, 2, 3, 4, 5, 6, 7, 8]),
+]);
+
+/// Injects `k` random violations into a solved board.
+///
+/// Returns the corrupted board together with its expected fitness score.
+/// Each violation overwrites one cell with a different digit. The cells are
+/// chosen so that no two share a row, column or box, which means every
+/// violation adds exactly one duplicate to each of the three units it
+/// touches, and the expected fitness is `3 * k`.
+///
+/// # Arguments
+///
+/// * `solved` - A valid, fully filled in Board
+/// * `k` - The number of violations to inject
+/// * `rng` - The random number generator to use
+///
+/// # Panics
+///
+/// Panics if `k` is greater than N, or if N is not a supported board size.
+#[inline]
+#[must_use]
+pub fn inject_violations<const N: usize, R: Rng + ?Sized>(
+    solved: &Board<N>,
+    k: usize,
+    rng: &mut R,
+) -> (Board<N>, Fitness) {
+    assert!(k <= N, "cannot inject more than N independent violations");
+
+    let (rows, cols) = box_dims::<N>();
+    let max_digit = u8::try_from(N).expect("digit size exceeds 255");
+    let mut board = *solved;
+
+    // Cell i of this pattern sits in row i and box (i / rows, i % rows), so
+    // the cells share no row, column or box.
+    for i in sample(rng, N, k) {
+        let col = (i % rows) * cols + i / rows;
+        let cell = &mut board.0[i].0[col];
+        let replacement = rng.gen_range(1..max_digit);
+        *cell = if replacement >= *cell {
+            replacement + 1
+        } else {
+            replacement
+        };
+    }
+
+    let expected = Fitness::new(u32::try_from(3 * k).expect("fitness exceeds u32::MAX"));
+
+    (board, expected)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_solved_boards() {
+        assert!(SOLVED_4.fitness().is_solution());
+        assert!(SOLVED_6.fitness().is_solution());
+        assert!(SOLVED_9.fitness().is_solution());
+    }
+
+    #[test]
+    fn test_inject_violations() {
+        let mut rng = rand::thread_rng();
+
+        for _ in 0..100 {
+            for k in 0..=4 {
+                let (board, expected) = inject_violations(&SOLVED_4, k, &mut rng);
+                assert_eq!(expected, board.fitness());
+            }
+
+            for k in 0..=6 {
+                let (board, expected) = inject_violations(&SOLVED_6, k, &mut rng);
+                assert_eq!(expected, board.fitness());
+            }
+
+            for k in 0..=9 {
+                let (board, expected) = inject_violations(&SOLVED_9, k, &mut rng);
+                assert_eq!(expected, board.fitness());
+            }
+        }
+    }
+
+    #[test]
+    fn test_violations_match_fitness() {
+        let mut rng = rand::thread_rng();
+
+        for k in 0..=9 {
+            let (board, expected) = inject_violations(&SOLVED_9, k, &mut rng);
+            let violations = board.violations();
+            let duplicates: u32 = violations
+                .iter()
+                .map(|v| u32::try_from(v.cells.len() - 1).unwrap())
+                .sum();
+
+            assert_eq!(3 * k, violations.len());
+            assert_eq!(expected.get(), duplicates);
+        }
+    }
+}
@@ -0,0 +1,138 @@
+#![warn(
+    clippy::all,
+    // clippy::restriction,
+    clippy::pedantic,
+    clippy::nursery,
+    clippy::cargo
+)]
+
+use super::cancel::CancellationToken;
+use super::solver::Observer;
+use notify::{Event, EventKind, RecommendedWatcher, RecursiveMode, Watcher};
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::thread::sleep;
+use std::time::Duration;
+
+/// How long a save takes to settle: the events that follow a change within
+/// this time count as the same change.
+const SETTLE: Duration = Duration::from_millis(100);
+
+/// Watches a file for changes, such as a puzzle being edited.
+///
+/// The file's directory is watched rather than the file itself, so that
+/// editors that save by replacing the file are noticed too. As an observer,
+/// it cancels the run in progress once the file changes.
+pub struct FileWatcher {
+    // Watching stops when the watcher is dropped.
+    _watcher: RecommendedWatcher,
+    changed: Arc<AtomicBool>,
+}
+
+impl FileWatcher {
+    /// Starts watching the file at `path`.
+    ///
+    /// # Errors
+    ///
+    /// Fails if the file's directory cannot be watched.
+    #[inline]
+    pub fn new(path: &Path) -> notify::Result<Self> {
+        let changed = Arc::new(AtomicBool::new(false));
+        let name = path.file_name().map(ToOwned::to_owned);
+        let flag = Arc::clone(&changed);
+
+        let mut watcher = notify::recommended_watcher(move |event: notify::Result<Event>| {
+            let Ok(event) = event else {
+                return;
+            };
+            let touches_file = event
+                .paths
+                .iter()
+                .any(|path| path.file_name() == name.as_deref());
+            if touches_file && !matches!(event.kind, EventKind::Access(_)) {
+                flag.store(true, Ordering::SeqCst);
+            }
+        })?;
+
+        let directory = match path.parent() {
+            Some(parent) if !parent.as_os_str().is_empty() => parent,
+            _ => Path::new("."),
+        };
+        watcher.watch(directory, RecursiveMode::NonRecursive)?;
+
+        Ok(Self {
+            _watcher: watcher,
+            changed,
+        })
+    }
+
+    /// Returns true if the file has changed since the last call, once the
+    /// change has settled.
+    #[inline]
+    #[must_use]
+    pub fn take_change(&self) -> bool {
+        if !self.changed.load(Ordering::SeqCst) {
+            return false;
+        }
+
+        sleep(SETTLE);
+        self.changed.store(false, Ordering::SeqCst);
+        true
+    }
+
+    /// Blocks until the file changes, and returns true, or until
+    /// `cancellation` is cancelled, and returns false.
+    #[inline]
+    #[must_use]
+    pub fn wait_for_change(&self, cancellation: &CancellationToken) -> bool {
+        while !self.take_change() {
+            if cancellation.is_cancelled() {
+                return false;
+            }
+            sleep(SETTLE);
+        }
+        true
+    }
+}
+
+impl<const N: usize> Observer<N> for FileWatcher {
+    #[inline]
+    fn needs_population(&self) -> bool {
+        false
+    }
+
+    #[inline]
+    fn is_cancelled(&self) -> bool {
+        self.changed.load(Ordering::SeqCst)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Instant;
+
+    #[test]
+    fn test_file_watcher() {
+        let directory = std::env::temp_dir().join(format!("watch-{}", std::process::id()));
+        std::fs::create_dir_all(&directory).unwrap();
+        let path = directory.join("board.txt");
+        std::fs::write(&path, "1").unwrap();
+
+        let watcher = FileWatcher::new(&path).unwrap();
+        assert!(!watcher.take_change());
+        assert!(!Observer::<4>::is_cancelled(&watcher));
+
+        std::fs::write(directory.join("other.txt"), "2").unwrap();
+        std::fs::write(&path, "2").unwrap();
+        let start = Instant::now();
+        while !Observer::<4>::is_cancelled(&watcher) && start.elapsed() < Duration::from_secs(5) {
+            sleep(SETTLE);
+        }
+        assert!(watcher.take_change());
+        assert!(!watcher.take_change());
+
+        std::fs::remove_dir_all(directory).unwrap();
+    }
+}
@@ -0,0 +1,269 @@
+#![warn(
+    clippy::all,
+    // clippy::restriction,
+    clippy::pedantic,
+    clippy::nursery,
+    clippy::cargo
+)]
+
+use super::solver::{Observer, Outcome, Progress};
+use super::sudoku::Fitness;
+use super::termination::Termination;
+use serde::Serialize;
+use std::fmt::Write;
+use std::time::{Duration, Instant};
+
+/// A notification sent to a webhook, as a JSON object tagged by its
+/// `"event"`. The `"text"` field summarizes it in a sentence, which chat
+/// services with Slack-compatible webhooks show as the message.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "event", rename_all = "kebab-case")]
+pub enum Notification {
+    /// How far a run has got.
+    Progress {
+        text: String,
+        /// The run the notification is about, counting from 0, for modes
+        /// that solve more than once.
+        run: u32,
+        generation: u64,
+        elapsed_secs: f64,
+        /// The best fitness score so far, if any generation was evaluated.
+        best: Option<Fitness>,
+        /// The fraction of the budget used, if the run has a budget.
+        budget_used: Option<f64>,
+    },
+    /// How a run ended.
+    Finished {
+        text: String,
+        run: u32,
+        termination: Termination,
+        generation: u64,
+        elapsed_secs: f64,
+        best: Option<Fitness>,
+        /// The solution, one row per line, if the run was solved.
+        solution: Option<String>,
+    },
+}
+
+impl Notification {
+    fn progress(run: u32, progress: &Progress) -> Self {
+        let mut text = format!(
+            "Run {run}: generation {}, {:.0?} elapsed",
+            progress.generation, progress.elapsed
+        );
+        if let Some(best) = progress.best {
+            let _ = write!(text, ", best fitness {best}");
+        }
+
+        Self::Progress {
+            text,
+            run,
+            generation: progress.generation,
+            elapsed_secs: progress.elapsed.as_secs_f64(),
+            best: progress.best,
+            budget_used: progress.budget_used,
+        }
+    }
+
+    fn finished<const N: usize>(run: u32, outcome: &Outcome<N>) -> Self {
+        let best = outcome.best.as_ref().map(|&(_, score)| score);
+        let mut text = format!(
+            "Run {run}: {} at generation {} after {:.0?}",
+            outcome.termination, outcome.generation, outcome.duration
+        );
+        if let (None, Some(best)) = (outcome.solution(), best) {
+            let _ = write!(text, ", best fitness {best}");
+        }
+
+        Self::Finished {
+            text,
+            run,
+            termination: outcome.termination,
+            generation: outcome.generation,
+            elapsed_secs: outcome.duration.as_secs_f64(),
+            best,
+            solution: outcome.solution().map(ToString::to_string),
+        }
+    }
+}
+
+/// Delivers the JSON of a notification.
+type Poster = Box<dyn FnMut(&str) -> std::io::Result<()>>;
+
+/// An observer that POSTs the progress of long runs to a webhook, such as a
+/// chat channel or a job tracker, at most once per interval, and the result
+/// of each run when it ends.
+///
+/// Observers cannot fail, so the first error a POST returns is kept and
+/// reported by `finish_run`; no more progress is sent after it, though the
+/// result still is.
+pub struct Webhook {
+    post: Poster,
+    interval: Duration,
+    run: u32,
+    last: Option<Instant>,
+    error: Option<std::io::Error>,
+}
+
+impl Webhook {
+    /// How often progress is sent by default.
+    pub const DEFAULT_INTERVAL: Duration = Duration::from_secs(60);
+
+    /// Returns a webhook that POSTs each notification to `url` as JSON.
+    ///
+    /// # Arguments
+    ///
+    /// * `url` - the `http://` or `https://` URL of the webhook
+    /// * `interval` - the least time between progress notifications
+    #[cfg(feature = "http")]
+    #[inline]
+    #[must_use]
+    pub fn new(url: &str, interval: Duration) -> Self {
+        let url = url.to_owned();
+        Self::with_poster(interval, move |body| {
+            ureq::post(&url)
+                .set("Content-Type", "application/json")
+                .send_string(body)
+                .map(drop)
+                .map_err(std::io::Error::other)
+        })
+    }
+
+    /// Returns a webhook that hands the JSON of each notification to
+    /// `post`, such as to deliver it some other way than over HTTP.
+    #[inline]
+    #[must_use]
+    pub fn with_poster(
+        interval: Duration,
+        post: impl FnMut(&str) -> std::io::Result<()> + 'static,
+    ) -> Self {
+        Self {
+            post: Box::new(post),
+            interval,
+            run: 0,
+            last: None,
+            error: None,
+        }
+    }
+
+    /// Sends the result of a run, and moves on to the next. Progress of the
+    /// next run is sent as soon as it starts.
+    ///
+    /// # Errors
+    ///
+    /// Fails with the first error a POST has returned since the last call,
+    /// or if the result cannot be sent.
+    #[inline]
+    pub fn finish_run<const N: usize>(&mut self, outcome: &Outcome<N>) -> std::io::Result<()> {
+        let sent = self.send(&Notification::finished(self.run, outcome));
+        self.run += 1;
+        self.last = None;
+
+        self.error.take().map_or(sent, Err)
+    }
+
+    fn send(&mut self, notification: &Notification) -> std::io::Result<()> {
+        let body = serde_json::to_string(notification).map_err(std::io::Error::other)?;
+        (self.post)(&body)
+    }
+}
+
+impl<const N: usize> Observer<N> for Webhook {
+    #[inline]
+    fn needs_population(&self) -> bool {
+        false
+    }
+
+    #[inline]
+    fn on_progress(&mut self, progress: &Progress) {
+        if self.error.is_some() || self.last.is_some_and(|last| last.elapsed() < self.interval) {
+            return;
+        }
+
+        self.last = Some(Instant::now());
+        if let Err(err) = self.send(&Notification::progress(self.run, progress)) {
+            self.error = Some(err);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sudoku::{Board, Row};
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    fn progress(generation: u64) -> Progress {
+        Progress {
+            generation,
+            elapsed: Duration::from_secs(generation),
+            budget_used: None,
+            best: Some(Fitness::new(3)),
+            fitness: 0.5,
+        }
+    }
+
+    fn outcome(termination: Termination) -> Outcome<4> {
+        Outcome {
+            termination,
+            best: Some((Board([Row::default(); 4]), Fitness::new(2))),
+            generation: 9,
+            duration: Duration::from_secs(9),
+        }
+    }
+
+    #[test]
+    fn test_webhook() {
+        let sent = Rc::new(RefCell::new(Vec::new()));
+        let mut webhook = Webhook::with_poster(Webhook::DEFAULT_INTERVAL, {
+            let sent = Rc::clone(&sent);
+            move |body| {
+                sent.borrow_mut()
+                    .push(serde_json::from_str::<serde_json::Value>(body).unwrap());
+                Ok(())
+            }
+        });
+
+        for generation in 0..5 {
+            Observer::<4>::on_progress(&mut webhook, &progress(generation));
+        }
+        webhook
+            .finish_run(&outcome(Termination::BudgetExhausted))
+            .unwrap();
+        Observer::<4>::on_progress(&mut webhook, &progress(0));
+
+        let sent = sent.borrow();
+        assert_eq!(3, sent.len());
+        assert_eq!("progress", sent[0]["event"]);
+        assert_eq!(0, sent[0]["generation"]);
+        assert_eq!(3, sent[0]["best"]);
+        assert_eq!("finished", sent[1]["event"]);
+        assert_eq!("budget-exhausted", sent[1]["termination"]);
+        assert_eq!(2, sent[1]["best"]);
+        assert!(sent[1]["solution"].is_null());
+        assert_eq!(
+            "Run 0: budget-exhausted at generation 9 after 9s, best fitness 2",
+            sent[1]["text"]
+        );
+        assert_eq!(1, sent[2]["run"]);
+    }
+
+    #[test]
+    fn test_webhook_error() {
+        let posts = Rc::new(RefCell::new(0));
+        let mut webhook = Webhook::with_poster(Duration::ZERO, {
+            let posts = Rc::clone(&posts);
+            move |_| {
+                *posts.borrow_mut() += 1;
+                Err(std::io::Error::other("unreachable"))
+            }
+        });
+
+        Observer::<4>::on_progress(&mut webhook, &progress(0));
+        Observer::<4>::on_progress(&mut webhook, &progress(1));
+        let err = webhook.finish_run(&outcome(Termination::Cancelled));
+        assert_eq!("unreachable", err.unwrap_err().to_string());
+        assert_eq!(2, *posts.borrow());
+    }
+}